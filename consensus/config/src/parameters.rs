@@ -61,6 +61,13 @@ pub struct Parameters {
     #[serde(default = "Parameters::default_commit_sync_batches_ahead")]
     pub commit_sync_batches_ahead: usize,
 
+    /// The number of rounds of blocks and commits kept in the store below the last committed
+    /// round. Older data is garbage collected. Peers lagging by more than this depth cannot
+    /// catch up via commit sync, so it should cover the expected downtime of an authority.
+    /// It should also be larger than dag_state_cached_rounds. Set to 0 to disable GC.
+    #[serde(default = "Parameters::default_store_gc_depth_rounds")]
+    pub store_gc_depth_rounds: u32,
+
     /// Anemo network settings.
     #[serde(default = "AnemoParameters::default")]
     pub anemo: AnemoParameters,
@@ -124,6 +131,16 @@ impl Parameters {
     pub(crate) fn default_commit_sync_batches_ahead() -> usize {
         200
     }
+
+    pub(crate) fn default_store_gc_depth_rounds() -> u32 {
+        if cfg!(msim) {
+            // Exercise GC, while leaving enough depth for restarted authorities to catch up.
+            2_000
+        } else {
+            // About 2 hours of rounds.
+            60_000
+        }
+    }
 }
 
 impl Default for Parameters {
@@ -138,6 +155,7 @@ impl Default for Parameters {
             commit_sync_parallel_fetches: Parameters::default_commit_sync_parallel_fetches(),
             commit_sync_batch_size: Parameters::default_commit_sync_batch_size(),
             commit_sync_batches_ahead: Parameters::default_commit_sync_batches_ahead(),
+            store_gc_depth_rounds: Parameters::default_store_gc_depth_rounds(),
             anemo: AnemoParameters::default(),
             tonic: TonicParameters::default(),
         }
@@ -19,15 +19,17 @@ use crate::{
     context::{Clock, Context},
     core::{Core, CoreSignals},
     core_thread::{ChannelCoreThreadDispatcher, CoreThreadHandle},
+    dag_inspector::LiveDagInspector,
     dag_state::DagState,
-    leader_schedule::{LeaderSchedule, LeaderSwapTable},
+    leader_schedule::LeaderSchedule,
     leader_timeout::{LeaderTimeoutTask, LeaderTimeoutTaskHandle},
     metrics::initialise_metrics,
     network::{
         anemo_network::AnemoManager, tonic_network::TonicManager, NetworkClient as _,
         NetworkManager,
     },
-    storage::rocksdb_store::RocksDBStore,
+    storage::{rocksdb_store::RocksDBStore, Store},
+    store_gc::{StoreGcTask, StoreGcTaskHandle},
     subscriber::Subscriber,
     synchronizer::{Synchronizer, SynchronizerHandle},
     transaction::{TransactionClient, TransactionConsumer, TransactionVerifier},
@@ -103,6 +105,14 @@ impl ConsensusAuthority {
         }
     }
 
+    /// Returns an inspector of the live DAG, for taking snapshots of its blocks and commits.
+    pub fn dag_inspector(&self) -> LiveDagInspector {
+        match self {
+            Self::WithAnemo(authority) => authority.dag_inspector(),
+            Self::WithTonic(authority) => authority.dag_inspector(),
+        }
+    }
+
     #[cfg(test)]
     fn context(&self) -> &Arc<Context> {
         match self {
@@ -122,12 +132,15 @@ where
     synchronizer: Arc<SynchronizerHandle>,
     commit_syncer: CommitSyncer<N::Client>,
     leader_timeout_handle: LeaderTimeoutTaskHandle,
+    store_gc_handle: StoreGcTaskHandle,
     core_thread_handle: CoreThreadHandle,
     // Only one of broadcaster and subscriber gets created, depending on
     // if streaming is supported.
     broadcaster: Option<Broadcaster>,
     subscriber: Option<Subscriber<N::Client, AuthorityService<ChannelCoreThreadDispatcher>>>,
     network_manager: N,
+    dag_state: Arc<RwLock<DagState>>,
+    store: Arc<dyn Store>,
 }
 
 impl<N> AuthorityNode<N>
@@ -160,6 +173,42 @@ where
             initialise_metrics(registry),
             Arc::new(Clock::new()),
         ));
+        let network_manager = N::new(context.clone());
+        let store_path = context
+            .parameters
+            .db_path
+            .as_ref()
+            .expect("DB path is not set")
+            .as_path()
+            .to_str()
+            .unwrap();
+        let store = Arc::new(RocksDBStore::new(store_path));
+
+        Self::start_with(
+            context,
+            network_manager,
+            store,
+            protocol_keypair,
+            network_keypair,
+            transaction_verifier,
+            commit_consumer,
+        )
+        .await
+    }
+
+    /// Starts the authority over the given network and store. Used directly to run authorities
+    /// over a simulated network, with in-memory stores that outlive restarts.
+    pub(crate) async fn start_with(
+        context: Arc<Context>,
+        mut network_manager: N,
+        store: Arc<dyn Store>,
+        // To avoid accidentally leaking the private key, the protocol key pair should only be
+        // kept in Core.
+        protocol_keypair: ProtocolKeyPair,
+        network_keypair: NetworkKeyPair,
+        transaction_verifier: Arc<dyn TransactionVerifier>,
+        commit_consumer: CommitConsumer,
+    ) -> Self {
         let start_time = Instant::now();
 
         let (tx_client, tx_receiver) = TransactionClient::new(context.clone());
@@ -167,7 +216,6 @@ where
 
         let (core_signals, signals_receivers) = CoreSignals::new(context.clone());
 
-        let mut network_manager = N::new(context.clone());
         let network_client = network_manager.client();
 
         // REQUIRED: Broadcaster must be created before Core, to start listening on the
@@ -182,15 +230,6 @@ where
             ))
         };
 
-        let store_path = context
-            .parameters
-            .db_path
-            .as_ref()
-            .expect("DB path is not set")
-            .as_path()
-            .to_str()
-            .unwrap();
-        let store = Arc::new(RocksDBStore::new(store_path));
         let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
 
         let block_verifier = Arc::new(SignedBlockVerifier::new(
@@ -201,6 +240,7 @@ where
         let block_manager =
             BlockManager::new(context.clone(), dag_state.clone(), block_verifier.clone());
 
+        let commit_consumer_monitor = commit_consumer.monitor();
         let commit_observer = CommitObserver::new(
             context.clone(),
             commit_consumer,
@@ -208,9 +248,9 @@ where
             store.clone(),
         );
 
-        let leader_schedule = Arc::new(LeaderSchedule::new(
+        let leader_schedule = Arc::new(LeaderSchedule::from_store(
             context.clone(),
-            LeaderSwapTable::default(),
+            dag_state.clone(),
         ));
 
         let core = Core::new(
@@ -230,6 +270,14 @@ where
         let leader_timeout_handle =
             LeaderTimeoutTask::start(core_dispatcher.clone(), &signals_receivers, context.clone());
 
+        let store_gc_handle = StoreGcTask::start(
+            context.clone(),
+            dag_state.clone(),
+            store.clone(),
+            commit_consumer_monitor,
+            &signals_receivers,
+        );
+
         let synchronizer = Synchronizer::start(
             network_client.clone(),
             context.clone(),
@@ -256,7 +304,7 @@ where
             core_dispatcher,
             signals_receivers.block_broadcast_receiver(),
             dag_state.clone(),
-            store,
+            store.clone(),
         ));
 
         let subscriber = if N::Client::SUPPORT_STREAMING {
@@ -264,7 +312,7 @@ where
                 context.clone(),
                 network_client,
                 network_service.clone(),
-                dag_state,
+                dag_state.clone(),
             );
             for (peer, _) in context.committee.authorities() {
                 if peer != context.own_index {
@@ -287,10 +335,13 @@ where
             synchronizer,
             commit_syncer,
             leader_timeout_handle,
+            store_gc_handle,
             core_thread_handle,
             broadcaster,
             subscriber,
             network_manager,
+            dag_state,
+            store,
         }
     }
 
@@ -304,6 +355,7 @@ where
         self.synchronizer.stop().await;
         self.commit_syncer.stop().await;
         self.leader_timeout_handle.stop().await;
+        self.store_gc_handle.stop().await;
         // Shutdown Core to stop block productions and broadcast.
         // When using streaming, all subscribers to broadcasted blocks stop after this.
         self.core_thread_handle.stop().await;
@@ -326,6 +378,10 @@ where
     pub(crate) fn transaction_client(&self) -> Arc<TransactionClient> {
         self.transaction_client.clone()
     }
+
+    pub(crate) fn dag_inspector(&self) -> LiveDagInspector {
+        LiveDagInspector::new(self.dag_state.clone(), self.store.clone())
+    }
 }
 
 #[cfg(test)]
@@ -142,7 +142,39 @@ impl BlockVerifier for SignedBlockVerifier {
             });
         }
 
-        // TODO: check transaction size, total size and count.
+        if self
+            .context
+            .protocol_config
+            .consensus_check_transaction_sizes()
+        {
+            let max_transaction_size = self
+                .context
+                .protocol_config
+                .consensus_max_transaction_size_bytes();
+            let max_transactions_size = self
+                .context
+                .protocol_config
+                .consensus_max_transactions_in_block_bytes();
+            let mut total_size = 0;
+            for transaction in block.transactions() {
+                let size = transaction.data().len() as u64;
+                if size > max_transaction_size {
+                    return Err(ConsensusError::TransactionTooLarge {
+                        size,
+                        limit: max_transaction_size,
+                    });
+                }
+                total_size += size;
+            }
+            if total_size > max_transactions_size {
+                return Err(ConsensusError::TransactionsTooLarge {
+                    size: total_size,
+                    limit: max_transactions_size,
+                });
+            }
+        }
+
+        // TODO: check transaction count.
         let batch: Vec<_> = block.transactions().iter().map(|t| t.data()).collect();
         self.transaction_verifier
             .verify_batch(&self.context.protocol_config, &batch)
@@ -445,6 +477,50 @@ mod test {
                 Err(ConsensusError::InvalidTransaction(_))
             ));
         }
+
+        // Block with an oversized transaction.
+        {
+            let max_transaction_size = context
+                .protocol_config
+                .consensus_max_transaction_size_bytes();
+            let block = test_block
+                .clone()
+                .set_transactions(vec![Transaction::new(vec![
+                    4;
+                    max_transaction_size as usize
+                        + 1
+                ])])
+                .build();
+            let signed_block = SignedBlock::new(block, authority_2_protocol_keypair).unwrap();
+            assert!(matches!(
+                verifier.verify(&signed_block),
+                Err(ConsensusError::TransactionTooLarge { size: _, limit: _ })
+            ));
+        }
+
+        // Block with too many transaction bytes.
+        {
+            let max_transaction_size = context
+                .protocol_config
+                .consensus_max_transaction_size_bytes();
+            let max_transactions_size = context
+                .protocol_config
+                .consensus_max_transactions_in_block_bytes();
+            let num_transactions = max_transactions_size / max_transaction_size + 1;
+            let block = test_block
+                .clone()
+                .set_transactions(
+                    (0..num_transactions)
+                        .map(|_| Transaction::new(vec![4; max_transaction_size as usize]))
+                        .collect(),
+                )
+                .build();
+            let signed_block = SignedBlock::new(block, authority_2_protocol_keypair).unwrap();
+            assert!(matches!(
+                verifier.verify(&signed_block),
+                Err(ConsensusError::TransactionsTooLarge { size: _, limit: _ })
+            ));
+        }
     }
 
     #[test]
@@ -0,0 +1,270 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Makes an authority in a `Simulator` misbehave towards its peers.
+//! The authority keeps running honest logic locally, and its own blocks are tampered with when
+//! they are sent over the `SimulatedNetwork`, so every misbehavior is exercised at every round.
+
+use bytes::Bytes;
+use consensus_config::{AuthorityIndex, ProtocolKeyPair};
+use rand::{rngs::StdRng, SeedableRng as _};
+use sui_protocol_config::ProtocolConfig;
+
+use crate::{
+    block::{
+        Block, BlockAPI as _, BlockDigest, BlockV1, SignedBlock, Transaction, VerifiedBlock,
+        GENESIS_ROUND,
+    },
+    error::{ConsensusError, ConsensusResult},
+};
+
+#[derive(Clone, Debug)]
+pub(crate) enum ByzantineBehavior {
+    /// Sends a different version of each block to every peer.
+    Equivocate,
+    /// Neither sends blocks to nor serves blocks to the peers.
+    Withhold(Vec<AuthorityIndex>),
+    /// Replaces an ancestor of each block with one that was never proposed.
+    UnknownAncestors,
+    /// Adds a transaction above the size limit to each block.
+    OversizedBlocks,
+    /// Signs each block with a key outside of the committee.
+    InvalidSignatures,
+}
+
+pub(crate) struct ByzantineAuthority {
+    protocol_keypair: ProtocolKeyPair,
+    invalid_keypair: ProtocolKeyPair,
+    max_transaction_size: u64,
+    behaviors: Vec<ByzantineBehavior>,
+}
+
+impl ByzantineAuthority {
+    pub(crate) fn new(
+        protocol_keypair: ProtocolKeyPair,
+        protocol_config: &ProtocolConfig,
+        behaviors: Vec<ByzantineBehavior>,
+    ) -> Self {
+        // Test committees are generated from the zero seed.
+        let invalid_keypair = ProtocolKeyPair::generate(&mut StdRng::from_seed([1; 32]));
+        Self {
+            protocol_keypair,
+            invalid_keypair,
+            max_transaction_size: protocol_config.consensus_max_transaction_size_bytes(),
+            behaviors,
+        }
+    }
+
+    /// Returns whether blocks can be sent to or fetched by `peer`.
+    pub(crate) fn serves(&self, peer: AuthorityIndex) -> bool {
+        !self.behaviors.iter().any(|behavior| match behavior {
+            ByzantineBehavior::Withhold(peers) => peers.contains(&peer),
+            _ => false,
+        })
+    }
+
+    /// Returns the serialized block sent to `peer` in place of the authority's own `block`.
+    pub(crate) fn tamper(
+        &self,
+        peer: AuthorityIndex,
+        block: &VerifiedBlock,
+    ) -> ConsensusResult<Bytes> {
+        let mut timestamp_ms = block.timestamp_ms();
+        let mut ancestors = block.ancestors().to_vec();
+        let mut transactions = block.transactions().to_vec();
+        let mut keypair = &self.protocol_keypair;
+        for behavior in &self.behaviors {
+            match behavior {
+                ByzantineBehavior::Equivocate => {
+                    // A later timestamp keeps the block valid.
+                    timestamp_ms += peer.value() as u64;
+                }
+                ByzantineBehavior::Withhold(_) => {}
+                ByzantineBehavior::UnknownAncestors => {
+                    // Genesis ancestors are checked by the block verifier.
+                    if let Some(ancestor) = ancestors[1..]
+                        .iter_mut()
+                        .rev()
+                        .find(|ancestor| ancestor.round > GENESIS_ROUND)
+                    {
+                        ancestor.digest = BlockDigest::MAX;
+                    }
+                }
+                ByzantineBehavior::OversizedBlocks => {
+                    transactions.push(Transaction::new(vec![
+                        0;
+                        self.max_transaction_size as usize + 1
+                    ]));
+                }
+                ByzantineBehavior::InvalidSignatures => {
+                    keypair = &self.invalid_keypair;
+                }
+            }
+        }
+
+        let tampered = Block::V1(BlockV1::new(
+            block.epoch(),
+            block.round(),
+            block.author(),
+            timestamp_ms,
+            ancestors,
+            transactions,
+            block.commit_votes().to_vec(),
+            block.misbehavior_reports().to_vec(),
+        ));
+        SignedBlock::new(tampered, keypair)?
+            .serialize()
+            .map_err(ConsensusError::SerializationFailure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, time::Duration};
+
+    use consensus_config::AuthorityIndex;
+
+    use super::ByzantineBehavior;
+    use crate::{block::BlockAPI as _, simulator::Simulator, storage::Store as _};
+
+    const NUM_COMMITS: usize = 30;
+
+    async fn run_honest_authorities(
+        seed: u64,
+        behaviors: Vec<ByzantineBehavior>,
+    ) -> (Simulator, AuthorityIndex) {
+        telemetry_subscribers::init_for_testing();
+        let mut simulator = Simulator::new(4, seed);
+        simulator
+            .network()
+            .set_latency(Duration::from_millis(20), Duration::from_millis(30));
+        let byzantine = simulator.authorities()[3];
+        simulator.make_byzantine(byzantine, behaviors);
+        simulator.start().await;
+
+        let honest = simulator.honest_authorities();
+        simulator
+            .wait_for_commits(&honest, NUM_COMMITS, Duration::from_secs(120))
+            .await;
+        simulator.check_safety();
+        (simulator, byzantine)
+    }
+
+    /// Returns the rounds of committed blocks from `author`, per honest authority.
+    fn committed_rounds(simulator: &Simulator, author: AuthorityIndex) -> Vec<Vec<u32>> {
+        simulator
+            .honest_authorities()
+            .into_iter()
+            .map(|index| {
+                simulator
+                    .committed_blocks(index)
+                    .into_iter()
+                    .filter(|b| b.author == author)
+                    .map(|b| b.round)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_equivocation() {
+        let (simulator, byzantine) =
+            run_honest_authorities(21, vec![ByzantineBehavior::Equivocate]).await;
+
+        // Honest authorities reference different versions of the byzantine blocks, and accept
+        // all of them when fetching missing ancestors.
+        let blocks = simulator
+            .store(simulator.honest_authorities()[0])
+            .scan_blocks_by_author(byzantine, 1)
+            .unwrap();
+        let mut versions = BTreeMap::<u32, usize>::new();
+        for block in &blocks {
+            *versions.entry(block.round()).or_default() += 1;
+        }
+        assert!(
+            versions.values().any(|count| *count > 1),
+            "No equivocation observed: {versions:?}"
+        );
+
+        simulator.stop().await;
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_withholding() {
+        let (simulator, byzantine) = run_honest_authorities(
+            22,
+            vec![ByzantineBehavior::Withhold(vec![
+                AuthorityIndex::new_for_test(0),
+                AuthorityIndex::new_for_test(1),
+            ])],
+        )
+        .await;
+
+        // Authorities 0 and 1 fetch the withheld blocks from authority 2.
+        for rounds in committed_rounds(&simulator, byzantine) {
+            assert!(!rounds.is_empty());
+        }
+
+        simulator.stop().await;
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_unknown_ancestors() {
+        let (simulator, byzantine) =
+            run_honest_authorities(23, vec![ByzantineBehavior::UnknownAncestors]).await;
+
+        // Blocks with unknown ancestors are never accepted.
+        for rounds in committed_rounds(&simulator, byzantine) {
+            assert!(rounds.is_empty(), "{rounds:?}");
+        }
+
+        simulator.stop().await;
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_oversized_blocks() {
+        let (simulator, byzantine) =
+            run_honest_authorities(24, vec![ByzantineBehavior::OversizedBlocks]).await;
+
+        for index in simulator.honest_authorities() {
+            let blocks = simulator
+                .store(index)
+                .scan_blocks_by_author(byzantine, 1)
+                .unwrap();
+            assert!(blocks.is_empty(), "{blocks:?}");
+        }
+
+        simulator.stop().await;
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_invalid_signatures() {
+        let (simulator, byzantine) =
+            run_honest_authorities(25, vec![ByzantineBehavior::InvalidSignatures]).await;
+
+        for index in simulator.honest_authorities() {
+            let blocks = simulator
+                .store(index)
+                .scan_blocks_by_author(byzantine, 1)
+                .unwrap();
+            assert!(blocks.is_empty(), "{blocks:?}");
+        }
+
+        simulator.stop().await;
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_combined_misbehaviors() {
+        let (simulator, _byzantine) = run_honest_authorities(
+            26,
+            vec![
+                ByzantineBehavior::Equivocate,
+                ByzantineBehavior::Withhold(vec![AuthorityIndex::new_for_test(2)]),
+                ByzantineBehavior::UnknownAncestors,
+            ],
+        )
+        .await;
+
+        simulator.stop().await;
+    }
+}
@@ -6,7 +6,10 @@ use std::{
     fmt::{self, Display, Formatter},
     hash::{Hash, Hasher},
     ops::{Deref, Range},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU32, Ordering as AtomicOrdering},
+        Arc,
+    },
 };
 
 use bytes::Bytes;
@@ -391,6 +394,8 @@ pub struct CommitConsumer {
     // First commit in the replayed sequence will have index last_processed_commit_index + 1.
     // Set 0 to replay from the start (as generated commit sequence starts at index = 1).
     pub last_processed_commit_index: CommitIndex,
+    // Tracks the commits handled by the consumer after startup.
+    monitor: Arc<CommitConsumerMonitor>,
 }
 
 impl CommitConsumer {
@@ -403,8 +408,39 @@ impl CommitConsumer {
             sender,
             last_processed_commit_round,
             last_processed_commit_index,
+            monitor: Arc::new(CommitConsumerMonitor::new(last_processed_commit_index)),
         }
     }
+
+    /// Returns the monitor the consumer reports its progress to.
+    pub fn monitor(&self) -> Arc<CommitConsumerMonitor> {
+        self.monitor.clone()
+    }
+}
+
+/// Tracks the highest commit handled by the consumer of commits. Commits after it are replayed to
+/// the consumer after a restart, so they must not be garbage collected from the store.
+pub struct CommitConsumerMonitor {
+    highest_handled_commit: AtomicU32,
+}
+
+impl CommitConsumerMonitor {
+    pub(crate) fn new(last_handled_commit: CommitIndex) -> Self {
+        Self {
+            highest_handled_commit: AtomicU32::new(last_handled_commit),
+        }
+    }
+
+    pub fn highest_handled_commit(&self) -> CommitIndex {
+        self.highest_handled_commit.load(AtomicOrdering::Acquire)
+    }
+
+    /// Records that the consumer has handled the commit at `commit_index`, such that it will not
+    /// be replayed after a restart.
+    pub fn set_highest_handled_commit(&self, commit_index: CommitIndex) {
+        self.highest_handled_commit
+            .fetch_max(commit_index, AtomicOrdering::Release);
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -104,6 +104,10 @@ impl Context {
 /// will remain monotonically increasing.
 pub(crate) struct Clock {
     unix_epoch_instant: Instant,
+    // Set for simulated clocks: the tokio instant when the clock was created, and the UNIX
+    // timestamp it starts from.
+    #[cfg(test)]
+    simulated_start: Option<(tokio::time::Instant, BlockTimestampMs)>,
 }
 
 impl Clock {
@@ -116,12 +120,31 @@ impl Clock {
             };
         let unix_epoch_instant = now.checked_sub(duration_since_unix_epoch).unwrap();
 
-        Self { unix_epoch_instant }
+        Self {
+            unix_epoch_instant,
+            #[cfg(test)]
+            simulated_start: None,
+        }
+    }
+
+    /// Creates a clock starting at `start_timestamp_ms`, which only advances with tokio time.
+    /// Under a paused tokio runtime it does not depend on the wall clock, so simulated runs are
+    /// reproducible.
+    #[cfg(test)]
+    pub(crate) fn new_simulated(start_timestamp_ms: BlockTimestampMs) -> Self {
+        Self {
+            simulated_start: Some((tokio::time::Instant::now(), start_timestamp_ms)),
+            ..Self::new()
+        }
     }
 
     // Returns the current time expressed as UNIX timestamp in milliseconds.
     // Calculated with Rust Instant to ensure monotonicity.
     pub(crate) fn timestamp_utc_ms(&self) -> BlockTimestampMs {
+        #[cfg(test)]
+        if let Some((start, start_timestamp_ms)) = self.simulated_start {
+            return start_timestamp_ms + start.elapsed().as_millis() as BlockTimestampMs;
+        }
         Instant::now()
             .checked_duration_since(self.unix_epoch_instant)
             .unwrap()
@@ -65,7 +65,6 @@ pub(crate) struct Core {
     last_decided_leader: Slot,
     /// The consensus leader schedule to be used to resolve the leader for a
     /// given round.
-    #[allow(unused)]
     leader_schedule: Arc<LeaderSchedule>,
     /// The commit observer is responsible for observing the commits and collecting
     /// + sending subdags over the consensus output channel.
@@ -442,33 +441,68 @@ impl Core {
             .with_label_values(&["Core::try_commit"])
             .start_timer();
 
-        // TODO: Add optimization to abort early without quorum for a round.
-        let sequenced_leaders = self.committer.try_commit(self.last_decided_leader);
+        let mut committed_sub_dags = Vec::new();
+        loop {
+            // The leader schedule is updated after the same commit on all authorities, so
+            // leaders after that commit are decided with the updated schedule.
+            let mut commits_until_update = usize::MAX;
+            if self
+                .context
+                .protocol_config
+                .mysticeti_leader_scoring_and_schedule()
+            {
+                commits_until_update = self
+                    .leader_schedule
+                    .commits_until_leader_schedule_update(self.dag_state.clone());
+                if commits_until_update == 0 {
+                    self.leader_schedule
+                        .update_leader_schedule(self.dag_state.clone());
+                    commits_until_update = self
+                        .leader_schedule
+                        .commits_until_leader_schedule_update(self.dag_state.clone());
+                }
+            }
 
-        if let Some(last) = sequenced_leaders.last() {
-            self.last_decided_leader = last.get_decided_slot();
+            // TODO: Add optimization to abort early without quorum for a round.
+            let sequenced_leaders = self.committer.try_commit(self.last_decided_leader);
+
+            // Leaders decided after the schedule update are discarded, and decided again on
+            // the next iteration.
+            let mut committed_leaders = Vec::new();
+            let mut decided_all = true;
+            for leader in sequenced_leaders {
+                if committed_leaders.len() == commits_until_update {
+                    decided_all = false;
+                    break;
+                }
+                self.last_decided_leader = leader.get_decided_slot();
+                if let Some(block) = leader.into_committed_block() {
+                    committed_leaders.push(block);
+                }
+            }
             self.context
                 .metrics
                 .node_metrics
                 .last_decided_leader_round
                 .set(self.last_decided_leader.round as i64);
-        }
 
-        let committed_leaders = sequenced_leaders
-            .into_iter()
-            .filter_map(|leader| leader.into_committed_block())
-            .collect::<Vec<_>>();
-        if !committed_leaders.is_empty() {
-            debug!(
-                "Committing leaders: {}",
-                committed_leaders
-                    .iter()
-                    .map(|b| b.reference().to_string())
-                    .join(",")
-            );
+            if !committed_leaders.is_empty() {
+                debug!(
+                    "Committing leaders: {}",
+                    committed_leaders
+                        .iter()
+                        .map(|b| b.reference().to_string())
+                        .join(",")
+                );
+            }
+            committed_sub_dags.extend(self.commit_observer.handle_commit(committed_leaders)?);
+
+            if decided_all {
+                break;
+            }
         }
 
-        self.commit_observer.handle_commit(committed_leaders)
+        Ok(committed_sub_dags)
     }
 
     pub(crate) fn get_missing_blocks(&self) -> BTreeSet<BlockRef> {
@@ -0,0 +1,569 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
+
+use consensus_config::AuthorityIndex;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::{BlockAPI as _, BlockRef, BlockTimestampMs, Round, Slot, VerifiedBlock, GENESIS_ROUND},
+    commit::{CommitAPI as _, CommitIndex, CommitRange, TrustedCommit},
+    dag_state::DagState,
+    error::ConsensusResult,
+    storage::{rocksdb_store::RocksDBStore, Store},
+};
+
+/// Number of commits read from storage at a time, when looking for the commits of a round range.
+const COMMIT_SCAN_BATCH_SIZE: CommitIndex = 100;
+
+/// Inspects the DAG persisted in a consensus RocksDB store, for debugging a node offline.
+///
+/// The store is opened read-only as a secondary instance, so it can also be in use by a running
+/// node, in which case the snapshot reflects the data flushed when the store was opened.
+pub struct DagInspector {
+    store: RocksDBStore,
+}
+
+impl DagInspector {
+    pub fn open(path: &Path) -> Self {
+        Self {
+            store: RocksDBStore::new_secondary(
+                path.to_str().expect("DB path should be valid UTF-8"),
+            ),
+        }
+    }
+
+    /// Returns the blocks within [`start_round`, `end_round`] and the commits deciding them.
+    pub fn snapshot(&self, start_round: Round, end_round: Round) -> ConsensusResult<DagSnapshot> {
+        let blocks = self.store.scan_blocks_by_round(start_round, end_round)?;
+        let commits = scan_commits_by_round(&self.store, start_round, end_round)?;
+        let last_commit = self.store.read_last_commit()?;
+        DagSnapshot::new(
+            start_round,
+            end_round,
+            blocks,
+            commits,
+            last_commit.as_ref().map_or(0, |c| c.index()),
+            last_commit.as_ref().map_or(GENESIS_ROUND, |c| c.round()),
+            |refs| self.store.contains_blocks(refs),
+        )
+    }
+}
+
+/// Inspects the live DAG of a running authority. It can outlive the authority, and can be moved
+/// to a blocking thread since snapshots read from storage.
+#[derive(Clone)]
+pub struct LiveDagInspector {
+    dag_state: Arc<RwLock<DagState>>,
+    store: Arc<dyn Store>,
+}
+
+impl LiveDagInspector {
+    pub(crate) fn new(dag_state: Arc<RwLock<DagState>>, store: Arc<dyn Store>) -> Self {
+        Self { dag_state, store }
+    }
+
+    /// Returns a snapshot of the live DAG within [`start_round`, `end_round`]. Blocks and commits
+    /// not flushed to the store yet are read from the DAG state.
+    ///
+    /// The lock on the DAG state is only held while copying cached data, so that storage reads
+    /// do not block consensus.
+    pub fn snapshot(&self, start_round: Round, end_round: Round) -> ConsensusResult<DagSnapshot> {
+        snapshot_dag_state(&self.dag_state, self.store.as_ref(), start_round, end_round)
+    }
+}
+
+fn snapshot_dag_state(
+    dag_state: &RwLock<DagState>,
+    store: &dyn Store,
+    start_round: Round,
+    end_round: Round,
+) -> ConsensusResult<DagSnapshot> {
+    let (cached_blocks, unflushed_commits, last_commit_index, last_commit_round) = {
+        let dag_state = dag_state.read();
+        (
+            dag_state.get_cached_blocks_in_rounds(start_round, end_round),
+            dag_state.unflushed_commits(),
+            dag_state.last_commit_index(),
+            dag_state.last_commit_round(),
+        )
+    };
+
+    let mut blocks = store.scan_blocks_by_round(start_round, end_round)?;
+    blocks.extend(cached_blocks);
+    let mut commits = scan_commits_by_round(store, start_round, end_round)?;
+    commits.extend(unflushed_commits.into_iter().filter(|commit| {
+        commit.round() >= start_round && commit.blocks().iter().any(|b| b.round <= end_round)
+    }));
+
+    DagSnapshot::new(
+        start_round,
+        end_round,
+        blocks,
+        commits,
+        last_commit_index,
+        last_commit_round,
+        |refs| Ok(dag_state.read().contains_blocks(refs.to_vec())),
+    )
+}
+
+/// Reads the commits with leaders from `start_round`, until the first commit after `end_round`
+/// that does not commit any block within the range.
+fn scan_commits_by_round(
+    store: &dyn Store,
+    start_round: Round,
+    end_round: Round,
+) -> ConsensusResult<Vec<TrustedCommit>> {
+    let Some(last_commit) = store.read_last_commit()? else {
+        return Ok(vec![]);
+    };
+
+    // Leader rounds increase with commit indices, so binary search for the first commit with
+    // a leader at or after start_round.
+    let (mut low, mut high) = (1, last_commit.index() + 1);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match store.scan_commits(CommitRange::new(mid..mid + 1))?.pop() {
+            Some(commit) if commit.round() < start_round => low = mid + 1,
+            _ => high = mid,
+        }
+    }
+
+    let mut commits = vec![];
+    let mut start = low;
+    while start <= last_commit.index() {
+        let end = (start + COMMIT_SCAN_BATCH_SIZE).min(last_commit.index() + 1);
+        for commit in store.scan_commits(CommitRange::new(start..end))? {
+            if commit.round() > end_round && commit.blocks().iter().all(|b| b.round > end_round) {
+                return Ok(commits);
+            }
+            commits.push(commit);
+        }
+        start = end;
+    }
+    Ok(commits)
+}
+
+/// Formats a `DagSnapshot` can be exported to.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DagExportFormat {
+    #[default]
+    Json,
+    /// Graphviz DOT.
+    Dot,
+}
+
+impl FromStr for DagExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "dot" => Ok(Self::Dot),
+            _ => Err(format!(
+                "unknown DAG export format '{s}', expected json or dot"
+            )),
+        }
+    }
+}
+
+/// A round range of the DAG, with the consensus decisions on it. Blocks are identified by their
+/// `BlockRef` with the full digest.
+#[derive(Clone, Debug, Serialize)]
+pub struct DagSnapshot {
+    pub start_round: Round,
+    pub end_round: Round,
+    /// The last commit of the DAG. Blocks after its round may still be committed.
+    pub last_commit_index: CommitIndex,
+    pub last_commit_round: Round,
+    /// Blocks within the round range, ordered by round and author.
+    pub blocks: Vec<DagBlock>,
+    /// Commits of blocks within the round range, ordered by index.
+    pub commits: Vec<DagCommit>,
+    /// Ancestors of the blocks which cannot be found locally.
+    pub missing_ancestors: Vec<String>,
+    /// Slots with more than one block.
+    pub equivocating_slots: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DagBlock {
+    pub reference: String,
+    pub round: Round,
+    pub author: u32,
+    pub digest: String,
+    pub timestamp_ms: BlockTimestampMs,
+    pub num_transactions: usize,
+    pub ancestors: Vec<String>,
+    /// Index of the commit which has this block as leader.
+    pub leader_of: Option<CommitIndex>,
+    /// Index of the commit which includes this block.
+    pub committed_by: Option<CommitIndex>,
+    pub equivocating: bool,
+    pub missing_ancestors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DagCommit {
+    pub index: CommitIndex,
+    pub digest: String,
+    pub leader: String,
+    pub leader_round: Round,
+    pub timestamp_ms: BlockTimestampMs,
+    pub num_blocks: usize,
+}
+
+impl DagSnapshot {
+    fn new(
+        start_round: Round,
+        end_round: Round,
+        blocks: Vec<VerifiedBlock>,
+        commits: Vec<TrustedCommit>,
+        last_commit_index: CommitIndex,
+        last_commit_round: Round,
+        contains_blocks: impl FnOnce(&[BlockRef]) -> ConsensusResult<Vec<bool>>,
+    ) -> ConsensusResult<Self> {
+        // Blocks and commits can be read from both storage and memory.
+        let blocks: BTreeMap<BlockRef, VerifiedBlock> = blocks
+            .into_iter()
+            .map(|block| (block.reference(), block))
+            .collect();
+        let commits: BTreeMap<CommitIndex, TrustedCommit> = commits
+            .into_iter()
+            .map(|commit| (commit.index(), commit))
+            .collect();
+
+        let mut leader_of = BTreeMap::new();
+        let mut committed_by = BTreeMap::new();
+        for commit in commits.values() {
+            leader_of.insert(commit.leader(), commit.index());
+            for block_ref in commit.blocks() {
+                committed_by.insert(*block_ref, commit.index());
+            }
+        }
+
+        let mut blocks_per_slot: BTreeMap<(Round, AuthorityIndex), usize> = BTreeMap::new();
+        for block_ref in blocks.keys() {
+            *blocks_per_slot
+                .entry((block_ref.round, block_ref.author))
+                .or_default() += 1;
+        }
+        let equivocating_slots: BTreeSet<(Round, AuthorityIndex)> = blocks_per_slot
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(slot, _)| slot)
+            .collect();
+
+        // Ancestors within the range are found in the snapshot. Others need a lookup.
+        let unknown_ancestors: Vec<BlockRef> = blocks
+            .values()
+            .flat_map(|block| block.ancestors().iter().copied())
+            .filter(|ancestor| ancestor.round != GENESIS_ROUND && !blocks.contains_key(ancestor))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let found = contains_blocks(&unknown_ancestors)?;
+        let missing_ancestors: BTreeSet<BlockRef> = unknown_ancestors
+            .into_iter()
+            .zip(found)
+            .filter(|(_, found)| !found)
+            .map(|(ancestor, _)| ancestor)
+            .collect();
+
+        Ok(Self {
+            start_round,
+            end_round,
+            last_commit_index,
+            last_commit_round,
+            blocks: blocks
+                .values()
+                .map(|block| DagBlock {
+                    reference: format!("{:?}", block.reference()),
+                    round: block.round(),
+                    author: block.author().value() as u32,
+                    digest: format!("{:?}", block.digest()),
+                    timestamp_ms: block.timestamp_ms(),
+                    num_transactions: block.transactions().len(),
+                    ancestors: block.ancestors().iter().map(|a| format!("{a:?}")).collect(),
+                    leader_of: leader_of.get(&block.reference()).copied(),
+                    committed_by: committed_by.get(&block.reference()).copied(),
+                    equivocating: equivocating_slots.contains(&(block.round(), block.author())),
+                    missing_ancestors: block
+                        .ancestors()
+                        .iter()
+                        .filter(|ancestor| missing_ancestors.contains(ancestor))
+                        .map(|a| format!("{a:?}"))
+                        .collect(),
+                })
+                .collect(),
+            commits: commits
+                .values()
+                .map(|commit| DagCommit {
+                    index: commit.index(),
+                    digest: format!("{:?}", commit.digest()),
+                    leader: format!("{:?}", commit.leader()),
+                    leader_round: commit.round(),
+                    timestamp_ms: commit.timestamp_ms(),
+                    num_blocks: commit.blocks().len(),
+                })
+                .collect(),
+            missing_ancestors: missing_ancestors.iter().map(|a| format!("{a:?}")).collect(),
+            equivocating_slots: equivocating_slots
+                .iter()
+                .map(|(round, author)| Slot::new(*round, *author).to_string())
+                .collect(),
+        })
+    }
+
+    pub fn export(&self, format: DagExportFormat) -> String {
+        match format {
+            DagExportFormat::Json => self.to_json(),
+            DagExportFormat::Dot => self.to_dot(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("DAG snapshot should serialize")
+    }
+
+    /// Renders the snapshot as a Graphviz digraph, with rounds from left to right and edges from
+    /// blocks to their ancestors.
+    /// - Commit leaders are gold double octagons, linked from their commit.
+    /// - Committed blocks are green, and blocks after the last commit round are grey.
+    /// - Equivocating blocks have a red border.
+    /// - Missing ancestors are dashed red nodes.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph dag {{").unwrap();
+        writeln!(dot, "  rankdir=LR;").unwrap();
+        writeln!(dot, "  node [shape=box, style=filled, fillcolor=white];").unwrap();
+        writeln!(
+            dot,
+            "  label=\"rounds {} to {}, last commit {} at round {}\";",
+            self.start_round, self.end_round, self.last_commit_index, self.last_commit_round
+        )
+        .unwrap();
+
+        let mut rounds: BTreeMap<Round, Vec<&DagBlock>> = BTreeMap::new();
+        for block in &self.blocks {
+            rounds.entry(block.round).or_default().push(block);
+        }
+        for (round, blocks) in rounds {
+            writeln!(dot, "  subgraph round_{round} {{").unwrap();
+            writeln!(dot, "    rank=same;").unwrap();
+            for block in blocks {
+                let mut attributes = vec![format!("label=\"{}\"", block_label(block))];
+                if block.leader_of.is_some() {
+                    attributes.push("shape=doubleoctagon".to_string());
+                    attributes.push("fillcolor=gold".to_string());
+                } else if block.committed_by.is_some() {
+                    attributes.push("fillcolor=palegreen".to_string());
+                } else if block.round > self.last_commit_round {
+                    attributes.push("fillcolor=lightgrey".to_string());
+                }
+                if block.equivocating {
+                    attributes.push("color=red".to_string());
+                    attributes.push("penwidth=3".to_string());
+                }
+                writeln!(
+                    dot,
+                    "    \"{}\" [{}];",
+                    block.reference,
+                    attributes.join(", ")
+                )
+                .unwrap();
+            }
+            writeln!(dot, "  }}").unwrap();
+        }
+
+        for ancestor in &self.missing_ancestors {
+            writeln!(
+                dot,
+                "  \"{ancestor}\" [label=\"missing\\n{ancestor}\", style=dashed, color=red];"
+            )
+            .unwrap();
+        }
+
+        // Ancestors before the range are omitted, unless they are missing.
+        let nodes: BTreeSet<&str> = self
+            .blocks
+            .iter()
+            .map(|b| b.reference.as_str())
+            .chain(self.missing_ancestors.iter().map(String::as_str))
+            .collect();
+        for block in &self.blocks {
+            for ancestor in &block.ancestors {
+                if nodes.contains(ancestor.as_str()) {
+                    writeln!(dot, "  \"{}\" -> \"{}\";", block.reference, ancestor).unwrap();
+                }
+            }
+        }
+
+        for commit in &self.commits {
+            writeln!(
+                dot,
+                "  \"commit_{}\" [label=\"commit {}\\n{}\\n{} blocks\", shape=note, fillcolor=lightblue];",
+                commit.index, commit.index, commit.digest, commit.num_blocks
+            )
+            .unwrap();
+            if nodes.contains(commit.leader.as_str()) {
+                writeln!(
+                    dot,
+                    "  \"commit_{}\" -> \"{}\" [style=bold, color=blue];",
+                    commit.index, commit.leader
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+fn block_label(block: &DagBlock) -> String {
+    // Shortens the digest in the reference, as in the Display format of BlockRef.
+    let reference = match block.reference.split_once(',') {
+        Some((slot, digest)) => format!("{slot},{})", &digest[..4]),
+        None => block.reference.clone(),
+    };
+    let mut label = format!("{reference}\\n{} txs", block.num_transactions);
+    if let Some(index) = block.leader_of {
+        write!(label, "\\nleader of commit {index}").unwrap();
+    } else if let Some(index) = block.committed_by {
+        write!(label, "\\ncommit {index}").unwrap();
+    }
+    if block.equivocating {
+        label.push_str("\\nequivocation");
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block::{BlockDigest, TestBlock},
+        commit::CommitDigest,
+        context::Context,
+        storage::mem_store::MemStore,
+        test_dag_builder::DagBuilder,
+    };
+
+    #[tokio::test]
+    async fn test_snapshot_dag_state() {
+        telemetry_subscribers::init_for_testing();
+        let (context, _) = Context::new_for_test(4);
+        let context = Arc::new(context);
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+
+        let mut dag_builder = DagBuilder::new(context.clone());
+        dag_builder.layers(1..=3).build();
+        dag_builder
+            .layer(4)
+            .authorities(vec![AuthorityIndex::new_for_test(3)])
+            .equivocate(1)
+            .build();
+        dag_builder.persist_all_blocks(dag_state.clone());
+
+        // A block with an ancestor that has never been received.
+        let unknown_ancestor =
+            BlockRef::new(4, AuthorityIndex::new_for_test(2), BlockDigest::default());
+        let mut ancestors = dag_builder
+            .blocks(4..=4)
+            .iter()
+            .map(|b| b.reference())
+            .collect::<Vec<_>>();
+        ancestors.push(unknown_ancestor);
+        let orphan =
+            VerifiedBlock::new_for_test(TestBlock::new(5, 0).set_ancestors(ancestors).build());
+        dag_state.write().accept_block(orphan.clone());
+
+        // Commit the leader of round 2 and flush it, and keep the leader of round 3 in memory.
+        let leader_2 = dag_builder.leader_block(2).unwrap();
+        let commit_1 = TrustedCommit::new_for_test(
+            1,
+            CommitDigest::MIN,
+            0,
+            leader_2.reference(),
+            dag_builder
+                .blocks(1..=2)
+                .iter()
+                .map(|b| b.reference())
+                .collect(),
+        );
+        dag_state.write().add_commit(commit_1.clone());
+        dag_state.write().flush();
+        let leader_3 = dag_builder.leader_block(3).unwrap();
+        let commit_2 = TrustedCommit::new_for_test(
+            2,
+            commit_1.digest(),
+            0,
+            leader_3.reference(),
+            vec![leader_3.reference()],
+        );
+        dag_state.write().add_commit(commit_2);
+
+        let snapshot = LiveDagInspector::new(dag_state.clone(), store.clone())
+            .snapshot(2, 5)
+            .unwrap();
+        assert_eq!(snapshot.last_commit_index, 2);
+        assert_eq!(snapshot.last_commit_round, 3);
+        assert_eq!(snapshot.blocks.len(), 4 + 4 + 5 + 1);
+        assert!(snapshot.blocks.iter().all(|b| (2..=5).contains(&b.round)));
+        assert_eq!(
+            snapshot.commits.iter().map(|c| c.index).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        let block = |reference: BlockRef| {
+            snapshot
+                .blocks
+                .iter()
+                .find(|b| b.reference == format!("{reference:?}"))
+                .unwrap()
+        };
+        assert_eq!(block(leader_2.reference()).leader_of, Some(1));
+        assert_eq!(block(leader_2.reference()).committed_by, Some(1));
+        assert_eq!(block(leader_3.reference()).leader_of, Some(2));
+        assert_eq!(
+            block(dag_builder.blocks(3..=3)[1].reference()).committed_by,
+            None
+        );
+
+        assert_eq!(snapshot.equivocating_slots, vec!["D4".to_string()]);
+        assert_eq!(snapshot.blocks.iter().filter(|b| b.equivocating).count(), 2);
+
+        assert_eq!(
+            snapshot.missing_ancestors,
+            vec![format!("{unknown_ancestor:?}")]
+        );
+        assert_eq!(
+            block(orphan.reference()).missing_ancestors,
+            vec![format!("{unknown_ancestor:?}")]
+        );
+
+        let dot = snapshot.to_dot();
+        assert!(dot.starts_with("digraph dag {"));
+        assert!(dot.contains(&format!("\"commit_1\" -> \"{:?}\"", leader_2.reference())));
+        assert!(dot.contains(&format!(
+            "\"{unknown_ancestor:?}\" [label=\"missing\\n{unknown_ancestor:?}\", style=dashed, color=red];"
+        )));
+
+        let json: serde_json::Value = serde_json::from_str(&snapshot.to_json()).unwrap();
+        assert_eq!(
+            json["blocks"].as_array().unwrap().len(),
+            snapshot.blocks.len()
+        );
+    }
+}
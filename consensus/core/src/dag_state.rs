@@ -18,7 +18,10 @@ use crate::{
         genesis_blocks, BlockAPI, BlockDigest, BlockRef, BlockTimestampMs, Round, Slot,
         VerifiedBlock, GENESIS_ROUND,
     },
-    commit::{CommitAPI as _, CommitDigest, CommitIndex, CommitInfo, CommitVote, TrustedCommit},
+    commit::{
+        load_committed_subdag_from_store, CommitAPI as _, CommitDigest, CommitIndex, CommitInfo,
+        CommitRange, CommitVote, CommittedSubDag, TrustedCommit,
+    },
     context::Context,
     leader_scoring::ReputationScores,
     stake_aggregator::{QuorumThreshold, StakeAggregator},
@@ -71,6 +74,13 @@ pub(crate) struct DagState {
 
     // The number of cached rounds
     cached_rounds: Round,
+
+    // Reputation scores of the last leader schedule update, persisted with commits.
+    reputation_scores: ReputationScores,
+
+    // Committed sub dags since the last leader schedule update, to be scored on the next one.
+    // Only tracked when leader scoring is enabled.
+    unscored_committed_subdags: Vec<CommittedSubDag>,
 }
 
 impl DagState {
@@ -87,15 +97,16 @@ impl DagState {
         let last_commit = store
             .read_last_commit()
             .unwrap_or_else(|e| panic!("Failed to read from storage: {:?}", e));
-        let last_committed_rounds = if let Some(commit) = last_commit.as_ref() {
+        let (last_committed_rounds, reputation_scores) = if let Some(commit) = last_commit.as_ref()
+        {
             let (commit_ref, commit_info) = store
                 .read_last_commit_info()
                 .unwrap_or_else(|e| panic!("Failed to read from storage: {:?}", e))
                 .unwrap_or_else(|| panic!("Last commit info should be available."));
             assert_eq!(commit_ref, commit.reference());
-            commit_info.committed_rounds
+            (commit_info.committed_rounds, commit_info.reputation_scores)
         } else {
-            vec![0; num_authorities]
+            (vec![0; num_authorities], ReputationScores::default())
         };
 
         let mut state = Self {
@@ -112,6 +123,8 @@ impl DagState {
             commits_to_write: vec![],
             store,
             cached_rounds,
+            reputation_scores,
+            unscored_committed_subdags: vec![],
         };
 
         for (i, round) in last_committed_rounds.into_iter().enumerate() {
@@ -128,6 +141,24 @@ impl DagState {
             }
         }
 
+        if state
+            .context
+            .protocol_config
+            .mysticeti_leader_scoring_and_schedule()
+        {
+            // Commits after the range of the last reputation scores have not been scored yet.
+            let unscored_commits = state
+                .store
+                .scan_commits(CommitRange::new(
+                    state.reputation_scores.commit_range.end() + 1..state.last_commit_index() + 1,
+                ))
+                .unwrap_or_else(|e| panic!("Failed to read from storage: {:?}", e));
+            state.unscored_committed_subdags = unscored_commits
+                .into_iter()
+                .map(|commit| load_committed_subdag_from_store(state.store.as_ref(), commit))
+                .collect();
+        }
+
         state
     }
 
@@ -379,6 +410,30 @@ impl DagState {
         blocks
     }
 
+    /// Returns cached recent blocks of all authorities, with rounds within
+    /// [`start_round`, `end_round`], in round ascending order.
+    pub(crate) fn get_cached_blocks_in_rounds(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> Vec<VerifiedBlock> {
+        self.recent_blocks
+            .range((
+                Included(BlockRef::new(
+                    start_round,
+                    AuthorityIndex::MIN,
+                    BlockDigest::MIN,
+                )),
+                Included(BlockRef::new(
+                    end_round,
+                    AuthorityIndex::MAX,
+                    BlockDigest::MAX,
+                )),
+            ))
+            .map(|(_, block)| block.clone())
+            .collect()
+    }
+
     /// Returns the last block proposed per authority with `round < end_round`.
     /// The method is guaranteed to return results only when the `end_round` is not earlier of the
     /// available cached data for each authority, otherwise the method will panic - it's the caller's
@@ -607,6 +662,17 @@ impl DagState {
         }
     }
 
+    /// Returns the lowest round of blocks in commits not yet scored by a leader schedule update.
+    /// Unscored commits are loaded from storage after restarts, so they must not be garbage
+    /// collected.
+    pub(crate) fn lowest_unscored_round(&self) -> Option<Round> {
+        self.unscored_committed_subdags
+            .iter()
+            .flat_map(|subdag| subdag.blocks.iter())
+            .map(|block| block.round())
+            .min()
+    }
+
     /// Highest round where a block is committed, which is last commit's leader round.
     pub(crate) fn last_commit_round(&self) -> Round {
         match &self.last_commit {
@@ -620,6 +686,40 @@ impl DagState {
         self.last_committed_rounds.clone()
     }
 
+    /// Adds a committed sub dag to be scored on the next leader schedule update. Does nothing
+    /// when leader scoring is disabled.
+    pub(crate) fn add_unscored_committed_subdag(&mut self, subdag: CommittedSubDag) {
+        if self
+            .context
+            .protocol_config
+            .mysticeti_leader_scoring_and_schedule()
+        {
+            self.unscored_committed_subdags.push(subdag);
+        }
+    }
+
+    pub(crate) fn unscored_committed_subdags_count(&self) -> u64 {
+        self.unscored_committed_subdags.len() as u64
+    }
+
+    pub(crate) fn take_unscored_committed_subdags(&mut self) -> Vec<CommittedSubDag> {
+        std::mem::take(&mut self.unscored_committed_subdags)
+    }
+
+    /// Reputation scores of the last leader schedule update.
+    pub(crate) fn reputation_scores(&self) -> ReputationScores {
+        self.reputation_scores.clone()
+    }
+
+    pub(crate) fn set_reputation_scores(&mut self, reputation_scores: ReputationScores) {
+        self.reputation_scores = reputation_scores;
+    }
+
+    /// Returns the commits buffered in memory, which have not been flushed to storage yet.
+    pub(crate) fn unflushed_commits(&self) -> Vec<TrustedCommit> {
+        self.commits_to_write.clone()
+    }
+
     /// After each flush, DagState becomes persisted in storage and it expected to recover
     /// all internal states from storage after restarts.
     pub(crate) fn flush(&mut self) {
@@ -647,10 +747,9 @@ impl DagState {
             None
         } else {
             let last_commit_ref = commits.last().as_ref().unwrap().reference();
-            // TODO: Replace this with calculated reputation scores
             let commit_info = CommitInfo::new(
                 self.last_committed_rounds.clone(),
-                ReputationScores::default(),
+                self.reputation_scores.clone(),
             );
             Some((last_commit_ref, commit_info))
         };
@@ -1259,7 +1358,7 @@ mod test {
                 CommitDigest::MIN,
                 0,
                 blocks.last().unwrap().reference(),
-                vec![],
+                vec![blocks.last().unwrap().reference()],
             ));
         }
 
@@ -1333,6 +1432,9 @@ mod test {
 
         // Last commit index should be 5.
         assert_eq!(dag_state.last_commit_index(), 5);
+
+        // No leader schedule update has been made, so all flushed commits are unscored.
+        assert_eq!(dag_state.unscored_committed_subdags_count(), 5);
     }
 
     #[test]
@@ -100,6 +100,12 @@ pub enum ConsensusError {
     #[error("Invalid transaction: {0}")]
     InvalidTransaction(String),
 
+    #[error("Transaction size {size} exceeds limit {limit}")]
+    TransactionTooLarge { size: u64, limit: u64 },
+
+    #[error("Total transactions size {size} in block exceeds limit {limit}")]
+    TransactionsTooLarge { size: u64, limit: u64 },
+
     #[error("Ancestors max timestamp {max_timestamp_ms} > block timestamp {block_timestamp_ms}")]
     InvalidBlockTimestamp {
         max_timestamp_ms: u64,
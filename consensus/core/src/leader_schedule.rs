@@ -11,7 +11,15 @@ use consensus_config::{AuthorityIndex, Stake};
 use parking_lot::RwLock;
 use rand::{prelude::SliceRandom, rngs::StdRng, SeedableRng};
 
-use crate::{commit::CommitRange, context::Context, leader_scoring::ReputationScores, Round};
+use crate::{
+    commit::CommitRange,
+    context::Context,
+    dag_state::DagState,
+    leader_scoring::{
+        scoring_strategy, ReputationScoreCalculator, ReputationScores, ScoringStrategy,
+    },
+    Round,
+};
 
 /// The `LeaderSchedule` is responsible for producing the leader schedule across
 /// an epoch. The leader schedule is subject to change periodically based on
@@ -19,26 +27,105 @@ use crate::{commit::CommitRange, context::Context, leader_scoring::ReputationSco
 #[derive(Clone)]
 pub(crate) struct LeaderSchedule {
     context: Arc<Context>,
-    #[allow(unused)]
     num_commits_per_schedule: u64,
+    scoring_strategy: Arc<dyn ScoringStrategy>,
     leader_swap_table: Arc<RwLock<LeaderSwapTable>>,
 }
 
 #[allow(unused)]
 impl LeaderSchedule {
     /// The window where the schedule change takes place in consensus. It represents
-    /// number of committed sub dags.
-    /// TODO: move this to protocol config
+    /// number of committed sub dags. Used with protocol versions which do not set
+    /// `consensus_commits_per_schedule`.
     const CONSENSUS_COMMITS_PER_SCHEDULE: u64 = 300;
 
     pub(crate) fn new(context: Arc<Context>, leader_swap_table: LeaderSwapTable) -> Self {
+        let num_commits_per_schedule = context
+            .protocol_config
+            .consensus_commits_per_schedule_as_option()
+            .unwrap_or(Self::CONSENSUS_COMMITS_PER_SCHEDULE);
+        assert!(num_commits_per_schedule > 0);
+        let scoring_strategy =
+            scoring_strategy(context.protocol_config.consensus_leader_scoring_strategy());
         Self {
             context,
-            num_commits_per_schedule: Self::CONSENSUS_COMMITS_PER_SCHEDULE,
+            num_commits_per_schedule,
+            scoring_strategy,
             leader_swap_table: Arc::new(RwLock::new(leader_swap_table)),
         }
     }
 
+    /// Restores the schedule from the reputation scores of the last commit in storage.
+    pub(crate) fn from_store(context: Arc<Context>, dag_state: Arc<RwLock<DagState>>) -> Self {
+        let reputation_scores = dag_state.read().reputation_scores();
+        let leader_swap_table = if reputation_scores.scores_per_authority.is_empty() {
+            LeaderSwapTable::default()
+        } else {
+            LeaderSwapTable::new(
+                context.clone(),
+                reputation_scores,
+                context
+                    .protocol_config
+                    .consensus_bad_nodes_stake_threshold(),
+            )
+        };
+        tracing::info!("Restored {leader_swap_table:?}");
+        Self::new(context, leader_swap_table)
+    }
+
+    /// Returns the number of commits until the leader schedule has to be updated. All
+    /// authorities update the schedule after the same commit, so leaders of later commits
+    /// must be decided only after the update.
+    pub(crate) fn commits_until_leader_schedule_update(
+        &self,
+        dag_state: Arc<RwLock<DagState>>,
+    ) -> usize {
+        let unscored_subdags_count = dag_state.read().unscored_committed_subdags_count();
+        assert!(
+            unscored_subdags_count <= self.num_commits_per_schedule,
+            "Unscored committed subdags count {unscored_subdags_count} exceeds the number of commits per schedule {}",
+            self.num_commits_per_schedule,
+        );
+        (self.num_commits_per_schedule - unscored_subdags_count) as usize
+    }
+
+    /// Scores the authorities on the committed sub dags since the last update, and swaps
+    /// the leaders with the worst scores from now on.
+    pub(crate) fn update_leader_schedule(&self, dag_state: Arc<RwLock<DagState>>) {
+        let _s = self
+            .context
+            .metrics
+            .node_metrics
+            .scope_processing_time
+            .with_label_values(&["LeaderSchedule::update_leader_schedule"])
+            .start_timer();
+        let mut dag_state = dag_state.write();
+        let unscored_subdags = dag_state.take_unscored_committed_subdags();
+        let reputation_scores = ReputationScoreCalculator::new(
+            self.context.clone(),
+            &unscored_subdags,
+            self.scoring_strategy.as_ref(),
+        )
+        .calculate();
+        reputation_scores.update_metrics(self.context.clone());
+        // Persisted with the next commit, to restore the schedule after restarts.
+        dag_state.set_reputation_scores(reputation_scores.clone());
+        drop(dag_state);
+
+        self.update_leader_swap_table(LeaderSwapTable::new(
+            self.context.clone(),
+            reputation_scores,
+            self.context
+                .protocol_config
+                .consensus_bad_nodes_stake_threshold(),
+        ));
+        self.context
+            .metrics
+            .node_metrics
+            .num_of_leader_schedule_updates
+            .inc();
+    }
+
     pub(crate) fn elect_leader(&self, round: u32, leader_offset: u32) -> AuthorityIndex {
         cfg_if::cfg_if! {
             // TODO: we need to differentiate the leader strategy in tests, so for
@@ -300,7 +387,10 @@ impl Debug for LeaderSwapTable {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::commit::CommitRange;
+    use crate::{
+        commit::CommitRange, linearizer::Linearizer, storage::mem_store::MemStore,
+        test_dag_builder::DagBuilder,
+    };
 
     #[test]
     fn test_elect_leader() {
@@ -526,4 +616,72 @@ mod tests {
         // Update leader from old swap table to new invalid swap table
         leader_schedule.update_leader_swap_table(leader_swap_table.clone());
     }
+
+    #[test]
+    fn test_update_leader_schedule() {
+        telemetry_subscribers::init_for_testing();
+        let mut context = Context::new_for_test(4).0;
+        context
+            .protocol_config
+            .set_consensus_commits_per_schedule(10);
+        context
+            .protocol_config
+            .set_consensus_bad_nodes_stake_threshold(33);
+        let context = Arc::new(context);
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        let leader_schedule = LeaderSchedule::from_store(context.clone(), dag_state.clone());
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state.clone()),
+            10
+        );
+
+        // Commit the leaders of rounds 1 ~ 10 in a fully connected DAG.
+        let mut dag_builder = DagBuilder::new(context.clone());
+        dag_builder
+            .layers(1..=11)
+            .build()
+            .persist_layers(dag_state.clone());
+        let mut linearizer = Linearizer::new(dag_state.clone());
+        let leaders = dag_builder
+            .leader_blocks(1..=11)
+            .into_iter()
+            .map(Option::unwrap)
+            .collect::<Vec<_>>();
+        linearizer.handle_commit(leaders[..10].to_vec());
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state.clone()),
+            0
+        );
+
+        // Votes for the leader of round 9 in round 10 are committed only for the leader of
+        // round 10, and votes for the leader of round 10 are not committed yet.
+        leader_schedule.update_leader_schedule(dag_state.clone());
+        let reputation_scores = dag_state.read().reputation_scores();
+        assert_eq!(reputation_scores.commit_range, CommitRange::new(1..10));
+        assert_eq!(reputation_scores.scores_per_authority, vec![8, 8, 9, 8]);
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state.clone()),
+            10
+        );
+        assert_eq!(
+            leader_schedule.elect_leader(4, 0),
+            AuthorityIndex::new_for_test(2)
+        );
+
+        // The scores are persisted with the next commit, and the schedule is restored from them.
+        linearizer.handle_commit(vec![leaders[10].clone()]);
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store)));
+        assert_eq!(dag_state.read().reputation_scores(), reputation_scores);
+        assert_eq!(dag_state.read().unscored_committed_subdags_count(), 1);
+        let leader_schedule = LeaderSchedule::from_store(context.clone(), dag_state.clone());
+        assert_eq!(
+            leader_schedule.elect_leader(4, 0),
+            AuthorityIndex::new_for_test(2)
+        );
+        assert_eq!(
+            leader_schedule.commits_until_leader_schedule_update(dag_state),
+            9
+        );
+    }
 }
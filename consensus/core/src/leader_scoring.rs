@@ -1,12 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{cmp::Ordering, fmt::Debug, sync::Arc};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    sync::Arc,
+};
 
 use consensus_config::AuthorityIndex;
 use serde::{Deserialize, Serialize};
+use sui_protocol_config::ConsensusLeaderScoringStrategy;
 
-use crate::{commit::CommitRange, context::Context};
+use crate::{
+    block::{BlockAPI, BlockRef, VerifiedBlock},
+    commit::{CommitRange, CommittedSubDag},
+    context::Context,
+    stake_aggregator::{QuorumThreshold, StakeAggregator},
+    Round,
+};
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub(crate) struct ReputationScores {
@@ -75,9 +87,178 @@ impl ReputationScores {
     }
 }
 
+/// Calculates the `ReputationScores` of the authorities from a window of committed sub dags,
+/// with the scoring strategy configured in the protocol config.
+pub(crate) struct ReputationScoreCalculator<'a> {
+    context: Arc<Context>,
+    unscored_subdags: &'a [CommittedSubDag],
+    scoring_strategy: &'a dyn ScoringStrategy,
+    // All blocks of the unscored sub dags, by round.
+    blocks_by_round: BTreeMap<Round, Vec<VerifiedBlock>>,
+}
+
+impl<'a> ReputationScoreCalculator<'a> {
+    pub(crate) fn new(
+        context: Arc<Context>,
+        unscored_subdags: &'a [CommittedSubDag],
+        scoring_strategy: &'a dyn ScoringStrategy,
+    ) -> Self {
+        assert!(
+            !unscored_subdags.is_empty(),
+            "Attempted to calculate scores with no unscored sub dags"
+        );
+        let mut blocks_by_round = BTreeMap::<Round, Vec<VerifiedBlock>>::new();
+        for block in unscored_subdags
+            .iter()
+            .flat_map(|subdag| subdag.blocks.iter())
+        {
+            blocks_by_round
+                .entry(block.round())
+                .or_default()
+                .push(block.clone());
+        }
+        Self {
+            context,
+            unscored_subdags,
+            scoring_strategy,
+            blocks_by_round,
+        }
+    }
+
+    pub(crate) fn calculate(&self) -> ReputationScores {
+        let _s = self
+            .context
+            .metrics
+            .node_metrics
+            .scope_processing_time
+            .with_label_values(&["ReputationScoreCalculator::calculate"])
+            .start_timer();
+        let mut scores_per_authority = vec![0_u64; self.context.committee.size()];
+        for subdag in self.unscored_subdags {
+            self.scoring_strategy
+                .add_scores(self, subdag, &mut scores_per_authority);
+        }
+        let commit_range = CommitRange::new(
+            self.unscored_subdags.first().unwrap().commit_index
+                ..self.unscored_subdags.last().unwrap().commit_index,
+        );
+        ReputationScores::new(commit_range, scores_per_authority)
+    }
+
+    /// Returns the committed blocks of the round, within the scored window.
+    fn blocks_at_round(&self, round: Round) -> &[VerifiedBlock] {
+        self.blocks_by_round
+            .get(&round)
+            .map(|blocks| blocks.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the committed blocks of the round which include `block_ref` as an ancestor.
+    fn blocks_including(
+        &self,
+        round: Round,
+        block_ref: BlockRef,
+    ) -> impl Iterator<Item = &VerifiedBlock> {
+        self.blocks_at_round(round)
+            .iter()
+            .filter(move |block| block.ancestors().contains(&block_ref))
+    }
+}
+
+/// A strategy for scoring authorities from committed sub dags. Scores are only derived from
+/// committed blocks, so all authorities calculate the same scores and agree on the leader
+/// schedule. For the same reason local measurements, like the peer latencies observed by the
+/// connection monitor, cannot be used for scoring. Instead, the `Timeliness` strategy uses the
+/// ancestors of committed blocks as a proxy for how fast the blocks of an authority propagate.
+pub(crate) trait ScoringStrategy: Send + Sync {
+    /// Adds the scores earned by the authorities in `subdag`.
+    fn add_scores(
+        &self,
+        calculator: &ReputationScoreCalculator,
+        subdag: &CommittedSubDag,
+        scores: &mut [u64],
+    );
+}
+
+pub(crate) fn scoring_strategy(
+    strategy: ConsensusLeaderScoringStrategy,
+) -> Arc<dyn ScoringStrategy> {
+    match strategy {
+        ConsensusLeaderScoringStrategy::Vote => Arc::new(VoteScoringStrategy),
+        ConsensusLeaderScoringStrategy::CertifiedVote => Arc::new(CertifiedVoteScoringStrategy),
+        ConsensusLeaderScoringStrategy::Timeliness => Arc::new(TimelinessScoringStrategy),
+    }
+}
+
+/// Scores an authority for each block voting for the leader of a committed sub dag, in the round
+/// after the leader.
+pub(crate) struct VoteScoringStrategy;
+
+impl ScoringStrategy for VoteScoringStrategy {
+    fn add_scores(
+        &self,
+        calculator: &ReputationScoreCalculator,
+        subdag: &CommittedSubDag,
+        scores: &mut [u64],
+    ) {
+        let leader = subdag.leader;
+        for vote in calculator.blocks_including(leader.round + 1, leader) {
+            scores[vote.author().value()] += 1;
+        }
+    }
+}
+
+/// Scores an authority for each vote for the leader of a committed sub dag, which is included
+/// by a quorum of blocks in the round after the votes. Votes which are proposed too late to be
+/// included by the next round do not count, unlike with `VoteScoringStrategy`.
+pub(crate) struct CertifiedVoteScoringStrategy;
+
+impl ScoringStrategy for CertifiedVoteScoringStrategy {
+    fn add_scores(
+        &self,
+        calculator: &ReputationScoreCalculator,
+        subdag: &CommittedSubDag,
+        scores: &mut [u64],
+    ) {
+        let leader = subdag.leader;
+        for vote in calculator.blocks_including(leader.round + 1, leader) {
+            let mut certificate = StakeAggregator::<QuorumThreshold>::new();
+            for block in calculator.blocks_including(leader.round + 2, vote.reference()) {
+                certificate.add(block.author(), &calculator.context.committee);
+            }
+            if certificate.reached_threshold(&calculator.context.committee) {
+                scores[vote.author().value()] += 1;
+            }
+        }
+    }
+}
+
+/// Scores an authority for each committed block of the sub dag, by the number of authorities
+/// which include the block as an ancestor in the next round. Blocks of slow authorities arrive
+/// after their peers have moved on to the next round, so they are rarely included.
+pub(crate) struct TimelinessScoringStrategy;
+
+impl ScoringStrategy for TimelinessScoringStrategy {
+    fn add_scores(
+        &self,
+        calculator: &ReputationScoreCalculator,
+        subdag: &CommittedSubDag,
+        scores: &mut [u64],
+    ) {
+        for block in &subdag.blocks {
+            let authors = calculator
+                .blocks_including(block.round() + 1, block.reference())
+                .map(|b| b.author())
+                .collect::<BTreeSet<_>>();
+            scores[block.author().value()] += authors.len() as u64;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::block::{BlockTimestampMs, TestBlock};
 
     #[test]
     fn test_reputation_scores_authorities_by_score_desc() {
@@ -130,4 +311,68 @@ mod tests {
             3
         );
     }
+
+    /// Builds 5 rounds where the blocks of authority 3 are too late to be included by the other
+    /// authorities, and one committed sub dag per round led by the round robin leader.
+    fn late_authority_subdags(context: &Context) -> Vec<CommittedSubDag> {
+        let mut ancestors = (0..4)
+            .map(|author| {
+                BlockRef::new(0, AuthorityIndex::new_for_test(author), Default::default())
+            })
+            .collect::<Vec<_>>();
+        let mut subdags = vec![];
+        for round in 1..=5 {
+            let blocks = (0..4)
+                .map(|author| {
+                    let ancestors = ancestors
+                        .iter()
+                        .filter(|ancestor| author == 3 || ancestor.author.value() != 3)
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    VerifiedBlock::new_for_test(
+                        TestBlock::new(round, author)
+                            .set_ancestors(ancestors)
+                            .set_timestamp_ms(round as BlockTimestampMs * 1000)
+                            .build(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            ancestors = blocks.iter().map(|b| b.reference()).collect();
+            let leader = blocks[round as usize % context.committee.size()].reference();
+            subdags.push(CommittedSubDag::new(
+                leader,
+                blocks,
+                round as BlockTimestampMs * 1000,
+                round,
+            ));
+        }
+        subdags
+    }
+
+    #[test]
+    fn test_reputation_score_calculator() {
+        telemetry_subscribers::init_for_testing();
+        let context = Arc::new(Context::new_for_test(4).0);
+        let subdags = late_authority_subdags(&context);
+
+        let cases = [
+            (ConsensusLeaderScoringStrategy::Vote, vec![3, 3, 3, 4]),
+            (
+                ConsensusLeaderScoringStrategy::CertifiedVote,
+                vec![2, 2, 2, 0],
+            ),
+            (
+                ConsensusLeaderScoringStrategy::Timeliness,
+                vec![16, 16, 16, 4],
+            ),
+        ];
+        for (strategy, expected_scores) in cases {
+            let strategy = scoring_strategy(strategy);
+            let scores =
+                ReputationScoreCalculator::new(context.clone(), &subdags, strategy.as_ref())
+                    .calculate();
+            assert_eq!(scores.commit_range, CommitRange::new(1..5));
+            assert_eq!(scores.scores_per_authority, expected_scores);
+        }
+    }
 }
@@ -14,6 +14,7 @@ mod commit_syncer;
 mod context;
 mod core;
 mod core_thread;
+mod dag_inspector;
 mod dag_state;
 mod error;
 mod leader_schedule;
@@ -24,12 +25,17 @@ mod metrics;
 mod network;
 mod stake_aggregator;
 mod storage;
+mod store_gc;
 mod subscriber;
 mod synchronizer;
 mod threshold_clock;
 mod transaction;
 mod universal_committer;
 
+#[cfg(test)]
+mod byzantine_authority;
+#[cfg(test)]
+mod simulator;
 #[cfg(test)]
 mod test_dag;
 #[cfg(test)]
@@ -39,5 +45,9 @@ mod test_dag_parser;
 
 pub use authority_node::ConsensusAuthority;
 pub use block::{BlockAPI, Round};
-pub use commit::{CommitConsumer, CommitIndex, CommittedSubDag};
+pub use commit::{CommitConsumer, CommitConsumerMonitor, CommitIndex, CommittedSubDag};
+pub use dag_inspector::{
+    DagBlock, DagCommit, DagExportFormat, DagInspector, DagSnapshot, LiveDagInspector,
+};
+pub use error::{ConsensusError, ConsensusResult};
 pub use transaction::{TransactionClient, TransactionVerifier, ValidationError};
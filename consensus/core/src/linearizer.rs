@@ -127,7 +127,10 @@ impl Linearizer {
 
             // Buffer commit in dag state for persistence later.
             // This also updates the last committed rounds.
-            self.dag_state.write().add_commit(commit.clone());
+            let mut dag_state = self.dag_state.write();
+            dag_state.add_commit(commit.clone());
+            dag_state.add_unscored_committed_subdag(sub_dag.clone());
+            drop(dag_state);
 
             committed_sub_dags.push(sub_dag);
         }
@@ -198,6 +201,12 @@ mod tests {
             }
             assert_eq!(subdag.commit_index, idx as CommitIndex + 1);
         }
+
+        // All commits are scored on the next leader schedule update.
+        assert_eq!(
+            dag_state.read().unscored_committed_subdags_count(),
+            num_rounds as u64
+        );
     }
 
     #[test]
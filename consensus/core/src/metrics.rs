@@ -96,6 +96,9 @@ pub(crate) struct NodeMetrics {
     pub(crate) dag_state_recent_refs: IntGauge,
     pub(crate) dag_state_store_read_count: IntCounterVec,
     pub(crate) dag_state_store_write_count: IntCounter,
+    pub(crate) dag_state_store_gc_count: IntCounter,
+    pub(crate) dag_state_store_gc_round: IntGauge,
+    pub(crate) dag_state_store_gc_commit_index: IntGauge,
     pub(crate) fetch_blocks_scheduler_inflight: IntGauge,
     pub(crate) fetched_blocks: IntCounterVec,
     pub(crate) invalid_blocks: IntCounterVec,
@@ -109,6 +112,7 @@ pub(crate) struct NodeMetrics {
     pub(crate) leader_timeout_total: IntCounterVec,
     pub(crate) missing_blocks_total: IntCounter,
     pub(crate) missing_blocks_after_fetch_total: IntCounter,
+    pub(crate) num_of_leader_schedule_updates: IntCounter,
     pub(crate) quorum_receive_latency: Histogram,
     pub(crate) reputation_scores: IntGaugeVec,
     pub(crate) scope_processing_time: HistogramVec,
@@ -234,6 +238,21 @@ impl NodeMetrics {
                 "Number of times DagState needs to write to store",
                 registry,
             ).unwrap(),
+            dag_state_store_gc_count: register_int_counter_with_registry!(
+                "dag_state_store_gc_count",
+                "Number of times old blocks and commits were garbage collected from store",
+                registry,
+            ).unwrap(),
+            dag_state_store_gc_round: register_int_gauge_with_registry!(
+                "dag_state_store_gc_round",
+                "The lowest round of blocks remaining in store after garbage collection",
+                registry,
+            ).unwrap(),
+            dag_state_store_gc_commit_index: register_int_gauge_with_registry!(
+                "dag_state_store_gc_commit_index",
+                "The lowest index of commits remaining in store after garbage collection",
+                registry,
+            ).unwrap(),
             fetch_blocks_scheduler_inflight: register_int_gauge_with_registry!(
                 "fetch_blocks_scheduler_inflight",
                 "Designates whether the synchronizer scheduler task to fetch blocks is currently running",
@@ -308,6 +327,11 @@ impl NodeMetrics {
                 "Total number of missing blocks after fetching blocks from peer",
                 registry,
             ).unwrap(),
+            num_of_leader_schedule_updates: register_int_counter_with_registry!(
+                "num_of_leader_schedule_updates",
+                "Total number of leader schedule updates, from the reputation scores of authorities",
+                registry,
+            ).unwrap(),
             quorum_receive_latency: register_histogram_with_registry!(
                 "quorum_receive_latency",
                 "The time it took to receive a new round quorum of blocks",
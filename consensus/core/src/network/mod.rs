@@ -45,6 +45,8 @@ pub(crate) mod connection_monitor;
 pub(crate) mod epoch_filter;
 pub(crate) mod metrics;
 #[cfg(test)]
+pub(crate) mod simulated_network;
+#[cfg(test)]
 pub(crate) mod test_network;
 pub(crate) mod tonic_network;
 
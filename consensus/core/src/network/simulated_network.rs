@@ -0,0 +1,349 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use consensus_config::{AuthorityIndex, NetworkKeyPair};
+use parking_lot::Mutex;
+use rand::{rngs::StdRng, Rng as _, SeedableRng as _};
+use tokio::time::sleep;
+
+use super::{BlockStream, NetworkClient, NetworkManager, NetworkService};
+use crate::{
+    block::{BlockRef, VerifiedBlock},
+    byzantine_authority::ByzantineAuthority,
+    context::Context,
+    error::{ConsensusError, ConsensusResult},
+    CommitIndex, Round,
+};
+
+/// An in-memory network connecting authorities running in the same process. Message latency,
+/// drops and partitions are programmable, and all random decisions are drawn from a seeded rng.
+/// Together with clocks driven by tokio time, as set up by the `Simulator`, a run is reproducible
+/// under a current thread runtime with paused time.
+///
+/// Messages to a crashed authority fail immediately. Messages which are dropped, or cross a
+/// partition, fail after the request timeout.
+#[derive(Clone)]
+pub(crate) struct SimulatedNetwork {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    services: BTreeMap<AuthorityIndex, Arc<dyn NetworkService>>,
+    latency: Duration,
+    jitter: Duration,
+    link_latencies: BTreeMap<(AuthorityIndex, AuthorityIndex), Duration>,
+    drop_rate: f64,
+    // Authorities in different groups cannot reach each other. Authorities in no group are
+    // isolated.
+    partitions: Option<Vec<BTreeSet<AuthorityIndex>>>,
+    byzantine: BTreeMap<AuthorityIndex, Arc<ByzantineAuthority>>,
+    rng: StdRng,
+    stats: SimulatedNetworkStats,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SimulatedNetworkStats {
+    pub(crate) delivered: u64,
+    pub(crate) dropped: u64,
+    pub(crate) partitioned: u64,
+    pub(crate) disconnected: u64,
+}
+
+impl SimulatedNetwork {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                services: BTreeMap::new(),
+                latency: Duration::ZERO,
+                jitter: Duration::ZERO,
+                link_latencies: BTreeMap::new(),
+                drop_rate: 0.0,
+                partitions: None,
+                byzantine: BTreeMap::new(),
+                rng: StdRng::seed_from_u64(seed),
+                stats: SimulatedNetworkStats::default(),
+            })),
+        }
+    }
+
+    /// Returns the network manager of an authority, to start it with.
+    pub(crate) fn manager(&self, context: Arc<Context>) -> SimulatedNetworkManager {
+        let client = Arc::new(SimulatedNetworkClient {
+            own_index: context.own_index,
+            network: self.clone(),
+        });
+        SimulatedNetworkManager {
+            context,
+            network: self.clone(),
+            client,
+        }
+    }
+
+    /// Sets the one way latency of all links, with a uniformly random jitter added per message.
+    pub(crate) fn set_latency(&self, latency: Duration, jitter: Duration) {
+        let mut inner = self.inner.lock();
+        inner.latency = latency;
+        inner.jitter = jitter;
+    }
+
+    /// Overrides the one way latency from `from` to `to`.
+    pub(crate) fn set_link_latency(
+        &self,
+        from: AuthorityIndex,
+        to: AuthorityIndex,
+        latency: Duration,
+    ) {
+        self.inner.lock().link_latencies.insert((from, to), latency);
+    }
+
+    /// Sets the probability of dropping each request or response.
+    pub(crate) fn set_drop_rate(&self, drop_rate: f64) {
+        assert!((0.0..=1.0).contains(&drop_rate));
+        self.inner.lock().drop_rate = drop_rate;
+    }
+
+    /// Splits the authorities into groups which cannot reach each other.
+    pub(crate) fn partition(&self, groups: Vec<Vec<AuthorityIndex>>) {
+        self.inner.lock().partitions = Some(
+            groups
+                .into_iter()
+                .map(|group| group.into_iter().collect())
+                .collect(),
+        );
+    }
+
+    /// Removes all partitions.
+    pub(crate) fn heal(&self) {
+        self.inner.lock().partitions = None;
+    }
+
+    /// Makes `authority` misbehave when sending its blocks or serving fetches.
+    pub(crate) fn set_byzantine(&self, authority: AuthorityIndex, byzantine: ByzantineAuthority) {
+        self.inner
+            .lock()
+            .byzantine
+            .insert(authority, Arc::new(byzantine));
+    }
+
+    pub(crate) fn stats(&self) -> SimulatedNetworkStats {
+        self.inner.lock().stats
+    }
+
+    fn byzantine(&self, authority: AuthorityIndex) -> Option<Arc<ByzantineAuthority>> {
+        self.inner.lock().byzantine.get(&authority).cloned()
+    }
+
+    /// Sends a request from `from` to `to`, and waits for the response of `handler`.
+    async fn request<T, F, Fut>(
+        &self,
+        from: AuthorityIndex,
+        to: AuthorityIndex,
+        timeout: Duration,
+        handler: F,
+    ) -> ConsensusResult<T>
+    where
+        F: FnOnce(Arc<dyn NetworkService>) -> Fut,
+        Fut: Future<Output = ConsensusResult<T>>,
+    {
+        let Some((request_delay, response_delay)) = self.route(from, to)? else {
+            sleep(timeout).await;
+            return Err(ConsensusError::NetworkRequestTimeout(format!(
+                "request from {from} to {to} was lost"
+            )));
+        };
+
+        tokio::time::timeout(timeout, async {
+            sleep(request_delay).await;
+            // The peer can crash while the request is in flight.
+            let service = self
+                .inner
+                .lock()
+                .services
+                .get(&to)
+                .cloned()
+                .ok_or_else(|| ConsensusError::PeerDisconnected(to.to_string()))?;
+            let response = handler(service).await?;
+            sleep(response_delay).await;
+            Ok(response)
+        })
+        .await
+        .map_err(|_| {
+            ConsensusError::NetworkRequestTimeout(format!("request from {from} to {to} timed out"))
+        })?
+    }
+
+    /// Decides the fate of a request and its response. Returns their delays, or None when
+    /// either is lost.
+    fn route(
+        &self,
+        from: AuthorityIndex,
+        to: AuthorityIndex,
+    ) -> ConsensusResult<Option<(Duration, Duration)>> {
+        let mut inner = self.inner.lock();
+        if !inner.services.contains_key(&to) {
+            inner.stats.disconnected += 1;
+            return Err(ConsensusError::PeerDisconnected(to.to_string()));
+        }
+        let partitioned = inner.partitions.as_ref().is_some_and(|partitions| {
+            !partitions
+                .iter()
+                .any(|group| group.contains(&from) && group.contains(&to))
+        });
+        if partitioned {
+            inner.stats.partitioned += 1;
+            return Ok(None);
+        }
+        let drop_rate = inner.drop_rate;
+        if drop_rate > 0.0 && (0..2).any(|_| inner.rng.gen_bool(drop_rate)) {
+            inner.stats.dropped += 1;
+            return Ok(None);
+        }
+        inner.stats.delivered += 1;
+        Ok(Some((inner.delay(from, to), inner.delay(to, from))))
+    }
+}
+
+impl Inner {
+    fn delay(&mut self, from: AuthorityIndex, to: AuthorityIndex) -> Duration {
+        let latency = self
+            .link_latencies
+            .get(&(from, to))
+            .copied()
+            .unwrap_or(self.latency);
+        if self.jitter.is_zero() {
+            latency
+        } else {
+            latency + self.jitter.mul_f64(self.rng.gen::<f64>())
+        }
+    }
+}
+
+pub(crate) struct SimulatedNetworkClient {
+    own_index: AuthorityIndex,
+    network: SimulatedNetwork,
+}
+
+#[async_trait]
+impl NetworkClient for SimulatedNetworkClient {
+    const SUPPORT_STREAMING: bool = false;
+
+    async fn send_block(
+        &self,
+        peer: AuthorityIndex,
+        block: &VerifiedBlock,
+        timeout: Duration,
+    ) -> ConsensusResult<()> {
+        let serialized_block = match self.network.byzantine(self.own_index) {
+            Some(byzantine) if !byzantine.serves(peer) => return Ok(()),
+            Some(byzantine) => byzantine.tamper(peer, block)?,
+            None => block.serialized().clone(),
+        };
+        self.network
+            .request(self.own_index, peer, timeout, |service| async move {
+                service
+                    .handle_send_block(self.own_index, serialized_block)
+                    .await
+            })
+            .await
+    }
+
+    async fn subscribe_blocks(
+        &self,
+        _peer: AuthorityIndex,
+        _last_received: Round,
+        _timeout: Duration,
+    ) -> ConsensusResult<BlockStream> {
+        Err(ConsensusError::NetworkError(
+            "block streaming is not supported by the simulated network".to_string(),
+        ))
+    }
+
+    async fn fetch_blocks(
+        &self,
+        peer: AuthorityIndex,
+        block_refs: Vec<BlockRef>,
+        highest_accepted_rounds: Vec<Round>,
+        timeout: Duration,
+    ) -> ConsensusResult<Vec<Bytes>> {
+        if let Some(byzantine) = self.network.byzantine(peer) {
+            if !byzantine.serves(self.own_index) {
+                return Ok(vec![]);
+            }
+        }
+        self.network
+            .request(self.own_index, peer, timeout, |service| async move {
+                service
+                    .handle_fetch_blocks(self.own_index, block_refs, highest_accepted_rounds)
+                    .await
+            })
+            .await
+    }
+
+    async fn fetch_commits(
+        &self,
+        peer: AuthorityIndex,
+        start: CommitIndex,
+        end: CommitIndex,
+        timeout: Duration,
+    ) -> ConsensusResult<(Vec<Bytes>, Vec<Bytes>)> {
+        let (commits, certifier_blocks) = self
+            .network
+            .request(self.own_index, peer, timeout, |service| async move {
+                service
+                    .handle_fetch_commits(self.own_index, start, end)
+                    .await
+            })
+            .await?;
+        Ok((
+            commits.iter().map(|c| c.serialized().clone()).collect(),
+            certifier_blocks
+                .iter()
+                .map(|b| b.serialized().clone())
+                .collect(),
+        ))
+    }
+}
+
+/// Connects an authority to a `SimulatedNetwork`. Created with `SimulatedNetwork::manager()`.
+pub(crate) struct SimulatedNetworkManager {
+    context: Arc<Context>,
+    network: SimulatedNetwork,
+    client: Arc<SimulatedNetworkClient>,
+}
+
+impl<S: NetworkService> NetworkManager<S> for SimulatedNetworkManager {
+    type Client = SimulatedNetworkClient;
+
+    fn new(_context: Arc<Context>) -> Self {
+        unreachable!("SimulatedNetworkManager must be created by SimulatedNetwork::manager()")
+    }
+
+    fn client(&self) -> Arc<Self::Client> {
+        self.client.clone()
+    }
+
+    async fn install_service(&mut self, _network_keypair: NetworkKeyPair, service: Arc<S>) {
+        self.network
+            .inner
+            .lock()
+            .services
+            .insert(self.context.own_index, service);
+    }
+
+    async fn stop(&mut self) {
+        self.network
+            .inner
+            .lock()
+            .services
+            .remove(&self.context.own_index);
+    }
+}
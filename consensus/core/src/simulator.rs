@@ -0,0 +1,642 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs a committee of authorities in one process, over a `SimulatedNetwork`.
+//! Tests drive faults through the network and `crash()` / `restart()`, then check that all
+//! authorities agree on the commit sequence and that commits keep being made.
+
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+use consensus_config::{
+    local_committee_and_keys, AuthorityIndex, Committee, NetworkKeyPair, Parameters,
+    ProtocolKeyPair,
+};
+use parking_lot::Mutex;
+use prometheus::Registry;
+use sui_protocol_config::ProtocolConfig;
+use tokio::{sync::mpsc::unbounded_channel, task::JoinHandle, time::sleep};
+
+use crate::{
+    authority_node::AuthorityNode,
+    block::{BlockAPI as _, BlockRef, BlockTimestampMs},
+    byzantine_authority::{ByzantineAuthority, ByzantineBehavior},
+    commit::{CommitConsumer, CommitIndex},
+    context::{Clock, Context},
+    metrics::initialise_metrics,
+    network::simulated_network::{SimulatedNetwork, SimulatedNetworkManager},
+    storage::mem_store::MemStore,
+    transaction::NoopTransactionVerifier,
+};
+
+/// The UNIX timestamp simulated clocks start from.
+const SIMULATED_START_TIMESTAMP_MS: BlockTimestampMs = 1_700_000_000_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SimulatedCommit {
+    index: CommitIndex,
+    leader: BlockRef,
+    blocks: Vec<BlockRef>,
+}
+
+struct SimulatedAuthority {
+    authority: Option<AuthorityNode<SimulatedNetworkManager>>,
+    // Kept across restarts, as the authority's persistent storage.
+    store: Arc<MemStore>,
+    // Commits received by the consumer of the authority, across restarts.
+    commits: Arc<Mutex<Vec<SimulatedCommit>>>,
+    collector: Option<JoinHandle<()>>,
+}
+
+pub(crate) struct Simulator {
+    committee: Committee,
+    keypairs: Vec<(NetworkKeyPair, ProtocolKeyPair)>,
+    parameters: Parameters,
+    protocol_config: ProtocolConfig,
+    network: SimulatedNetwork,
+    // Shared by all authorities, and driven by tokio time so that block timestamps are
+    // reproducible.
+    clock: Arc<Clock>,
+    nodes: Vec<SimulatedAuthority>,
+    byzantine: BTreeSet<AuthorityIndex>,
+}
+
+impl Simulator {
+    pub(crate) fn new(num_authorities: usize, seed: u64) -> Self {
+        Self::new_with_parameters(num_authorities, seed, Parameters::default())
+    }
+
+    pub(crate) fn new_with_parameters(
+        num_authorities: usize,
+        seed: u64,
+        parameters: Parameters,
+    ) -> Self {
+        Self::new_with_config(
+            num_authorities,
+            seed,
+            parameters,
+            ProtocolConfig::get_for_max_version_UNSAFE(),
+        )
+    }
+
+    pub(crate) fn new_with_config(
+        num_authorities: usize,
+        seed: u64,
+        parameters: Parameters,
+        protocol_config: ProtocolConfig,
+    ) -> Self {
+        let (committee, keypairs) = local_committee_and_keys(0, vec![1; num_authorities]);
+        let nodes = (0..num_authorities)
+            .map(|_| SimulatedAuthority {
+                authority: None,
+                store: Arc::new(MemStore::new()),
+                commits: Arc::new(Mutex::new(Vec::new())),
+                collector: None,
+            })
+            .collect();
+        Self {
+            committee,
+            keypairs,
+            parameters,
+            protocol_config,
+            network: SimulatedNetwork::new(seed),
+            clock: Arc::new(Clock::new_simulated(SIMULATED_START_TIMESTAMP_MS)),
+            nodes,
+            byzantine: BTreeSet::new(),
+        }
+    }
+
+    pub(crate) fn network(&self) -> &SimulatedNetwork {
+        &self.network
+    }
+
+    pub(crate) fn authorities(&self) -> Vec<AuthorityIndex> {
+        self.committee
+            .authorities()
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Authorities which have not been made byzantine.
+    pub(crate) fn honest_authorities(&self) -> Vec<AuthorityIndex> {
+        self.authorities()
+            .into_iter()
+            .filter(|index| !self.byzantine.contains(index))
+            .collect()
+    }
+
+    /// Makes an authority misbehave towards its peers.
+    pub(crate) fn make_byzantine(
+        &mut self,
+        index: AuthorityIndex,
+        behaviors: Vec<ByzantineBehavior>,
+    ) {
+        let byzantine = ByzantineAuthority::new(
+            self.keypairs[index.value()].1.clone(),
+            &self.protocol_config,
+            behaviors,
+        );
+        self.network.set_byzantine(index, byzantine);
+        self.byzantine.insert(index);
+    }
+
+    /// Starts all authorities.
+    pub(crate) async fn start(&mut self) {
+        for index in self.authorities() {
+            self.start_authority(index).await;
+        }
+    }
+
+    async fn start_authority(&mut self, index: AuthorityIndex) {
+        let node = &mut self.nodes[index.value()];
+        assert!(node.authority.is_none(), "Authority {index} is running");
+
+        let context = Arc::new(Context::new(
+            index,
+            self.committee.clone(),
+            self.parameters.clone(),
+            self.protocol_config.clone(),
+            initialise_metrics(Registry::new()),
+            self.clock.clone(),
+        ));
+
+        // Resume from the last commit received before a crash. Commits already in the store
+        // but not yet received are replayed.
+        let (sender, mut receiver) = unbounded_channel();
+        let (last_processed_commit_round, last_processed_commit_index) = node
+            .commits
+            .lock()
+            .last()
+            .map(|c| (c.leader.round, c.index))
+            .unwrap_or((0, 0));
+        let commit_consumer = CommitConsumer::new(
+            sender,
+            last_processed_commit_round,
+            last_processed_commit_index,
+        );
+        let monitor = commit_consumer.monitor();
+
+        let commits = node.commits.clone();
+        node.collector = Some(tokio::spawn(async move {
+            while let Some(subdag) = receiver.recv().await {
+                let mut commits = commits.lock();
+                assert_eq!(
+                    subdag.commit_index as usize,
+                    commits.len() + 1,
+                    "Authority {index} received commits out of order"
+                );
+                commits.push(SimulatedCommit {
+                    index: subdag.commit_index,
+                    leader: subdag.leader,
+                    blocks: subdag.blocks.iter().map(|b| b.reference()).collect(),
+                });
+                monitor.set_highest_handled_commit(subdag.commit_index);
+            }
+        }));
+
+        let (network_keypair, protocol_keypair) = self.keypairs[index.value()].clone();
+        node.authority = Some(
+            AuthorityNode::start_with(
+                context.clone(),
+                self.network.manager(context),
+                node.store.clone(),
+                protocol_keypair,
+                network_keypair,
+                Arc::new(NoopTransactionVerifier {}),
+                commit_consumer,
+            )
+            .await,
+        );
+    }
+
+    /// Stops an authority, keeping its store.
+    pub(crate) async fn crash(&mut self, index: AuthorityIndex) {
+        let node = &mut self.nodes[index.value()];
+        let authority = node
+            .authority
+            .take()
+            .unwrap_or_else(|| panic!("Authority {index} is not running"));
+        authority.stop().await;
+        // The commit channel closes once Core is dropped.
+        if let Some(collector) = node.collector.take() {
+            collector.await.unwrap();
+        }
+    }
+
+    /// Starts a crashed authority from its store.
+    pub(crate) async fn restart(&mut self, index: AuthorityIndex) {
+        self.start_authority(index).await;
+    }
+
+    pub(crate) async fn stop(mut self) {
+        for index in self.authorities() {
+            if self.nodes[index.value()].authority.is_some() {
+                self.crash(index).await;
+            }
+        }
+    }
+
+    pub(crate) fn num_commits(&self, index: AuthorityIndex) -> usize {
+        self.nodes[index.value()].commits.lock().len()
+    }
+
+    /// Returns the blocks committed by an authority, in commit order.
+    pub(crate) fn committed_blocks(&self, index: AuthorityIndex) -> Vec<BlockRef> {
+        self.nodes[index.value()]
+            .commits
+            .lock()
+            .iter()
+            .flat_map(|c| c.blocks.iter().copied())
+            .collect()
+    }
+
+    /// Returns the leaders committed by an authority, in commit order.
+    pub(crate) fn committed_leaders(&self, index: AuthorityIndex) -> Vec<BlockRef> {
+        self.nodes[index.value()]
+            .commits
+            .lock()
+            .iter()
+            .map(|c| c.leader)
+            .collect()
+    }
+
+    pub(crate) fn store(&self, index: AuthorityIndex) -> &Arc<MemStore> {
+        &self.nodes[index.value()].store
+    }
+
+    /// Waits until each of `authorities` has received at least `target` commits. Panics after
+    /// `timeout`.
+    pub(crate) async fn wait_for_commits(
+        &self,
+        authorities: &[AuthorityIndex],
+        target: usize,
+        timeout: Duration,
+    ) {
+        tokio::time::timeout(timeout, async {
+            while authorities
+                .iter()
+                .any(|index| self.num_commits(*index) < target)
+            {
+                sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| {
+            let counts = authorities
+                .iter()
+                .map(|index| (*index, self.num_commits(*index)))
+                .collect::<Vec<_>>();
+            panic!("Timed out waiting for {target} commits: {counts:?}")
+        });
+    }
+
+    /// Checks that the commit sequence of each honest authority is a prefix of the longest one.
+    pub(crate) fn check_safety(&self) {
+        let sequences = self
+            .honest_authorities()
+            .into_iter()
+            .map(|index| (index, self.nodes[index.value()].commits.lock().clone()))
+            .collect::<Vec<_>>();
+        let (_, longest) = sequences.iter().max_by_key(|(_, s)| s.len()).unwrap();
+        for (index, sequence) in &sequences {
+            for (commit, expected) in sequence.iter().zip(longest.iter()) {
+                assert_eq!(
+                    commit, expected,
+                    "Authority {index} diverged at commit {}",
+                    expected.index
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use consensus_config::{AuthorityIndex, Parameters};
+    use sui_protocol_config::{ConsensusLeaderScoringStrategy, ProtocolConfig};
+    use tokio::time::{sleep, Instant};
+
+    use super::{SimulatedCommit, Simulator};
+    use crate::storage::Store as _;
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_latency_and_drops() {
+        telemetry_subscribers::init_for_testing();
+        let mut simulator = Simulator::new(4, 42);
+        simulator
+            .network()
+            .set_latency(Duration::from_millis(50), Duration::from_millis(100));
+        simulator.network().set_drop_rate(0.05);
+        simulator.start().await;
+
+        let authorities = simulator.authorities();
+        simulator
+            .wait_for_commits(&authorities, 30, Duration::from_secs(120))
+            .await;
+        simulator.check_safety();
+
+        let stats = simulator.network().stats();
+        assert!(stats.delivered > 0);
+        assert!(stats.dropped > 0);
+
+        simulator.stop().await;
+    }
+
+    /// Runs 4 authorities with jittery links until `target` commits, and returns the commits of
+    /// the first authority.
+    async fn run_with_seed(seed: u64, target: usize) -> Vec<SimulatedCommit> {
+        let mut simulator = Simulator::new(4, seed);
+        simulator
+            .network()
+            .set_latency(Duration::from_millis(50), Duration::from_millis(100));
+        simulator.start().await;
+
+        let authorities = simulator.authorities();
+        simulator
+            .wait_for_commits(&authorities, target, Duration::from_secs(120))
+            .await;
+        simulator.check_safety();
+        let mut commits = simulator.nodes[authorities[0].value()]
+            .commits
+            .lock()
+            .clone();
+        commits.truncate(target);
+        simulator.stop().await;
+        commits
+    }
+
+    #[test]
+    fn test_same_seed_same_commits() {
+        telemetry_subscribers::init_for_testing();
+        // Each run gets its own paused runtime, so that they start from the same state.
+        let run = |seed| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .start_paused(true)
+                .build()
+                .unwrap()
+                .block_on(run_with_seed(seed, 20))
+        };
+
+        let commits = run(5);
+        assert_eq!(commits.len(), 20);
+        assert_eq!(commits, run(5));
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_partition_and_heal() {
+        telemetry_subscribers::init_for_testing();
+        let mut simulator = Simulator::new(4, 7);
+        simulator
+            .network()
+            .set_latency(Duration::from_millis(20), Duration::from_millis(20));
+        simulator.start().await;
+
+        let authorities = simulator.authorities();
+        simulator
+            .wait_for_commits(&authorities, 10, Duration::from_secs(60))
+            .await;
+
+        // Neither side of the partition has a quorum.
+        simulator.network().partition(vec![
+            vec![authorities[0], authorities[1]],
+            vec![authorities[2], authorities[3]],
+        ]);
+        // Let in flight blocks settle.
+        sleep(Duration::from_secs(5)).await;
+        let halted = authorities
+            .iter()
+            .map(|index| simulator.num_commits(*index))
+            .collect::<Vec<_>>();
+        sleep(Duration::from_secs(30)).await;
+        for (index, num_commits) in authorities.iter().zip(halted.iter()) {
+            assert_eq!(simulator.num_commits(*index), *num_commits);
+        }
+        assert!(simulator.network().stats().partitioned > 0);
+
+        simulator.network().heal();
+        let target = halted.iter().max().unwrap() + 10;
+        simulator
+            .wait_for_commits(&authorities, target, Duration::from_secs(120))
+            .await;
+        simulator.check_safety();
+
+        simulator.stop().await;
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_crash_and_restart() {
+        telemetry_subscribers::init_for_testing();
+        // Cache less blocks to exercise commit sync on restart.
+        let parameters = Parameters {
+            dag_state_cached_rounds: 5,
+            commit_sync_parallel_fetches: 3,
+            commit_sync_batch_size: 3,
+            ..Default::default()
+        };
+        let mut simulator = Simulator::new_with_parameters(4, 11, parameters);
+        simulator
+            .network()
+            .set_latency(Duration::from_millis(20), Duration::from_millis(30));
+        simulator.start().await;
+
+        let authorities = simulator.authorities();
+        simulator
+            .wait_for_commits(&authorities, 5, Duration::from_secs(60))
+            .await;
+
+        // The remaining authorities hold a quorum and keep committing.
+        let crashed = authorities[3];
+        simulator.crash(crashed).await;
+        let crashed_commits = simulator.num_commits(crashed);
+        simulator
+            .wait_for_commits(
+                &authorities[..3],
+                crashed_commits + 30,
+                Duration::from_secs(120),
+            )
+            .await;
+        assert_eq!(simulator.num_commits(crashed), crashed_commits);
+        simulator.check_safety();
+
+        // The restarted authority catches up.
+        simulator.restart(crashed).await;
+        let target = authorities
+            .iter()
+            .map(|index| simulator.num_commits(*index))
+            .max()
+            .unwrap()
+            + 5;
+        simulator
+            .wait_for_commits(&authorities, target, Duration::from_secs(120))
+            .await;
+        simulator.check_safety();
+
+        simulator.stop().await;
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_catch_up_after_gc() {
+        telemetry_subscribers::init_for_testing();
+        let parameters = Parameters {
+            dag_state_cached_rounds: 5,
+            commit_sync_parallel_fetches: 3,
+            commit_sync_batch_size: 3,
+            store_gc_depth_rounds: 40,
+            ..Default::default()
+        };
+        // Unscored commits are not garbage collected, so schedule updates must be frequent.
+        let mut protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+        protocol_config.set_consensus_commits_per_schedule(10);
+        let mut simulator = Simulator::new_with_config(4, 13, parameters, protocol_config);
+        simulator
+            .network()
+            .set_latency(Duration::from_millis(20), Duration::from_millis(30));
+        simulator.start().await;
+
+        let authorities = simulator.authorities();
+        simulator
+            .wait_for_commits(&authorities, 60, Duration::from_secs(120))
+            .await;
+
+        // Crash an authority for fewer rounds than the GC depth.
+        let crashed = authorities[3];
+        simulator.crash(crashed).await;
+        let crashed_commits = simulator.num_commits(crashed);
+        simulator
+            .wait_for_commits(
+                &authorities[..3],
+                crashed_commits + 20,
+                Duration::from_secs(120),
+            )
+            .await;
+
+        // Early commits and blocks have been garbage collected by the running authorities.
+        for index in &authorities[..3] {
+            let store = simulator.store(*index);
+            assert!(store.scan_commits((1..2).into()).unwrap().is_empty());
+            assert!(store.scan_blocks_by_round(1, 1).unwrap().is_empty());
+        }
+
+        // The crashed authority catches up from the remaining commits.
+        simulator.restart(crashed).await;
+        let target = authorities
+            .iter()
+            .map(|index| simulator.num_commits(*index))
+            .max()
+            .unwrap()
+            + 5;
+        simulator
+            .wait_for_commits(&authorities, target, Duration::from_secs(120))
+            .await;
+        simulator.check_safety();
+
+        simulator.stop().await;
+    }
+
+    const COMMITS_PER_SCHEDULE: u64 = 50;
+
+    #[derive(Debug)]
+    struct SlowLeaderRun {
+        // Time taken to make the commits after the first schedule update.
+        elapsed: Duration,
+        // Leader rounds skipped by the commits after the first schedule update.
+        skipped_leader_rounds: u32,
+        // Reputation scores of the last commit.
+        scores: Vec<u64>,
+    }
+
+    /// Runs 7 authorities where the blocks of `slow` reach their peers after the leader
+    /// timeout, with leader scoring disabled when `strategy` is None.
+    async fn run_slow_leader(
+        seed: u64,
+        slow: AuthorityIndex,
+        strategy: Option<ConsensusLeaderScoringStrategy>,
+    ) -> SlowLeaderRun {
+        let mut protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+        protocol_config.set_mysticeti_leader_scoring_and_schedule(strategy.is_some());
+        protocol_config.set_consensus_leader_scoring_strategy(strategy.unwrap_or_default());
+        protocol_config.set_consensus_commits_per_schedule(COMMITS_PER_SCHEDULE);
+        let mut simulator =
+            Simulator::new_with_config(7, seed, Parameters::default(), protocol_config);
+        simulator
+            .network()
+            .set_latency(Duration::from_millis(20), Duration::from_millis(10));
+        let authorities = simulator.authorities();
+        for peer in &authorities {
+            if *peer != slow {
+                simulator
+                    .network()
+                    .set_link_latency(slow, *peer, Duration::from_secs(1));
+            }
+        }
+        simulator.start().await;
+
+        let warmup = COMMITS_PER_SCHEDULE as usize + 1;
+        let target = warmup + 100;
+        simulator
+            .wait_for_commits(&authorities, warmup, Duration::from_secs(300))
+            .await;
+        let start = Instant::now();
+        simulator
+            .wait_for_commits(&authorities, target, Duration::from_secs(300))
+            .await;
+        let elapsed = start.elapsed();
+        simulator.check_safety();
+
+        let leaders = simulator.committed_leaders(authorities[0]);
+        let skipped_leader_rounds = leaders[warmup - 1..target]
+            .windows(2)
+            .map(|pair| pair[1].round - pair[0].round - 1)
+            .sum();
+        let (_, commit_info) = simulator
+            .store(authorities[0])
+            .read_last_commit_info()
+            .unwrap()
+            .unwrap();
+        simulator.stop().await;
+
+        SlowLeaderRun {
+            elapsed,
+            skipped_leader_rounds,
+            scores: commit_info.reputation_scores.scores_per_authority,
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_slow_leader_scoring_strategies() {
+        telemetry_subscribers::init_for_testing();
+        let slow = AuthorityIndex::new_for_test(6);
+
+        let baseline = run_slow_leader(31, slow, None).await;
+        tracing::info!("Without leader scoring: {baseline:?}");
+        assert!(baseline.skipped_leader_rounds > 0);
+        assert!(baseline.scores.is_empty());
+
+        // Votes of the slow authority are late but still committed, so it is not necessarily
+        // scored the lowest.
+        let vote = run_slow_leader(31, slow, Some(ConsensusLeaderScoringStrategy::Vote)).await;
+        tracing::info!("Vote scoring: {vote:?}");
+
+        // Strategies which penalize late blocks swap out the slow leader.
+        for strategy in [
+            ConsensusLeaderScoringStrategy::CertifiedVote,
+            ConsensusLeaderScoringStrategy::Timeliness,
+        ] {
+            let run = run_slow_leader(31, slow, Some(strategy)).await;
+            tracing::info!("{strategy:?} scoring: {run:?}");
+            let slow_score = run.scores[slow.value()];
+            assert!(
+                run.scores
+                    .iter()
+                    .enumerate()
+                    .all(|(index, score)| index == slow.value() || *score > slow_score),
+                "{strategy:?} did not score the slow authority the lowest: {:?}",
+                run.scores
+            );
+            assert!(run.skipped_leader_rounds < baseline.skipped_leader_rounds);
+            assert!(run.elapsed < baseline.elapsed);
+        }
+    }
+}
@@ -125,6 +125,23 @@ impl Store for MemStore {
         Ok(blocks)
     }
 
+    fn scan_blocks_by_round(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>> {
+        let inner = self.inner.read();
+        let blocks = inner
+            .blocks
+            .range((
+                Included((start_round, AuthorityIndex::MIN, BlockDigest::MIN)),
+                Included((end_round, AuthorityIndex::MAX, BlockDigest::MAX)),
+            ))
+            .map(|(_, block)| block.clone())
+            .collect();
+        Ok(blocks)
+    }
+
     fn contains_block_at_slot(&self, slot: Slot) -> ConsensusResult<bool> {
         let inner = self.inner.read();
         let found = inner
@@ -209,4 +226,43 @@ impl Store for MemStore {
             .last_key_value()
             .map(|(k, v)| (CommitRef::new(k.0, k.1), v.clone())))
     }
+
+    fn gc(
+        &self,
+        _authorities: &[AuthorityIndex],
+        gc_round: Round,
+        gc_commit_index: CommitIndex,
+    ) -> ConsensusResult<Option<(Round, CommitIndex)>> {
+        let mut inner = self.inner.write();
+        let Some(commit) = inner
+            .commits
+            .values()
+            .find(|c| c.leader().round >= gc_round || c.index() >= gc_commit_index)
+            .cloned()
+        else {
+            return Ok(None);
+        };
+        let block_gc_round = commit
+            .blocks()
+            .iter()
+            .map(|b| b.round)
+            .min()
+            .map_or(gc_round, |round| round.min(gc_round));
+        let commit_index = commit.index();
+
+        inner
+            .blocks
+            .retain(|(round, _, _), _| *round >= block_gc_round);
+        inner
+            .digests_by_authorities
+            .retain(|(_, round, _)| *round >= block_gc_round);
+        inner.commits.retain(|(index, _), _| *index >= commit_index);
+        inner
+            .commit_votes
+            .retain(|(index, _, _)| *index >= commit_index);
+        inner
+            .commit_info
+            .retain(|(index, _), _| *index >= commit_index);
+        Ok(Some((block_gc_round, commit_index)))
+    }
 }
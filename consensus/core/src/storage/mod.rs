@@ -36,6 +36,14 @@ pub(crate) trait Store: Send + Sync {
         start_round: Round,
     ) -> ConsensusResult<Vec<VerifiedBlock>>;
 
+    /// Reads blocks of all authorities from start_round (inclusive) until end_round (inclusive),
+    /// in round ascending order.
+    fn scan_blocks_by_round(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>>;
+
     // The method returns the last `num_of_rounds` rounds blocks by author in round ascending order.
     // When a `before_round` is defined then the blocks of round `<=before_round` are returned. If not
     // then the max value for round will be used as cut off.
@@ -57,6 +65,20 @@ pub(crate) trait Store: Send + Sync {
 
     /// Reads the last commit info, written atomically with the last commit.
     fn read_last_commit_info(&self) -> ConsensusResult<Option<(CommitRef, CommitInfo)>>;
+
+    /// Removes commits with leader round below `gc_round` and index below `gc_commit_index`,
+    /// together with their votes and infos. Blocks below `gc_round` are removed too, except for
+    /// blocks of the first remaining commit. Blocks of later commits below the lowest round of
+    /// the first remaining commit's blocks are removed, so callers must pick a `gc_round` low
+    /// enough to keep the blocks of every commit they still serve or replay.
+    /// Returns the lowest remaining block round and commit index, or None when no commit has
+    /// leader round >= `gc_round` or index >= `gc_commit_index`, and nothing is removed.
+    fn gc(
+        &self,
+        authorities: &[AuthorityIndex],
+        gc_round: Round,
+        gc_commit_index: CommitIndex,
+    ) -> ConsensusResult<Option<(Round, CommitIndex)>>;
 }
 
 /// Represents data to be written to the store together atomically.
@@ -13,7 +13,10 @@ use sui_macros::fail_point;
 use typed_store::{
     metrics::SamplingInterval,
     reopen,
-    rocks::{default_db_options, open_cf_opts, DBMap, MetricConf, ReadWriteOptions},
+    rocks::{
+        default_db_options, open_cf_opts, open_cf_opts_secondary, DBMap, MetricConf,
+        ReadWriteOptions,
+    },
     Map as _,
 };
 
@@ -48,11 +51,16 @@ impl RocksDBStore {
 
     /// Creates a new instance of RocksDB storage.
     pub(crate) fn new(path: &str) -> Self {
-        // Consensus data has high write throughput (all transactions) and is rarely read
-        // (only during recovery and when helping peers catch up).
-        let db_options = default_db_options().optimize_db_for_write_throughput(2);
-        let mut metrics_conf = MetricConf::new("consensus");
-        metrics_conf.read_sample_interval = SamplingInterval::new(Duration::from_secs(60), 0);
+        Self::open(path, false)
+    }
+
+    /// Opens the storage at `path` read-only, as a secondary instance which can be used while
+    /// the storage is also open by a running node.
+    pub(crate) fn new_secondary(path: &str) -> Self {
+        Self::open(path, true)
+    }
+
+    fn open(path: &str, secondary: bool) -> Self {
         let cf_options = default_db_options().optimize_for_write_throughput().options;
         let column_family_options = vec![
             (
@@ -69,12 +77,27 @@ impl RocksDBStore {
             (Self::COMMIT_VOTES_CF, cf_options.clone()),
             (Self::COMMIT_INFO_CF, cf_options.clone()),
         ];
-        let rocksdb = open_cf_opts(
-            path,
-            Some(db_options.options),
-            metrics_conf,
-            &column_family_options,
-        )
+        let rocksdb = if secondary {
+            open_cf_opts_secondary(
+                path,
+                None,
+                None,
+                MetricConf::new("consensus_secondary"),
+                &column_family_options,
+            )
+        } else {
+            // Consensus data has high write throughput (all transactions) and is rarely read
+            // (only during recovery and when helping peers catch up).
+            let db_options = default_db_options().optimize_db_for_write_throughput(2);
+            let mut metrics_conf = MetricConf::new("consensus");
+            metrics_conf.read_sample_interval = SamplingInterval::new(Duration::from_secs(60), 0);
+            open_cf_opts(
+                path,
+                Some(db_options.options),
+                metrics_conf,
+                &column_family_options,
+            )
+        }
         .expect("Cannot open database");
 
         let (blocks, digests_by_authorities, commits, commit_votes, commit_info) = reopen!(&rocksdb,
@@ -218,6 +241,27 @@ impl Store for RocksDBStore {
         Ok(blocks)
     }
 
+    fn scan_blocks_by_round(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>> {
+        let mut blocks = vec![];
+        for kv in self.blocks.safe_range_iter((
+            Included((start_round, AuthorityIndex::MIN, BlockDigest::MIN)),
+            Included((end_round, AuthorityIndex::MAX, BlockDigest::MAX)),
+        )) {
+            let ((round, author, digest), serialized) = kv?;
+            let signed_block: SignedBlock =
+                bcs::from_bytes(&serialized).map_err(ConsensusError::MalformedBlock)?;
+            let block = VerifiedBlock::new_verified(signed_block, serialized);
+            // Makes sure block data is not corrupted, by comparing digests.
+            assert_eq!(BlockRef::new(round, author, digest), block.reference());
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
     // The method returns the last `num_of_rounds` rounds blocks by author in round ascending order.
     // When a `before_round` is defined then the blocks of round `<=before_round` are returned. If not
     // then the max value for round will be used as cut off.
@@ -301,4 +345,78 @@ impl Store for RocksDBStore {
         let (key, commit_info) = result.map_err(ConsensusError::RocksDBFailure)?;
         Ok(Some((CommitRef::new(key.0, key.1), commit_info)))
     }
+
+    fn gc(
+        &self,
+        authorities: &[AuthorityIndex],
+        gc_round: Round,
+        gc_commit_index: CommitIndex,
+    ) -> ConsensusResult<Option<(Round, CommitIndex)>> {
+        // Leader rounds increase with commit indices, so removed commits form a prefix.
+        // Earlier GC runs have removed everything before the first remaining commit.
+        let mut first_remaining = None;
+        for result in self.commits.safe_iter() {
+            let ((_index, digest), serialized) = result?;
+            let commit = TrustedCommit::new_trusted(
+                bcs::from_bytes(&serialized).map_err(ConsensusError::MalformedCommit)?,
+                serialized,
+            );
+            assert_eq!(commit.digest(), digest);
+            if commit.leader().round >= gc_round || commit.index() >= gc_commit_index {
+                first_remaining = Some(commit);
+                break;
+            }
+        }
+        let Some(commit) = first_remaining else {
+            return Ok(None);
+        };
+        let block_gc_round = commit
+            .blocks()
+            .iter()
+            .map(|b| b.round)
+            .min()
+            .map_or(gc_round, |round| round.min(gc_round));
+        let commit_index = commit.index();
+
+        let mut batch = self.blocks.batch();
+        batch
+            .schedule_delete_range(
+                &self.blocks,
+                &(Round::MIN, AuthorityIndex::MIN, BlockDigest::MIN),
+                &(block_gc_round, AuthorityIndex::MIN, BlockDigest::MIN),
+            )
+            .map_err(ConsensusError::RocksDBFailure)?;
+        for authority in authorities {
+            batch
+                .schedule_delete_range(
+                    &self.digests_by_authorities,
+                    &(*authority, Round::MIN, BlockDigest::MIN),
+                    &(*authority, block_gc_round, BlockDigest::MIN),
+                )
+                .map_err(ConsensusError::RocksDBFailure)?;
+        }
+        batch
+            .schedule_delete_range(
+                &self.commits,
+                &(CommitIndex::MIN, CommitDigest::MIN),
+                &(commit_index, CommitDigest::MIN),
+            )
+            .map_err(ConsensusError::RocksDBFailure)?;
+        batch
+            .schedule_delete_range(
+                &self.commit_votes,
+                &(CommitIndex::MIN, CommitDigest::MIN, BlockRef::MIN),
+                &(commit_index, CommitDigest::MIN, BlockRef::MIN),
+            )
+            .map_err(ConsensusError::RocksDBFailure)?;
+        batch
+            .schedule_delete_range(
+                &self.commit_info,
+                &(CommitIndex::MIN, CommitDigest::MIN),
+                &(commit_index, CommitDigest::MIN),
+            )
+            .map_err(ConsensusError::RocksDBFailure)?;
+        batch.write()?;
+        Ok(Some((block_gc_round, commit_index)))
+    }
 }
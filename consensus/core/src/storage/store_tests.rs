@@ -198,6 +198,26 @@ async fn scan_blocks(
             .expect("Scan blocks should not fail");
         assert_eq!(scanned_blocks.len(), 0);
     }
+    {
+        let scanned_blocks = store
+            .scan_blocks_by_round(11, 13)
+            .expect("Scan blocks should not fail");
+        assert_eq!(
+            scanned_blocks,
+            vec![
+                written_blocks[3].clone(),
+                written_blocks[4].clone(),
+                written_blocks[5].clone(),
+                written_blocks[7].clone(),
+                written_blocks[6].clone(),
+            ]
+        );
+
+        let scanned_blocks = store
+            .scan_blocks_by_round(17, 20)
+            .expect("Scan blocks should not fail");
+        assert!(scanned_blocks.is_empty(), "{:?}", scanned_blocks);
+    }
 }
 
 #[rstest]
@@ -297,3 +317,88 @@ async fn read_and_scan_commits(
         assert_eq!(scanned_commits, written_commits,);
     }
 }
+
+#[rstest]
+#[tokio::test]
+async fn gc(#[values(new_rocksdb_teststore(), new_mem_teststore())] test_store: TestStore) {
+    let store = test_store.store();
+    let authorities = (0..4).map(AuthorityIndex::new_for_test).collect::<Vec<_>>();
+
+    let mut written_blocks = vec![];
+    for round in 1..=6 {
+        for author in 0..4 {
+            written_blocks.push(VerifiedBlock::new_for_test(
+                TestBlock::new(round, author).build(),
+            ));
+        }
+    }
+    let block_refs = |round: u32| {
+        written_blocks
+            .iter()
+            .filter(|b| b.round() == round)
+            .map(|b| b.reference())
+            .collect::<Vec<_>>()
+    };
+
+    // Commit k has its leader at round k. The round 2 block of authority 3 is committed late,
+    // by commit 4.
+    let mut written_commits = vec![];
+    for index in 1..=5 {
+        let mut blocks = block_refs(index);
+        if index == 2 {
+            blocks.retain(|b| b.author != authorities[3]);
+        } else if index == 4 {
+            blocks.push(block_refs(2)[3]);
+        }
+        written_commits.push(TrustedCommit::new_for_test(
+            index,
+            CommitDigest::MIN,
+            index as u64,
+            block_refs(index)[0],
+            blocks,
+        ));
+    }
+    store
+        .write(
+            WriteBatch::default()
+                .blocks(written_blocks.clone())
+                .commits(written_commits.clone()),
+        )
+        .unwrap();
+
+    // Only commit 1 has been handled by the consumer, so later commits and their blocks are kept.
+    assert_eq!(store.gc(&authorities, 4, 2).unwrap(), Some((2, 2)));
+    assert_eq!(store.scan_commits((0..10).into()).unwrap().len(), 4);
+    assert_eq!(store.scan_blocks_by_round(0, 10).unwrap().len(), 20);
+
+    // Commits with leader round below 4 are removed. Blocks below round 2 are removed, because
+    // commit 4 still refers to round 2.
+    assert_eq!(store.gc(&authorities, 4, 10).unwrap(), Some((2, 4)));
+
+    let scanned_commits = store.scan_commits((0..10).into()).unwrap();
+    assert_eq!(scanned_commits, written_commits[3..].to_vec());
+    assert_eq!(
+        store.read_last_commit().unwrap().as_ref(),
+        written_commits.last()
+    );
+
+    let scanned_blocks = store.scan_blocks_by_round(0, 10).unwrap();
+    assert_eq!(scanned_blocks, written_blocks[4..].to_vec());
+    assert_eq!(
+        store.contains_blocks(&block_refs(1)).unwrap(),
+        vec![false; 4]
+    );
+    for authority in &authorities {
+        let scanned_blocks = store.scan_blocks_by_author(*authority, 0).unwrap();
+        assert_eq!(scanned_blocks.len(), 5);
+        assert_eq!(scanned_blocks[0].round(), 2);
+        assert!(!store
+            .contains_block_at_slot(Slot::new(1, *authority))
+            .unwrap());
+    }
+
+    // No commit has leader round >= 10, so nothing is removed.
+    assert_eq!(store.gc(&authorities, 10, 10).unwrap(), None);
+    assert_eq!(store.scan_commits((0..10).into()).unwrap().len(), 2);
+    assert_eq!(store.scan_blocks_by_round(0, 10).unwrap().len(), 20);
+}
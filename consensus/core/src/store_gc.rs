@@ -0,0 +1,168 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use tokio::{
+    sync::{oneshot, watch},
+    task::JoinHandle,
+};
+use tracing::{debug, warn};
+
+use crate::{
+    block::Round,
+    commit::{CommitConsumerMonitor, CommitIndex},
+    context::Context,
+    core::CoreSignalsReceivers,
+    dag_state::DagState,
+    storage::Store,
+};
+
+pub(crate) struct StoreGcTaskHandle {
+    handle: JoinHandle<()>,
+    stop: oneshot::Sender<()>,
+}
+
+impl StoreGcTaskHandle {
+    pub async fn stop(self) {
+        self.stop.send(()).ok();
+        self.handle.await.ok();
+    }
+}
+
+/// Garbage collects blocks and commits from storage, which are more than `store_gc_depth_rounds`
+/// below the last committed round. Commits not yet handled by the commit consumer are kept, since
+/// they are replayed to the consumer after a restart.
+///
+/// GC runs on its own task, off the Core thread, and is checked each time a new round is reached.
+pub(crate) struct StoreGcTask {
+    context: Arc<Context>,
+    dag_state: Arc<RwLock<DagState>>,
+    store: Arc<dyn Store>,
+    commit_consumer_monitor: Arc<CommitConsumerMonitor>,
+    new_round_receiver: watch::Receiver<Round>,
+    // Round below which blocks and commits have been garbage collected.
+    gc_round: Round,
+    stop: oneshot::Receiver<()>,
+}
+
+impl StoreGcTask {
+    pub fn start(
+        context: Arc<Context>,
+        dag_state: Arc<RwLock<DagState>>,
+        store: Arc<dyn Store>,
+        commit_consumer_monitor: Arc<CommitConsumerMonitor>,
+        signals_receivers: &CoreSignalsReceivers,
+    ) -> StoreGcTaskHandle {
+        let (stop_sender, stop) = oneshot::channel();
+        let me = Self {
+            context,
+            dag_state,
+            store,
+            commit_consumer_monitor,
+            new_round_receiver: signals_receivers.new_round_receiver(),
+            gc_round: 0,
+            stop,
+        };
+        let handle = tokio::spawn(me.run());
+
+        StoreGcTaskHandle {
+            handle,
+            stop: stop_sender,
+        }
+    }
+
+    async fn run(mut self) {
+        let gc_depth = self.context.parameters.store_gc_depth_rounds as Round;
+        if gc_depth == 0 {
+            return;
+        }
+        // Blocks evicted from the DagState cache are read from storage, so they must not be
+        // collected.
+        let gc_depth = gc_depth.max(self.context.parameters.dag_state_cached_rounds + 1);
+
+        loop {
+            tokio::select! {
+                result = self.new_round_receiver.changed() => {
+                    if result.is_err() {
+                        debug!("Core signals closed, stopping store GC");
+                        return;
+                    }
+                },
+                _ = &mut self.stop => {
+                    debug!("Stop signal has been received, stopping store GC");
+                    return;
+                }
+            }
+
+            // GC runs each time the GC round advances by a tenth of the depth, to amortize the
+            // cost of scanning commits.
+            let gc_round = {
+                let dag_state = self.dag_state.read();
+                let gc_round = dag_state.last_commit_round().saturating_sub(gc_depth);
+                match dag_state.lowest_unscored_round() {
+                    Some(round) => gc_round.min(round),
+                    None => gc_round,
+                }
+            };
+            if gc_round < self.gc_round + (gc_depth / 10).max(1) {
+                continue;
+            }
+            // Commits after the last one handled by the consumer must be kept.
+            let gc_commit_index = self.commit_consumer_monitor.highest_handled_commit() + 1;
+
+            let store = self.store.clone();
+            let authorities = self
+                .context
+                .committee
+                .authorities()
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>();
+            let context = self.context.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let _s = context
+                    .metrics
+                    .node_metrics
+                    .scope_processing_time
+                    .with_label_values(&["StoreGcTask::gc"])
+                    .start_timer();
+                store.gc(&authorities, gc_round, gc_commit_index)
+            })
+            .await;
+            match result {
+                Ok(Ok(result)) => {
+                    self.gc_round = gc_round;
+                    self.report(gc_round, gc_commit_index, result);
+                }
+                Ok(Err(e)) => panic!("Failed to garbage collect storage: {:?}", e),
+                Err(e) => {
+                    warn!("Store GC task failed, probably due to shutdown: {e:?}");
+                    return;
+                }
+            }
+        }
+    }
+
+    fn report(
+        &self,
+        gc_round: Round,
+        gc_commit_index: CommitIndex,
+        result: Option<(Round, CommitIndex)>,
+    ) {
+        let Some((lowest_block_round, lowest_commit_index)) = result else {
+            return;
+        };
+        debug!(
+            "Garbage collected storage below round {gc_round} and commit index {gc_commit_index}. Lowest remaining block round {lowest_block_round}, commit index {lowest_commit_index}."
+        );
+        let metrics = &self.context.metrics.node_metrics;
+        metrics.dag_state_store_gc_count.inc();
+        metrics
+            .dag_state_store_gc_round
+            .set(lowest_block_round as i64);
+        metrics
+            .dag_state_store_gc_commit_index
+            .set(lowest_commit_index as i64);
+    }
+}
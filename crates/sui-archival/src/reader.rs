@@ -91,6 +91,16 @@ impl ArchiveReaderBalancer {
         let checkpoints: Result<Vec<CheckpointSequenceNumber>> = checkpoints.into_iter().collect();
         checkpoints.map(|vec| vec.into_iter().min())
     }
+    /// Returns the highest checkpoint available from any of the archives, if any.
+    pub async fn latest_available_checkpoint(&self) -> Option<CheckpointSequenceNumber> {
+        let mut latest = None;
+        for reader in self.readers.iter() {
+            if let Ok(checkpoint) = reader.latest_available_checkpoint().await {
+                latest = latest.max(Some(checkpoint));
+            }
+        }
+        latest
+    }
     pub async fn pick_one_random(
         &self,
         checkpoint_range: Range<CheckpointSequenceNumber>,
@@ -179,6 +179,15 @@ pub struct NodeConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub firewall_config: Option<RemoteFirewallConfig>,
+
+    /// Maximum number of calls in a JSON-RPC batch request. Uses the server default if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_rpc_max_batch_size: Option<usize>,
+
+    /// Keep hot objects and packages in an on-disk tier of the execution cache, under
+    /// `<db-path>/warm_cache`, so that they are still cached after a restart.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub enable_execution_cache_warm_tier: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -760,6 +769,17 @@ pub struct AuthorityOverloadConfig {
     // is above the threshold.
     #[serde(default = "default_max_transaction_manager_per_object_queue_length")]
     pub max_transaction_manager_per_object_queue_length: usize,
+
+    // When set to true, load shedding rejects transactions from the clients submitting the most
+    // transactions touching congested objects first, instead of rejecting transactions uniformly.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub client_aware_load_shedding: bool,
+
+    // CIDR blocks of the proxies trusted to forward the address of their clients in the
+    // `x-forwarded-for` header. Client aware load shedding identifies other peers by the address
+    // of their connection, since any client can set the header.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub load_shedding_trusted_proxies: Vec<String>,
 }
 
 fn default_max_txn_age_in_queue() -> Duration {
@@ -818,6 +838,8 @@ impl Default for AuthorityOverloadConfig {
             max_transaction_manager_queue_length: default_max_transaction_manager_queue_length(),
             max_transaction_manager_per_object_queue_length:
                 default_max_transaction_manager_per_object_queue_length(),
+            client_aware_load_shedding: false,
+            load_shedding_trusted_proxies: vec![],
         }
     }
 }
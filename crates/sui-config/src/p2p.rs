@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
+use std::{net::SocketAddr, num::NonZeroU32, path::PathBuf, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use sui_types::{
@@ -187,6 +187,56 @@ pub struct StateSyncConfig {
     /// If unspecified, this will set to default value
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wait_interval_when_no_peer_to_sync_content_ms: Option<u64>,
+
+    /// Number of consecutive failed requests (errors, timeouts or invalid responses) after which
+    /// a peer is temporarily excluded from checkpoint summary and contents downloads.
+    ///
+    /// If unspecified, this will default to `5`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_failure_ban_threshold: Option<u32>,
+
+    /// The amount of time a peer stays excluded from downloads once it has been banned.
+    ///
+    /// If unspecified, this will default to `30,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_ban_duration_ms: Option<u64>,
+
+    /// Where checkpoint contents are downloaded from. See [`StateSyncMode`].
+    ///
+    /// If unspecified, this will default to `Fallback`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_mode: Option<StateSyncMode>,
+
+    /// In `ArchiveFirst` mode, how many checkpoints behind the tip of the chain a node has to be
+    /// for the archive to be preferred over peers.
+    ///
+    /// If unspecified, this will default to `10,000`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_first_lag_threshold: Option<u64>,
+
+    /// In `ArchiveFirst` and `Hybrid` modes, the number of checkpoints to read from the archive
+    /// before deciding again which source to download from.
+    ///
+    /// If unspecified, this will default to `1,000`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_sync_batch_size: Option<u64>,
+}
+
+/// Source selection strategy for downloading checkpoint contents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum StateSyncMode {
+    /// Download from peers, only falling back to the archive for checkpoints that all of our peers
+    /// have already pruned.
+    #[default]
+    Fallback,
+    /// Download from the archive until within `archive_first_lag_threshold` checkpoints of the tip
+    /// of the chain, then from peers. Useful to bootstrap new fullnodes.
+    ArchiveFirst,
+    /// Only download from peers, never from the archive.
+    PeersOnly,
+    /// Download each batch of checkpoints from whichever of the archive or peers has the higher
+    /// measured throughput.
+    Hybrid,
 }
 
 impl StateSyncConfig {
@@ -252,6 +302,39 @@ impl StateSyncConfig {
             .unwrap_or(self.default_wait_interval_when_no_peer_to_sync_content())
     }
 
+    pub fn peer_failure_ban_threshold(&self) -> u32 {
+        const PEER_FAILURE_BAN_THRESHOLD: u32 = 5;
+
+        self.peer_failure_ban_threshold
+            .unwrap_or(PEER_FAILURE_BAN_THRESHOLD)
+    }
+
+    pub fn peer_ban_duration(&self) -> Duration {
+        const DEFAULT_PEER_BAN_DURATION: Duration = Duration::from_secs(30);
+
+        self.peer_ban_duration_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_PEER_BAN_DURATION)
+    }
+
+    pub fn sync_mode(&self) -> StateSyncMode {
+        self.sync_mode.unwrap_or_default()
+    }
+
+    pub fn archive_first_lag_threshold(&self) -> u64 {
+        const ARCHIVE_FIRST_LAG_THRESHOLD: u64 = 10_000;
+
+        self.archive_first_lag_threshold
+            .unwrap_or(ARCHIVE_FIRST_LAG_THRESHOLD)
+    }
+
+    pub fn archive_sync_batch_size(&self) -> u64 {
+        const ARCHIVE_SYNC_BATCH_SIZE: u64 = 1_000;
+
+        self.archive_sync_batch_size
+            .unwrap_or(ARCHIVE_SYNC_BATCH_SIZE)
+    }
+
     fn default_wait_interval_when_no_peer_to_sync_content(&self) -> Duration {
         if cfg!(msim) {
             Duration::from_secs(5)
@@ -318,6 +401,43 @@ pub struct DiscoveryConfig {
     /// to this peer, nor advertise this peer's info to other peers in the network.
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub allowlisted_peers: Vec<AllowlistedPeer>,
+
+    /// Named groups of peers, for example a fleet of fullnodes run by the same operator, that
+    /// this node always tries to stay connected to. Connections to group members are maintained
+    /// separately from, and don't count towards, `target_concurrent_connections`. Group members
+    /// are also allowlisted.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub peer_groups: Vec<PeerGroup>,
+
+    /// Path of the file where discovered peers, along with when they were last seen and how
+    /// often connecting to them succeeded, are persisted so that they can be reloaded on restart.
+    ///
+    /// If unspecified, sui-node will default to `discovery_peers` in its `db-path`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_store_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PeerGroup {
+    /// Name of the group, used in logs and metrics.
+    pub name: String,
+
+    /// Members of the group. Members without an address are dialed using the address they
+    /// advertise through discovery.
+    pub peers: Vec<AllowlistedPeer>,
+
+    /// Maximum number of members of the group to dial connections to.
+    ///
+    /// If unspecified, this will default to all members of the group.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_connections: Option<usize>,
+}
+
+impl PeerGroup {
+    pub fn max_connections(&self) -> usize {
+        self.max_connections.unwrap_or(self.peers.len())
+    }
 }
 
 impl DiscoveryConfig {
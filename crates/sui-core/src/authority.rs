@@ -30,6 +30,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
 use std::io::Write;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 use std::time::Duration;
@@ -64,9 +65,9 @@ use sui_config::genesis::Genesis;
 use sui_config::node::{DBCheckpointConfig, ExpensiveSafetyCheckConfig};
 use sui_framework::{BuiltInFramework, SystemPackage};
 use sui_json_rpc_types::{
-    DevInspectResults, DryRunTransactionBlockResponse, EventFilter, SuiEvent, SuiMoveValue,
+    DevInspectResults, DryRunTransactionBlockResponse, EventFilter, Filter, SuiEvent, SuiMoveValue,
     SuiObjectDataFilter, SuiTransactionBlockData, SuiTransactionBlockEffects,
-    SuiTransactionBlockEvents, TransactionFilter,
+    SuiTransactionBlockEvents, TransactionFilter, MAX_EVENTS_SCANNED_PER_QUERY,
 };
 use sui_macros::{fail_point, fail_point_async, fail_point_if};
 use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
@@ -146,7 +147,10 @@ use crate::execution_driver::execution_process;
 use crate::metrics::LatencyObserver;
 use crate::metrics::RateTracker;
 use crate::module_cache_metrics::ResolverMetrics;
-use crate::overload_monitor::{overload_monitor_accept_tx, AuthorityOverloadInfo};
+use crate::overload_monitor::{
+    load_shedding_decision, AuthorityOverloadInfo, ClientLoadTracker, LoadSheddingDecision,
+    CONGESTED_OBJECT_QUEUE_LENGTH_RATIO,
+};
 use crate::stake_aggregator::StakeAggregator;
 use crate::state_accumulator::{AccumulatorStore, StateAccumulator, WrappedObject};
 use crate::subscription_handler::SubscriptionHandler;
@@ -256,6 +260,9 @@ pub struct AuthorityMetrics {
 
     pub(crate) authority_overload_status: IntGauge,
     pub(crate) authority_load_shedding_percentage: IntGauge,
+    pub(crate) load_shedding_tracked_clients: IntGauge,
+    pub(crate) load_shedding_heavy_clients: IntGauge,
+    pub(crate) load_shedding_rejected_transactions: IntCounterVec,
 
     /// Post processing metrics
     post_processing_total_events_emitted: IntCounter,
@@ -517,6 +524,22 @@ impl AuthorityMetrics {
                 "The percentage of transactions is shed when the authority is in load shedding mode.",
                 registry)
             .unwrap(),
+            load_shedding_tracked_clients: register_int_gauge_with_registry!(
+                "load_shedding_tracked_clients",
+                "The number of clients whose transactions touching congested objects are counted for load shedding.",
+                registry)
+            .unwrap(),
+            load_shedding_heavy_clients: register_int_gauge_with_registry!(
+                "load_shedding_heavy_clients",
+                "The number of clients whose transactions are shed first when the authority is in load shedding mode.",
+                registry)
+            .unwrap(),
+            load_shedding_rejected_transactions: register_int_counter_vec_with_registry!(
+                "load_shedding_rejected_transactions",
+                "Number of transactions rejected by load shedding, by the reason of the rejection.",
+                &["reason"],
+                registry)
+            .unwrap(),
             transaction_manager_object_cache_misses: register_int_counter_with_registry!(
                 "transaction_manager_object_cache_misses",
                 "Number of object-availability cache misses in TransactionManager",
@@ -974,10 +997,11 @@ impl AuthorityState {
         &self,
         consensus_adapter: &Arc<ConsensusAdapter>,
         tx_data: &SenderSignedData,
+        client: Option<IpAddr>,
         do_authority_overload_check: bool,
     ) -> SuiResult {
         if do_authority_overload_check {
-            self.check_authority_overload(tx_data)?;
+            self.check_authority_overload(tx_data, client)?;
         }
         self.transaction_manager
             .check_execution_overload(self.overload_config(), tx_data)?;
@@ -985,7 +1009,21 @@ impl AuthorityState {
         Ok(())
     }
 
-    fn check_authority_overload(&self, tx_data: &SenderSignedData) -> SuiResult {
+    fn check_authority_overload(
+        &self,
+        tx_data: &SenderSignedData,
+        client: Option<IpAddr>,
+    ) -> SuiResult {
+        if let Some(client) = client {
+            if self.overload_config().client_aware_load_shedding
+                && self.touches_congested_objects(tx_data)
+            {
+                self.overload_info
+                    .client_load
+                    .record_congested_transaction(client);
+            }
+        }
+
         if !self.overload_info.is_overload.load(Ordering::Relaxed) {
             return Ok(());
         }
@@ -994,7 +1032,36 @@ impl AuthorityState {
             .overload_info
             .load_shedding_percentage
             .load(Ordering::Relaxed);
-        overload_monitor_accept_tx(load_shedding_percentage, tx_data.digest())
+        let decision = load_shedding_decision(
+            &self.overload_info.client_load,
+            load_shedding_percentage,
+            tx_data.digest(),
+            client,
+        );
+        if decision != LoadSheddingDecision::Accept {
+            self.metrics
+                .load_shedding_rejected_transactions
+                .with_label_values(&[decision.as_str()])
+                .inc();
+        }
+        decision.into_result()
+    }
+
+    // Returns whether an input object of the transaction has enough transactions pending on it in
+    // the transaction manager to be considered congested.
+    fn touches_congested_objects(&self, tx_data: &SenderSignedData) -> bool {
+        let Ok(input_objects) = tx_data.transaction_data().input_objects() else {
+            return false;
+        };
+        let threshold = (self
+            .overload_config()
+            .max_transaction_manager_per_object_queue_length
+            / CONGESTED_OBJECT_QUEUE_LENGTH_RATIO)
+            .max(1);
+        self.transaction_manager
+            .objects_queue_len_and_age(input_objects.iter().map(|o| o.object_id()).collect())
+            .into_iter()
+            .any(|(_, queue_len, _)| queue_len >= threshold)
     }
 
     /// Executes a transaction that's known to have correct effects.
@@ -1559,6 +1626,16 @@ impl AuthorityState {
         let protocol_config = epoch_store.protocol_config();
         let transaction_data = &certificate.data().intent_message().value;
         let (kind, signer, gas) = transaction_data.execution_parts();
+        let congestion_cancellation = epoch_store.get_congestion_cancellation(&tx_digest)?;
+        if congestion_cancellation.is_some()
+            && !epoch_store.executor().supports_congestion_cancellation()
+        {
+            return Err(SuiError::UnsupportedFeatureError {
+                error: format!(
+                    "transaction {tx_digest} was cancelled due to congestion, which the execution layer of this epoch doesn't support"
+                ),
+            });
+        }
 
         #[allow(unused_mut)]
         let (inner_temp_store, _, mut effects, execution_error_opt) =
@@ -1583,6 +1660,7 @@ impl AuthorityState {
                 kind,
                 signer,
                 tx_digest,
+                congestion_cancellation,
             );
 
         fail_point_if!("cp_execution_nondeterminism", || {
@@ -1764,6 +1842,7 @@ impl AuthorityState {
                 kind,
                 signer,
                 transaction_digest,
+                None,
             );
         let tx_digest = *effects.transaction_digest();
 
@@ -2632,6 +2711,14 @@ impl AuthorityState {
         let input_loader = TransactionInputLoader::new(execution_cache.clone());
         let cache_pointers = ExecutionCacheTraitPointers::new(&execution_cache);
         let epoch = epoch_store.epoch();
+        let overload_info = AuthorityOverloadInfo {
+            client_load: ClientLoadTracker::new(
+                &config
+                    .authority_overload_config
+                    .load_shedding_trusted_proxies,
+            ),
+            ..Default::default()
+        };
         let state = Arc::new(AuthorityState {
             name,
             secret,
@@ -2651,7 +2738,7 @@ impl AuthorityState {
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
             config,
-            overload_info: AuthorityOverloadInfo::default(),
+            overload_info,
         });
 
         // Start a task to execute ready certificates.
@@ -3736,6 +3823,78 @@ impl AuthorityState {
         cursor: Option<EventID>,
         limit: usize,
         descending: bool,
+    ) -> SuiResult<Vec<SuiEvent>> {
+        let (indexed_query, exact) = query.indexed_filter(Self::is_indexed_event_filter);
+        if exact {
+            return self
+                .query_indexed_events(kv_store, query, cursor, limit, descending)
+                .await;
+        }
+
+        // Filters without an index are evaluated against the events of an indexed filter that
+        // they imply, a page at a time, until enough events match. A filter that implies no
+        // indexed filter would have to be checked against every event.
+        if matches!(&indexed_query, EventFilter::All(filters) if filters.is_empty()) {
+            return Err(SuiError::UserInputError {
+                error: UserInputError::Unsupported(
+                    "This query type is not supported by the full node unless combined with a supported filter using All or And.".to_string(),
+                ),
+            });
+        }
+        let mut events = vec![];
+        let mut cursor = cursor;
+        let mut scanned = 0;
+        loop {
+            let page = self
+                .query_indexed_events(kv_store, indexed_query.clone(), cursor, limit, descending)
+                .await?;
+            let exhausted = page.len() < limit;
+            scanned += page.len();
+            cursor = page.last().map(|event| event.id).or(cursor);
+            events.extend(page.into_iter().filter(|event| query.matches(event)));
+            if exhausted || events.len() >= limit {
+                break;
+            }
+            if scanned >= MAX_EVENTS_SCANNED_PER_QUERY {
+                return Err(SuiError::UserInputError {
+                    error: UserInputError::Unsupported(format!(
+                        "Scanned {scanned} events without finding {limit} that match the query, narrow the query or continue from cursor {cursor:?}"
+                    )),
+                });
+            }
+        }
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    /// Whether events matching `filter` can be looked up by an index.
+    pub fn is_indexed_event_filter(filter: &EventFilter) -> bool {
+        match filter {
+            EventFilter::All(filters) => filters.is_empty(),
+            EventFilter::Transaction(_)
+            | EventFilter::MoveModule { .. }
+            | EventFilter::MoveEventType(_)
+            | EventFilter::Sender(_)
+            | EventFilter::TimeRange { .. }
+            | EventFilter::MoveEventModule { .. } => true,
+            EventFilter::Package(_)
+            | EventFilter::MoveEventField { .. }
+            | EventFilter::MoveEventFieldCondition { .. }
+            | EventFilter::Any(_)
+            | EventFilter::And(_, _)
+            | EventFilter::Or(_, _)
+            | EventFilter::Not(_) => false,
+        }
+    }
+
+    async fn query_indexed_events(
+        &self,
+        kv_store: &Arc<TransactionKeyValueStore>,
+        query: EventFilter,
+        // If `Some`, the query will start from the next item after the specified cursor
+        cursor: Option<EventID>,
+        limit: usize,
+        descending: bool,
     ) -> SuiResult<Vec<SuiEvent>> {
         let index_store = self.get_indexes()?;
 
@@ -3801,9 +3960,11 @@ impl AuthorityState {
             // not using "_ =>" because we want to make sure we remember to add new variants here
             EventFilter::Package(_)
             | EventFilter::MoveEventField { .. }
+            | EventFilter::MoveEventFieldCondition { .. }
             | EventFilter::Any(_)
             | EventFilter::And(_, _)
-            | EventFilter::Or(_, _) => {
+            | EventFilter::Or(_, _)
+            | EventFilter::Not(_) => {
                 return Err(SuiError::UserInputError {
                     error: UserInputError::Unsupported(
                         "This query type is not supported by the full node.".to_string(),
@@ -48,7 +48,9 @@ use typed_store::{
 
 use super::authority_store_tables::ENV_VAR_LOCKS_BLOCK_CACHE_SIZE;
 use super::epoch_start_configuration::EpochStartConfigTrait;
-use super::shared_object_congestion_tracker::SharedObjectCongestionTracker;
+use super::shared_object_congestion_tracker::{
+    estimate_execution_cost, SharedObjectCongestionTracker,
+};
 use crate::authority::epoch_start_configuration::{EpochFlag, EpochStartConfiguration};
 use crate::authority::AuthorityMetrics;
 use crate::authority::ResolverWrapper;
@@ -65,7 +67,8 @@ use crate::consensus_handler::{
     SequencedConsensusTransactionKind, VerifiedSequencedConsensusTransaction,
 };
 use crate::epoch::epoch_metrics::EpochMetrics;
-use crate::epoch::randomness::{DkgStatus, RandomnessManager, RandomnessReporter};
+use crate::epoch::randomness::{DkgStatus, RandomnessManager, RandomnessReporter, SINGLETON_KEY};
+use crate::epoch::randomness_verifier::DkgTranscript;
 use crate::epoch::reconfiguration::ReconfigState;
 use crate::execution_cache::{ExecutionCache, ExecutionCacheRead};
 use crate::module_cache_metrics::ResolverMetrics;
@@ -83,10 +86,11 @@ use sui_execution::{self, Executor};
 use sui_macros::fail_point;
 use sui_protocol_config::{Chain, PerObjectCongestionControlMode, ProtocolConfig, ProtocolVersion};
 use sui_storage::mutex_table::{MutexGuard, MutexTable};
-use sui_types::effects::TransactionEffects;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
 use sui_types::executable_transaction::{
     TrustedExecutableTransaction, VerifiedExecutableTransaction,
 };
+use sui_types::execution_status::{CongestedObjects, CongestionCancellation};
 use sui_types::message_envelope::TrustedEnvelope;
 use sui_types::messages_checkpoint::{
     CheckpointContents, CheckpointSequenceNumber, CheckpointSignatureMessage, CheckpointSummary,
@@ -467,6 +471,10 @@ pub struct AuthorityEpochTables {
     /// Transactions that are being deferred until some future time
     deferred_transactions: DBMap<DeferralKey, Vec<VerifiedSequencedConsensusTransaction>>,
 
+    /// Transactions that consensus cancelled instead of deferring them again, because they have
+    /// been deferred for too long due to shared object congestion.
+    congestion_cancelled_transactions: DBMap<TransactionDigest, CongestionCancellation>,
+
     /// This table is no longer used (can be removed when DBMap supports removing tables)
     #[allow(dead_code)]
     randomness_rounds_written: DBMap<narwhal_types::RandomnessRound, ()>,
@@ -669,8 +677,11 @@ async fn test_fetching_deferred_txs() {
 enum DeferralReason {
     RandomnessNotReady,
 
-    // The list of objects are congested objects.
-    SharedObjectCongestion(Vec<ObjectID>),
+    SharedObjectCongestion {
+        congested_objects: Vec<ObjectID>,
+        // A gas price likely to get the transaction scheduled in the next commit.
+        suggested_gas_price: u64,
+    },
 }
 
 fn signed_transactions_table_default_config() -> DBOptions {
@@ -726,6 +737,17 @@ impl AuthorityEpochTables {
         )
     }
 
+    /// Opens the tables as a secondary instance, for tools that use the accessors of the tables
+    /// and must not write to them.
+    pub fn open_secondary(epoch: EpochId, parent_path: &Path) -> Self {
+        Self::open_tables_secondary(
+            Self::path(epoch, parent_path),
+            None,
+            MetricConf::new("epoch_secondary"),
+            None,
+        )
+    }
+
     pub fn path(epoch: EpochId, parent_path: &Path) -> PathBuf {
         parent_path.join(format!("{}{}", EPOCH_DB_PREFIX, epoch))
     }
@@ -766,6 +788,30 @@ impl AuthorityEpochTables {
         Ok(self.last_consensus_stats.get(&LAST_CONSENSUS_STATS_ADDR)?)
     }
 
+    /// Returns the randomness DKG messages, confirmations and output recorded in this epoch, for
+    /// offline verification of its randomness.
+    pub fn get_dkg_transcript(&self) -> SuiResult<DkgTranscript> {
+        Ok(DkgTranscript {
+            messages: self
+                .dkg_processed_messages
+                .unbounded_iter()
+                .map(|(_, processed)| processed.message)
+                .collect(),
+            used_messages: self.dkg_used_messages.get(&SINGLETON_KEY)?.map(|used| {
+                used.0
+                    .into_iter()
+                    .map(|processed| processed.message)
+                    .collect()
+            }),
+            confirmations: self
+                .dkg_confirmations
+                .unbounded_iter()
+                .map(|(_, confirmation)| confirmation)
+                .collect(),
+            output: self.dkg_output.get(&SINGLETON_KEY)?,
+        })
+    }
+
     pub fn get_pending_checkpoint_signatures_iter(
         &self,
         checkpoint_seq: CheckpointSequenceNumber,
@@ -1765,26 +1811,41 @@ impl AuthorityPerEpochStore {
         }
 
         // Defer transaction if it uses shared objects that are congested.
+        let tx_cost = self.transaction_execution_cost(cert)?;
+        if let Some((deferral_key, congested_objects)) = shared_object_congestion_tracker
+            .should_defer_due_to_object_congestion(
+                cert,
+                tx_cost,
+                self.protocol_config()
+                    .max_accumulated_txn_cost_per_object_in_checkpoint(),
+                previously_deferred_tx_digests,
+                commit_round,
+            )
+        {
+            let suggested_gas_price = shared_object_congestion_tracker
+                .suggested_gas_price(&congested_objects, cert.transaction_data().gas_price());
+            Some((
+                deferral_key,
+                DeferralReason::SharedObjectCongestion {
+                    congested_objects,
+                    suggested_gas_price,
+                },
+            ))
+        } else {
+            None
+        }
+    }
+
+    // Returns the execution cost that `cert` adds to the shared objects it uses, or None if
+    // per object congestion control is disabled.
+    fn transaction_execution_cost(&self, cert: &VerifiedExecutableTransaction) -> Option<u64> {
         match self.protocol_config().per_object_congestion_control_mode() {
             PerObjectCongestionControlMode::None => None,
-            PerObjectCongestionControlMode::TotalGasBudget => {
-                if let Some((deferral_key, congested_objects)) = shared_object_congestion_tracker
-                    .should_defer_due_to_object_congestion(
-                        cert,
-                        self.protocol_config()
-                            .max_accumulated_txn_cost_per_object_in_checkpoint(),
-                        previously_deferred_tx_digests,
-                        commit_round,
-                    )
-                {
-                    Some((
-                        deferral_key,
-                        DeferralReason::SharedObjectCongestion(congested_objects),
-                    ))
-                } else {
-                    None
-                }
-            }
+            PerObjectCongestionControlMode::TotalGasBudget => Some(cert.gas_budget()),
+            PerObjectCongestionControlMode::ExecutionCostEstimate => Some(estimate_execution_cost(
+                cert.transaction_data(),
+                self.protocol_config(),
+            )),
         }
     }
 
@@ -1795,17 +1856,47 @@ impl AuthorityPerEpochStore {
         cert: &VerifiedExecutableTransaction,
         shared_object_congestion_tracker: &mut SharedObjectCongestionTracker,
     ) {
-        match self.protocol_config().per_object_congestion_control_mode() {
-            PerObjectCongestionControlMode::None => {}
-            PerObjectCongestionControlMode::TotalGasBudget => {
-                shared_object_congestion_tracker.bump_object_execution_cost(
-                    &cert.shared_input_objects().collect::<Vec<_>>(),
-                    cert.gas_budget(),
-                );
-            }
+        if let Some(tx_cost) = self.transaction_execution_cost(cert) {
+            let shared_input_objects = cert.shared_input_objects().collect::<Vec<_>>();
+            shared_object_congestion_tracker
+                .bump_object_execution_cost(&shared_input_objects, tx_cost);
+            shared_object_congestion_tracker.record_scheduled_gas_price(
+                &shared_input_objects,
+                cert.transaction_data().gas_price(),
+            );
         }
     }
 
+    // Returns true if a transaction deferred with `deferral_key` due to shared object congestion
+    // has been deferred for too long, and must be cancelled instead of deferred again. The executor
+    // is chosen by the protocol config, so this is deterministic across validators.
+    fn should_cancel_due_to_congestion(
+        &self,
+        deferral_key: &DeferralKey,
+        commit_round: Round,
+    ) -> bool {
+        self.executor().supports_congestion_cancellation()
+            && self
+                .protocol_config()
+                .max_deferral_rounds_for_congestion_control_as_option()
+                .is_some_and(|max_deferral_rounds| {
+                    commit_round.saturating_sub(deferral_key.deferred_from_round())
+                        >= max_deferral_rounds
+                })
+    }
+
+    /// Returns the cancellation of `digest` if consensus cancelled it due to shared object
+    /// congestion, in which case it must fail without being executed.
+    pub fn get_congestion_cancellation(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<CongestionCancellation>> {
+        Ok(self
+            .tables()?
+            .congestion_cancelled_transactions
+            .get(digest)?)
+    }
+
     /// Lock a sequence number for the shared objects of the input transaction based on the effects
     /// of that transaction.
     /// Used by full nodes who don't listen to consensus, and validators who catch up by state sync.
@@ -1826,6 +1917,15 @@ impl AuthorityPerEpochStore {
         let mut db_batch = self.tables()?.assigned_shared_object_versions.batch();
         self.set_assigned_shared_object_versions_with_db_batch(versions, &mut db_batch)
             .await?;
+        // The transaction was cancelled by consensus. Record the cancellation so that executing
+        // it produces the same effects.
+        if let Some(cancellation) = CongestionCancellation::from_execution_status(effects.status())
+        {
+            db_batch.insert_batch(
+                &self.tables()?.congestion_cancelled_transactions,
+                [(*certificate.digest(), cancellation)],
+            )?;
+        }
         db_batch.write()?;
         Ok(())
     }
@@ -3207,15 +3307,37 @@ impl AuthorityPerEpochStore {
                 );
 
                 if let Some((deferral_key, deferral_reason)) = deferral_info {
-                    debug!(
-                        "Deferring consensus certificate for transaction {:?} until {deferral_key:?}",
-                        certificate.digest(),
-                    );
-                    if let DeferralReason::SharedObjectCongestion(_) = deferral_reason {
+                    if let DeferralReason::SharedObjectCongestion {
+                        congested_objects,
+                        suggested_gas_price,
+                    } = deferral_reason
+                    {
                         authority_metrics
                             .consensus_handler_congested_transactions
                             .inc();
+                        if self.should_cancel_due_to_congestion(&deferral_key, commit_round) {
+                            debug!(
+                                "Cancelling consensus certificate for transaction {:?} due to congestion on {congested_objects:?}",
+                                certificate.digest(),
+                            );
+                            // The transaction is scheduled, but fails without being executed.
+                            batch.insert_batch(
+                                &self.tables()?.congestion_cancelled_transactions,
+                                [(
+                                    *certificate.digest(),
+                                    CongestionCancellation {
+                                        congested_objects: CongestedObjects(congested_objects),
+                                        suggested_gas_price,
+                                    },
+                                )],
+                            )?;
+                            return Ok(ConsensusCertificateResult::SuiTransaction(certificate));
+                        }
                     }
+                    debug!(
+                        "Deferring consensus certificate for transaction {:?} until {deferral_key:?}",
+                        certificate.digest(),
+                    );
                     return Ok(ConsensusCertificateResult::Deferred(deferral_key));
                 }
 
@@ -151,6 +151,17 @@ impl AuthorityPerpetualTables {
         )
     }
 
+    /// Opens the tables as a secondary instance, for tools that use the accessors of the tables
+    /// and must not write to them.
+    pub fn open_secondary(parent_path: &Path) -> Self {
+        Self::open_tables_secondary(
+            Self::path(parent_path),
+            None,
+            MetricConf::new("perpetual_secondary"),
+            None,
+        )
+    }
+
     // This is used by indexer to find the correct version of dynamic field child object.
     // We do not store the version of the child object, but because of lamport timestamp,
     // we know the child must have version number less then or eq to the parent.
@@ -321,6 +332,13 @@ impl AuthorityPerpetualTables {
         Ok(Some(transaction))
     }
 
+    pub fn multi_get_transactions(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<Option<TrustedTransaction>>> {
+        Ok(self.transactions.multi_get(digests)?)
+    }
+
     pub fn get_effects(&self, digest: &TransactionDigest) -> SuiResult<Option<TransactionEffects>> {
         let Some(effect_digest) = self.executed_effects.get(digest)? else {
             return Ok(None);
@@ -328,6 +346,46 @@ impl AuthorityPerpetualTables {
         Ok(self.effects.get(&effect_digest)?)
     }
 
+    pub fn get_executed_effects_digest(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<TransactionEffectsDigest>> {
+        Ok(self.executed_effects.get(digest)?)
+    }
+
+    pub fn get_effects_by_digest(
+        &self,
+        digest: &TransactionEffectsDigest,
+    ) -> SuiResult<Option<TransactionEffects>> {
+        Ok(self.effects.get(digest)?)
+    }
+
+    /// Overwrites the transaction and effects of an already certified transaction with known good
+    /// copies, e.g. recovered from the archive after the local copies were lost or corrupted.
+    /// `executed` controls whether the transaction is also marked as executed by this node.
+    pub fn repair_transaction_and_effects(
+        &self,
+        transaction: &VerifiedTransaction,
+        effects: &TransactionEffects,
+        executed: bool,
+    ) -> SuiResult {
+        let effects_digest = effects.digest();
+        let mut wb = self.transactions.batch();
+        wb.insert_batch(
+            &self.transactions,
+            [(transaction.digest(), transaction.serializable_ref())],
+        )?
+        .insert_batch(&self.effects, [(effects_digest, effects)])?;
+        if executed {
+            wb.insert_batch(
+                &self.executed_effects,
+                [(transaction.digest(), effects_digest)],
+            )?;
+        }
+        wb.write()?;
+        Ok(())
+    }
+
     // DEPRECATED as the backing table has been moved to authority_per_epoch_store.
     // Please do not add new accessors/callsites.
     pub fn get_checkpoint_sequence_number(
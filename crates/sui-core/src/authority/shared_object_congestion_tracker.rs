@@ -4,9 +4,48 @@
 use crate::authority::authority_per_epoch_store::DeferralKey;
 use narwhal_types::Round;
 use std::collections::HashMap;
+use sui_protocol_config::ProtocolConfig;
 use sui_types::base_types::{ObjectID, TransactionDigest};
 use sui_types::executable_transaction::VerifiedExecutableTransaction;
-use sui_types::transaction::SharedInputObject;
+use sui_types::transaction::{
+    Command, SharedInputObject, TransactionData, TransactionDataAPI, TransactionKind,
+};
+
+// Estimates the execution cost of a transaction, in the unit of its gas budget, from the number of
+// Move calls it makes. The estimate only depends on the transaction and the protocol config, so
+// all validators agree on it.
+//
+// Transactions that aren't programmable, or protocol configs without cost estimates, fall back to
+// the gas budget as the cost. The estimate never exceeds the gas budget, which bounds the cost of
+// the transaction anyway.
+pub fn estimate_execution_cost(
+    transaction: &TransactionData,
+    protocol_config: &ProtocolConfig,
+) -> u64 {
+    let gas_budget = transaction.gas_budget();
+    let (
+        TransactionKind::ProgrammableTransaction(programmable),
+        Some(base_cost_units),
+        Some(move_call_cost_units),
+    ) = (
+        transaction.kind(),
+        protocol_config.congestion_control_base_tx_cost_units_as_option(),
+        protocol_config.congestion_control_move_call_cost_units_as_option(),
+    )
+    else {
+        return gas_budget;
+    };
+    let num_move_calls = programmable
+        .commands
+        .iter()
+        .filter(|command| matches!(command, Command::MoveCall(_)))
+        .count() as u64;
+    move_call_cost_units
+        .saturating_mul(num_move_calls)
+        .saturating_add(base_cost_units)
+        .saturating_mul(transaction.gas_price())
+        .min(gas_budget)
+}
 
 // SharedObjectCongestionTracker stores the accumulated cost of executing transactions on an object, for
 // all transactions in a consensus commit.
@@ -20,6 +59,8 @@ use sui_types::transaction::SharedInputObject;
 #[derive(Default, PartialEq, Eq, Clone, Debug)]
 pub struct SharedObjectCongestionTracker {
     object_execution_cost: HashMap<ObjectID, u64>,
+    // The highest gas price of the transactions scheduled on each object in this consensus commit.
+    object_max_gas_price: HashMap<ObjectID, u64>,
 }
 
 impl SharedObjectCongestionTracker {
@@ -30,6 +71,7 @@ impl SharedObjectCongestionTracker {
         }
         Self {
             object_execution_cost,
+            object_max_gas_price: HashMap::new(),
         }
     }
 
@@ -46,17 +88,19 @@ impl SharedObjectCongestionTracker {
             .expect("There must be at least one object in shared_input_objects.")
     }
 
-    // Given a transaction, returns the deferral key and the congested objects if the transaction should be deferred.
+    // Given a transaction and its execution cost, returns the deferral key and the congested objects if the
+    // transaction should be deferred.
     pub fn should_defer_due_to_object_congestion(
         &self,
         cert: &VerifiedExecutableTransaction,
+        tx_cost: u64,
         max_accumulated_txn_cost_per_object_in_checkpoint: u64,
         previously_deferred_tx_digests: &HashMap<TransactionDigest, DeferralKey>,
         commit_round: Round,
     ) -> Option<(DeferralKey, Vec<ObjectID>)> {
         let shared_input_objects: Vec<_> = cert.shared_input_objects().collect();
         let start_cost = self.compute_tx_start_at_cost(&shared_input_objects);
-        if start_cost + cert.gas_budget() <= max_accumulated_txn_cost_per_object_in_checkpoint {
+        if start_cost + tx_cost <= max_accumulated_txn_cost_per_object_in_checkpoint {
             return None;
         }
 
@@ -69,11 +113,10 @@ impl SharedObjectCongestionTracker {
         // object A, it may be shown up as congested objects.
         let mut congested_objects = vec![];
         for obj in shared_input_objects {
-            // TODO: right now, we only return objects that are on the execution critical path in this consensus commit.
-            // However, for objects that are no on the critical path, they may potentially also be congested (e.g., an
-            // object has start cost == start_cost - 1, and adding the gas budget will exceed the limit). We don't
-            // return them for now because it's unclear how they can be used to return suggested gas price for the
-            // user. We need to revisit this later once we have a clear idea of how to determine the suggested gas price.
+            // Only objects on the execution critical path in this consensus commit are returned. Objects
+            // that are not on the critical path may also be congested (e.g., an object has start
+            // cost == start_cost - 1, and adding the transaction cost will exceed the limit), but
+            // re-pricing the transaction for them would not get it scheduled any sooner.
             if &start_cost == self.object_execution_cost.get(&obj.id).unwrap_or(&0) {
                 congested_objects.push(obj.id);
             }
@@ -96,6 +139,32 @@ impl SharedObjectCongestionTracker {
         Some((deferral_key, congested_objects))
     }
 
+    // Returns a gas price likely to get a transaction with `gas_price`, deferred because of
+    // `congested_objects`, scheduled in the next consensus commit: one above the highest gas price
+    // of the transactions scheduled on these objects in this commit. This assumes consensus orders
+    // transactions by gas price.
+    pub fn suggested_gas_price(&self, congested_objects: &[ObjectID], gas_price: u64) -> u64 {
+        congested_objects
+            .iter()
+            .filter_map(|id| self.object_max_gas_price.get(id))
+            .max()
+            .map_or(gas_price, |max_gas_price| {
+                max_gas_price.saturating_add(1).max(gas_price)
+            })
+    }
+
+    // Records the gas price of a transaction scheduled on `shared_input_objects`.
+    pub fn record_scheduled_gas_price(
+        &mut self,
+        shared_input_objects: &[SharedInputObject],
+        gas_price: u64,
+    ) {
+        for obj in shared_input_objects {
+            let max_gas_price = self.object_max_gas_price.entry(obj.id).or_default();
+            *max_gas_price = (*max_gas_price).max(gas_price);
+        }
+    }
+
     pub fn bump_object_execution_cost(
         &mut self,
         shared_input_objects: &[SharedInputObject],
@@ -117,9 +186,11 @@ impl SharedObjectCongestionTracker {
 mod object_cost_tests {
     use super::*;
 
+    use move_core_types::identifier::Identifier;
     use sui_test_transaction_builder::TestTransactionBuilder;
-    use sui_types::base_types::{random_object_ref, SequenceNumber};
+    use sui_types::base_types::{random_object_ref, SequenceNumber, SuiAddress};
     use sui_types::crypto::{get_key_pair, AccountKeyPair};
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
     use sui_types::transaction::{CallArg, ObjectArg, TransactionDataAPI, VerifiedTransaction};
 
     fn construct_shared_input_objects(objects: &[(ObjectID, bool)]) -> Vec<SharedInputObject> {
@@ -238,6 +309,7 @@ mod object_cost_tests {
             if let Some((_, congested_objects)) = shared_object_congestion_tracker
                 .should_defer_due_to_object_congestion(
                     &tx,
+                    tx.gas_budget(),
                     max_accumulated_txn_cost_per_object_in_checkpoint,
                     &HashMap::new(),
                     0,
@@ -256,6 +328,7 @@ mod object_cost_tests {
             assert!(shared_object_congestion_tracker
                 .should_defer_due_to_object_congestion(
                     &tx,
+                    tx.gas_budget(),
                     max_accumulated_txn_cost_per_object_in_checkpoint,
                     &HashMap::new(),
                     0,
@@ -271,6 +344,7 @@ mod object_cost_tests {
                 if let Some((_, congested_objects)) = shared_object_congestion_tracker
                     .should_defer_due_to_object_congestion(
                         &tx,
+                        tx.gas_budget(),
                         max_accumulated_txn_cost_per_object_in_checkpoint,
                         &HashMap::new(),
                         0,
@@ -312,6 +386,7 @@ mod object_cost_tests {
             _,
         )) = shared_object_congestion_tracker.should_defer_due_to_object_congestion(
             &tx,
+            tx.gas_budget(),
             max_accumulated_txn_cost_per_object_in_checkpoint,
             &previously_deferred_tx_digests,
             10,
@@ -339,6 +414,7 @@ mod object_cost_tests {
             _,
         )) = shared_object_congestion_tracker.should_defer_due_to_object_congestion(
             &tx,
+            tx.gas_budget(),
             max_accumulated_txn_cost_per_object_in_checkpoint,
             &previously_deferred_tx_digests,
             10,
@@ -367,6 +443,7 @@ mod object_cost_tests {
             _,
         )) = shared_object_congestion_tracker.should_defer_due_to_object_congestion(
             &tx,
+            tx.gas_budget(),
             max_accumulated_txn_cost_per_object_in_checkpoint,
             &previously_deferred_tx_digests,
             10,
@@ -430,4 +507,99 @@ mod object_cost_tests {
             ])
         );
     }
+
+    #[test]
+    fn test_suggested_gas_price() {
+        let object_id_0 = ObjectID::random();
+        let object_id_1 = ObjectID::random();
+        let object_id_2 = ObjectID::random();
+
+        let mut shared_object_congestion_tracker = SharedObjectCongestionTracker::default();
+        shared_object_congestion_tracker.record_scheduled_gas_price(
+            &construct_shared_input_objects(&[(object_id_0, true), (object_id_1, false)]),
+            1000,
+        );
+        shared_object_congestion_tracker.record_scheduled_gas_price(
+            &construct_shared_input_objects(&[(object_id_1, true)]),
+            2000,
+        );
+
+        // Suggest outbidding the most expensive transaction scheduled on the congested objects.
+        assert_eq!(
+            shared_object_congestion_tracker.suggested_gas_price(&[object_id_0], 500),
+            1001
+        );
+        assert_eq!(
+            shared_object_congestion_tracker.suggested_gas_price(&[object_id_0, object_id_1], 500),
+            2001
+        );
+
+        // Never suggest lowering the gas price.
+        assert_eq!(
+            shared_object_congestion_tracker.suggested_gas_price(&[object_id_0], 5000),
+            5000
+        );
+
+        // Without scheduled transactions on the congested objects, keep the gas price.
+        assert_eq!(
+            shared_object_congestion_tracker.suggested_gas_price(&[object_id_2], 500),
+            500
+        );
+    }
+
+    #[test]
+    fn test_estimate_execution_cost() {
+        let (sender, _): (_, AccountKeyPair) = get_key_pair();
+        let gas_price = 1000;
+        let builder = || TestTransactionBuilder::new(sender, random_object_ref(), gas_price);
+        let move_calls = |num_move_calls| {
+            let mut ptb = ProgrammableTransactionBuilder::new();
+            for _ in 0..num_move_calls {
+                ptb.programmable_move_call(
+                    ObjectID::random(),
+                    Identifier::new("unimportant_module").unwrap(),
+                    Identifier::new("unimportant_function").unwrap(),
+                    vec![],
+                    vec![],
+                );
+            }
+            builder().programmable(ptb.finish()).build()
+        };
+        let transfer = builder()
+            .transfer_sui(None, SuiAddress::random_for_testing_only())
+            .build();
+
+        // Without cost estimates in the protocol config, the gas budget is the cost.
+        let mut protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+        for transaction in [move_calls(1), transfer.clone()] {
+            assert_eq!(
+                estimate_execution_cost(&transaction, &protocol_config),
+                transaction.gas_budget()
+            );
+        }
+
+        // Each Move call adds to the base cost of the transaction.
+        protocol_config.set_congestion_control_base_tx_cost_units_for_testing(1_000);
+        protocol_config.set_congestion_control_move_call_cost_units_for_testing(5_000);
+        assert_eq!(
+            estimate_execution_cost(&transfer, &protocol_config),
+            1_000 * gas_price
+        );
+        assert_eq!(
+            estimate_execution_cost(&move_calls(1), &protocol_config),
+            6_000 * gas_price
+        );
+        assert_eq!(
+            estimate_execution_cost(&move_calls(3), &protocol_config),
+            16_000 * gas_price
+        );
+
+        // The estimate is capped by the gas budget.
+        protocol_config.set_congestion_control_move_call_cost_units_for_testing(u64::MAX);
+        let transaction = move_calls(2);
+        assert_eq!(
+            estimate_execution_cost(&transaction, &protocol_config),
+            transaction.gas_budget()
+        );
+    }
 }
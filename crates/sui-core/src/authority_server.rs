@@ -11,7 +11,12 @@ use prometheus::{
     register_int_counter_vec_with_registry, register_int_counter_with_registry, IntCounter,
     IntCounterVec, Registry,
 };
-use std::{io, net::SocketAddr, sync::Arc, time::SystemTime};
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::SystemTime,
+};
 use sui_network::{
     api::{Validator, ValidatorServer},
     tonic,
@@ -42,7 +47,7 @@ use crate::{
     authority::AuthorityState,
     consensus_adapter::{ConsensusAdapter, ConsensusAdapterMetrics},
     traffic_controller::policies::TrafficTally,
-    traffic_controller::TrafficController,
+    traffic_controller::{access_lists::TrafficAccessLists, TrafficController},
 };
 use crate::{
     consensus_adapter::ConnectionMonitorStatusForTests,
@@ -289,17 +294,47 @@ impl ValidatorService {
         traffic_controller_metrics: TrafficControllerMetrics,
         policy_config: Option<PolicyConfig>,
         firewall_config: Option<RemoteFirewallConfig>,
+    ) -> Self {
+        Self::new_with_access_lists(
+            state,
+            consensus_adapter,
+            validator_metrics,
+            traffic_controller_metrics,
+            policy_config,
+            firewall_config,
+            None,
+        )
+    }
+
+    /// Creates a validator service whose traffic controller uses `access_lists`, if any, instead
+    /// of loading its own from `policy_config`.
+    pub fn new_with_access_lists(
+        state: Arc<AuthorityState>,
+        consensus_adapter: Arc<ConsensusAdapter>,
+        validator_metrics: Arc<ValidatorServiceMetrics>,
+        traffic_controller_metrics: TrafficControllerMetrics,
+        policy_config: Option<PolicyConfig>,
+        firewall_config: Option<RemoteFirewallConfig>,
+        access_lists: Option<TrafficAccessLists>,
     ) -> Self {
         Self {
             state,
             consensus_adapter,
             metrics: validator_metrics,
             traffic_controller: policy_config.map(|policy| {
-                Arc::new(TrafficController::spawn(
-                    policy,
-                    traffic_controller_metrics,
-                    firewall_config,
-                ))
+                Arc::new(match access_lists {
+                    Some(access_lists) => TrafficController::spawn_with_access_lists(
+                        policy,
+                        traffic_controller_metrics,
+                        firewall_config,
+                        access_lists,
+                    ),
+                    None => TrafficController::spawn(
+                        policy,
+                        traffic_controller_metrics,
+                        firewall_config,
+                    ),
+                })
             }),
         }
     }
@@ -334,6 +369,7 @@ impl ValidatorService {
             metrics,
             traffic_controller: _,
         } = self.clone();
+        let client = request_client_ip(&state, &request);
         let transaction = request.into_inner();
         let epoch_store = state.load_epoch_store_one_call_per_task();
 
@@ -353,6 +389,7 @@ impl ValidatorService {
         let overload_check_res = state.check_system_overload(
             &consensus_adapter,
             transaction.data(),
+            client,
             state.check_system_overload_at_signing(),
         );
         if let Err(error) = overload_check_res {
@@ -405,6 +442,7 @@ impl ValidatorService {
         request: HandleCertificateRequestV3,
         epoch_store: &Arc<AuthorityPerEpochStore>,
         wait_for_effects: bool,
+        client: Option<IpAddr>,
     ) -> Result<Option<HandleCertificateResponseV3>, tonic::Status> {
         let certificate = request.certificate;
 
@@ -463,6 +501,7 @@ impl ValidatorService {
         let overload_check_res = self.state.check_system_overload(
             &self.consensus_adapter,
             certificate.data(),
+            client,
             self.state.check_system_overload_at_execution(),
         );
         if let Err(error) = overload_check_res {
@@ -574,6 +613,7 @@ impl ValidatorService {
         request: tonic::Request<CertifiedTransaction>,
     ) -> Result<tonic::Response<SubmitCertificateResponse>, tonic::Status> {
         let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let client = request_client_ip(&self.state, &request);
         let certificate = request.into_inner();
         // CRITICAL: DO NOT ADD ANYTHING BEFORE THIS CHECK.
         // This must be the first thing to check before anything else, because the transaction
@@ -589,7 +629,7 @@ impl ValidatorService {
             include_output_objects: false,
             include_auxiliary_data: false,
         };
-        self.handle_certificate(request, &epoch_store, false)
+        self.handle_certificate(request, &epoch_store, false, client)
             .instrument(span)
             .await
             .map(|executed| {
@@ -604,6 +644,7 @@ impl ValidatorService {
         request: tonic::Request<CertifiedTransaction>,
     ) -> Result<tonic::Response<HandleCertificateResponseV2>, tonic::Status> {
         let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let client = request_client_ip(&self.state, &request);
         let certificate = request.into_inner();
         // CRITICAL: DO NOT ADD ANYTHING BEFORE THIS CHECK.
         // This must be the first thing to check before anything else, because the transaction
@@ -619,7 +660,7 @@ impl ValidatorService {
             include_output_objects: false,
             include_auxiliary_data: false,
         };
-        self.handle_certificate(request, &epoch_store, true)
+        self.handle_certificate(request, &epoch_store, true, client)
             .instrument(span)
             .await
             .map(|v| {
@@ -637,6 +678,7 @@ impl ValidatorService {
         request: tonic::Request<HandleCertificateRequestV3>,
     ) -> Result<tonic::Response<HandleCertificateResponseV3>, tonic::Status> {
         let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let client = request_client_ip(&self.state, &request);
         let request = request.into_inner();
         // CRITICAL: DO NOT ADD ANYTHING BEFORE THIS CHECK.
         // This must be the first thing to check before anything else, because the transaction
@@ -645,7 +687,7 @@ impl ValidatorService {
         Self::transaction_validity_check(&epoch_store, request.certificate.data())?;
         let span = error_span!("handle_certificate_v3", tx_digest = ?request.certificate.digest());
 
-        self.handle_certificate(request, &epoch_store, true)
+        self.handle_certificate(request, &epoch_store, true, client)
             .instrument(span)
             .await
             .map(|v| {
@@ -787,6 +829,22 @@ impl ValidatorService {
     }
 }
 
+// Returns the client of `request` for client aware load shedding. The `x-forwarded-for` header is
+// only trusted from the configured proxies. Malformed headers are ignored here, they are reported
+// by `handle_with_decoration`.
+fn request_client_ip<T>(state: &AuthorityState, request: &tonic::Request<T>) -> Option<IpAddr> {
+    let proxy_ip = request
+        .metadata()
+        .get("x-forwarded-for")
+        .and_then(|op| op.to_str().ok())
+        .and_then(|ip| ip.parse::<SocketAddr>().ok())
+        .map(|ip| ip.ip());
+    state
+        .overload_info
+        .client_load
+        .client_identity(request.remote_addr().map(|ip| ip.ip()), proxy_ip)
+}
+
 fn make_tonic_request_for_testing<T>(message: T) -> tonic::Request<T> {
     // simulate a TCP connection, which would have added extensions to
     // the request object that would be used downstream
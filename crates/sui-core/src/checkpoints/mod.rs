@@ -204,6 +204,17 @@ impl CheckpointStore {
         )
     }
 
+    /// Opens the store as a secondary instance, for tools that use the accessors of the store and
+    /// must not write to it.
+    pub fn open_secondary(path: &Path) -> Arc<Self> {
+        Arc::new(Self::open_tables_secondary(
+            path.to_path_buf(),
+            None,
+            MetricConf::new("checkpoint_secondary"),
+            None,
+        ))
+    }
+
     #[instrument(level = "info", skip_all)]
     pub fn insert_genesis_checkpoint(
         &self,
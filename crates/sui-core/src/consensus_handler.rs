@@ -507,12 +507,17 @@ impl MysticetiConsensusHandler {
     pub fn new(
         mut consensus_handler: ConsensusHandler<CheckpointService>,
         mut receiver: tokio::sync::mpsc::UnboundedReceiver<consensus_core::CommittedSubDag>,
+        commit_consumer_monitor: Arc<consensus_core::CommitConsumerMonitor>,
     ) -> Self {
         let handle = spawn_monitored_task!(async move {
             while let Some(committed_subdag) = receiver.recv().await {
+                let commit_index = committed_subdag.commit_index;
                 consensus_handler
                     .handle_consensus_output_internal(committed_subdag)
                     .await;
+                // The commit is persisted with the consensus stats, so consensus can garbage
+                // collect it.
+                commit_consumer_monitor.set_highest_handled_commit(commit_index);
             }
         });
         Self {
@@ -1044,6 +1049,91 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_order_by_gas_price_fair_by_sender() {
+        let (a, b, c) = (1, 2, 3);
+        let mut v = vec![
+            sender_txn(a, 100),
+            sender_txn(a, 100),
+            sender_txn(a, 100),
+            cap_txn(10),
+            sender_txn(b, 100),
+            sender_txn(c, 1000),
+            sender_txn(a, 1000),
+            sender_txn(b, 100),
+            sender_txn(c, 100),
+            cap_txn(1),
+        ];
+        PostConsensusTxReorder::reorder(
+            &mut v,
+            ConsensusTransactionOrdering::ByGasPriceFairBySender,
+        );
+        assert_eq!(
+            v.into_iter().map(extract_with_sender).collect::<Vec<_>>(),
+            vec![
+                "cap(10)".to_string(),
+                "cap(1)".to_string(),
+                // The higher gas price lane first, in consensus order within the same gas price.
+                "user(1000) from 3".to_string(),
+                "user(1000) from 1".to_string(),
+                // Then the lower lane, one transaction of each sender in each round.
+                "user(100) from 1".to_string(),
+                "user(100) from 2".to_string(),
+                "user(100) from 3".to_string(),
+                "user(100) from 1".to_string(),
+                "user(100) from 2".to_string(),
+                "user(100) from 1".to_string(),
+            ]
+        );
+
+        // Bumping the gas price within a lane doesn't get a sender's extra transactions ahead of
+        // the others.
+        let mut v = vec![
+            sender_txn(a, 101),
+            sender_txn(a, 101),
+            sender_txn(a, 101),
+            sender_txn(b, 100),
+            sender_txn(c, 100),
+        ];
+        PostConsensusTxReorder::reorder(
+            &mut v,
+            ConsensusTransactionOrdering::ByGasPriceFairBySender,
+        );
+        assert_eq!(
+            v.into_iter().map(extract_with_sender).collect::<Vec<_>>(),
+            vec![
+                "user(101) from 1".to_string(),
+                "user(100) from 2".to_string(),
+                "user(100) from 3".to_string(),
+                "user(101) from 1".to_string(),
+                "user(101) from 1".to_string(),
+            ]
+        );
+
+        // Paying enough for a higher lane puts all of a sender's transactions in it first.
+        let mut v = vec![
+            sender_txn(b, 100),
+            sender_txn(a, 200),
+            sender_txn(a, 200),
+            sender_txn(c, 100),
+            sender_txn(a, 200),
+        ];
+        PostConsensusTxReorder::reorder(
+            &mut v,
+            ConsensusTransactionOrdering::ByGasPriceFairBySender,
+        );
+        assert_eq!(
+            v.into_iter().map(extract_with_sender).collect::<Vec<_>>(),
+            vec![
+                "user(200) from 1".to_string(),
+                "user(200) from 1".to_string(),
+                "user(200) from 1".to_string(),
+                "user(100) from 2".to_string(),
+                "user(100) from 3".to_string(),
+            ]
+        );
+    }
+
     fn extract(v: Vec<VerifiedSequencedConsensusTransaction>) -> Vec<String> {
         v.into_iter().map(extract_one).collect()
     }
@@ -1066,6 +1156,17 @@ mod tests {
         }
     }
 
+    fn extract_with_sender(t: VerifiedSequencedConsensusTransaction) -> String {
+        let sender = match &t.0.transaction {
+            SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                kind: ConsensusTransactionKind::UserTransaction(txn),
+                ..
+            }) => txn.transaction_data().sender(),
+            _ => return extract_one(t),
+        };
+        format!("{} from {}", extract_one(t), sender.to_inner()[31])
+    }
+
     fn eop_txn(a: u8) -> VerifiedSequencedConsensusTransaction {
         let mut authority = AuthorityName::default();
         authority.0[0] = a;
@@ -1084,12 +1185,18 @@ mod tests {
     }
 
     fn user_txn(gas_price: u64) -> VerifiedSequencedConsensusTransaction {
+        sender_txn(0, gas_price)
+    }
+
+    fn sender_txn(sender: u8, gas_price: u64) -> VerifiedSequencedConsensusTransaction {
+        let mut address = [0; 32];
+        address[31] = sender;
         let (committee, keypairs) = Committee::new_simple_test_committee();
         let data = SenderSignedData::new(
             TransactionData::new_transfer(
                 SuiAddress::default(),
                 random_object_ref(),
-                SuiAddress::default(),
+                SuiAddress::from_bytes(address).unwrap(),
                 random_object_ref(),
                 1000 * gas_price,
                 gas_price,
@@ -9,6 +9,7 @@ use crate::consensus_validator::SuiTxValidator;
 use crate::mysticeti_adapter::LazyMysticetiClient;
 use arc_swap::ArcSwapOption;
 use async_trait::async_trait;
+use consensus_core::LiveDagInspector;
 use enum_dispatch::enum_dispatch;
 use fastcrypto::traits::KeyPair as _;
 use mysten_metrics::RegistryService;
@@ -151,6 +152,15 @@ impl ConsensusManager {
         self.consensus_config.db_path().to_path_buf()
     }
 
+    /// Returns an inspector of the consensus DAG, or None when Mysticeti is not the running
+    /// consensus protocol.
+    pub async fn dag_inspector(&self) -> Option<LiveDagInspector> {
+        match &self.mysticeti_manager {
+            ProtocolManager::Mysticeti(manager) => manager.dag_inspector().await,
+            ProtocolManager::Narwhal(_) => None,
+        }
+    }
+
     // Picks the consensus protocol based on the protocol config and the epoch.
     fn pick_protocol(&self, epoch_store: &AuthorityPerEpochStore) -> ConsensusProtocol {
         let protocol_config = epoch_store.protocol_config();
@@ -3,7 +3,7 @@
 
 use anemo::PeerId;
 use fastcrypto::encoding::{Encoding, Hex};
-use fastcrypto::error::FastCryptoError;
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
 use fastcrypto::groups::bls12381;
 use fastcrypto::serde_helpers::ToFromByteArray;
 use fastcrypto::traits::{KeyPair, ToFromBytes};
@@ -20,6 +20,7 @@ use std::sync::{Arc, Weak};
 use std::time::Instant;
 use sui_macros::fail_point_if;
 use sui_network::randomness;
+use sui_protocol_config::ProtocolConfig;
 use sui_types::base_types::AuthorityName;
 use sui_types::committee::{Committee, EpochId, StakeUnit};
 use sui_types::crypto::{AuthorityKeyPair, RandomnessRound};
@@ -39,7 +40,7 @@ use crate::consensus_adapter::ConsensusAdapter;
 type PkG = bls12381::G2Element;
 type EncG = bls12381::G2Element;
 
-const SINGLETON_KEY: u64 = 0;
+pub(crate) const SINGLETON_KEY: u64 = 0;
 
 // State machine for randomness DKG and generation.
 //
@@ -132,32 +133,14 @@ impl RandomnessManager {
                 (name, (peer_id, id))
             })
             .collect();
-        let nodes = info
-            .iter()
-            .map(|(id, _, pk, stake)| nodes::Node::<EncG> {
-                id: *id,
-                pk: pk.clone(),
-                weight: (*stake).try_into().expect("stake should fit in u16"),
-            })
-            .collect();
-        let (nodes, t) = match nodes::Nodes::new_reduced(
-            nodes,
-            committee
-                .validity_threshold()
-                .try_into()
-                .expect("validity threshold should fit in u16"),
-            protocol_config.random_beacon_reduction_allowed_delta(),
-            protocol_config
-                .random_beacon_reduction_lower_bound()
-                .try_into()
-                .expect("should fit u16"),
-        ) {
-            Ok((nodes, t)) => (nodes, t),
-            Err(err) => {
-                error!("random beacon: error while initializing Nodes: {err:?}");
-                return None;
-            }
-        };
+        let (nodes, t) =
+            match RandomnessManager::randomness_dkg_nodes(committee, protocol_config, &info) {
+                Ok((nodes, t)) => (nodes, t),
+                Err(err) => {
+                    error!("random beacon: error while initializing Nodes: {err:?}");
+                    return None;
+                }
+            };
         let total_weight = nodes.total_weight();
         let num_nodes = nodes.num_nodes();
         let prefix_str = format!(
@@ -639,7 +622,7 @@ impl RandomnessManager {
         self.epoch_store()?.tables()
     }
 
-    fn randomness_dkg_info_from_committee(
+    pub(crate) fn randomness_dkg_info_from_committee(
         committee: &Committee,
     ) -> Vec<(
         u16,
@@ -673,6 +656,40 @@ impl RandomnessManager {
             })
             .collect()
     }
+
+    // Returns the DKG nodes, with weights reduced according to the protocol config, and the
+    // threshold t of the reduced weights.
+    pub(crate) fn randomness_dkg_nodes(
+        committee: &Committee,
+        protocol_config: &ProtocolConfig,
+        info: &[(
+            u16,
+            AuthorityName,
+            fastcrypto_tbls::ecies::PublicKey<bls12381::G2Element>,
+            StakeUnit,
+        )],
+    ) -> FastCryptoResult<(nodes::Nodes<EncG>, u16)> {
+        let nodes = info
+            .iter()
+            .map(|(id, _, pk, stake)| nodes::Node::<EncG> {
+                id: *id,
+                pk: pk.clone(),
+                weight: (*stake).try_into().expect("stake should fit in u16"),
+            })
+            .collect();
+        nodes::Nodes::new_reduced(
+            nodes,
+            committee
+                .validity_threshold()
+                .try_into()
+                .expect("validity threshold should fit in u16"),
+            protocol_config.random_beacon_reduction_allowed_delta(),
+            protocol_config
+                .random_beacon_reduction_lower_bound()
+                .try_into()
+                .expect("should fit u16"),
+        )
+    }
 }
 
 // Used by other components to notify the randomness system of observed randomness.
@@ -727,9 +744,13 @@ mod tests {
             MockSubmitToConsensus,
         },
         epoch::randomness::*,
+        epoch::randomness_verifier::{RandomnessVerificationError, RandomnessVerifier},
     };
+    use fastcrypto_tbls::{tbls::ThresholdBls, types::ThresholdBls12381MinSig};
     use std::num::NonZeroUsize;
+    use sui_types::base_types::SequenceNumber;
     use sui_types::messages_consensus::ConsensusTransactionKind;
+    use sui_types::transaction::RandomnessStateUpdate;
     use tokio::sync::mpsc;
 
     #[tokio::test]
@@ -853,6 +874,173 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_randomness_verifier() {
+        telemetry_subscribers::init_for_testing();
+
+        let network_config =
+            sui_swarm_config::network_config_builder::ConfigBuilder::new_with_temp_dir()
+                .committee_size(NonZeroUsize::new(4).unwrap())
+                .with_reference_gas_price(500)
+                .build();
+
+        let mut epoch_stores = Vec::new();
+        let mut randomness_managers = Vec::new();
+        let (tx_consensus, mut rx_consensus) = mpsc::channel(100);
+
+        for validator in network_config.validator_configs.iter() {
+            // Send consensus messages to channel.
+            let mut mock_consensus_client = MockSubmitToConsensus::new();
+            let tx_consensus = tx_consensus.clone();
+            mock_consensus_client
+                .expect_submit_to_consensus()
+                .withf(move |transaction: &ConsensusTransaction, _epoch_store| {
+                    tx_consensus.try_send(transaction.clone()).unwrap();
+                    true
+                })
+                .returning(|_, _| Ok(()));
+
+            let state = TestAuthorityBuilder::new()
+                .with_genesis_and_keypair(&network_config.genesis, validator.protocol_key_pair())
+                .build()
+                .await;
+            let consensus_adapter = Arc::new(ConsensusAdapter::new(
+                Arc::new(mock_consensus_client),
+                state.name,
+                Arc::new(ConnectionMonitorStatusForTests {}),
+                100_000,
+                100_000,
+                None,
+                None,
+                ConsensusAdapterMetrics::new_test(),
+                state.epoch_store_for_testing().protocol_config().clone(),
+            ));
+            let epoch_store = state.epoch_store_for_testing();
+            let randomness_manager = RandomnessManager::try_new(
+                Arc::downgrade(&epoch_store),
+                consensus_adapter.clone(),
+                sui_network::randomness::Handle::new_stub(),
+                validator.protocol_key_pair(),
+            )
+            .await
+            .unwrap();
+
+            epoch_stores.push(epoch_store);
+            randomness_managers.push(randomness_manager);
+        }
+
+        // Generate and distribute Messages.
+        let mut dkg_messages = Vec::new();
+        for randomness_manager in randomness_managers.iter_mut() {
+            randomness_manager.start_dkg().unwrap();
+
+            let dkg_message = rx_consensus.recv().await.unwrap();
+            match dkg_message.kind {
+                ConsensusTransactionKind::RandomnessDkgMessage(_, bytes) => {
+                    let msg: fastcrypto_tbls::dkg::Message<PkG, EncG> = bcs::from_bytes(&bytes)
+                        .expect("DKG message deserialization should not fail");
+                    dkg_messages.push(msg);
+                }
+                _ => panic!("wrong type of message sent"),
+            }
+        }
+        for i in 0..randomness_managers.len() {
+            let mut batch = epoch_stores[i]
+                .tables()
+                .unwrap()
+                .dkg_processed_messages
+                .batch();
+            for (j, dkg_message) in dkg_messages.iter().cloned().enumerate() {
+                randomness_managers[i]
+                    .add_message(&epoch_stores[j].name, dkg_message)
+                    .unwrap();
+            }
+            randomness_managers[i]
+                .advance_dkg(&mut batch, 0)
+                .await
+                .unwrap();
+            batch.write().unwrap();
+        }
+
+        // Generate and distribute Confirmations.
+        let mut dkg_confirmations = Vec::new();
+        for _ in 0..randomness_managers.len() {
+            let dkg_confirmation = rx_consensus.recv().await.unwrap();
+            match dkg_confirmation.kind {
+                ConsensusTransactionKind::RandomnessDkgConfirmation(_, bytes) => {
+                    let msg: fastcrypto_tbls::dkg::Confirmation<EncG> = bcs::from_bytes(&bytes)
+                        .expect("DKG confirmation deserialization should not fail");
+                    dkg_confirmations.push(msg);
+                }
+                _ => panic!("wrong type of message sent"),
+            }
+        }
+        for i in 0..randomness_managers.len() {
+            let mut batch = epoch_stores[i].tables().unwrap().dkg_confirmations.batch();
+            for (j, dkg_confirmation) in dkg_confirmations.iter().cloned().enumerate() {
+                randomness_managers[i]
+                    .add_confirmation(&mut batch, &epoch_stores[j].name, dkg_confirmation)
+                    .unwrap();
+            }
+            randomness_managers[i]
+                .advance_dkg(&mut batch, 0)
+                .await
+                .unwrap();
+            batch.write().unwrap();
+        }
+
+        // Verify DKG completed.
+        for randomness_manager in &randomness_managers {
+            assert_eq!(DkgStatus::Successful, randomness_manager.dkg_status());
+        }
+
+        // Verify the recorded transcript, and randomness aggregated from all partial signatures.
+        let epoch_store = &epoch_stores[0];
+        let verifier = RandomnessVerifier::new(
+            epoch_store.committee(),
+            epoch_store.protocol_config(),
+            epoch_store.tables().unwrap().get_dkg_transcript().unwrap(),
+        )
+        .unwrap();
+        let summary = verifier.verify_dkg().unwrap();
+        assert_eq!(summary.num_messages, randomness_managers.len());
+        assert!(summary.excluded_dealers.is_empty());
+
+        let round = RandomnessRound(0);
+        let partial_sigs: Vec<_> = randomness_managers
+            .iter()
+            .flat_map(|randomness_manager| {
+                let output = randomness_manager
+                    .dkg_output
+                    .get()
+                    .unwrap()
+                    .as_ref()
+                    .unwrap();
+                ThresholdBls12381MinSig::partial_sign_batch(
+                    output.shares.as_ref().unwrap().iter(),
+                    &round.signature_message(),
+                )
+            })
+            .collect();
+        let sig = ThresholdBls12381MinSig::aggregate(summary.t, partial_sigs.iter()).unwrap();
+        let mut update = RandomnessStateUpdate {
+            epoch: verifier.epoch(),
+            randomness_round: round,
+            random_bytes: bcs::to_bytes(&sig).unwrap(),
+            randomness_obj_initial_shared_version: SequenceNumber::new(),
+        };
+        assert_eq!(verifier.verify_rounds([&update]).unwrap(), 1);
+
+        // Randomness of another round doesn't verify.
+        update.randomness_round = RandomnessRound(1);
+        assert!(matches!(
+            verifier.verify_round(&update),
+            Err(RandomnessVerificationError::InvalidRandomness(
+                RandomnessRound(1)
+            ))
+        ));
+    }
+
     #[tokio::test]
     async fn test_dkg_expiration() {
         telemetry_subscribers::init_for_testing();
@@ -0,0 +1,431 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline verification of an epoch's randomness.
+//!
+//! During DKG the validators of an epoch agree on a VSS public key, and the randomness of each
+//! round is then a threshold BLS signature of the round number under that key. Given the DKG
+//! transcript recorded in an epoch store and the `RandomnessStateUpdate` transactions of the
+//! epoch, [`RandomnessVerifier`] re-checks both steps without trusting the node that recorded
+//! them.
+//!
+//! The shares encrypted in DKG messages can only be decrypted by their recipients, so they aren't
+//! checked here. Dealers that sent invalid shares are excluded from the DKG output through
+//! complaints in the confirmations, which is accounted for when checking the output.
+
+use fastcrypto::error::FastCryptoError;
+use fastcrypto::groups::{bls12381, GroupElement};
+use fastcrypto_tbls::nodes::PartyId;
+use fastcrypto_tbls::tbls::ThresholdBls;
+use fastcrypto_tbls::types::ThresholdBls12381MinSig;
+use fastcrypto_tbls::{dkg, nodes};
+use itertools::Itertools;
+use std::collections::{BTreeMap, BTreeSet};
+use sui_protocol_config::ProtocolConfig;
+use sui_types::base_types::AuthorityName;
+use sui_types::committee::{Committee, EpochId, StakeUnit};
+use sui_types::crypto::{RandomnessRound, RandomnessSignature};
+use sui_types::transaction::RandomnessStateUpdate;
+use thiserror::Error;
+
+use crate::epoch::randomness::RandomnessManager;
+
+type PkG = bls12381::G2Element;
+type EncG = bls12381::G2Element;
+
+/// Maximum number of dealers searched for when matching the DKG output to the messages it was
+/// created from, after some of them were excluded by complaints.
+const MAX_EXCLUDED_DEALERS: usize = 3;
+
+/// The DKG messages, confirmations and output of an epoch, as recorded in its epoch store.
+pub struct DkgTranscript {
+    /// All the DKG messages that were processed, one per dealer.
+    pub messages: Vec<dkg::Message<PkG, EncG>>,
+    /// The messages used to create this node's confirmation, if DKG got that far.
+    pub used_messages: Option<Vec<dkg::Message<PkG, EncG>>>,
+    pub confirmations: Vec<dkg::Confirmation<EncG>>,
+    /// The output of DKG, if it completed.
+    pub output: Option<dkg::Output<PkG, EncG>>,
+}
+
+#[derive(Debug, Error)]
+pub enum RandomnessVerificationError {
+    #[error("failed to compute the DKG nodes of the committee: {0}")]
+    InvalidCommittee(FastCryptoError),
+    #[error("DKG message was sent by party {0}, which is not in the committee")]
+    UnknownDealer(PartyId),
+    #[error(
+        "DKG message of party {party} has a VSS polynomial of degree {degree}, expected {expected}"
+    )]
+    InvalidMessageDegree {
+        party: PartyId,
+        degree: u32,
+        expected: u32,
+    },
+    #[error("DKG confirmation was sent by party {0}, which is not in the committee")]
+    UnknownConfirmer(PartyId),
+    #[error("DKG message of party {0} was used but doesn't match the processed message")]
+    UnprocessedUsedMessage(PartyId),
+    #[error("used DKG messages have a total weight of {weight}, below the threshold {t}")]
+    InsufficientUsedWeight { weight: u32, t: u16 },
+    #[error("DKG did not complete, so no randomness can be verified")]
+    DkgIncomplete,
+    #[error("DKG output has a VSS polynomial of degree {degree}, expected {expected}")]
+    InvalidOutputDegree { degree: u32, expected: u32 },
+    #[error("DKG output VSS public key is not the sum of the used messages' VSS public keys")]
+    OutputMismatch,
+    #[error(
+        "DKG output VSS public key doesn't match the used messages with up to {max_excluded} dealers excluded, and {num_complaints} complaints may have excluded more"
+    )]
+    OutputUnverifiable {
+        num_complaints: usize,
+        max_excluded: usize,
+    },
+    #[error("randomness of round {round} is for epoch {update_epoch}, expected {epoch}")]
+    WrongEpoch {
+        round: RandomnessRound,
+        update_epoch: EpochId,
+        epoch: EpochId,
+    },
+    #[error("randomness of round {0} is not a valid signature")]
+    MalformedRandomness(RandomnessRound),
+    #[error("randomness of round {0} doesn't verify against the DKG VSS public key")]
+    InvalidRandomness(RandomnessRound),
+    #[error("randomness of round {0} appears more than once")]
+    DuplicateRound(RandomnessRound),
+    #[error("randomness of rounds {first} to {last} is missing")]
+    MissingRounds {
+        first: RandomnessRound,
+        last: RandomnessRound,
+    },
+}
+
+/// What was verified about a DKG transcript.
+#[derive(Debug, Default)]
+pub struct DkgSummary {
+    /// Threshold of the reduced DKG weights.
+    pub t: u16,
+    pub num_messages: usize,
+    pub num_used_messages: usize,
+    /// Total stake of the dealers whose messages were used.
+    pub used_stake: StakeUnit,
+    pub num_confirmations: usize,
+    pub num_complaints: usize,
+    /// Dealers whose messages were used but left out of the DKG output, after complaints.
+    pub excluded_dealers: Vec<PartyId>,
+}
+
+/// Verifies the DKG transcript and randomness rounds of one epoch.
+pub struct RandomnessVerifier {
+    epoch: EpochId,
+    parties: BTreeMap<PartyId, (AuthorityName, StakeUnit)>,
+    nodes: nodes::Nodes<EncG>,
+    t: u16,
+    transcript: DkgTranscript,
+}
+
+impl RandomnessVerifier {
+    /// `committee` and `protocol_config` must be those of the epoch the transcript was recorded
+    /// in, as they determine the DKG parties and threshold.
+    pub fn new(
+        committee: &Committee,
+        protocol_config: &ProtocolConfig,
+        transcript: DkgTranscript,
+    ) -> Result<Self, RandomnessVerificationError> {
+        let info = RandomnessManager::randomness_dkg_info_from_committee(committee);
+        let (nodes, t) = RandomnessManager::randomness_dkg_nodes(committee, protocol_config, &info)
+            .map_err(RandomnessVerificationError::InvalidCommittee)?;
+        Ok(Self {
+            epoch: committee.epoch(),
+            parties: info
+                .into_iter()
+                .map(|(id, name, _, stake)| (id, (name, stake)))
+                .collect(),
+            nodes,
+            t,
+            transcript,
+        })
+    }
+
+    pub fn epoch(&self) -> EpochId {
+        self.epoch
+    }
+
+    /// Checks the DKG transcript, returning all the problems found.
+    pub fn verify_dkg(&self) -> Result<DkgSummary, Vec<RandomnessVerificationError>> {
+        let transcript = &self.transcript;
+        let expected_degree = u32::from(self.t) - 1;
+        let mut errors = Vec::new();
+        let mut summary = DkgSummary {
+            t: self.t,
+            num_messages: transcript.messages.len(),
+            num_confirmations: transcript.confirmations.len(),
+            num_complaints: transcript
+                .confirmations
+                .iter()
+                .map(|confirmation| confirmation.complaints.len())
+                .sum(),
+            ..Default::default()
+        };
+
+        for message in &transcript.messages {
+            if !self.parties.contains_key(&message.sender) {
+                errors.push(RandomnessVerificationError::UnknownDealer(message.sender));
+            }
+            if message.vss_pk.degree() != expected_degree {
+                errors.push(RandomnessVerificationError::InvalidMessageDegree {
+                    party: message.sender,
+                    degree: message.vss_pk.degree(),
+                    expected: expected_degree,
+                });
+            }
+        }
+        for confirmation in &transcript.confirmations {
+            if !self.parties.contains_key(&confirmation.sender) {
+                errors.push(RandomnessVerificationError::UnknownConfirmer(
+                    confirmation.sender,
+                ));
+            }
+        }
+
+        if let Some(used_messages) = &transcript.used_messages {
+            summary.num_used_messages = used_messages.len();
+            let mut weight = 0;
+            for message in used_messages {
+                let processed = transcript
+                    .messages
+                    .iter()
+                    .find(|processed| processed.sender == message.sender);
+                if processed.map_or(true, |processed| {
+                    processed.vss_pk.c0() != message.vss_pk.c0()
+                }) {
+                    errors.push(RandomnessVerificationError::UnprocessedUsedMessage(
+                        message.sender,
+                    ));
+                }
+                summary.used_stake += self
+                    .parties
+                    .get(&message.sender)
+                    .map_or(0, |(_, stake)| *stake);
+                weight += self
+                    .nodes
+                    .node_id_to_node(message.sender)
+                    .map_or(0, |node| u32::from(node.weight));
+            }
+            if weight < u32::from(self.t) {
+                errors.push(RandomnessVerificationError::InsufficientUsedWeight {
+                    weight,
+                    t: self.t,
+                });
+            }
+        }
+
+        match &transcript.output {
+            None => errors.push(RandomnessVerificationError::DkgIncomplete),
+            Some(output) => {
+                if output.vss_pk.degree() != expected_degree {
+                    errors.push(RandomnessVerificationError::InvalidOutputDegree {
+                        degree: output.vss_pk.degree(),
+                        expected: expected_degree,
+                    });
+                }
+                let excluded = match &transcript.used_messages {
+                    Some(used_messages) => {
+                        let used_pks: Vec<_> = used_messages
+                            .iter()
+                            .map(|message| (message.sender, *message.vss_pk.c0()))
+                            .collect();
+                        find_excluded_dealers(&used_pks, output.vss_pk.c0(), summary.num_complaints)
+                    }
+                    None => Err(RandomnessVerificationError::OutputMismatch),
+                };
+                match excluded {
+                    Ok(excluded) => summary.excluded_dealers = excluded,
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(summary)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Checks that the randomness of a single round is the threshold signature of the round under
+    /// the DKG VSS public key.
+    pub fn verify_round(
+        &self,
+        update: &RandomnessStateUpdate,
+    ) -> Result<(), RandomnessVerificationError> {
+        let round = update.randomness_round;
+        if update.epoch != self.epoch {
+            return Err(RandomnessVerificationError::WrongEpoch {
+                round,
+                update_epoch: update.epoch,
+                epoch: self.epoch,
+            });
+        }
+        let Some(output) = &self.transcript.output else {
+            return Err(RandomnessVerificationError::DkgIncomplete);
+        };
+        let sig: RandomnessSignature = bcs::from_bytes(&update.random_bytes)
+            .map_err(|_| RandomnessVerificationError::MalformedRandomness(round))?;
+        ThresholdBls12381MinSig::verify(output.vss_pk.c0(), &round.signature_message(), &sig)
+            .map_err(|_| RandomnessVerificationError::InvalidRandomness(round))
+    }
+
+    /// Checks the randomness of each round, and that no round is repeated or skipped between the
+    /// lowest and highest rounds given. Returns the number of rounds verified, or all the problems
+    /// found.
+    pub fn verify_rounds<'a>(
+        &self,
+        updates: impl IntoIterator<Item = &'a RandomnessStateUpdate>,
+    ) -> Result<usize, Vec<RandomnessVerificationError>> {
+        let mut errors = Vec::new();
+        let mut rounds = BTreeSet::new();
+        for update in updates {
+            if let Err(e) = self.verify_round(update) {
+                errors.push(e);
+            }
+            if !rounds.insert(update.randomness_round) {
+                errors.push(RandomnessVerificationError::DuplicateRound(
+                    update.randomness_round,
+                ));
+            }
+        }
+        for (prev, next) in rounds.iter().tuple_windows() {
+            if next.0 > prev.0 + 1 {
+                errors.push(RandomnessVerificationError::MissingRounds {
+                    first: RandomnessRound(prev.0 + 1),
+                    last: RandomnessRound(next.0 - 1),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(rounds.len())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+// The VSS public key of the DKG output is the sum of the used messages' VSS public keys, except
+// for those of dealers excluded by valid complaints. This searches for the smallest set of dealers
+// whose exclusion matches `output_pk`, given the sender and VSS public key of each used message.
+//
+// Each complaint accuses a single dealer, so at most `num_complaints` dealers were excluded and
+// the search is exhaustive up to `MAX_EXCLUDED_DEALERS` complaints. Beyond that, a failed search
+// can't tell a mismatching output from more exclusions than were searched for.
+fn find_excluded_dealers(
+    used_pks: &[(PartyId, PkG)],
+    output_pk: &PkG,
+    num_complaints: usize,
+) -> Result<Vec<PartyId>, RandomnessVerificationError> {
+    let max_excluded = num_complaints.min(MAX_EXCLUDED_DEALERS);
+    let total = used_pks.iter().fold(PkG::zero(), |sum, (_, pk)| sum + *pk);
+    (0..=max_excluded.min(used_pks.len()))
+        .find_map(|num_excluded| {
+            used_pks
+                .iter()
+                .combinations(num_excluded)
+                .find(|excluded| {
+                    let excluded_pk = excluded.iter().fold(PkG::zero(), |sum, (_, pk)| sum + *pk);
+                    total - excluded_pk == *output_pk
+                })
+                .map(|excluded| excluded.iter().map(|(party, _)| *party).collect())
+        })
+        .ok_or(if num_complaints > MAX_EXCLUDED_DEALERS {
+            RandomnessVerificationError::OutputUnverifiable {
+                num_complaints,
+                max_excluded,
+            }
+        } else {
+            RandomnessVerificationError::OutputMismatch
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Dealers whose VSS public keys are distinct powers of two times the generator, so that each
+    // set of excluded dealers gives a different output.
+    fn used_pks(num_dealers: u16) -> Vec<(PartyId, PkG)> {
+        (0..num_dealers)
+            .map(|party| {
+                let scalar = bls12381::Scalar::from(1u128 << party);
+                (party, PkG::generator() * scalar)
+            })
+            .collect()
+    }
+
+    fn output_pk(used_pks: &[(PartyId, PkG)], excluded: &[PartyId]) -> PkG {
+        used_pks
+            .iter()
+            .filter(|(party, _)| !excluded.contains(party))
+            .fold(PkG::zero(), |sum, (_, pk)| sum + *pk)
+    }
+
+    #[test]
+    fn test_find_excluded_dealers() {
+        let used_pks = used_pks(6);
+
+        // No dealer excluded.
+        let output = output_pk(&used_pks, &[]);
+        assert_eq!(
+            find_excluded_dealers(&used_pks, &output, 0).unwrap(),
+            vec![]
+        );
+
+        // Dealers excluded by complaints are found.
+        let output = output_pk(&used_pks, &[1, 4]);
+        assert_eq!(
+            find_excluded_dealers(&used_pks, &output, 2).unwrap(),
+            vec![1, 4]
+        );
+        assert_eq!(
+            find_excluded_dealers(&used_pks, &output, 5).unwrap(),
+            vec![1, 4]
+        );
+
+        // Fewer complaints than excluded dealers is a mismatch.
+        assert!(matches!(
+            find_excluded_dealers(&used_pks, &output, 1),
+            Err(RandomnessVerificationError::OutputMismatch)
+        ));
+
+        // An output that doesn't match any exclusion is a mismatch while the search is
+        // exhaustive.
+        let output = output_pk(&used_pks, &[]) + PkG::generator();
+        assert!(matches!(
+            find_excluded_dealers(&used_pks, &output, MAX_EXCLUDED_DEALERS),
+            Err(RandomnessVerificationError::OutputMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_find_excluded_dealers_above_limit() {
+        let used_pks = used_pks(6);
+
+        // More dealers excluded than searched for can't be told apart from a mismatch.
+        let excluded: Vec<_> = (0..MAX_EXCLUDED_DEALERS as PartyId + 1).collect();
+        let output = output_pk(&used_pks, &excluded);
+        assert!(matches!(
+            find_excluded_dealers(&used_pks, &output, excluded.len()),
+            Err(RandomnessVerificationError::OutputUnverifiable {
+                num_complaints: 4,
+                max_excluded: MAX_EXCLUDED_DEALERS,
+            })
+        ));
+
+        // Exclusions within the limit are still found when there are more complaints.
+        let output = output_pk(&used_pks, &[2]);
+        assert_eq!(
+            find_excluded_dealers(&used_pks, &output, excluded.len()).unwrap(),
+            vec![2]
+        );
+    }
+}
@@ -36,9 +36,11 @@ use sui_types::{
 };
 use tracing::instrument;
 
+pub mod cache_stats;
 pub(crate) mod cache_types;
 mod object_locks;
 pub mod passthrough_cache;
+pub mod warm_tier;
 pub mod writeback_cache;
 
 use passthrough_cache::PassthroughCache;
@@ -0,0 +1,193 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hit and miss counts for each tier of the execution cache, and a tracker of the most frequently
+//! read objects. They are reported by the admin API to diagnose cold-cache latency.
+
+use moka::sync::Cache as MokaCache;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use sui_types::base_types::ObjectID;
+
+/// Maximum number of objects whose reads are counted. Objects that are rarely read are evicted
+/// first, so the hottest objects are retained.
+const HOT_OBJECT_TRACKER_CAPACITY: u64 = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheTier {
+    /// The in-memory caches
+    Memory,
+    /// The on-disk warm tier, see `WarmTier`
+    Warm,
+    /// The authority store. A miss means that the data does not exist.
+    Store,
+}
+
+#[derive(Default)]
+struct TierCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl TierCounters {
+    fn stats(&self, tier: CacheTier) -> TierStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        TierStats {
+            tier,
+            hits,
+            misses,
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                hits as f64 / lookups as f64
+            },
+        }
+    }
+}
+
+pub struct ExecutionCacheStats {
+    memory: TierCounters,
+    warm: TierCounters,
+    store: TierCounters,
+    object_reads: MokaCache<ObjectID, Arc<AtomicU64>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TierStats {
+    pub tier: CacheTier,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct HotObject {
+    pub object_id: ObjectID,
+    pub reads: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExecutionCacheStatsSnapshot {
+    pub tiers: Vec<TierStats>,
+    /// The most read objects, in descending order of reads
+    pub hottest_objects: Vec<HotObject>,
+}
+
+impl Default for ExecutionCacheStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExecutionCacheStats {
+    pub fn new() -> Self {
+        Self {
+            memory: TierCounters::default(),
+            warm: TierCounters::default(),
+            store: TierCounters::default(),
+            object_reads: MokaCache::builder()
+                .max_capacity(HOT_OBJECT_TRACKER_CAPACITY)
+                .build(),
+        }
+    }
+
+    fn counters(&self, tier: CacheTier) -> &TierCounters {
+        match tier {
+            CacheTier::Memory => &self.memory,
+            CacheTier::Warm => &self.warm,
+            CacheTier::Store => &self.store,
+        }
+    }
+
+    pub fn record_lookup(&self, tier: CacheTier, hit: bool) {
+        self.record_lookups(tier, hit as u64, !hit as u64);
+    }
+
+    pub fn record_lookups(&self, tier: CacheTier, hits: u64, misses: u64) {
+        let counters = self.counters(tier);
+        counters.hits.fetch_add(hits, Ordering::Relaxed);
+        counters.misses.fetch_add(misses, Ordering::Relaxed);
+    }
+
+    /// Counts a read of `object_id`, returning the number of reads counted so far.
+    pub fn record_object_read(&self, object_id: &ObjectID) -> u64 {
+        self.object_reads
+            .get_with(*object_id, Default::default)
+            .fetch_add(1, Ordering::Relaxed)
+            + 1
+    }
+
+    pub fn object_reads(&self, object_id: &ObjectID) -> u64 {
+        self.object_reads
+            .get(object_id)
+            .map_or(0, |reads| reads.load(Ordering::Relaxed))
+    }
+
+    pub fn snapshot(&self, num_hottest_objects: usize) -> ExecutionCacheStatsSnapshot {
+        let mut hottest_objects: Vec<_> = self
+            .object_reads
+            .iter()
+            .map(|(object_id, reads)| HotObject {
+                object_id: *object_id,
+                reads: reads.load(Ordering::Relaxed),
+            })
+            .collect();
+        hottest_objects.sort_by(|a, b| b.reads.cmp(&a.reads).then(a.object_id.cmp(&b.object_id)));
+        hottest_objects.truncate(num_hottest_objects);
+
+        ExecutionCacheStatsSnapshot {
+            tiers: [CacheTier::Memory, CacheTier::Warm, CacheTier::Store]
+                .into_iter()
+                .map(|tier| self.counters(tier).stats(tier))
+                .collect(),
+            hottest_objects,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let stats = ExecutionCacheStats::new();
+        stats.record_lookup(CacheTier::Memory, true);
+        stats.record_lookup(CacheTier::Memory, false);
+        stats.record_lookups(CacheTier::Warm, 3, 1);
+
+        let hot = ObjectID::random();
+        let warm = ObjectID::random();
+        for _ in 0..3 {
+            stats.record_object_read(&hot);
+        }
+        assert_eq!(stats.record_object_read(&warm), 1);
+        assert_eq!(stats.object_reads(&hot), 3);
+
+        let snapshot = stats.snapshot(1);
+        let rates: Vec<_> = snapshot
+            .tiers
+            .iter()
+            .map(|t| (t.tier, t.hits, t.misses, t.hit_rate))
+            .collect();
+        assert_eq!(
+            rates,
+            vec![
+                (CacheTier::Memory, 1, 1, 0.5),
+                (CacheTier::Warm, 3, 1, 0.75),
+                (CacheTier::Store, 0, 0, 0.0),
+            ]
+        );
+        assert_eq!(
+            snapshot.hottest_objects,
+            vec![HotObject {
+                object_id: hot,
+                reads: 3
+            }]
+        );
+    }
+}
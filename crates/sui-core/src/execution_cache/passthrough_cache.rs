@@ -33,13 +33,18 @@ use sui_types::error::{SuiError, SuiResult};
 use sui_types::message_envelope::Message;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Object;
-use sui_types::storage::{MarkerValue, ObjectKey, ObjectOrTombstone, ObjectStore, PackageObject};
+use sui_types::storage::{
+    error::Result as StorageResult, MarkerValue, ObjectKey, ObjectOrTombstone, ObjectStore,
+    PackageObject,
+};
 use sui_types::sui_system_state::{get_sui_system_state, SuiSystemState};
 use sui_types::transaction::{VerifiedSignedTransaction, VerifiedTransaction};
 use tap::TapFallible;
 use tracing::instrument;
 use typed_store::Map;
 
+use super::cache_stats::{CacheTier, ExecutionCacheStats};
+use super::warm_tier::{self, WarmTier, WARM_TIER_MIN_OBJECT_READS};
 use super::{
     implement_passthrough_traits, CheckpointCache, ExecutionCacheCommit, ExecutionCacheMetrics,
     ExecutionCacheRead, ExecutionCacheReconfigAPI, ExecutionCacheWrite, NotifyReadWrapper,
@@ -50,19 +55,104 @@ pub struct PassthroughCache {
     store: Arc<AuthorityStore>,
     metrics: Arc<ExecutionCacheMetrics>,
     package_cache: Arc<PackageObjectCache>,
+    // PassthroughCache has no in-memory object cache, so hot objects are kept in the warm tier
+    // when they are read from the store, rather than when they are evicted from memory.
+    warm_tier: Option<Arc<WarmTier>>,
+    stats: ExecutionCacheStats,
     executed_effects_digests_notify_read: NotifyRead<TransactionDigest, TransactionEffectsDigest>,
 }
 
+/// Reads the packages that are missing from the in-memory package cache from the warm tier,
+/// falling back to the store.
+struct PackageFallback<'a>(&'a PassthroughCache);
+
+impl ObjectStore for PackageFallback<'_> {
+    fn get_object(&self, object_id: &ObjectID) -> StorageResult<Option<Object>> {
+        self.0.get_package_from_warm_tier_or_store(object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> StorageResult<Option<Object>> {
+        self.0.store.get_object_by_key(object_id, version)
+    }
+}
+
 impl PassthroughCache {
     pub fn new(store: Arc<AuthorityStore>, metrics: Arc<ExecutionCacheMetrics>) -> Self {
+        Self::new_with_warm_tier(store, metrics, None)
+    }
+
+    pub fn new_with_warm_tier(
+        store: Arc<AuthorityStore>,
+        metrics: Arc<ExecutionCacheMetrics>,
+        warm_tier: Option<Arc<WarmTier>>,
+    ) -> Self {
         Self {
             store,
             metrics,
             package_cache: PackageObjectCache::new(),
+            warm_tier,
+            stats: ExecutionCacheStats::new(),
             executed_effects_digests_notify_read: NotifyRead::new(),
         }
     }
 
+    pub fn cache_stats(&self) -> &ExecutionCacheStats {
+        &self.stats
+    }
+
+    fn get_package_from_warm_tier_or_store(
+        &self,
+        package_id: &ObjectID,
+    ) -> StorageResult<Option<Object>> {
+        if let Some(warm_tier) = &self.warm_tier {
+            let package = warm_tier.get_package(package_id);
+            self.stats.record_lookup(CacheTier::Warm, package.is_some());
+            if package.is_some() {
+                return Ok(package);
+            }
+        }
+        let package = self.store.get_object(package_id)?;
+        self.stats
+            .record_lookup(CacheTier::Store, package.is_some());
+        if let (Some(warm_tier), Some(package)) = (&self.warm_tier, &package) {
+            warm_tier.insert_package(package);
+        }
+        Ok(package)
+    }
+
+    fn multi_get_objects_from_warm_tier_or_store(
+        &self,
+        object_keys: &[ObjectKey],
+    ) -> SuiResult<Vec<Option<Object>>> {
+        for key in object_keys {
+            self.stats.record_object_read(&key.0);
+        }
+        warm_tier::multi_get_objects_by_key(
+            self.warm_tier.as_deref(),
+            &self.stats,
+            object_keys,
+            |store_keys| {
+                let objects = self.store.multi_get_objects_by_key(store_keys)?;
+                for object in objects.iter().flatten() {
+                    self.keep_in_warm_tier_if_hot(object);
+                }
+                Ok(objects)
+            },
+        )
+    }
+
+    fn keep_in_warm_tier_if_hot(&self, object: &Object) {
+        if let Some(warm_tier) = &self.warm_tier {
+            if self.stats.object_reads(&object.id()) >= WARM_TIER_MIN_OBJECT_READS {
+                warm_tier.insert_object(object);
+            }
+        }
+    }
+
     pub fn new_for_tests(store: Arc<AuthorityStore>, registry: &Registry) -> Self {
         let metrics = Arc::new(ExecutionCacheMetrics::new(registry));
         Self::new(store, metrics)
@@ -98,6 +188,14 @@ impl PassthroughCache {
     }
 
     fn revert_state_update_impl(&self, digest: &TransactionDigest) -> SuiResult {
+        if let Some(warm_tier) = &self.warm_tier {
+            if let Some(effects) = self.store.get_executed_effects(digest)? {
+                for ((object_id, version, _), _, _) in effects.all_changed_objects() {
+                    warm_tier.remove_object(&ObjectKey(object_id, version));
+                    warm_tier.remove_package(&object_id);
+                }
+            }
+        }
         self.store.revert_state_update(digest)
     }
 
@@ -113,8 +211,15 @@ impl PassthroughCache {
 
 impl ExecutionCacheRead for PassthroughCache {
     fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<PackageObject>> {
+        if self.package_cache.contains(package_id) {
+            self.stats.record_lookup(CacheTier::Memory, true);
+            return self
+                .package_cache
+                .get_package_object(package_id, &*self.store);
+        }
+        self.stats.record_lookup(CacheTier::Memory, false);
         self.package_cache
-            .get_package_object(package_id, &*self.store)
+            .get_package_object(package_id, &PackageFallback(self))
     }
 
     fn force_reload_system_packages(&self, system_package_ids: &[ObjectID]) {
@@ -123,7 +228,14 @@ impl ExecutionCacheRead for PassthroughCache {
     }
 
     fn get_object(&self, id: &ObjectID) -> SuiResult<Option<Object>> {
-        self.store.get_object(id).map_err(Into::into)
+        // The latest version of an object can change, so it is always read from the store.
+        self.stats.record_object_read(id);
+        let object = self.store.get_object(id)?;
+        self.stats.record_lookup(CacheTier::Store, object.is_some());
+        if let Some(object) = &object {
+            self.keep_in_warm_tier_if_hot(object);
+        }
+        Ok(object)
     }
 
     fn get_object_by_key(
@@ -131,14 +243,17 @@ impl ExecutionCacheRead for PassthroughCache {
         object_id: &ObjectID,
         version: SequenceNumber,
     ) -> SuiResult<Option<Object>> {
-        Ok(self.store.get_object_by_key(object_id, version)?)
+        Ok(self
+            .multi_get_objects_from_warm_tier_or_store(&[ObjectKey(*object_id, version)])?
+            .pop()
+            .flatten())
     }
 
     fn multi_get_objects_by_key(
         &self,
         object_keys: &[ObjectKey],
     ) -> Result<Vec<Option<Object>>, SuiError> {
-        Ok(self.store.multi_get_objects_by_key(object_keys)?)
+        self.multi_get_objects_from_warm_tier_or_store(object_keys)
     }
 
     fn object_exists_by_key(
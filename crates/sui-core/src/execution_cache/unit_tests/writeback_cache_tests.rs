@@ -866,6 +866,44 @@ async fn test_invalidate_package_cache_on_revert() {
     .await;
 }
 
+#[tokio::test]
+async fn test_warm_tier_packages_are_cached_on_startup() {
+    telemetry_subscribers::init_for_testing();
+    let mut s = Scenario::new(None, Arc::new(AtomicU32::new(0))).await;
+    s.with_packages(&[1]);
+    let tx = s.do_tx().await;
+    s.commit(tx).await.unwrap();
+    let package = s.object(1);
+
+    let dir = tempfile::tempdir().unwrap();
+    let warm_tier = WarmTier::open(dir.path().to_path_buf(), s.store.as_ref());
+    warm_tier.insert_package(&package);
+
+    // A cache started with the warm tier serves the package from memory.
+    let cache = WritebackCache::new_with_warm_tier(
+        s.store.clone(),
+        s.cache.metrics.clone(),
+        Some(warm_tier),
+    );
+    let cached = cache.get_package_object(&package.id()).unwrap().unwrap();
+    assert_eq!(cached.object().digest(), package.digest());
+
+    let stats = cache.cache_stats().snapshot(0);
+    let hits: Vec<_> = stats
+        .tiers
+        .iter()
+        .map(|t| (t.tier, t.hits, t.misses))
+        .collect();
+    assert_eq!(
+        hits,
+        vec![
+            (CacheTier::Memory, 1, 0),
+            (CacheTier::Warm, 0, 0),
+            (CacheTier::Store, 0, 0),
+        ]
+    );
+}
+
 #[sim_test]
 async fn test_concurrent_readers() {
     telemetry_subscribers::init_for_testing();
@@ -0,0 +1,380 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The warm tier is an on-disk layer of the execution cache that sits between the in-memory caches
+//! and the authority store. It keeps a bounded set of hot objects and packages in a database of
+//! its own, so that they survive restarts and can be read without going through the much larger
+//! store tables.
+//!
+//! Only data that never changes is kept: versions of shared and immutable objects, which can only
+//! be read by key, and user packages. System packages are upgraded in place, so they are never
+//! kept. Because reads from this tier are best-effort, errors are logged and treated as misses.
+//!
+//! The tier may be out of sync with the store it was opened with, if the node stopped before
+//! removing the objects of reverted transactions, or if the store was replaced, e.g. restored from
+//! a snapshot. Entries are therefore checked against the store when the tier is opened, and those
+//! that don't match are discarded.
+
+use super::cache_stats::{CacheTier, ExecutionCacheStats};
+use moka::notification::RemovalCause;
+use moka::sync::Cache as MokaCache;
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_types::base_types::ObjectID;
+use sui_types::error::SuiResult;
+use sui_types::object::Object;
+use sui_types::storage::{ObjectKey, ObjectStore};
+use tracing::{info, warn};
+use typed_store::rocks::{DBMap, MetricConf};
+use typed_store::traits::{TableSummary, TypedStoreDebug};
+use typed_store::Map;
+use typed_store_derive::DBMapUtils;
+
+const WARM_TIER_OBJECT_CAPACITY: u64 = 100_000;
+const WARM_TIER_PACKAGE_CAPACITY: u64 = 10_000;
+
+/// Number of reads after which an object is hot enough to be kept in the warm tier.
+pub const WARM_TIER_MIN_OBJECT_READS: u64 = 3;
+
+#[derive(DBMapUtils)]
+pub struct WarmTierTables {
+    /// Versions of hot shared and immutable objects.
+    objects: DBMap<ObjectKey, Object>,
+    /// User packages, keyed by their ID.
+    packages: DBMap<ObjectID, Object>,
+}
+
+pub struct WarmTier {
+    tables: Arc<WarmTierTables>,
+    // In-memory indexes of the keys in `tables`. They answer misses without reading the database,
+    // and bound the size of the tables: a key evicted from an index is deleted from its table.
+    object_index: MokaCache<ObjectKey, ()>,
+    package_index: MokaCache<ObjectID, ()>,
+}
+
+impl WarmTier {
+    /// Opens the warm tier at `path`, keeping the entries written by a previous run that are also
+    /// in `store`.
+    pub fn open(path: PathBuf, store: &impl ObjectStore) -> Arc<Self> {
+        let tables = Arc::new(WarmTierTables::open_tables_read_write(
+            path,
+            MetricConf::new("warm_cache"),
+            None,
+            None,
+        ));
+
+        let object_index = {
+            let tables = tables.clone();
+            MokaCache::builder()
+                .max_capacity(WARM_TIER_OBJECT_CAPACITY)
+                .eviction_listener(move |key: Arc<ObjectKey>, _, cause: RemovalCause| {
+                    if cause.was_evicted() {
+                        if let Err(e) = tables.objects.remove(&key) {
+                            warn!(?key, "failed to evict object from warm tier: {e}");
+                        }
+                    }
+                })
+                .build()
+        };
+        let package_index = {
+            let tables = tables.clone();
+            MokaCache::builder()
+                .max_capacity(WARM_TIER_PACKAGE_CAPACITY)
+                .eviction_listener(move |id: Arc<ObjectID>, _, cause: RemovalCause| {
+                    if cause.was_evicted() {
+                        if let Err(e) = tables.packages.remove(&id) {
+                            warn!(?id, "failed to evict package from warm tier: {e}");
+                        }
+                    }
+                })
+                .build()
+        };
+
+        let mut stale_objects = vec![];
+        for entry in tables.objects.safe_iter() {
+            match entry {
+                Ok((key, object)) if is_in_store(store, &key, &object) => {
+                    object_index.insert(key, ())
+                }
+                Ok((key, _)) => stale_objects.push(key),
+                Err(e) => warn!("failed to load warm tier objects: {e}"),
+            }
+        }
+        let mut stale_packages = vec![];
+        for entry in tables.packages.safe_iter() {
+            match entry {
+                Ok((id, package))
+                    if is_in_store(store, &ObjectKey(id, package.version()), &package) =>
+                {
+                    package_index.insert(id, ())
+                }
+                Ok((id, _)) => stale_packages.push(id),
+                Err(e) => warn!("failed to load warm tier packages: {e}"),
+            }
+        }
+        for key in &stale_objects {
+            if let Err(e) = tables.objects.remove(key) {
+                warn!(?key, "failed to remove stale object from warm tier: {e}");
+            }
+        }
+        for id in &stale_packages {
+            if let Err(e) = tables.packages.remove(id) {
+                warn!(?id, "failed to remove stale package from warm tier: {e}");
+            }
+        }
+        info!(
+            objects = object_index.entry_count(),
+            packages = package_index.entry_count(),
+            stale_objects = stale_objects.len(),
+            stale_packages = stale_packages.len(),
+            "opened execution cache warm tier"
+        );
+
+        Arc::new(Self {
+            tables,
+            object_index,
+            package_index,
+        })
+    }
+
+    pub fn get_object_by_key(&self, key: &ObjectKey) -> Option<Object> {
+        if !self.object_index.contains_key(key) {
+            return None;
+        }
+        self.tables
+            .objects
+            .get(key)
+            .map_err(|e| warn!(?key, "failed to read object from warm tier: {e}"))
+            .ok()
+            .flatten()
+    }
+
+    /// Keeps `object` if it is a version of a shared or immutable object. Callers must remove the
+    /// objects written by transactions that are reverted.
+    pub fn insert_object(&self, object: &Object) {
+        if object.is_package() || !(object.is_shared() || object.is_immutable()) {
+            return;
+        }
+        let key = ObjectKey(object.id(), object.version());
+        if self.object_index.contains_key(&key) {
+            return;
+        }
+        if let Err(e) = self.tables.objects.insert(&key, object) {
+            warn!(?key, "failed to write object to warm tier: {e}");
+            return;
+        }
+        self.object_index.insert(key, ());
+    }
+
+    pub fn remove_object(&self, key: &ObjectKey) {
+        if self.object_index.contains_key(key) {
+            self.object_index.invalidate(key);
+            if let Err(e) = self.tables.objects.remove(key) {
+                warn!(?key, "failed to remove object from warm tier: {e}");
+            }
+        }
+    }
+
+    pub fn get_package(&self, id: &ObjectID) -> Option<Object> {
+        if !self.package_index.contains_key(id) {
+            return None;
+        }
+        self.tables
+            .packages
+            .get(id)
+            .map_err(|e| warn!(?id, "failed to read package from warm tier: {e}"))
+            .ok()
+            .flatten()
+    }
+
+    /// Keeps `package` unless it is a system package. Callers must remove the packages published
+    /// by transactions that are reverted.
+    pub fn insert_package(&self, package: &Object) {
+        if !package.is_package() || package.is_system_package() {
+            return;
+        }
+        let id = package.id();
+        if self.package_index.contains_key(&id) {
+            return;
+        }
+        if let Err(e) = self.tables.packages.insert(&id, package) {
+            warn!(?id, "failed to write package to warm tier: {e}");
+            return;
+        }
+        self.package_index.insert(id, ());
+    }
+
+    pub fn remove_package(&self, id: &ObjectID) {
+        if self.package_index.contains_key(id) {
+            self.package_index.invalidate(id);
+            if let Err(e) = self.tables.packages.remove(id) {
+                warn!(?id, "failed to remove package from warm tier: {e}");
+            }
+        }
+    }
+
+    /// Returns every package in the warm tier, to prefill the in-memory caches.
+    pub fn packages(&self) -> Vec<Object> {
+        self.tables
+            .packages
+            .safe_iter()
+            .filter_map(|entry| {
+                entry
+                    .map_err(|e| warn!("failed to read packages from warm tier: {e}"))
+                    .ok()
+                    .map(|(_, package)| package)
+            })
+            .collect()
+    }
+}
+
+// Whether `store` has the same `object` at `key`. Objects that can't be read from the store are
+// treated as missing.
+fn is_in_store(store: &impl ObjectStore, key: &ObjectKey, object: &Object) -> bool {
+    match store.get_object_by_key(&key.0, key.1) {
+        Ok(stored) => stored.is_some_and(|stored| stored.digest() == object.digest()),
+        Err(e) => {
+            warn!(
+                ?key,
+                "failed to check warm tier entry against the store: {e}"
+            );
+            false
+        }
+    }
+}
+
+/// Reads `object_keys` that missed the in-memory caches from `warm_tier`, if any, and the rest with
+/// `store_fallback`, recording the lookups of each tier in `stats`.
+pub(super) fn multi_get_objects_by_key(
+    warm_tier: Option<&WarmTier>,
+    stats: &ExecutionCacheStats,
+    object_keys: &[ObjectKey],
+    store_fallback: impl FnOnce(&[ObjectKey]) -> SuiResult<Vec<Option<Object>>>,
+) -> SuiResult<Vec<Option<Object>>> {
+    let mut results = vec![None; object_keys.len()];
+    let mut store_keys = Vec::with_capacity(object_keys.len());
+    let mut store_indices = Vec::with_capacity(object_keys.len());
+    for (i, key) in object_keys.iter().enumerate() {
+        match warm_tier.and_then(|warm_tier| warm_tier.get_object_by_key(key)) {
+            Some(object) => results[i] = Some(object),
+            None => {
+                store_keys.push(*key);
+                store_indices.push(i);
+            }
+        }
+    }
+    if warm_tier.is_some() {
+        stats.record_lookups(
+            CacheTier::Warm,
+            (object_keys.len() - store_keys.len()) as u64,
+            store_keys.len() as u64,
+        );
+    }
+    if store_keys.is_empty() {
+        return Ok(results);
+    }
+
+    let store_results = store_fallback(&store_keys)?;
+    assert_eq!(store_results.len(), store_keys.len());
+    let found = store_results.iter().flatten().count();
+    stats.record_lookups(
+        CacheTier::Store,
+        found as u64,
+        (store_keys.len() - found) as u64,
+    );
+    for (i, object) in store_indices.into_iter().zip(store_results) {
+        results[i] = object;
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::SuiAddress;
+
+    #[test]
+    fn test_warm_tier_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let immutable = Object::immutable_for_testing();
+        let shared = Object::shared_for_testing();
+        let owned = Object::with_id_owner_for_testing(
+            ObjectID::random(),
+            SuiAddress::random_for_testing_only(),
+        );
+        let store = [immutable.clone(), shared.clone(), owned.clone()];
+        let store = &store[..];
+        {
+            let warm_tier = WarmTier::open(dir.path().to_path_buf(), &store);
+            warm_tier.insert_object(&immutable);
+            warm_tier.insert_object(&shared);
+            warm_tier.insert_object(&owned);
+            warm_tier.remove_object(&ObjectKey(shared.id(), shared.version()));
+        }
+
+        let warm_tier = WarmTier::open(dir.path().to_path_buf(), &store);
+        assert_eq!(
+            warm_tier.get_object_by_key(&ObjectKey(immutable.id(), immutable.version())),
+            Some(immutable.clone())
+        );
+        // Removed objects and owned objects are not kept.
+        assert_eq!(
+            warm_tier.get_object_by_key(&ObjectKey(shared.id(), shared.version())),
+            None
+        );
+        assert_eq!(
+            warm_tier.get_object_by_key(&ObjectKey(owned.id(), owned.version())),
+            None
+        );
+        // Other versions of a kept object are misses.
+        assert_eq!(
+            warm_tier.get_object_by_key(&ObjectKey(immutable.id(), immutable.version().next())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_warm_tier_discards_entries_not_in_store() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let kept = Object::immutable_with_id_for_testing(ObjectID::random());
+        let missing = Object::with_id_shared_for_testing(ObjectID::random());
+        let changed = Object::immutable_with_id_for_testing(ObjectID::random());
+        {
+            let store = [kept.clone(), missing.clone(), changed.clone()];
+            let warm_tier = WarmTier::open(dir.path().to_path_buf(), &&store[..]);
+            warm_tier.insert_object(&kept);
+            warm_tier.insert_object(&missing);
+            warm_tier.insert_object(&changed);
+        }
+
+        // The store no longer has one of the objects, and has a different object at the key of
+        // another, as if the transactions that wrote them were reverted.
+        let different = Object::with_id_shared_for_testing(changed.id());
+        assert_eq!(different.version(), changed.version());
+        assert_ne!(different.digest(), changed.digest());
+        let store = [kept.clone(), different];
+        let warm_tier = WarmTier::open(dir.path().to_path_buf(), &&store[..]);
+        assert_eq!(
+            warm_tier.get_object_by_key(&ObjectKey(kept.id(), kept.version())),
+            Some(kept.clone())
+        );
+        assert_eq!(
+            warm_tier.get_object_by_key(&ObjectKey(missing.id(), missing.version())),
+            None
+        );
+        assert_eq!(
+            warm_tier.get_object_by_key(&ObjectKey(changed.id(), changed.version())),
+            None
+        );
+        // Stale entries are deleted, not just skipped.
+        let keys: Vec<_> = warm_tier
+            .tables
+            .objects
+            .safe_iter()
+            .map(|entry| entry.unwrap().0)
+            .collect();
+        assert_eq!(keys, vec![ObjectKey(kept.id(), kept.version())]);
+    }
+}
@@ -58,6 +58,7 @@ use futures::{
     future::{join_all, BoxFuture},
     FutureExt,
 };
+use moka::notification::RemovalCause;
 use moka::sync::Cache as MokaCache;
 use mysten_common::sync::notify_read::NotifyRead;
 use parking_lot::Mutex;
@@ -83,6 +84,8 @@ use sui_types::sui_system_state::{get_sui_system_state, SuiSystemState};
 use sui_types::transaction::{VerifiedSignedTransaction, VerifiedTransaction};
 use tracing::{debug, info, instrument, trace};
 
+use super::cache_stats::{CacheTier, ExecutionCacheStats};
+use super::warm_tier::{self, WarmTier, WARM_TIER_MIN_OBJECT_READS};
 use super::ExecutionCacheAPI;
 use super::{
     cache_types::CachedVersionMap, implement_passthrough_traits, object_locks::ObjectLocks,
@@ -240,11 +243,29 @@ struct CachedCommittedData {
 }
 
 impl CachedCommittedData {
-    fn new() -> Self {
-        let object_cache = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
+    fn new(warm_tier: Option<Arc<WarmTier>>, stats: Arc<ExecutionCacheStats>) -> Self {
+        let mut object_cache = MokaCache::builder()
             .max_capacity(MAX_CACHE_SIZE)
-            .build();
+            .max_capacity(MAX_CACHE_SIZE);
+        if let Some(warm_tier) = warm_tier {
+            // Evicted versions are committed, so the latest one of a hot object can be kept.
+            object_cache = object_cache.eviction_listener(
+                move |object_id: Arc<ObjectID>,
+                      versions: Arc<Mutex<CachedVersionMap<ObjectEntry>>>,
+                      cause: RemovalCause| {
+                    if cause.was_evicted()
+                        && stats.object_reads(&object_id) >= WARM_TIER_MIN_OBJECT_READS
+                    {
+                        if let Some((_, ObjectEntry::Object(object))) =
+                            versions.lock().get_highest()
+                        {
+                            warm_tier.insert_object(object);
+                        }
+                    }
+                },
+            );
+        }
+        let object_cache = object_cache.build();
         let marker_cache = MokaCache::builder()
             .max_capacity(MAX_CACHE_SIZE)
             .max_capacity(MAX_CACHE_SIZE)
@@ -300,6 +321,11 @@ pub struct WritebackCache {
 
     object_locks: ObjectLocks,
 
+    // Hot objects and packages evicted from the in-memory caches are kept in the warm tier, and
+    // packages kept by a previous run are loaded into the package cache on startup.
+    warm_tier: Option<Arc<WarmTier>>,
+    stats: Arc<ExecutionCacheStats>,
+
     executed_effects_digests_notify_read: NotifyRead<TransactionDigest, TransactionEffectsDigest>,
     store: Arc<AuthorityStore>,
     metrics: Arc<ExecutionCacheMetrics>,
@@ -337,28 +363,72 @@ macro_rules! check_cache_entry_by_latest {
 
 impl WritebackCache {
     fn new(store: Arc<AuthorityStore>, metrics: Arc<ExecutionCacheMetrics>) -> Self {
-        let packages = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
+        Self::new_with_warm_tier(store, metrics, None)
+    }
+
+    pub fn new_with_warm_tier(
+        store: Arc<AuthorityStore>,
+        metrics: Arc<ExecutionCacheMetrics>,
+        warm_tier: Option<Arc<WarmTier>>,
+    ) -> Self {
+        let stats = Arc::new(ExecutionCacheStats::new());
+        let mut packages = MokaCache::builder()
             .max_capacity(MAX_CACHE_SIZE)
-            .build();
+            .max_capacity(MAX_CACHE_SIZE);
+        if let Some(warm_tier) = warm_tier.clone() {
+            let store = store.clone();
+            packages = packages.eviction_listener(
+                move |package_id: Arc<ObjectID>, package: PackageObject, cause: RemovalCause| {
+                    if !cause.was_evicted() {
+                        return;
+                    }
+                    // Packages are cached before they are committed, so only those that are
+                    // already in the store are kept.
+                    match store.get_object(&package_id) {
+                        Ok(Some(stored)) if stored.digest() == package.object().digest() => {
+                            warm_tier.insert_package(&stored)
+                        }
+                        Ok(_) => (),
+                        Err(e) => debug!(?package_id, "not keeping evicted package: {e}"),
+                    }
+                },
+            );
+        }
+        let packages = packages.build();
+        if let Some(warm_tier) = &warm_tier {
+            for package in warm_tier.packages() {
+                packages.insert(package.id(), PackageObject::new(package));
+            }
+        }
+
         Self {
             dirty: UncommittedData::new(),
-            cached: CachedCommittedData::new(),
+            cached: CachedCommittedData::new(warm_tier.clone(), stats.clone()),
             packages,
             object_locks: ObjectLocks::new(),
+            warm_tier,
+            stats,
             executed_effects_digests_notify_read: NotifyRead::new(),
             store,
             metrics,
         }
     }
 
+    pub fn cache_stats(&self) -> &ExecutionCacheStats {
+        &self.stats
+    }
+
     pub fn new_for_tests(store: Arc<AuthorityStore>, registry: &Registry) -> Self {
         Self::new(store, ExecutionCacheMetrics::new(registry).into())
     }
 
     #[cfg(test)]
     pub fn reset_for_test(&mut self) {
-        let mut new = Self::new(self.store.clone(), self.metrics.clone());
+        let mut new = Self::new_with_warm_tier(
+            self.store.clone(),
+            self.metrics.clone(),
+            self.warm_tier.clone(),
+        );
         std::mem::swap(self, &mut new);
     }
 
@@ -932,7 +1002,10 @@ impl ExecutionCacheCommit for WritebackCache {
 
 impl ExecutionCacheRead for WritebackCache {
     fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<PackageObject>> {
-        if let Some(p) = self.packages.get(package_id) {
+        let cached = self.packages.get(package_id);
+        self.stats
+            .record_lookup(CacheTier::Memory, cached.is_some());
+        if let Some(p) = cached {
             if cfg!(debug_assertions) {
                 if let Some(store_package) = self.store.get_object(package_id).unwrap() {
                     assert_eq!(
@@ -946,6 +1019,16 @@ impl ExecutionCacheRead for WritebackCache {
             return Ok(Some(p));
         }
 
+        if let Some(warm_tier) = &self.warm_tier {
+            let package = warm_tier.get_package(package_id);
+            self.stats.record_lookup(CacheTier::Warm, package.is_some());
+            if let Some(package) = package {
+                let p = PackageObject::new(package);
+                self.packages.insert(*package_id, p.clone());
+                return Ok(Some(p));
+            }
+        }
+
         // We try the dirty objects cache as well before going to the database. This is necessary
         // because the package could be evicted from the package cache before it is committed
         // to the database.
@@ -983,10 +1066,19 @@ impl ExecutionCacheRead for WritebackCache {
     // of the cache.
 
     fn get_object(&self, id: &ObjectID) -> SuiResult<Option<Object>> {
-        match self.get_object_by_id_cache_only(id) {
+        self.stats.record_object_read(id);
+        let result = self.get_object_by_id_cache_only(id);
+        self.stats
+            .record_lookup(CacheTier::Memory, !matches!(result, CacheResult::Miss));
+        match result {
             CacheResult::Hit((_, object)) => Ok(Some(object)),
             CacheResult::NegativeHit => Ok(None),
-            CacheResult::Miss => Ok(self.store.get_object(id)?),
+            CacheResult::Miss => {
+                // The warm tier cannot tell whether it holds the latest version of an object.
+                let object = self.store.get_object(id)?;
+                self.stats.record_lookup(CacheTier::Store, object.is_some());
+                Ok(object)
+            }
         }
     }
 
@@ -995,11 +1087,10 @@ impl ExecutionCacheRead for WritebackCache {
         object_id: &ObjectID,
         version: SequenceNumber,
     ) -> SuiResult<Option<Object>> {
-        match self.get_object_by_key_cache_only(object_id, version) {
-            CacheResult::Hit(object) => Ok(Some(object)),
-            CacheResult::NegativeHit => Ok(None),
-            CacheResult::Miss => Ok(self.store.get_object_by_key(object_id, version)?),
-        }
+        Ok(self
+            .multi_get_objects_by_key(&[ObjectKey(*object_id, version)])?
+            .pop()
+            .flatten())
     }
 
     fn multi_get_objects_by_key(
@@ -1009,16 +1100,27 @@ impl ExecutionCacheRead for WritebackCache {
         do_fallback_lookup(
             object_keys,
             |key| {
-                Ok(match self.get_object_by_key_cache_only(&key.0, key.1) {
+                self.stats.record_object_read(&key.0);
+                let result = self.get_object_by_key_cache_only(&key.0, key.1);
+                self.stats
+                    .record_lookup(CacheTier::Memory, !matches!(result, CacheResult::Miss));
+                Ok(match result {
                     CacheResult::Hit(maybe_object) => CacheResult::Hit(Some(maybe_object)),
                     CacheResult::NegativeHit => CacheResult::NegativeHit,
                     CacheResult::Miss => CacheResult::Miss,
                 })
             },
             |remaining| {
-                self.store
-                    .multi_get_objects_by_key(remaining)
-                    .map_err(Into::into)
+                warm_tier::multi_get_objects_by_key(
+                    self.warm_tier.as_deref(),
+                    &self.stats,
+                    remaining,
+                    |store_keys| {
+                        self.store
+                            .multi_get_objects_by_key(store_keys)
+                            .map_err(Into::into)
+                    },
+                )
             },
         )
     }
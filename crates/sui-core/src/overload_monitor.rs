@@ -2,8 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::authority::AuthorityState;
+use ipnetwork::IpNetwork;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
 use std::hash::Hasher;
+use std::net::IpAddr;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Weak;
 use std::time::Duration;
@@ -14,7 +19,7 @@ use sui_types::error::SuiError;
 use sui_types::error::SuiResult;
 use sui_types::fp_bail;
 use tokio::time::sleep;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use twox_hash::XxHash64;
 
 #[cfg(test)]
@@ -28,6 +33,10 @@ pub struct AuthorityOverloadInfo {
 
     /// The calculated percentage of transactions to drop.
     pub load_shedding_percentage: AtomicU32,
+
+    /// The transactions touching congested objects submitted by each client, used to shed load
+    /// from the heaviest clients first.
+    pub client_load: ClientLoadTracker,
 }
 
 impl AuthorityOverloadInfo {
@@ -50,6 +59,218 @@ const ADDITIONAL_LOAD_SHEDDING: f64 = 0.02;
 // The update interval of the random seed used to determine whether a txn should be rejected.
 const SEED_UPDATE_DURATION_SECS: u64 = 30;
 
+// The maximum number of clients whose transactions are counted. Transactions from other clients
+// are not counted until the counts are rotated.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// An object is congested when the number of transactions pending on it in the transaction manager
+/// reaches this fraction of `max_transaction_manager_per_object_queue_length`.
+pub const CONGESTED_OBJECT_QUEUE_LENGTH_RATIO: usize = 10;
+
+// Clients that submitted fewer transactions touching congested objects in the last two overload
+// monitor intervals are light users, and are never selected as heavy clients.
+const MIN_HEAVY_CLIENT_TRANSACTIONS: u64 = 20;
+
+/// How the transactions of a client are shed, as set through the admin API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientLoadSheddingOverride {
+    /// The client's transactions are never shed.
+    Exempt,
+    /// All of the client's transactions are shed while the authority is overloaded.
+    Shed,
+}
+
+#[derive(Default)]
+struct ClientLoadTrackerInner {
+    // Transactions touching congested objects per client, in the current and the previous
+    // overload monitor interval.
+    current: HashMap<IpAddr, u64>,
+    previous: HashMap<IpAddr, u64>,
+    heavy_clients: HashSet<IpAddr>,
+    // The percentage of the transactions of other clients to shed, when the heavy clients don't
+    // account for all of the load to shed.
+    residual_shedding_percentage: u32,
+    overrides: HashMap<IpAddr, ClientLoadSheddingOverride>,
+}
+
+impl ClientLoadTrackerInner {
+    fn transactions(&self, client: &IpAddr) -> u64 {
+        self.current.get(client).copied().unwrap_or_default()
+            + self.previous.get(client).copied().unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+pub struct ClientLoadTracker {
+    inner: RwLock<ClientLoadTrackerInner>,
+    // Proxies whose `x-forwarded-for` header identifies the client of a request.
+    trusted_proxies: Vec<IpNetwork>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientLoad {
+    pub client: IpAddr,
+    /// Transactions touching congested objects in the last two overload monitor intervals
+    pub congested_transactions: u64,
+    pub heavy: bool,
+    #[serde(rename = "override")]
+    pub load_shedding_override: Option<ClientLoadSheddingOverride>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientLoadSnapshot {
+    pub num_tracked_clients: usize,
+    pub num_heavy_clients: usize,
+    /// The clients submitting the most transactions touching congested objects, and the clients
+    /// with an override, in descending order of transactions
+    pub clients: Vec<ClientLoad>,
+}
+
+impl ClientLoadTracker {
+    /// Creates a tracker that trusts the proxies in the `trusted_proxies` CIDR blocks to identify
+    /// their clients. Invalid blocks are logged and ignored.
+    pub fn new(trusted_proxies: &[String]) -> Self {
+        Self {
+            inner: Default::default(),
+            trusted_proxies: trusted_proxies
+                .iter()
+                .filter_map(|block| {
+                    block
+                        .parse()
+                        .map_err(|e| warn!("Invalid load shedding trusted proxy {block}: {e}"))
+                        .ok()
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the client behind a request: the address forwarded in `proxy_ip` when the request
+    /// comes from a trusted proxy, and the connected peer otherwise.
+    pub fn client_identity(
+        &self,
+        connection_ip: Option<IpAddr>,
+        proxy_ip: Option<IpAddr>,
+    ) -> Option<IpAddr> {
+        match (connection_ip, proxy_ip) {
+            (Some(connection_ip), Some(proxy_ip))
+                if self
+                    .trusted_proxies
+                    .iter()
+                    .any(|block| block.contains(connection_ip)) =>
+            {
+                Some(proxy_ip)
+            }
+            (connection_ip, _) => connection_ip,
+        }
+    }
+
+    /// Counts a transaction touching congested objects submitted by `client`.
+    pub fn record_congested_transaction(&self, client: IpAddr) {
+        let mut inner = self.inner.write();
+        if inner.current.len() >= MAX_TRACKED_CLIENTS && !inner.current.contains_key(&client) {
+            return;
+        }
+        *inner.current.entry(client).or_default() += 1;
+    }
+
+    /// Selects the heaviest clients, which together submitted `load_shedding_percentage` of the
+    /// transactions touching congested objects in the last two intervals, and starts a new
+    /// interval. If they submitted less, the rest is shed from the other clients uniformly.
+    /// Returns the number of tracked and heavy clients.
+    pub fn update_heavy_clients(&self, load_shedding_percentage: u32) -> (usize, usize) {
+        let mut inner = self.inner.write();
+        let mut counts = inner.previous.clone();
+        for (client, count) in &inner.current {
+            *counts.entry(*client).or_default() += count;
+        }
+        let num_tracked_clients = counts.len();
+        // Exempt clients are never shed, so they can't make up for the load to shed.
+        counts.retain(|client, _| {
+            inner.overrides.get(client) != Some(&ClientLoadSheddingOverride::Exempt)
+        });
+        (inner.heavy_clients, inner.residual_shedding_percentage) =
+            select_heavy_clients(counts, load_shedding_percentage);
+        inner.previous = std::mem::take(&mut inner.current);
+        (num_tracked_clients, inner.heavy_clients.len())
+    }
+
+    pub fn set_override(&self, client: IpAddr, load_shedding_override: ClientLoadSheddingOverride) {
+        self.inner
+            .write()
+            .overrides
+            .insert(client, load_shedding_override);
+    }
+
+    /// Returns whether `client` had an override.
+    pub fn clear_override(&self, client: &IpAddr) -> bool {
+        self.inner.write().overrides.remove(client).is_some()
+    }
+
+    pub fn snapshot(&self, limit: usize) -> ClientLoadSnapshot {
+        let inner = self.inner.read();
+        let mut clients: HashSet<IpAddr> = inner.current.keys().copied().collect();
+        clients.extend(inner.previous.keys().copied());
+        let num_tracked_clients = clients.len();
+        let mut clients: Vec<_> = clients
+            .into_iter()
+            .map(|client| (inner.transactions(&client), client))
+            .collect();
+        clients.sort_by(|a, b| b.cmp(a));
+        clients.truncate(limit);
+        let overridden: Vec<_> = inner
+            .overrides
+            .keys()
+            .filter(|client| !clients.iter().any(|(_, c)| c == *client))
+            .map(|client| (inner.transactions(client), *client))
+            .collect();
+        clients.extend(overridden);
+        clients.sort_by(|a, b| b.cmp(a));
+
+        ClientLoadSnapshot {
+            num_tracked_clients,
+            num_heavy_clients: inner.heavy_clients.len(),
+            clients: clients
+                .into_iter()
+                .map(|(congested_transactions, client)| ClientLoad {
+                    client,
+                    congested_transactions,
+                    heavy: inner.heavy_clients.contains(&client),
+                    load_shedding_override: inner.overrides.get(&client).copied(),
+                })
+                .collect(),
+        }
+    }
+}
+
+// Returns the clients with the most transactions in `counts`, which together account for at least
+// `load_shedding_percentage` of the transactions, excluding light users. If there aren't enough of
+// them, also returns the percentage of the other clients' transactions to shed to make up for it.
+fn select_heavy_clients(
+    counts: HashMap<IpAddr, u64>,
+    load_shedding_percentage: u32,
+) -> (HashSet<IpAddr>, u32) {
+    let total: u64 = counts.values().sum();
+    let target = (total * load_shedding_percentage as u64).div_ceil(100);
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut heavy_clients = HashSet::new();
+    let mut shed = 0;
+    for (client, count) in counts {
+        if shed >= target || count < MIN_HEAVY_CLIENT_TRANSACTIONS {
+            break;
+        }
+        heavy_clients.insert(client);
+        shed += count;
+    }
+    let residual_shedding_percentage = match total - shed {
+        0 => 0,
+        rest => (target.saturating_sub(shed) * 100).div_ceil(rest) as u32,
+    };
+    (heavy_clients, residual_shedding_percentage)
+}
+
 // Monitors the overload signals in `authority_state` periodically, and updates its `overload_info`
 // when the signals indicates overload.
 pub async fn overload_monitor(
@@ -123,6 +344,19 @@ fn check_authority_overload(
         .metrics
         .authority_load_shedding_percentage
         .set(load_shedding_percentage as i64);
+
+    let (num_tracked_clients, num_heavy_clients) = authority
+        .overload_info
+        .client_load
+        .update_heavy_clients(load_shedding_percentage);
+    authority
+        .metrics
+        .load_shedding_tracked_clients
+        .set(num_tracked_clients as i64);
+    authority
+        .metrics
+        .load_shedding_heavy_clients
+        .set(num_heavy_clients as i64);
     true
 }
 
@@ -258,6 +492,72 @@ pub fn overload_monitor_accept_tx(
     Ok(())
 }
 
+/// The outcome of load shedding for a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadSheddingDecision {
+    Accept,
+    /// The client is shed through an admin override.
+    RejectOverride,
+    /// The client is one of the heaviest submitters of transactions touching congested objects.
+    RejectHeavyClient,
+    /// The transaction is shed uniformly, regardless of its client.
+    RejectUniform,
+}
+
+impl LoadSheddingDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accept => "accept",
+            Self::RejectOverride => "override",
+            Self::RejectHeavyClient => "heavy_client",
+            Self::RejectUniform => "uniform",
+        }
+    }
+
+    pub fn into_result(self) -> SuiResult {
+        if self != Self::Accept {
+            fp_bail!(SuiError::ValidatorOverloadedRetryAfter {
+                retry_after_secs: SEED_UPDATE_DURATION_SECS
+            });
+        }
+        Ok(())
+    }
+}
+
+// Decides whether to accept the transaction with `tx_digest` submitted by `client` while the
+// authority is shedding `load_shedding_percentage` of transactions. When heavy clients are known,
+// their transactions are shed first, and those of other clients only as far as the heavy clients
+// don't account for the load to shed. Otherwise, or when the client is unknown, transactions are
+// shed uniformly.
+pub fn load_shedding_decision(
+    client_load: &ClientLoadTracker,
+    load_shedding_percentage: u32,
+    tx_digest: TransactionDigest,
+    client: Option<IpAddr>,
+) -> LoadSheddingDecision {
+    if let Some(client) = client {
+        let inner = client_load.inner.read();
+        match inner.overrides.get(&client) {
+            Some(ClientLoadSheddingOverride::Exempt) => return LoadSheddingDecision::Accept,
+            Some(ClientLoadSheddingOverride::Shed) => return LoadSheddingDecision::RejectOverride,
+            None => (),
+        }
+        if !inner.heavy_clients.is_empty() {
+            if inner.heavy_clients.contains(&client) {
+                return LoadSheddingDecision::RejectHeavyClient;
+            }
+            return match overload_monitor_accept_tx(inner.residual_shedding_percentage, tx_digest) {
+                Ok(()) => LoadSheddingDecision::Accept,
+                Err(_) => LoadSheddingDecision::RejectUniform,
+            };
+        }
+    }
+    match overload_monitor_accept_tx(load_shedding_percentage, tx_digest) {
+        Ok(()) => LoadSheddingDecision::Accept,
+        Err(_) => LoadSheddingDecision::RejectUniform,
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::disallowed_methods)] // allow unbounded_channel() since tests are simulating txn manager execution driver interaction.
 mod tests {
@@ -747,6 +1047,92 @@ mod tests {
         }
     }
 
+    // Tests that the heaviest clients are shed first, and that light users are unaffected.
+    #[test]
+    fn test_client_aware_load_shedding() {
+        let client_load = ClientLoadTracker::default();
+        let heavy: IpAddr = [10, 0, 0, 1].into();
+        let medium: IpAddr = [10, 0, 0, 2].into();
+        let light: IpAddr = [10, 0, 0, 3].into();
+        let exempt: IpAddr = [10, 0, 0, 4].into();
+        for _ in 0..100 {
+            client_load.record_congested_transaction(heavy);
+            client_load.record_congested_transaction(exempt);
+        }
+        for _ in 0..50 {
+            client_load.record_congested_transaction(medium);
+        }
+        for _ in 0..MIN_HEAVY_CLIENT_TRANSACTIONS - 1 {
+            client_load.record_congested_transaction(light);
+        }
+        client_load.set_override(exempt, ClientLoadSheddingOverride::Exempt);
+
+        let decision = |client| {
+            load_shedding_decision(&client_load, 90, TransactionDigest::random(), Some(client))
+        };
+
+        // Without heavy clients, transactions are shed uniformly.
+        assert_eq!(client_load.update_heavy_clients(0), (4, 0));
+        assert!((0..100).any(|_| decision(light) == LoadSheddingDecision::RejectUniform));
+
+        // The heaviest clients are shed first, skipping exempt clients. Counts from the previous
+        // interval are still used.
+        assert_eq!(client_load.update_heavy_clients(70), (4, 2));
+        assert_eq!(decision(heavy), LoadSheddingDecision::RejectHeavyClient);
+        assert_eq!(decision(medium), LoadSheddingDecision::RejectHeavyClient);
+        assert_eq!(decision(exempt), LoadSheddingDecision::Accept);
+        assert_eq!(decision(light), LoadSheddingDecision::Accept);
+
+        // Light users are never selected as heavy clients, but when the heavy clients don't
+        // account for all of the load to shed, the rest is shed uniformly.
+        for _ in 0..30 {
+            client_load.record_congested_transaction(heavy);
+        }
+        for _ in 0..MIN_HEAVY_CLIENT_TRANSACTIONS - 1 {
+            client_load.record_congested_transaction(light);
+        }
+        assert_eq!(client_load.update_heavy_clients(90), (2, 1));
+        assert_eq!(decision(heavy), LoadSheddingDecision::RejectHeavyClient);
+        let decisions: Vec<_> = (0..100).map(|_| decision(light)).collect();
+        assert!(decisions.contains(&LoadSheddingDecision::RejectUniform));
+        assert!(decisions.contains(&LoadSheddingDecision::Accept));
+
+        client_load.set_override(light, ClientLoadSheddingOverride::Shed);
+        assert_eq!(decision(light), LoadSheddingDecision::RejectOverride);
+        assert!(client_load.clear_override(&light));
+        assert_ne!(decision(light), LoadSheddingDecision::RejectOverride);
+
+        let snapshot = client_load.snapshot(1);
+        assert_eq!(snapshot.num_heavy_clients, 1);
+        let clients: Vec<_> = snapshot.clients.iter().map(|c| c.client).collect();
+        assert_eq!(clients, vec![heavy, exempt]);
+    }
+
+    #[test]
+    fn test_client_identity() {
+        let client_load =
+            ClientLoadTracker::new(&["10.0.0.0/8".to_string(), "invalid".to_string()]);
+        let proxy: IpAddr = [10, 0, 0, 1].into();
+        let peer: IpAddr = [192, 168, 0, 1].into();
+        let forwarded: IpAddr = [1, 2, 3, 4].into();
+
+        // Only trusted proxies can identify their clients.
+        assert_eq!(
+            client_load.client_identity(Some(proxy), Some(forwarded)),
+            Some(forwarded)
+        );
+        assert_eq!(
+            client_load.client_identity(Some(peer), Some(forwarded)),
+            Some(peer)
+        );
+        assert_eq!(client_load.client_identity(Some(proxy), None), Some(proxy));
+        assert_eq!(client_load.client_identity(None, Some(forwarded)), None);
+        assert_eq!(
+            ClientLoadTracker::default().client_identity(Some(proxy), Some(forwarded)),
+            Some(proxy)
+        );
+    }
+
     // Tests that rejected transaction will have a chance to be accepted in the future.
     #[sim_test]
     async fn test_txn_rejection_over_time() {
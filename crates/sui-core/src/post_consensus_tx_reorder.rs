@@ -5,8 +5,11 @@ use crate::consensus_handler::{
     SequencedConsensusTransactionKind, VerifiedSequencedConsensusTransaction,
 };
 use mysten_metrics::monitored_scope;
+use std::collections::HashMap;
 use sui_protocol_config::ConsensusTransactionOrdering;
+use sui_types::base_types::SuiAddress;
 use sui_types::messages_consensus::{ConsensusTransaction, ConsensusTransactionKind};
+use sui_types::transaction::TransactionDataAPI;
 
 pub struct PostConsensusTxReorder {}
 
@@ -20,6 +23,9 @@ impl PostConsensusTxReorder {
         // are put to the beginning of the sequenced_transactions vector.
         match kind {
             ConsensusTransactionOrdering::ByGasPrice => Self::order_by_gas_price(transactions),
+            ConsensusTransactionOrdering::ByGasPriceFairBySender => {
+                Self::order_by_gas_price_fair_by_sender(transactions)
+            }
             ConsensusTransactionOrdering::None => (),
         }
     }
@@ -28,17 +34,91 @@ impl PostConsensusTxReorder {
         let _scope = monitored_scope("HandleConsensusOutput::order_by_gas_price");
         transactions.sort_by_key(|txn| {
             // Reverse order, so that transactions with higher gas price are put to the beginning.
-            std::cmp::Reverse({
-                match &txn.0.transaction {
-                    SequencedConsensusTransactionKind::External(ConsensusTransaction {
-                        tracking_id: _,
-                        kind: ConsensusTransactionKind::UserTransaction(cert),
-                    }) => cert.gas_price(),
-                    // Non-user transactions are considered to have gas price of MAX u64 and are
-                    // put to the beginning.
-                    _ => u64::MAX,
-                }
-            })
+            std::cmp::Reverse(Self::gas_price_and_sender(txn).0)
         })
     }
+
+    /// Orders transactions by gas price lane, highest first, and round-robin by sender within
+    /// each lane: round `k` of a lane takes the `k`-th highest priced transaction of each sender in
+    /// the lane, and each round is ordered by gas price like `order_by_gas_price`, then by
+    /// consensus order. Paying enough to move up a lane puts a transaction ahead of all the lower
+    /// lanes, while a small gas price bump within a lane doesn't get a sender's extra transactions
+    /// ahead of the other senders in it. The order only depends on the consensus output, so it is
+    /// the same on every validator.
+    fn order_by_gas_price_fair_by_sender(
+        transactions: &mut [VerifiedSequencedConsensusTransaction],
+    ) {
+        let _scope = monitored_scope("HandleConsensusOutput::order_by_gas_price_fair_by_sender");
+        let gas_prices_and_senders: Vec<_> = transactions
+            .iter()
+            .map(Self::gas_price_and_sender)
+            .collect();
+        // The positions of each sender's transactions in each lane, highest gas price first.
+        let mut senders: HashMap<(u32, SuiAddress), Vec<usize>> = HashMap::new();
+        for (position, (gas_price, sender)) in gas_prices_and_senders.iter().enumerate() {
+            if let Some(sender) = sender {
+                senders
+                    .entry((Self::gas_price_lane(*gas_price), *sender))
+                    .or_default()
+                    .push(position);
+            }
+        }
+        // Non-user transactions are all in the first round of the highest lane and keep their
+        // relative order.
+        let mut rounds = vec![0; transactions.len()];
+        for positions in senders.values_mut() {
+            positions.sort_by_key(|position| {
+                (
+                    std::cmp::Reverse(gas_prices_and_senders[*position].0),
+                    *position,
+                )
+            });
+            for (round, position) in positions.iter().enumerate() {
+                rounds[*position] = round;
+            }
+        }
+        let keys: Vec<_> = gas_prices_and_senders
+            .iter()
+            .enumerate()
+            .map(|(position, (gas_price, _))| {
+                (
+                    std::cmp::Reverse(Self::gas_price_lane(*gas_price)),
+                    rounds[position],
+                    std::cmp::Reverse(*gas_price),
+                    position,
+                )
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..transactions.len()).collect();
+        order.sort_by_key(|position| keys[*position]);
+        // Move the transaction at `order[i]` to position `i`. Transactions before `i` have already
+        // been moved, so follow the permutation to find where the one we want ended up.
+        for i in 0..order.len() {
+            let mut source = order[i];
+            while source < i {
+                source = order[source];
+            }
+            transactions.swap(i, source);
+        }
+    }
+
+    // Gas prices from 2^k up to 2^(k+1) - 1 share lane k, so each lane costs twice the previous.
+    fn gas_price_lane(gas_price: u64) -> u32 {
+        gas_price.checked_ilog2().unwrap_or(0)
+    }
+
+    fn gas_price_and_sender(
+        txn: &VerifiedSequencedConsensusTransaction,
+    ) -> (u64, Option<SuiAddress>) {
+        match &txn.0.transaction {
+            SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                tracking_id: _,
+                kind: ConsensusTransactionKind::UserTransaction(cert),
+            }) => (cert.gas_price(), Some(cert.transaction_data().sender())),
+            // Non-user transactions are considered to have gas price of MAX u64 and are
+            // put to the beginning.
+            _ => (u64::MAX, None),
+        }
+    }
 }
@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::subscription_handler::{SubscriptionMetrics, EVENT_DISPATCH_BUFFER_SIZE};
+use async_trait::async_trait;
 use futures::Stream;
 use mysten_metrics::metered_channel::Sender;
 use mysten_metrics::spawn_monitored_task;
 use parking_lot::RwLock;
 use prometheus::Registry;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::fmt::Debug;
+use std::hash::Hash;
 use std::sync::Arc;
 use sui_json_rpc_types::Filter;
 use sui_types::base_types::ObjectID;
@@ -19,6 +21,72 @@ use tracing::{debug, warn};
 
 type Subscribers<T, F> = Arc<RwLock<BTreeMap<String, (tokio::sync::mpsc::Sender<T>, F)>>>;
 
+/// Number of items read back at a time while a resumable subscription catches up.
+const REPLAY_PAGE_SIZE: usize = 100;
+
+/// A page of data read back by a [`SubscriptionReplay`].
+pub struct ReplayPage<S, C> {
+    /// The items of the subscription in the page, oldest first.
+    pub items: Vec<S>,
+    /// The cursor to continue replaying from, or `None` once there is nothing more to replay. It
+    /// may be ahead of the last item, when the page ends with data the subscription skips.
+    pub next_cursor: Option<C>,
+}
+
+/// Reads back data that was already streamed, so that a resumable subscription can catch up from
+/// a cursor.
+#[async_trait]
+pub trait SubscriptionReplay<S>: Send + Sync + 'static {
+    type Cursor: Clone + Eq + Hash + Debug + Send + Sync + 'static;
+
+    /// The cursor of `item`, as accepted by `replay_after`.
+    fn cursor(item: &S) -> Self::Cursor;
+
+    /// Reads back up to `limit` items of data that come after `cursor`, and returns those of the
+    /// subscription. A page may hold fewer items than `limit`, or none at all, without the replay
+    /// being over.
+    async fn replay_after(
+        &self,
+        cursor: Self::Cursor,
+        limit: usize,
+    ) -> anyhow::Result<ReplayPage<S, Self::Cursor>>;
+}
+
+/// Cursors of the items most recently sent to a resumable subscriber. Live data may overlap with
+/// the data that was just replayed, and these are used to skip the duplicates.
+struct RecentCursors<C> {
+    order: VecDeque<C>,
+    cursors: HashSet<C>,
+}
+
+impl<C: Clone + Eq + Hash> RecentCursors<C> {
+    // Live data overlaps with at most one subscriber channel worth of replayed data.
+    const CAPACITY: usize = 2 * EVENT_DISPATCH_BUFFER_SIZE;
+
+    fn new() -> Self {
+        Self {
+            order: VecDeque::with_capacity(Self::CAPACITY),
+            cursors: HashSet::with_capacity(Self::CAPACITY),
+        }
+    }
+
+    fn contains(&self, cursor: &C) -> bool {
+        self.cursors.contains(cursor)
+    }
+
+    fn insert(&mut self, cursor: C) {
+        if !self.cursors.insert(cursor.clone()) {
+            return;
+        }
+        self.order.push_back(cursor);
+        if self.order.len() > Self::CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.cursors.remove(&oldest);
+            }
+        }
+    }
+}
+
 /// The Streamer splits a mpsc channel into multiple mpsc channels using the subscriber's `Filter<T>` object.
 /// Data will be sent to the subscribers in parallel and the subscription will be dropped if it received a send error.
 pub struct Streamer<T, S, F: Filter<T>> {
@@ -127,13 +195,111 @@ where
 
     /// Subscribe to the data stream filtered by the filter object.
     pub fn subscribe(&self, filter: F) -> impl Stream<Item = S> {
+        let (_, rx) = Self::register(&self.subscribers, filter);
+        ReceiverStream::new(rx)
+    }
+
+    /// Subscribe to the data stream filtered by the filter object, starting right after `cursor`.
+    /// The data streamed since `cursor` is first read back from `replay`, then the subscription
+    /// moves on to live data.
+    ///
+    /// Unlike `subscribe`, the subscriber is never dropped for falling behind. Replay only
+    /// proceeds as fast as the subscriber consumes data, and if the subscriber falls behind the
+    /// live data it catches up from `replay` again, starting after the last item it received.
+    pub fn subscribe_from<R: SubscriptionReplay<S>>(
+        &self,
+        filter: F,
+        cursor: R::Cursor,
+        replay: R,
+    ) -> impl Stream<Item = S> {
         let (tx, rx) = mpsc::channel::<S>(EVENT_DISPATCH_BUFFER_SIZE);
-        self.subscribers
-            .write()
-            .insert(ObjectID::random().to_string(), (tx, filter));
+        let subscribers = self.subscribers.clone();
+        spawn_monitored_task!(Self::stream_from(subscribers, filter, cursor, replay, tx));
         ReceiverStream::new(rx)
     }
 
+    async fn stream_from<R: SubscriptionReplay<S>>(
+        subscribers: Subscribers<S, F>,
+        filter: F,
+        mut cursor: R::Cursor,
+        replay: R,
+        tx: mpsc::Sender<S>,
+    ) {
+        let mut sent = RecentCursors::new();
+        loop {
+            // Register for live data before catching up, so that nothing streamed in the
+            // meantime is missed.
+            let (id, mut live) = Self::register(&subscribers, filter.clone());
+            let mut caught_up = false;
+            while !caught_up {
+                let page = match replay.replay_after(cursor.clone(), REPLAY_PAGE_SIZE).await {
+                    Ok(page) => page,
+                    Err(e) => {
+                        warn!(
+                            subscription_id = id,
+                            ?cursor,
+                            "Error when replaying subscription, closing it. Error: {e}"
+                        );
+                        subscribers.write().remove(&id);
+                        return;
+                    }
+                };
+                for item in page.items {
+                    let item_cursor = R::cursor(&item);
+                    cursor = item_cursor.clone();
+                    if sent.contains(&item_cursor) {
+                        continue;
+                    }
+                    if tx.send(item).await.is_err() {
+                        subscribers.write().remove(&id);
+                        return;
+                    }
+                    sent.insert(item_cursor);
+                }
+                match page.next_cursor {
+                    Some(next_cursor) => cursor = next_cursor,
+                    None => caught_up = true,
+                }
+            }
+
+            loop {
+                let item = tokio::select! {
+                    item = live.recv() => item,
+                    _ = tx.closed() => {
+                        subscribers.write().remove(&id);
+                        return;
+                    }
+                };
+                // The streamer closes the live channel when the subscriber falls behind.
+                let Some(item) = item else {
+                    break;
+                };
+                let item_cursor = R::cursor(&item);
+                if sent.contains(&item_cursor) {
+                    continue;
+                }
+                if tx.send(item).await.is_err() {
+                    subscribers.write().remove(&id);
+                    return;
+                }
+                sent.insert(item_cursor.clone());
+                cursor = item_cursor;
+            }
+            debug!(
+                subscription_id = id,
+                ?cursor,
+                "Subscriber fell behind live data, catching up."
+            );
+        }
+    }
+
+    fn register(subscribers: &Subscribers<S, F>, filter: F) -> (String, mpsc::Receiver<S>) {
+        let (tx, rx) = mpsc::channel::<S>(EVENT_DISPATCH_BUFFER_SIZE);
+        let id = ObjectID::random().to_string();
+        subscribers.write().insert(id.clone(), (tx, filter));
+        (id, rx)
+    }
+
     pub async fn send(&self, data: T) -> Result<(), SuiError> {
         self.streamer_queue
             .send(data)
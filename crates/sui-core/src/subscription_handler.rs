@@ -10,13 +10,15 @@ use prometheus::{
 use tokio_stream::Stream;
 use tracing::{error, instrument, trace};
 
-use crate::streamer::Streamer;
+use crate::streamer::{Streamer, SubscriptionReplay};
 use sui_json_rpc_types::{
     EffectsWithInput, EventFilter, SuiTransactionBlockEffects, SuiTransactionBlockEvents,
     TransactionFilter,
 };
 use sui_json_rpc_types::{SuiEvent, SuiTransactionBlockEffectsAPI};
+use sui_types::digests::TransactionDigest;
 use sui_types::error::SuiResult;
+use sui_types::event::EventID;
 use sui_types::transaction::TransactionData;
 
 #[cfg(test)]
@@ -118,4 +120,29 @@ impl SubscriptionHandler {
     ) -> impl Stream<Item = SuiTransactionBlockEffects> {
         self.transaction_streamer.subscribe(filter)
     }
+
+    /// Subscribe to the events matching `filter` emitted after the event at `cursor`. Past events
+    /// are read back from `replay` before moving on to live events, and the subscription is not
+    /// dropped if the subscriber falls behind.
+    pub fn subscribe_events_from(
+        &self,
+        filter: EventFilter,
+        cursor: EventID,
+        replay: impl SubscriptionReplay<SuiEvent, Cursor = EventID>,
+    ) -> impl Stream<Item = SuiEvent> {
+        self.event_streamer.subscribe_from(filter, cursor, replay)
+    }
+
+    /// Subscribe to the effects of the transactions matching `filter` executed after the
+    /// transaction at `cursor`. Past transactions are read back from `replay` before moving on to
+    /// live transactions, and the subscription is not dropped if the subscriber falls behind.
+    pub fn subscribe_transactions_from(
+        &self,
+        filter: TransactionFilter,
+        cursor: TransactionDigest,
+        replay: impl SubscriptionReplay<SuiTransactionBlockEffects, Cursor = TransactionDigest>,
+    ) -> impl Stream<Item = SuiTransactionBlockEffects> {
+        self.transaction_streamer
+            .subscribe_from(filter, cursor, replay)
+    }
 }
@@ -0,0 +1,190 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context;
+use arc_swap::ArcSwap;
+use ipnetwork::IpNetwork;
+use std::fs::File;
+use std::net::IpAddr;
+use std::sync::Arc;
+use sui_types::traffic_control::{AccessListConfig, PolicyConfig};
+use tracing::info;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Allow,
+    Deny,
+}
+
+/// Static allow and deny lists of CIDR blocks. Clients in the allow list are never blocked, and
+/// clients in the deny list are always blocked. When a client is in both, the most specific block
+/// decides, and the deny list wins ties.
+#[derive(Debug, Default)]
+pub struct AccessLists {
+    allow_list: Vec<IpNetwork>,
+    deny_list: Vec<IpNetwork>,
+}
+
+impl AccessLists {
+    /// Loads the lists of `policy_config`, and of the file at its `access_list_path` if any.
+    pub fn load(policy_config: &PolicyConfig) -> anyhow::Result<Self> {
+        let mut config = AccessListConfig {
+            allow_list: policy_config.allow_list.clone(),
+            deny_list: policy_config.deny_list.clone(),
+        };
+        if let Some(path) = &policy_config.access_list_path {
+            let file = File::open(path)
+                .with_context(|| format!("unable to open access lists at {}", path.display()))?;
+            let AccessListConfig {
+                allow_list,
+                deny_list,
+            } = serde_yaml::from_reader(file)
+                .with_context(|| format!("unable to parse access lists at {}", path.display()))?;
+            config.allow_list.extend(allow_list);
+            config.deny_list.extend(deny_list);
+        }
+        Ok(Self {
+            allow_list: parse_cidr_blocks(&config.allow_list)?,
+            deny_list: parse_cidr_blocks(&config.deny_list)?,
+        })
+    }
+
+    pub fn access(&self, ip: &IpAddr) -> Option<Access> {
+        let longest_match = |list: &[IpNetwork]| {
+            list.iter()
+                .filter(|block| block.contains(*ip))
+                .map(|block| block.prefix())
+                .max()
+        };
+        match (
+            longest_match(&self.allow_list),
+            longest_match(&self.deny_list),
+        ) {
+            (None, None) => None,
+            (Some(allow), Some(deny)) if allow > deny => Some(Access::Allow),
+            (Some(_), None) => Some(Access::Allow),
+            (_, Some(_)) => Some(Access::Deny),
+        }
+    }
+
+    pub fn to_config(&self) -> AccessListConfig {
+        AccessListConfig {
+            allow_list: self.allow_list.iter().map(ToString::to_string).collect(),
+            deny_list: self.deny_list.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+fn parse_cidr_blocks(blocks: &[String]) -> anyhow::Result<Vec<IpNetwork>> {
+    blocks
+        .iter()
+        .map(|block| {
+            block
+                .parse()
+                .with_context(|| format!("invalid CIDR block '{block}'"))
+        })
+        .collect()
+}
+
+/// Access lists shared by the traffic controllers of a node, so that they can all be reloaded
+/// at once without restarting the node.
+#[derive(Clone)]
+pub struct TrafficAccessLists {
+    policy_config: Arc<PolicyConfig>,
+    lists: Arc<ArcSwap<AccessLists>>,
+}
+
+impl TrafficAccessLists {
+    pub fn new(policy_config: &PolicyConfig) -> anyhow::Result<Self> {
+        let lists = AccessLists::load(policy_config)?;
+        Ok(Self {
+            policy_config: Arc::new(policy_config.clone()),
+            lists: Arc::new(ArcSwap::from_pointee(lists)),
+        })
+    }
+
+    /// Access lists that are empty until the lists of `policy_config` are successfully reloaded.
+    pub fn empty(policy_config: &PolicyConfig) -> Self {
+        Self {
+            policy_config: Arc::new(policy_config.clone()),
+            lists: Default::default(),
+        }
+    }
+
+    pub fn load(&self) -> Arc<AccessLists> {
+        self.lists.load_full()
+    }
+
+    /// Loads the lists again from the policy config captured when these access lists were
+    /// created, and from its access list file, which is read again. The current lists are kept if
+    /// the new ones are invalid.
+    pub fn reload(&self) -> anyhow::Result<Arc<AccessLists>> {
+        let lists = Arc::new(AccessLists::load(&self.policy_config)?);
+        info!(
+            allow_list_len = lists.allow_list.len(),
+            deny_list_len = lists.deny_list.len(),
+            "reloaded traffic control access lists"
+        );
+        self.lists.store(lists.clone());
+        Ok(lists)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_empty_access_lists_reload() {
+        let policy_config = PolicyConfig {
+            deny_list: vec!["10.0.0.0/8".to_string()],
+            ..Default::default()
+        };
+        let access_lists = TrafficAccessLists::empty(&policy_config);
+        assert_eq!(
+            access_lists.load().access(&IpAddr::from([10, 0, 0, 1])),
+            None
+        );
+        access_lists.reload().unwrap();
+        assert_eq!(
+            access_lists.load().access(&IpAddr::from([10, 0, 0, 1])),
+            Some(Access::Deny)
+        );
+    }
+
+    #[test]
+    fn test_access_lists_reload() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "allow-list: [\"10.0.0.0/8\"]").unwrap();
+        let policy_config = PolicyConfig {
+            allow_list: vec!["10.1.2.3".to_string()],
+            deny_list: vec!["10.1.0.0/16".to_string(), "192.168.0.0/16".to_string()],
+            access_list_path: Some(file.path().to_path_buf()),
+            ..Default::default()
+        };
+        let access_lists = TrafficAccessLists::new(&policy_config).unwrap();
+
+        let access = |ip: [u8; 4]| access_lists.load().access(&IpAddr::from(ip));
+        // The most specific block decides.
+        assert_eq!(access([10, 0, 0, 1]), Some(Access::Allow));
+        assert_eq!(access([10, 1, 0, 1]), Some(Access::Deny));
+        assert_eq!(access([10, 1, 2, 3]), Some(Access::Allow));
+        assert_eq!(access([192, 168, 0, 1]), Some(Access::Deny));
+        assert_eq!(access([1, 2, 3, 4]), None);
+
+        // Invalid lists are rejected, and the current ones are kept.
+        std::fs::write(file.path(), "deny-list: [\"1.2.3.0/33\"]\n").unwrap();
+        assert!(access_lists.reload().is_err());
+        assert_eq!(access([10, 0, 0, 1]), Some(Access::Allow));
+
+        std::fs::write(file.path(), "deny-list: [\"1.2.3.0/24\"]\n").unwrap();
+        access_lists.reload().unwrap();
+        assert_eq!(access([10, 0, 0, 1]), None);
+        assert_eq!(access([1, 2, 3, 4]), Some(Access::Deny));
+        assert_eq!(
+            access_lists.load().to_config().deny_list,
+            vec!["10.1.0.0/16", "192.168.0.0/16", "1.2.3.0/24"]
+        );
+    }
+}
@@ -15,6 +15,7 @@ pub struct TrafficControllerMetrics {
     pub blocks_delegated_to_firewall: IntCounter,
     pub firewall_delegation_request_fail: IntCounter,
     pub tally_channel_overflow: IntCounter,
+    pub requests_denied_by_access_list: IntCounter,
 }
 
 impl TrafficControllerMetrics {
@@ -64,6 +65,12 @@ impl TrafficControllerMetrics {
                 registry
             )
             .unwrap(),
+            requests_denied_by_access_list: register_int_counter_with_registry!(
+                "requests_denied_by_access_list",
+                "Number of requests blocked by this node because the client is in the deny list",
+                registry
+            )
+            .unwrap(),
         }
     }
 
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod access_lists;
 pub mod metrics;
 pub mod nodefw_client;
 pub mod nodefw_test_server;
@@ -14,6 +15,7 @@ use std::net::{IpAddr, Ipv4Addr};
 use std::ops::Add;
 use std::sync::Arc;
 
+use self::access_lists::{Access, TrafficAccessLists};
 use self::metrics::TrafficControllerMetrics;
 use crate::traffic_controller::nodefw_client::{BlockAddress, BlockAddresses, NodeFWClient};
 use crate::traffic_controller::policies::{
@@ -40,6 +42,7 @@ struct Blocklists {
 pub struct TrafficController {
     tally_channel: mpsc::Sender<TrafficTally>,
     blocklists: Blocklists,
+    access_lists: TrafficAccessLists,
     metrics: Arc<TrafficControllerMetrics>,
     dry_run_mode: bool,
 }
@@ -65,10 +68,28 @@ impl Debug for TrafficController {
 }
 
 impl TrafficController {
+    /// Spawns a traffic controller loading its own access lists from `policy_config`. If they are
+    /// invalid, the error is logged and the controller starts without access lists, until they are
+    /// fixed and reloaded.
     pub fn spawn(
         policy_config: PolicyConfig,
         metrics: TrafficControllerMetrics,
         fw_config: Option<RemoteFirewallConfig>,
+    ) -> Self {
+        let access_lists = TrafficAccessLists::new(&policy_config).unwrap_or_else(|e| {
+            error!("Invalid traffic control access lists, starting without them: {e:?}");
+            TrafficAccessLists::empty(&policy_config)
+        });
+        Self::spawn_with_access_lists(policy_config, metrics, fw_config, access_lists)
+    }
+
+    /// Spawns a traffic controller using `access_lists`, which may be shared with other
+    /// traffic controllers, instead of loading its own from `policy_config`.
+    pub fn spawn_with_access_lists(
+        policy_config: PolicyConfig,
+        metrics: TrafficControllerMetrics,
+        fw_config: Option<RemoteFirewallConfig>,
+        access_lists: TrafficAccessLists,
     ) -> Self {
         let metrics = Arc::new(metrics);
         let (tx, rx) = mpsc::channel(policy_config.channel_capacity);
@@ -87,6 +108,7 @@ impl TrafficController {
                 connection_ips: Arc::new(DashMap::new()),
                 proxy_ips: Arc::new(DashMap::new()),
             },
+            access_lists,
             metrics: metrics.clone(),
             dry_run_mode: policy_config.dry_run,
         };
@@ -110,7 +132,16 @@ impl TrafficController {
         Self::spawn(policy_config, metrics, fw_config)
     }
 
-    pub fn tally(&self, tally: TrafficTally) {
+    pub fn tally(&self, mut tally: TrafficTally) {
+        // Allowlisted clients are never blocked, so policies don't need to see their traffic.
+        let access_lists = self.access_lists.load();
+        let allowed = |ip: &IpAddr| access_lists.access(ip) == Some(Access::Allow);
+        tally.connection_ip = tally.connection_ip.filter(|ip| !allowed(ip));
+        tally.proxy_ip = tally.proxy_ip.filter(|ip| !allowed(ip));
+        if tally.connection_ip.is_none() && tally.proxy_ip.is_none() {
+            return;
+        }
+
         // Use try_send rather than send mainly to avoid creating backpressure
         // on the caller if the channel is full, which may slow down the critical
         // path. Dropping the tally on the floor should be ok, as in this case
@@ -133,6 +164,21 @@ impl TrafficController {
 
     /// Returns true if the connection is allowed, false if it is blocked
     pub async fn check(&self, connection_ip: Option<IpAddr>, proxy_ip: Option<IpAddr>) -> bool {
+        // Access lists take precedence over the blocklists: denied clients are always blocked, and
+        // allowed clients skip the blocklists.
+        let access_lists = self.access_lists.load();
+        let check_access = |ip: Option<IpAddr>| match ip.and_then(|ip| access_lists.access(&ip)) {
+            Some(Access::Allow) => Ok(None),
+            Some(Access::Deny) => Err(()),
+            None => Ok(ip),
+        };
+        let (Ok(connection_ip), Ok(proxy_ip)) =
+            (check_access(connection_ip), check_access(proxy_ip))
+        else {
+            self.metrics.requests_denied_by_access_list.inc();
+            return false;
+        };
+
         let connection_check = self.check_and_clear_blocklist(
             connection_ip,
             self.blocklists.connection_ips.clone(),
@@ -151,6 +197,10 @@ impl TrafficController {
         self.dry_run_mode
     }
 
+    pub fn access_lists(&self) -> &TrafficAccessLists {
+        &self.access_lists
+    }
+
     async fn check_and_clear_blocklist(
         &self,
         ip: Option<IpAddr>,
@@ -11,7 +11,10 @@ use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::time::Duration;
 use std::time::{Instant, SystemTime};
-use sui_types::traffic_control::{FreqThresholdConfig, PolicyConfig, PolicyType, Weight};
+use sui_types::traffic_control::{
+    ClientIdSource, FreqThresholdConfig, PolicyConfig, PolicyType, SlidingWindowErrorRateConfig,
+    TokenBucketConfig, Weight,
+};
 use tracing::info;
 
 pub struct TrafficSketch {
@@ -157,6 +160,8 @@ pub trait Policy {
 // not object safe, so we can't use a trait object instead
 pub enum TrafficControlPolicy {
     FreqThreshold(FreqThresholdPolicy),
+    TokenBucket(TokenBucketPolicy),
+    SlidingWindowErrorRate(SlidingWindowErrorRatePolicy),
     NoOp(NoOpPolicy),
     // Test policies below this point
     TestNConnIP(TestNConnIPPolicy),
@@ -169,6 +174,8 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::FreqThreshold(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::TokenBucket(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::SlidingWindowErrorRate(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestNConnIP(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestInspectIp(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.handle_tally(tally),
@@ -179,6 +186,8 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.policy_config(),
             TrafficControlPolicy::FreqThreshold(policy) => policy.policy_config(),
+            TrafficControlPolicy::TokenBucket(policy) => policy.policy_config(),
+            TrafficControlPolicy::SlidingWindowErrorRate(policy) => policy.policy_config(),
             TrafficControlPolicy::TestNConnIP(policy) => policy.policy_config(),
             TrafficControlPolicy::TestInspectIp(policy) => policy.policy_config(),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.policy_config(),
@@ -199,6 +208,12 @@ impl TrafficControlPolicy {
             PolicyType::FreqThreshold(freq_threshold_config) => Self::FreqThreshold(
                 FreqThresholdPolicy::new(policy_config, freq_threshold_config),
             ),
+            PolicyType::TokenBucket(token_bucket_config) => {
+                Self::TokenBucket(TokenBucketPolicy::new(policy_config, token_bucket_config))
+            }
+            PolicyType::SlidingWindowErrorRate(error_rate_config) => Self::SlidingWindowErrorRate(
+                SlidingWindowErrorRatePolicy::new(policy_config, error_rate_config),
+            ),
             PolicyType::TestNConnIP(n) => {
                 Self::TestNConnIP(TestNConnIPPolicy::new(policy_config, n).await)
             }
@@ -264,6 +279,199 @@ impl FreqThresholdPolicy {
     }
 }
 
+// Returns the IP address identifying the client of `tally`.
+fn client_ip(tally: &TrafficTally, source: ClientIdSource) -> Option<IpAddr> {
+    match source {
+        ClientIdSource::ConnectionIp => tally.connection_ip,
+        ClientIdSource::ForwardedHeader => tally.proxy_ip,
+    }
+}
+
+// Returns the response blocking the client `ip`, identified by `source`.
+fn block_client(ip: IpAddr, source: ClientIdSource) -> PolicyResponse {
+    match source {
+        ClientIdSource::ConnectionIp => PolicyResponse {
+            block_connection_ip: Some(ip),
+            block_proxy_ip: None,
+        },
+        ClientIdSource::ForwardedHeader => PolicyResponse {
+            block_connection_ip: None,
+            block_proxy_ip: Some(ip),
+        },
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+pub struct TokenBucketPolicy {
+    config: PolicyConfig,
+    token_bucket_config: TokenBucketConfig,
+    buckets: HashMap<IpAddr, TokenBucket>,
+}
+
+impl TokenBucketPolicy {
+    pub fn new(config: PolicyConfig, token_bucket_config: TokenBucketConfig) -> Self {
+        assert!(
+            token_bucket_config.refill_rate > 0.0,
+            "Token bucket refill rate must be positive"
+        );
+        Self {
+            config,
+            token_bucket_config,
+            buckets: HashMap::new(),
+        }
+    }
+
+    fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let TokenBucketConfig {
+            client_id_source,
+            capacity,
+            refill_rate,
+            max_tracked_clients,
+        } = self.token_bucket_config;
+        let Some(ip) = client_ip(&tally, client_id_source) else {
+            return PolicyResponse::default();
+        };
+        let now = Instant::now();
+        let capacity = capacity as f64;
+
+        if !self.buckets.contains_key(&ip) && self.buckets.len() >= max_tracked_clients {
+            // Buckets that are full again are the same as untracked ones.
+            self.buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.last_refill).as_secs_f64() * refill_rate
+                    < capacity
+            });
+            if self.buckets.len() >= max_tracked_clients {
+                return PolicyResponse::default();
+            }
+        }
+        let bucket = self.buckets.entry(ip).or_insert(TokenBucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+        bucket.tokens = (bucket.tokens
+            + now.duration_since(bucket.last_refill).as_secs_f64() * refill_rate)
+            .min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens < 1.0 {
+            return block_client(ip, client_id_source);
+        }
+        bucket.tokens -= 1.0;
+        PolicyResponse::default()
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
+// Tallies of a client in each update interval of a sliding window, oldest first.
+#[derive(Default)]
+struct ErrorRateWindow {
+    // (update interval index, number of tallies, total error weight)
+    intervals: VecDeque<(u64, u64, f64)>,
+}
+
+impl ErrorRateWindow {
+    fn expire(&mut self, first_interval: u64) {
+        while matches!(self.intervals.front(), Some((interval, _, _)) if *interval < first_interval)
+        {
+            self.intervals.pop_front();
+        }
+    }
+
+    fn record(&mut self, interval: u64, error_weight: f64) {
+        match self.intervals.back_mut() {
+            Some((last, tallies, errors)) if *last == interval => {
+                *tallies += 1;
+                *errors += error_weight;
+            }
+            _ => self.intervals.push_back((interval, 1, error_weight)),
+        }
+    }
+
+    fn totals(&self) -> (u64, f64) {
+        self.intervals
+            .iter()
+            .fold((0, 0.0), |(tallies, errors), (_, t, e)| {
+                (tallies + t, errors + e)
+            })
+    }
+}
+
+pub struct SlidingWindowErrorRatePolicy {
+    config: PolicyConfig,
+    error_rate_config: SlidingWindowErrorRateConfig,
+    num_intervals: u64,
+    start_time: Instant,
+    windows: HashMap<IpAddr, ErrorRateWindow>,
+}
+
+impl SlidingWindowErrorRatePolicy {
+    pub fn new(config: PolicyConfig, error_rate_config: SlidingWindowErrorRateConfig) -> Self {
+        assert!(
+            error_rate_config.update_interval_secs >= 1,
+            "Update interval too short, must be at least 1 second"
+        );
+        let num_intervals =
+            error_rate_config.window_size_secs / error_rate_config.update_interval_secs;
+        assert!(
+            num_intervals >= 1,
+            "Window size may not be smaller than update interval"
+        );
+        Self {
+            config,
+            error_rate_config,
+            num_intervals,
+            start_time: Instant::now(),
+            windows: HashMap::new(),
+        }
+    }
+
+    fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let SlidingWindowErrorRateConfig {
+            client_id_source,
+            error_rate_threshold,
+            min_tallies,
+            update_interval_secs,
+            max_tracked_clients,
+            ..
+        } = self.error_rate_config;
+        let Some(ip) = client_ip(&tally, client_id_source) else {
+            return PolicyResponse::default();
+        };
+        let interval = self.start_time.elapsed().as_secs() / update_interval_secs;
+        let first_interval = (interval + 1).saturating_sub(self.num_intervals);
+
+        if !self.windows.contains_key(&ip) && self.windows.len() >= max_tracked_clients {
+            self.windows.retain(|_, window| {
+                window.expire(first_interval);
+                !window.intervals.is_empty()
+            });
+            if self.windows.len() >= max_tracked_clients {
+                return PolicyResponse::default();
+            }
+        }
+        let window = self.windows.entry(ip).or_default();
+        window.expire(first_interval);
+        window.record(interval, tally.error_weight.value() as f64);
+
+        let (tallies, errors) = window.totals();
+        if tallies >= min_tallies && errors >= error_rate_threshold * tallies as f64 {
+            return block_client(ip, client_id_source);
+        }
+        PolicyResponse::default()
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
 ////////////// *** Test policies below this point *** //////////////
 
 #[derive(Clone)]
@@ -465,6 +673,84 @@ mod tests {
         assert_eq!(response.block_proxy_ip, None);
     }
 
+    #[sim_test]
+    async fn test_token_bucket_policy() {
+        // Allow bursts of 5 tallies, refilled at 2 tallies per second.
+        let mut policy = TrafficControlPolicy::TokenBucket(TokenBucketPolicy::new(
+            PolicyConfig::default(),
+            TokenBucketConfig {
+                client_id_source: ClientIdSource::ForwardedHeader,
+                capacity: 5,
+                refill_rate: 2.0,
+                ..Default::default()
+            },
+        ));
+        let alice = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))),
+            Weight::zero(),
+        );
+        let bob = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            Some(IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1))),
+            Weight::zero(),
+        );
+
+        // Alice bursts through her bucket and her forwarded IP is blocked, while bob,
+        // behind the same proxy, is not.
+        for _ in 0..5 {
+            assert_eq!(policy.handle_tally(alice.clone()).block_proxy_ip, None);
+        }
+        let response = policy.handle_tally(alice.clone());
+        assert_eq!(response.block_proxy_ip, alice.proxy_ip);
+        assert_eq!(response.block_connection_ip, None);
+        assert_eq!(policy.handle_tally(bob.clone()).block_proxy_ip, None);
+
+        // After a second, 2 more tallies are allowed.
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        for _ in 0..2 {
+            assert_eq!(policy.handle_tally(alice.clone()).block_proxy_ip, None);
+        }
+        assert_eq!(
+            policy.handle_tally(alice.clone()).block_proxy_ip,
+            alice.proxy_ip
+        );
+    }
+
+    #[sim_test]
+    async fn test_sliding_window_error_rate_policy() {
+        let mut policy =
+            TrafficControlPolicy::SlidingWindowErrorRate(SlidingWindowErrorRatePolicy::new(
+                PolicyConfig::default(),
+                SlidingWindowErrorRateConfig {
+                    error_rate_threshold: 0.5,
+                    min_tallies: 10,
+                    window_size_secs: 4,
+                    update_interval_secs: 1,
+                    ..Default::default()
+                },
+            ));
+        let ip = Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)));
+        let ok = TrafficTally::new(ip, None, Weight::zero());
+        let error = TrafficTally::new(ip, None, Weight::one());
+
+        // Errors are not blocked until the client sent enough tallies.
+        for _ in 0..9 {
+            assert_eq!(policy.handle_tally(error.clone()).block_connection_ip, None);
+        }
+        assert_eq!(policy.handle_tally(error.clone()).block_connection_ip, ip);
+
+        // Once the errors leave the window, successful tallies dilute the error rate.
+        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+        for _ in 0..6 {
+            assert_eq!(policy.handle_tally(ok.clone()).block_connection_ip, None);
+        }
+        for _ in 0..5 {
+            assert_eq!(policy.handle_tally(error.clone()).block_connection_ip, None);
+        }
+        assert_eq!(policy.handle_tally(error.clone()).block_connection_ip, ip);
+    }
+
     #[sim_test]
     async fn test_traffic_sketch_mem_estimate() {
         // Test for getting a rough estimate of memory usage for the traffic sketch
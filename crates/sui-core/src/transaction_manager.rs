@@ -12,6 +12,7 @@ use indexmap::IndexMap;
 use lru::LruCache;
 use mysten_metrics::monitored_scope;
 use parking_lot::RwLock;
+use serde::Serialize;
 use sui_types::{
     base_types::{ObjectID, SequenceNumber, TransactionDigest},
     committee::EpochId,
@@ -43,6 +44,12 @@ mod transaction_manager_tests;
 /// Minimum capacity of HashMaps used in TransactionManager.
 const MIN_HASHMAP_CAPACITY: usize = 1000;
 
+// Bounds on the dependency chains walked by `TransactionManager::snapshot`, which holds the lock
+// of the transaction manager: the length of each chain, and the number of pending certificates
+// examined for all of them.
+const MAX_SNAPSHOT_DEPENDENCY_CHAIN_LENGTH: usize = 100;
+const MAX_SNAPSHOT_CERTIFICATES_EXAMINED: usize = 100_000;
+
 /// TransactionManager is responsible for managing object dependencies of pending transactions,
 /// and publishing a stream of certified transactions (certificates) ready to execute.
 /// It receives certificates from Narwhal, validator RPC handlers, and checkpoint executor.
@@ -81,6 +88,49 @@ pub struct PendingCertificate {
     pub stats: PendingCertificateStats,
 }
 
+/// A missing input of a pending certificate. The version is None for packages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct MissingInput {
+    pub object_id: ObjectID,
+    pub version: Option<SequenceNumber>,
+}
+
+impl From<&InputKey> for MissingInput {
+    fn from(key: &InputKey) -> Self {
+        Self {
+            object_id: key.id(),
+            version: key.version(),
+        }
+    }
+}
+
+/// Why a pending certificate is not executing.
+#[derive(Clone, Debug, Serialize)]
+pub struct PendingCertificateInfo {
+    pub digest: TransactionDigest,
+    pub waiting_input_objects: Vec<MissingInput>,
+    pub waiting_ms: u64,
+    // Pending certificates that must execute before this one, each waiting on the next. Starts
+    // with the certificate this one waits on directly.
+    pub dependency_chain: Vec<TransactionDigest>,
+    // Set if the chain was cut short to bound the cost of the snapshot, in which case the root
+    // blocker is unknown.
+    pub dependency_chain_truncated: bool,
+    // The missing input of the last certificate in the chain (or of this certificate, when the
+    // chain is empty) that no pending certificate is ahead of. It is either being written by an
+    // executing certificate, or its writer has not reached the transaction manager.
+    pub root_blocker: Option<MissingInput>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionManagerSnapshot {
+    pub epoch: EpochId,
+    pub num_pending_certificates: usize,
+    pub num_executing_certificates: usize,
+    // Ordered by the time spent waiting, longest first.
+    pub pending_certificates: Vec<PendingCertificateInfo>,
+}
+
 struct CacheInner {
     versioned_cache: LruCache<ObjectID, SequenceNumber>,
     // we cache packages separately, because they are more expensive to look up in the db, so we
@@ -316,6 +366,86 @@ impl Inner {
         ready_certificates
     }
 
+    // Returns the pending certificate waiting on the highest version of `input_key`'s object that
+    // is lower than the version of `input_key`. Versions of an object become available in order,
+    // so `input_key` cannot become available before that certificate's input does. Subtracts the
+    // number of certificates examined from `budget`.
+    fn pending_predecessor(
+        &self,
+        input_key: &InputKey,
+        budget: &mut usize,
+    ) -> Option<TransactionDigest> {
+        let version = input_key.version()?;
+        let waiting = self.input_objects.get(&input_key.id())?;
+        *budget = budget.saturating_sub(waiting.len());
+        waiting
+            .keys()
+            .filter_map(|digest| {
+                self.pending_certificates
+                    .get(digest)?
+                    .waiting_input_objects
+                    .iter()
+                    .filter(|key| key.id() == input_key.id())
+                    .filter_map(|key| key.version())
+                    .filter(|v| *v < version)
+                    .max()
+                    .map(|v| (v, *digest))
+            })
+            .max()
+            .map(|(_, digest)| digest)
+    }
+
+    // Walks the dependency chain of `pending_cert` until it ends, it reaches
+    // `MAX_SNAPSHOT_DEPENDENCY_CHAIN_LENGTH`, or `budget` runs out.
+    fn pending_certificate_info(
+        &self,
+        pending_cert: &PendingCertificate,
+        budget: &mut usize,
+    ) -> PendingCertificateInfo {
+        let digest = *pending_cert.certificate.digest();
+        let mut dependency_chain = Vec::new();
+        let mut dependency_chain_truncated = false;
+        let mut visited = HashSet::from([digest]);
+        let mut current = pending_cert;
+        let root_blocker = loop {
+            if *budget == 0 || dependency_chain.len() >= MAX_SNAPSHOT_DEPENDENCY_CHAIN_LENGTH {
+                dependency_chain_truncated = true;
+                break None;
+            }
+            let next = current
+                .waiting_input_objects
+                .iter()
+                .find_map(|key| self.pending_predecessor(key, budget));
+            match next {
+                // A cycle can only be observed while the inputs of a certificate are updated.
+                Some(next) if !visited.insert(next) => break None,
+                Some(next) => {
+                    dependency_chain.push(next);
+                    current = &self.pending_certificates[&next];
+                }
+                None => {
+                    break current
+                        .waiting_input_objects
+                        .first()
+                        .map(MissingInput::from)
+                }
+            }
+        };
+
+        PendingCertificateInfo {
+            digest,
+            waiting_input_objects: pending_cert
+                .waiting_input_objects
+                .iter()
+                .map(MissingInput::from)
+                .collect(),
+            waiting_ms: pending_cert.stats.enqueue_time.elapsed().as_millis() as u64,
+            dependency_chain,
+            dependency_chain_truncated,
+            root_blocker,
+        }
+    }
+
     fn maybe_reserve_capacity(&mut self) {
         self.missing_inputs.maybe_reserve_capacity();
         self.input_objects.maybe_reserve_capacity();
@@ -766,6 +896,40 @@ impl TransactionManager {
             .map(|cert| cert.waiting_input_objects.clone().into_iter().collect())
     }
 
+    /// Describes why pending certificates are not executing: the `limit` certificates that have
+    /// waited longest, or only `digest` if set. The dependency chains of the certificates are
+    /// bounded, since they are walked while holding the lock.
+    pub fn snapshot(
+        &self,
+        digest: Option<TransactionDigest>,
+        limit: usize,
+    ) -> TransactionManagerSnapshot {
+        let reconfig_lock = self.inner.read();
+        let inner = reconfig_lock.read();
+
+        let mut pending: Vec<_> = match digest {
+            Some(digest) => inner
+                .pending_certificates
+                .get(&digest)
+                .into_iter()
+                .collect(),
+            None => inner.pending_certificates.values().collect(),
+        };
+        pending.sort_by_key(|cert| (cert.stats.enqueue_time, *cert.certificate.digest()));
+        pending.truncate(limit);
+
+        let mut budget = MAX_SNAPSHOT_CERTIFICATES_EXAMINED;
+        TransactionManagerSnapshot {
+            epoch: inner.epoch,
+            num_pending_certificates: inner.pending_certificates.len(),
+            num_executing_certificates: inner.executing_certificates.len(),
+            pending_certificates: pending
+                .into_iter()
+                .map(|cert| inner.pending_certificate_info(cert, &mut budget))
+                .collect(),
+        }
+    }
+
     // Returns the number of transactions waiting on each object ID, as well as the age of the oldest transaction in the queue.
     pub(crate) fn objects_queue_len_and_age(
         &self,
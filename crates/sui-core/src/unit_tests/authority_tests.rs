@@ -38,7 +38,9 @@ use sui_types::dynamic_field::DynamicFieldType;
 use sui_types::effects::TransactionEffects;
 use sui_types::epoch_data::EpochData;
 use sui_types::error::UserInputError;
-use sui_types::execution_status::{ExecutionFailureStatus, ExecutionStatus};
+use sui_types::execution_status::{
+    CongestedObjects, CongestionCancellation, ExecutionFailureStatus, ExecutionStatus,
+};
 use sui_types::gas_coin::GasCoin;
 use sui_types::object::Data;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
@@ -5825,3 +5827,116 @@ async fn test_per_object_congestion_control() {
         .unwrap()
         .is_empty());
 }
+
+#[sim_test]
+async fn test_congestion_cancellation() {
+    let (sender, keypair): (_, AccountKeyPair) = get_key_pair();
+
+    // In this test, 8 transactions with gas prices from 1000 to 8000 operate on the same shared
+    // object, and only 2 of them fit in each commit. Transactions deferred for 2 rounds are
+    // cancelled instead of being deferred again.
+    let shared_objects = create_shared_objects(1);
+    let gas_objects = create_gas_objects(8, sender);
+
+    let mut protocol_config =
+        ProtocolConfig::get_for_version(ProtocolVersion::max(), Chain::Unknown);
+    protocol_config
+        .set_per_object_congestion_control_mode(PerObjectCongestionControlMode::TotalGasBudget);
+    protocol_config.set_max_accumulated_txn_cost_per_object_in_checkpoint(200_000_000);
+    protocol_config.set_max_deferral_rounds_for_congestion_control(2);
+    let authority = TestAuthorityBuilder::new()
+        .with_reference_gas_price(1000)
+        .with_protocol_config(protocol_config)
+        .build()
+        .await;
+    let mut genesis_objects = gas_objects.clone();
+    genesis_objects.extend(shared_objects.clone());
+    authority.insert_genesis_objects(&genesis_objects).await;
+
+    let mut certificates: Vec<VerifiedCertificate> = vec![];
+    for (index, gas_object) in gas_objects.iter().enumerate() {
+        let certificate = make_test_transaction(
+            &sender,
+            &keypair,
+            shared_objects[0].id(),
+            OBJECT_START_VERSION,
+            &gas_object.compute_object_reference(),
+            &[&authority],
+            12345,
+            Some(1000 * (index + 1) as u64),
+            Some(100_000_000),
+        )
+        .await;
+        certificates.push(certificate);
+    }
+    certificates.shuffle(&mut rand::thread_rng());
+
+    let epoch_store = authority.epoch_store_for_testing();
+    let gas_prices = |txns: &[VerifiedExecutableTransaction]| {
+        let mut gas_prices: Vec<_> = txns
+            .iter()
+            .map(|cert| cert.data().transaction_data().gas_price())
+            .collect();
+        gas_prices.sort();
+        gas_prices
+    };
+
+    // The first two rounds schedule the 2 transactions with the highest gas prices, and defer the
+    // rest.
+    let mut scheduled_txns = send_batch_consensus_no_execution(&authority, &certificates).await;
+    assert_eq!(gas_prices(&scheduled_txns), vec![7000, 8000]);
+    let round_2_txns = send_batch_consensus_no_execution(&authority, &[]).await;
+    assert_eq!(gas_prices(&round_2_txns), vec![5000, 6000]);
+    scheduled_txns.extend(round_2_txns);
+    for cert in &scheduled_txns {
+        assert!(epoch_store
+            .get_congestion_cancellation(cert.digest())
+            .unwrap()
+            .is_none());
+    }
+
+    // In the third round, the remaining transactions have been deferred for 2 rounds. The 2 that
+    // fit are scheduled, and the other 2 are cancelled, with a gas price above those scheduled.
+    let round_3_txns = send_batch_consensus_no_execution(&authority, &[]).await;
+    assert_eq!(gas_prices(&round_3_txns), vec![1000, 2000, 3000, 4000]);
+    assert!(epoch_store
+        .get_all_deferred_transactions_for_test()
+        .unwrap()
+        .is_empty());
+    for cert in &round_3_txns {
+        let cancellation = epoch_store
+            .get_congestion_cancellation(cert.digest())
+            .unwrap();
+        if cert.data().transaction_data().gas_price() <= 2000 {
+            assert_eq!(
+                cancellation,
+                Some(CongestionCancellation {
+                    congested_objects: CongestedObjects(vec![shared_objects[0].id()]),
+                    suggested_gas_price: 4001,
+                })
+            );
+        } else {
+            assert!(cancellation.is_none());
+        }
+    }
+    scheduled_txns.extend(round_3_txns);
+
+    // Cancelled transactions fail without being executed, and report the congestion in their
+    // effects.
+    for cert in &scheduled_txns {
+        let (effects, _) = authority
+            .try_execute_immediately(cert, None, &epoch_store)
+            .await
+            .unwrap();
+        let cancellation = CongestionCancellation::from_execution_status(effects.status());
+        assert_eq!(
+            cancellation,
+            epoch_store
+                .get_congestion_cancellation(cert.digest())
+                .unwrap()
+        );
+        if cancellation.is_none() {
+            assert!(effects.status().is_ok());
+        }
+    }
+}
@@ -1,6 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::StreamExt;
 use move_core_types::account_address::AccountAddress;
 use move_core_types::identifier::Identifier;
 
@@ -13,13 +17,16 @@ use move_core_types::{
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::json;
-use sui_json_rpc_types::SuiMoveStruct;
+use sui_json_rpc_types::{Filter, SuiMoveStruct};
 
 use sui_types::base_types::ObjectID;
 use sui_types::gas_coin::GasCoin;
 use sui_types::object::bounded_visitor::BoundedVisitor;
 use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
 
+use crate::streamer::{ReplayPage, Streamer, SubscriptionReplay};
+use crate::subscription_handler::SubscriptionMetrics;
+
 #[test]
 fn test_to_json_value() {
     let move_event = TestEvent {
@@ -64,6 +71,100 @@ fn test_to_json_value() {
     assert_eq!(Some(&json!("test_event")), json_value.pointer("/name"));
 }
 
+#[derive(Clone)]
+struct AllItems;
+
+impl Filter<u64> for AllItems {
+    fn matches(&self, _item: &u64) -> bool {
+        true
+    }
+}
+
+#[derive(Clone)]
+struct ItemsAbove(u64);
+
+impl Filter<u64> for ItemsAbove {
+    fn matches(&self, item: &u64) -> bool {
+        *item > self.0
+    }
+}
+
+/// Replays the items it was created with that match its filter, using each item as its own
+/// cursor.
+struct VecReplay<F>(Vec<u64>, F);
+
+#[async_trait]
+impl<F: Filter<u64> + Send + Sync + 'static> SubscriptionReplay<u64> for VecReplay<F> {
+    type Cursor = u64;
+
+    fn cursor(item: &u64) -> u64 {
+        *item
+    }
+
+    async fn replay_after(
+        &self,
+        cursor: u64,
+        limit: usize,
+    ) -> anyhow::Result<ReplayPage<u64, u64>> {
+        let read: Vec<_> = self
+            .0
+            .iter()
+            .copied()
+            .filter(|item| *item > cursor)
+            .take(limit)
+            .collect();
+        let next_cursor = if read.len() < limit {
+            None
+        } else {
+            read.last().copied()
+        };
+        Ok(ReplayPage {
+            items: read
+                .into_iter()
+                .filter(|item| self.1.matches(item))
+                .collect(),
+            next_cursor,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_subscribe_from_cursor() {
+    let metrics = Arc::new(SubscriptionMetrics::new(&prometheus::Registry::new()));
+    let streamer: Streamer<u64, u64, AllItems> = Streamer::spawn(100, metrics, "test");
+    let mut stream =
+        Box::pin(streamer.subscribe_from(AllItems, 10, VecReplay((1..=250).collect(), AllItems)));
+
+    // Everything after the cursor is replayed first, across several pages.
+    let replayed: Vec<_> = stream.by_ref().take(240).collect().await;
+    assert_eq!(replayed, (11..=250).collect::<Vec<_>>());
+
+    // Live items that were already replayed are skipped.
+    streamer.send(250).await.unwrap();
+    streamer.send(251).await.unwrap();
+    assert_eq!(stream.next().await, Some(251));
+}
+
+#[tokio::test]
+async fn test_subscribe_from_cursor_across_skipped_pages() {
+    let metrics = Arc::new(SubscriptionMetrics::new(&prometheus::Registry::new()));
+    let streamer: Streamer<u64, u64, ItemsAbove> = Streamer::spawn(100, metrics, "test");
+    let filter = ItemsAbove(200);
+    let mut stream = Box::pin(streamer.subscribe_from(
+        filter.clone(),
+        0,
+        VecReplay((1..=250).collect(), filter),
+    ));
+
+    // Replay keeps going past pages that hold none of the subscription's items.
+    let replayed: Vec<_> = stream.by_ref().take(50).collect().await;
+    assert_eq!(replayed, (201..=250).collect::<Vec<_>>());
+
+    streamer.send(150).await.unwrap();
+    streamer.send(251).await.unwrap();
+    assert_eq!(stream.next().await, Some(251));
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TestEvent {
     creator: AccountAddress,
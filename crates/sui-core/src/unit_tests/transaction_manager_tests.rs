@@ -22,7 +22,7 @@ use tokio::{
 
 use crate::{
     authority::{authority_tests::init_state_with_objects, AuthorityState},
-    transaction_manager::{PendingCertificate, TransactionManager},
+    transaction_manager::{MissingInput, PendingCertificate, TransactionManager},
 };
 
 #[allow(clippy::disallowed_methods)] // allow unbounded_channel()
@@ -361,6 +361,110 @@ async fn transaction_manager_object_dependency() {
     transaction_manager.check_empty_for_testing();
 }
 
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn transaction_manager_snapshot() {
+    // Initialize an authority state, with gas objects and a shared object.
+    let (owner, _keypair) = deterministic_random_account_key();
+    let gas_objects: Vec<Object> = (0..2)
+        .map(|_| Object::with_id_owner_for_testing(ObjectID::random(), owner))
+        .collect();
+    let shared_object = Object::with_id_shared_for_testing(ObjectID::random());
+    let state =
+        init_state_with_objects([gas_objects.clone(), vec![shared_object.clone()]].concat()).await;
+    let (transaction_manager, mut rx_ready_certificates) = make_transaction_manager(&state);
+
+    // Enqueue two transactions mutating the shared object at consecutive versions, neither of
+    // which is available yet.
+    let shared_object_arg = ObjectArg::SharedObject {
+        id: shared_object.id(),
+        initial_shared_version: 0.into(),
+        mutable: true,
+    };
+    let transaction_first = make_transaction(
+        gas_objects[0].clone(),
+        vec![CallArg::Object(shared_object_arg)],
+    );
+    let transaction_second = make_transaction(
+        gas_objects[1].clone(),
+        vec![CallArg::Object(shared_object_arg)],
+    );
+    let version_first = 1000.into();
+    let version_second = 1001.into();
+    for (transaction, version) in [
+        (&transaction_first, version_first),
+        (&transaction_second, version_second),
+    ] {
+        state
+            .epoch_store_for_testing()
+            .set_shared_object_versions_for_testing(
+                transaction.digest(),
+                &vec![(shared_object.id(), version)],
+            )
+            .unwrap();
+    }
+    transaction_manager.enqueue(
+        vec![transaction_first.clone(), transaction_second.clone()],
+        &state.epoch_store_for_testing(),
+    );
+    sleep(Duration::from_secs(1)).await;
+
+    let missing = |version| MissingInput {
+        object_id: shared_object.id(),
+        version: Some(version),
+    };
+
+    // The second transaction waits on the first one, and both are blocked by the first version.
+    let snapshot = transaction_manager.snapshot(None, 10);
+    assert_eq!(snapshot.num_pending_certificates, 2);
+    assert_eq!(snapshot.num_executing_certificates, 0);
+    let first = snapshot
+        .pending_certificates
+        .iter()
+        .find(|info| info.digest == *transaction_first.digest())
+        .unwrap();
+    assert_eq!(first.waiting_input_objects, vec![missing(version_first)]);
+    assert!(first.waiting_ms >= 1000);
+    assert!(first.dependency_chain.is_empty());
+    assert_eq!(first.root_blocker, Some(missing(version_first)));
+    let second = transaction_manager
+        .snapshot(Some(*transaction_second.digest()), 10)
+        .pending_certificates
+        .pop()
+        .unwrap();
+    assert_eq!(second.digest, *transaction_second.digest());
+    assert_eq!(second.dependency_chain, vec![*transaction_first.digest()]);
+    assert!(!second.dependency_chain_truncated);
+    assert_eq!(second.root_blocker, Some(missing(version_first)));
+
+    // Once the first transaction is executing, the second one is only blocked by its input.
+    transaction_manager.objects_available(
+        vec![InputKey::VersionedObject {
+            id: shared_object.id(),
+            version: version_first,
+        }],
+        &state.epoch_store_for_testing(),
+    );
+    let tx_first = rx_ready_certificates.recv().await.unwrap().certificate;
+    let snapshot = transaction_manager.snapshot(None, 10);
+    assert_eq!(snapshot.num_pending_certificates, 1);
+    assert_eq!(snapshot.num_executing_certificates, 1);
+    let second = &snapshot.pending_certificates[0];
+    assert!(second.dependency_chain.is_empty());
+    assert_eq!(second.root_blocker, Some(missing(version_second)));
+
+    transaction_manager.notify_commit(
+        tx_first.digest(),
+        vec![InputKey::VersionedObject {
+            id: shared_object.id(),
+            version: version_second,
+        }],
+        &state.epoch_store_for_testing(),
+    );
+    let tx_second = rx_ready_certificates.recv().await.unwrap().certificate;
+    transaction_manager.notify_commit(tx_second.digest(), vec![], &state.epoch_store_for_testing());
+    transaction_manager.check_empty_for_testing();
+}
+
 #[tokio::test(flavor = "current_thread", start_paused = true)]
 async fn transaction_manager_receiving_notify_commit() {
     telemetry_subscribers::init_for_testing();
@@ -22,7 +22,8 @@ use sui_test_transaction_builder::batch_make_transfer_transactions;
 use sui_types::{
     quorum_driver_types::ExecuteTransactionRequestType,
     traffic_control::{
-        FreqThresholdConfig, PolicyConfig, PolicyType, RemoteFirewallConfig, Weight,
+        FreqThresholdConfig, PolicyConfig, PolicyType, RemoteFirewallConfig, TokenBucketConfig,
+        Weight,
     },
 };
 use test_cluster::{TestCluster, TestClusterBuilder};
@@ -40,6 +41,7 @@ async fn test_validator_traffic_control_ok() -> Result<(), anyhow::Error> {
         channel_capacity: 100,
         dry_run: false,
         spam_sample_rate: Weight::one(),
+        ..Default::default()
     };
     let network_config = ConfigBuilder::new_with_temp_dir()
         .with_policy_config(Some(policy_config))
@@ -85,6 +87,7 @@ async fn test_validator_traffic_control_dry_run() -> Result<(), anyhow::Error> {
         error_policy_type: PolicyType::TestPanicOnInvocation,
         channel_capacity: 100,
         dry_run: true,
+        ..Default::default()
     };
     let network_config = ConfigBuilder::new_with_temp_dir()
         .with_policy_config(Some(policy_config))
@@ -379,6 +382,7 @@ async fn test_traffic_sketch_with_sampled_spam() {
         spam_sample_rate: Weight::new(0.5).unwrap(),
         channel_capacity: 100,
         dry_run: false,
+        ..Default::default()
     };
     let metrics = TrafficSim::run(
         policy,
@@ -400,6 +404,41 @@ async fn test_traffic_sketch_with_sampled_spam() {
     assert!(metrics.num_blocked > (expected_requests / 5) - 1000);
 }
 
+#[sim_test]
+async fn test_traffic_token_bucket_with_blocks() {
+    let token_bucket_config = TokenBucketConfig {
+        capacity: 1_000,
+        refill_rate: 5_000.0,
+        ..Default::default()
+    };
+    let policy = PolicyConfig {
+        connection_blocklist_ttl_sec: 1,
+        proxy_blocklist_ttl_sec: 1,
+        spam_policy_type: PolicyType::TokenBucket(token_bucket_config),
+        error_policy_type: PolicyType::NoOp,
+        spam_sample_rate: Weight::one(),
+        channel_capacity: 100,
+        dry_run: false,
+        ..Default::default()
+    };
+    let metrics = TrafficSim::run(
+        policy,
+        1,      // num_clients
+        10_000, // per_client_tps
+        Duration::from_secs(10),
+        true, // report
+    )
+    .await;
+
+    let expected_requests = 10_000 * 10;
+    assert!(metrics.num_requests > expected_requests - 1_000);
+    assert!(metrics.num_requests < expected_requests + 200);
+    // The bucket holds 1_000 tallies and loses 5_000 per second, so the client is
+    // blocked after roughly 0.2 seconds, and after each time it is unblocked.
+    assert!(metrics.abs_time_to_first_block.unwrap() < Duration::from_secs(1));
+    assert!(metrics.num_blocklist_adds >= 5);
+}
+
 async fn assert_traffic_control_ok(mut test_cluster: TestCluster) -> Result<(), anyhow::Error> {
     let context = &mut test_cluster.wallet;
     let jsonrpc_client = &test_cluster.fullnode_handle.rpc_client;
@@ -15,8 +15,8 @@ use sui_types::{
     effects::{TransactionEffects as NativeTransactionEffects, TransactionEffectsAPI},
     event::Event as NativeEvent,
     execution_status::{
-        ExecutionFailureStatus, ExecutionStatus as NativeExecutionStatus, MoveLocation,
-        MoveLocationOpt,
+        CongestionCancellation, ExecutionFailureStatus, ExecutionStatus as NativeExecutionStatus,
+        MoveLocation, MoveLocationOpt,
     },
     transaction::{
         Command, ProgrammableTransaction, SenderSignedData as NativeSenderSignedData,
@@ -28,6 +28,7 @@ use sui_types::{
 use super::{
     balance_change::BalanceChange,
     base64::Base64,
+    big_int::BigInt,
     checkpoint::{Checkpoint, CheckpointId},
     cursor::{JsonCursor, Page},
     date_time::DateTime,
@@ -36,6 +37,7 @@ use super::{
     event::Event,
     gas::GasEffects,
     object_change::ObjectChange,
+    sui_address::SuiAddress,
     transaction_block::{TransactionBlock, TransactionBlockInner},
     unchanged_shared_object::UnchangedSharedObject,
 };
@@ -82,6 +84,17 @@ pub enum ExecutionStatus {
     Failure,
 }
 
+/// The shared objects that were too congested for a transaction block to be scheduled, when
+/// consensus cancelled it instead of executing it.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct SharedObjectCongestion {
+    /// The congested shared objects the transaction block used.
+    pub congested_objects: Vec<SuiAddress>,
+
+    /// A gas price at which the transaction block would likely have been scheduled.
+    pub suggested_gas_price: BigInt,
+}
+
 /// Type to override names of the Dependencies Connection (which has nullable transactions and
 /// therefore must be a different types to the default `TransactionBlockConnection`).
 struct DependencyConnectionNames;
@@ -196,6 +209,21 @@ impl TransactionBlockEffects {
         }
     }
 
+    /// The shared objects that were too congested for this transaction to be executed, if
+    /// consensus cancelled it.
+    async fn shared_object_congestion(&self) -> Option<SharedObjectCongestion> {
+        let cancellation = CongestionCancellation::from_execution_status(self.native().status())?;
+        Some(SharedObjectCongestion {
+            congested_objects: cancellation
+                .congested_objects
+                .0
+                .into_iter()
+                .map(SuiAddress::from)
+                .collect(),
+            suggested_gas_price: BigInt::from(cancellation.suggested_gas_price),
+        })
+    }
+
     /// Transactions whose outputs this transaction depends upon.
     async fn dependencies(
         &self,
@@ -289,7 +289,12 @@ impl<T: R2D2Connection + 'static> IndexerApiServer for IndexerApi<T> {
         ))
     }
 
-    fn subscribe_event(&self, _sink: SubscriptionSink, _filter: EventFilter) -> SubscriptionResult {
+    fn subscribe_event(
+        &self,
+        _sink: SubscriptionSink,
+        _filter: EventFilter,
+        _cursor: Option<EventID>,
+    ) -> SubscriptionResult {
         Err(SubscriptionEmptyError)
     }
 
@@ -297,6 +302,7 @@ impl<T: R2D2Connection + 'static> IndexerApiServer for IndexerApi<T> {
         &self,
         _sink: SubscriptionSink,
         _filter: TransactionFilter,
+        _cursor: Option<TransactionDigest>,
     ) -> SubscriptionResult {
         Err(SubscriptionEmptyError)
     }
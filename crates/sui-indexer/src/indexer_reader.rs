@@ -24,8 +24,8 @@ use sui_json_rpc_types::{
     SuiTransactionBlockEffectsAPI,
 };
 use sui_json_rpc_types::{
-    CheckpointId, EpochInfo, EventFilter, SuiEvent, SuiObjectDataFilter,
-    SuiTransactionBlockResponse, TransactionFilter,
+    CheckpointId, EpochInfo, EventFilter, Filter, SuiEvent, SuiObjectDataFilter,
+    SuiTransactionBlockResponse, TransactionFilter, MAX_EVENTS_SCANNED_PER_QUERY,
 };
 use sui_package_resolver::Package;
 use sui_package_resolver::PackageStore;
@@ -1044,6 +1044,71 @@ impl<U: R2D2Connection> IndexerReader<U> {
         cursor: Option<EventID>,
         limit: usize,
         descending_order: bool,
+    ) -> IndexerResult<Vec<SuiEvent>> {
+        let (indexed_filter, exact) = filter.indexed_filter(Self::is_indexed_event_filter);
+        if exact {
+            return self
+                .query_indexed_events(filter, cursor, limit, descending_order)
+                .await;
+        }
+
+        // Filters that cannot be expressed in SQL are evaluated against the events of a filter
+        // that they imply, a page at a time, until enough events match. A filter that implies no
+        // such filter would have to be checked against every event.
+        if matches!(&indexed_filter, EventFilter::All(filters) if filters.is_empty()) {
+            return Err(IndexerError::NotSupportedError(
+                "This type of EventFilter is not supported unless combined with a supported filter using All or And.".into(),
+            ));
+        }
+        let mut events = vec![];
+        let mut cursor = cursor;
+        let mut scanned = 0;
+        loop {
+            let page = self
+                .query_indexed_events(indexed_filter.clone(), cursor, limit, descending_order)
+                .await?;
+            let exhausted = page.len() < limit;
+            scanned += page.len();
+            cursor = page.last().map(|event| event.id).or(cursor);
+            events.extend(page.into_iter().filter(|event| filter.matches(event)));
+            if exhausted || events.len() >= limit {
+                break;
+            }
+            if scanned >= MAX_EVENTS_SCANNED_PER_QUERY {
+                return Err(IndexerError::InvalidArgumentError(format!(
+                    "Scanned {scanned} events without finding {limit} that match the filter, narrow the filter or continue from cursor {cursor:?}"
+                )));
+            }
+        }
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    fn is_indexed_event_filter(filter: &EventFilter) -> bool {
+        match filter {
+            EventFilter::All(filters) => filters.is_empty(),
+            EventFilter::Sender(_)
+            | EventFilter::Transaction(_)
+            | EventFilter::Package(_)
+            | EventFilter::MoveModule { .. }
+            | EventFilter::MoveEventType(_)
+            | EventFilter::MoveEventModule { .. } => true,
+            EventFilter::MoveEventField { .. }
+            | EventFilter::MoveEventFieldCondition { .. }
+            | EventFilter::Any(_)
+            | EventFilter::And(_, _)
+            | EventFilter::Or(_, _)
+            | EventFilter::Not(_)
+            | EventFilter::TimeRange { .. } => false,
+        }
+    }
+
+    async fn query_indexed_events(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
     ) -> IndexerResult<Vec<SuiEvent>> {
         let pool = self.get_pool();
         let (tx_seq, event_seq) = if let Some(cursor) = cursor {
@@ -1129,11 +1194,14 @@ impl<U: R2D2Connection> IndexerReader<U> {
                     // Processed above
                     unreachable!()
                 }
+                EventFilter::All(filters) if filters.is_empty() => "TRUE".to_string(),
                 EventFilter::MoveEventField { .. }
+                | EventFilter::MoveEventFieldCondition { .. }
                 | EventFilter::All(_)
                 | EventFilter::Any(_)
                 | EventFilter::And(_, _)
                 | EventFilter::Or(_, _)
+                | EventFilter::Not(_)
                 | EventFilter::TimeRange { .. } => {
                     return Err(IndexerError::NotSupportedError(
                         "This type of EventFilter is not supported.".into(),
@@ -71,11 +71,18 @@ pub trait IndexerApi {
         &self,
         /// The filter criteria of the event stream. See [Event filter](https://docs.sui.io/build/event_api#event-filters) documentation for examples.
         filter: EventFilter,
+        /// An optional cursor. If provided, the stream starts with the events emitted after this event, and the subscription is not dropped if the client falls behind. Filters without an index of their own, such as `Package`, must then be combined with one that has an index using `All` or `And`.
+        cursor: Option<EventID>,
     );
 
     /// Subscribe to a stream of Sui transaction effects
     #[subscription(name = "subscribeTransaction", item = SuiTransactionBlockEffects)]
-    fn subscribe_transaction(&self, filter: TransactionFilter);
+    fn subscribe_transaction(
+        &self,
+        filter: TransactionFilter,
+        /// An optional cursor. If provided, the stream starts with the transactions executed after this transaction, and the subscription is not dropped if the client falls behind.
+        cursor: Option<TransactionDigest>,
+    );
 
     /// Return the list of dynamic field objects owned by an object.
     #[method(name = "getDynamicFields")]
@@ -11,6 +11,7 @@ use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::rpc_params;
 use jsonrpsee::RpcModule;
 use prometheus::Registry;
+use serde_json::{json, Value};
 use std::env;
 use sui_config::local_ip_utils;
 use sui_json_rpc::{JsonRpcServerBuilder, SuiRpcModule};
@@ -131,77 +132,54 @@ async fn test_disable_routing() {
     assert!(response.is_err());
 }
 
-// TODO(chris): clean up this after March 27th, 2023
-// #[tokio::test]
-// async fn test_rpc_backward_compatibility_batched_request() {
-//     let mut builder = JsonRpcServerBuilder::new(
-//          "1.5", &Registry::new(), None, None,
-//     );
-//     let mut builder = JsonRpcServerBuilder::new(
-//          "1.5", &Registry::new(), None, None,
-//     );
-//     builder.register_module(TestApiModule).unwrap();
-
-//     let port = get_available_port("0.0.0.0");
-//     let handle = builder
-//         .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
-//         .await
-//         .unwrap();
-//     let url = format!("http://0.0.0.0:{}", port);
-
-//     // Test with un-versioned client
-//     let client = HttpClientBuilder::default().build(&url).unwrap();
-
-//     let mut builder = BatchRequestBuilder::default();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-
-//     let response = client.batch_request::<String>(builder).await.unwrap();
-//     assert_eq!(3, response.num_successful_calls());
-
-//     // try to access old method directly should fail
-//     let mut builder = BatchRequestBuilder::default();
-//     builder.insert("test_foo_1_5", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-
-//     let response = client.batch_request::<String>(builder).await.unwrap();
-//     assert_eq!(2, response.num_successful_calls());
-
-//     // One malformed request shouldn't fail the whole batch
-//     let client = Client::new();
-//     let response = client
-//         .post(format!("http://127.0.0.1:{}/", port))
-//         .json(&vec![
-//             json!(&Request {
-//                 jsonrpc: Default::default(),
-//                 id: Id::Number(1),
-//                 method: "test_foo".into(),
-//                 params: Some(&JsonRawValue::from_string("[true]".into()).unwrap()),
-//             }),
-//             json!("Bad json input"),
-//         ])
-//         .send()
-//         .await
-//         .unwrap();
-
-//     let responses = response.text().await.unwrap();
-//     let responses: Vec<&JsonRawValue> = serde_json::from_str(&responses).unwrap();
-
-//     // Should have 2 results
-//     assert_eq!(2, responses.len());
-
-//     // First response should success
-//     let response = serde_json::from_str::<Response<String>>(responses[0].get());
-//     assert!(matches!(response, Ok(result) if result.result == "Some string"));
-
-//     // Second response should fail
-//     let response = serde_json::from_str::<ErrorResponse>(responses[1].get());
-//     assert!(matches!(response, Ok(result) if result.error_object().message() == "Invalid request"));
-
-//     handle.stop().unwrap()
-// }
+#[tokio::test]
+async fn test_rpc_batched_request() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new(), None, None);
+    builder.set_max_batch_size(3);
+    builder.register_module(TestApiModule).unwrap();
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None, None).await.unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+    let post = |body: Value| {
+        let url = url.clone();
+        async move {
+            reqwest::Client::new()
+                .post(url)
+                .json(&body)
+                .send()
+                .await
+                .unwrap()
+                .json::<Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    // Each call in a batch succeeds or fails on its own, and is routed like a single request.
+    let responses = post(json!([
+        { "jsonrpc": "2.0", "id": 1, "method": "test_foo", "params": [true] },
+        { "jsonrpc": "2.0", "id": 2, "method": "test_foo_1_5", "params": ["string"] },
+        "Bad json input",
+    ]))
+    .await;
+    let responses = responses.as_array().unwrap();
+    assert_eq!(3, responses.len());
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["result"], "Some string");
+    assert_eq!(responses[1]["id"], 2);
+    assert!(responses[1].get("error").is_some());
+    assert_eq!(responses[2]["error"]["code"], -32600);
+
+    // Batches over the limit are rejected as a whole.
+    let call = json!({ "jsonrpc": "2.0", "id": 1, "method": "test_foo", "params": [true] });
+    let response = post(json!([call, call, call, call])).await;
+    assert_eq!(response["error"]["code"], -32600);
+
+    // So are empty batches.
+    let response = post(json!([])).await;
+    assert_eq!(response["error"]["code"], -32600);
+}
 
 #[open_rpc(namespace = "test")]
 #[rpc(server, client, namespace = "test")]
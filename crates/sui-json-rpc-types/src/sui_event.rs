@@ -10,6 +10,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use serde_with::{serde_as, DisplayFromStr};
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::Display;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
@@ -26,6 +28,10 @@ use sui_types::sui_serde::SuiStructTag;
 #[cfg(any(feature = "test-utils", test))]
 use std::str::FromStr;
 
+/// Maximum number of events an event store checks against a filter it has no index for, in a
+/// single query.
+pub const MAX_EVENTS_SCANNED_PER_QUERY: usize = 10_000;
+
 pub type EventPage = Page<SuiEvent, EventID>;
 
 #[serde_as]
@@ -244,10 +250,19 @@ pub enum EventFilter {
         #[serde_as(as = "DisplayFromStr")]
         module: Identifier,
     },
+    /// Return events with a field equal to the given value. The path is either a JSON pointer
+    /// into the event's `parsedJson`, such as `/pool/reserves/0`, or the same path with dots,
+    /// such as `pool.reserves.0`.
     MoveEventField {
         path: String,
         value: Value,
     },
+    /// Return events with a field that satisfies the given condition. The path is interpreted as
+    /// for `MoveEventField`.
+    MoveEventFieldCondition {
+        path: String,
+        condition: EventFieldCondition,
+    },
     /// Return events emitted in [start_time, end_time] interval
     #[serde(rename_all = "camelCase")]
     TimeRange {
@@ -265,6 +280,89 @@ pub enum EventFilter {
     Any(Vec<EventFilter>),
     And(Box<EventFilter>, Box<EventFilter>),
     Or(Box<EventFilter>, Box<EventFilter>),
+    /// Return events that do not match the given filter.
+    Not(Box<EventFilter>),
+}
+
+/// A condition on a field of an event. Numbers are unsigned integers, given either as JSON
+/// numbers or as decimal strings, which is how integers wider than 32 bits appear in an event's
+/// `parsedJson`. A field that is not a number never satisfies a numeric comparison.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum EventFieldCondition {
+    /// The field is greater than the given number.
+    Gt(Value),
+    /// The field is greater than or equal to the given number.
+    Gte(Value),
+    /// The field is less than the given number.
+    Lt(Value),
+    /// The field is less than or equal to the given number.
+    Lte(Value),
+    /// The field is in the [min, max) interval.
+    Range { min: Value, max: Value },
+    /// The field is equal to one of the given values. Numbers are compared by value.
+    In(Vec<Value>),
+}
+
+impl EventFieldCondition {
+    pub fn matches(&self, field: &Value) -> bool {
+        let compare = |bound: &Value| compare_unsigned_integers(field, bound);
+        match self {
+            EventFieldCondition::Gt(bound) => compare(bound) == Some(Ordering::Greater),
+            EventFieldCondition::Gte(bound) => {
+                matches!(compare(bound), Some(Ordering::Greater | Ordering::Equal))
+            }
+            EventFieldCondition::Lt(bound) => compare(bound) == Some(Ordering::Less),
+            EventFieldCondition::Lte(bound) => {
+                matches!(compare(bound), Some(Ordering::Less | Ordering::Equal))
+            }
+            EventFieldCondition::Range { min, max } => {
+                matches!(compare(min), Some(Ordering::Greater | Ordering::Equal))
+                    && compare(max) == Some(Ordering::Less)
+            }
+            EventFieldCondition::In(values) => values
+                .iter()
+                .any(|value| value == field || compare(value) == Some(Ordering::Equal)),
+        }
+    }
+}
+
+/// Returns the digits of `value` without leading zeros, if it is an unsigned integer, either as
+/// a JSON number or as a decimal string.
+fn unsigned_integer_digits(value: &Value) -> Option<Cow<'_, str>> {
+    let digits = match value {
+        Value::Number(number) => return number.as_u64().map(|n| Cow::Owned(n.to_string())),
+        Value::String(digits) => digits.as_str(),
+        _ => return None,
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let trimmed = digits.trim_start_matches('0');
+    Some(Cow::Borrowed(if trimmed.is_empty() {
+        "0"
+    } else {
+        trimmed
+    }))
+}
+
+/// Compares two unsigned integers of any width, or returns None if either is not one.
+fn compare_unsigned_integers(a: &Value, b: &Value) -> Option<Ordering> {
+    let a = unsigned_integer_digits(a)?;
+    let b = unsigned_integer_digits(b)?;
+    Some(a.len().cmp(&b.len()).then_with(|| a.cmp(&b)))
+}
+
+/// Converts an event field path to a JSON pointer. Paths that are not already pointers are
+/// read as dot separated field names and indices.
+fn field_pointer(path: &str) -> Cow<'_, str> {
+    if path.is_empty() || path.starts_with('/') {
+        return Cow::Borrowed(path);
+    }
+    Cow::Owned(
+        path.split('.')
+            .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+            .collect(),
+    )
 }
 
 impl EventFilter {
@@ -272,7 +370,10 @@ impl EventFilter {
         Ok(match self {
             EventFilter::MoveEventType(event_type) => &item.type_ == event_type,
             EventFilter::MoveEventField { path, value } => {
-                matches!(item.parsed_json.pointer(path), Some(v) if v == value)
+                matches!(item.parsed_json.pointer(&field_pointer(path)), Some(v) if v == value)
+            }
+            EventFilter::MoveEventFieldCondition { path, condition } => {
+                matches!(item.parsed_json.pointer(&field_pointer(path)), Some(v) if condition.matches(v))
             }
             EventFilter::Sender(sender) => &item.sender == sender,
             EventFilter::Package(object_id) => &item.package_id == object_id,
@@ -287,6 +388,7 @@ impl EventFilter {
             EventFilter::Or(f1, f2) => {
                 EventFilter::Any(vec![*(*f1).clone(), *(*f2).clone()]).matches(item)
             }
+            EventFilter::Not(f) => !f.matches(item),
             EventFilter::Transaction(digest) => digest == &item.id.tx_digest,
 
             EventFilter::TimeRange {
@@ -311,6 +413,31 @@ impl EventFilter {
     pub fn or(self, other_filter: EventFilter) -> Self {
         Self::Any(vec![self, other_filter])
     }
+    pub fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    /// Returns a filter that matches every event this filter matches, and that `is_indexed`
+    /// accepts, along with whether the two filters are equivalent. This lets an event store that
+    /// can only look up events by some filters evaluate the others, by checking the events of
+    /// the returned filter against this one. Falls back to all events, `All(vec![])`, which event
+    /// stores should reject rather than scan. Scans of the returned filter should stop after
+    /// [`MAX_EVENTS_SCANNED_PER_QUERY`] events.
+    pub fn indexed_filter(&self, is_indexed: impl Fn(&EventFilter) -> bool + Copy) -> (Self, bool) {
+        if is_indexed(self) {
+            return (self.clone(), true);
+        }
+        let conjuncts = match self {
+            EventFilter::All(filters) => filters.iter().collect(),
+            EventFilter::And(f1, f2) => vec![f1.as_ref(), f2.as_ref()],
+            _ => vec![],
+        };
+        conjuncts
+            .into_iter()
+            .map(|filter| filter.indexed_filter(is_indexed).0)
+            .find(|filter| !matches!(filter, EventFilter::All(filters) if filters.is_empty()))
+            .map_or((EventFilter::All(vec![]), false), |filter| (filter, false))
+    }
 }
 
 impl Filter<SuiEvent> for EventFilter {
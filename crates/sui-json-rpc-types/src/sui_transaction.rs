@@ -30,7 +30,7 @@ use sui_types::crypto::SuiSignature;
 use sui_types::digests::{ConsensusCommitDigest, ObjectDigest, TransactionEventsDigest};
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
 use sui_types::error::{ExecutionError, SuiError, SuiResult};
-use sui_types::execution_status::ExecutionStatus;
+use sui_types::execution_status::{CongestedObjects, CongestionCancellation, ExecutionStatus};
 use sui_types::gas::GasCostSummary;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::{MoveObject, Owner};
@@ -687,6 +687,7 @@ pub trait SuiTransactionBlockEffectsAPI {
     fn executed_epoch(&self) -> EpochId;
     fn transaction_digest(&self) -> &TransactionDigest;
     fn gas_cost_summary(&self) -> &GasCostSummary;
+    fn shared_object_congestion(&self) -> Option<&SuiSharedObjectCongestion>;
 
     /// Return an iterator of mutated objects, but excluding the gas object.
     fn mutated_excluding_gas(&self) -> Vec<OwnedObjectRef>;
@@ -715,6 +716,10 @@ pub struct SuiTransactionBlockEffectsModifiedAtVersions {
 pub struct SuiTransactionBlockEffectsV1 {
     /// The status of the execution
     pub status: SuiExecutionStatus,
+    /// Set if the transaction was cancelled due to shared object congestion instead of being
+    /// executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_object_congestion: Option<SuiSharedObjectCongestion>,
     /// The epoch when this transaction was executed.
     #[schemars(with = "BigInt<u64>")]
     #[serde_as(as = "BigInt<u64>")]
@@ -810,6 +815,9 @@ impl SuiTransactionBlockEffectsAPI for SuiTransactionBlockEffectsV1 {
     fn gas_cost_summary(&self) -> &GasCostSummary {
         &self.gas_used
     }
+    fn shared_object_congestion(&self) -> Option<&SuiSharedObjectCongestion> {
+        self.shared_object_congestion.as_ref()
+    }
 
     fn mutated_excluding_gas(&self) -> Vec<OwnedObjectRef> {
         self.mutated
@@ -866,6 +874,7 @@ impl SuiTransactionBlockEffects {
         Self::V1(SuiTransactionBlockEffectsV1 {
             transaction_digest,
             status,
+            shared_object_congestion: None,
             gas_object: OwnedObjectRef {
                 owner: Owner::AddressOwner(SuiAddress::random_for_testing_only()),
                 reference: sui_types::base_types::random_object_ref().into(),
@@ -893,6 +902,10 @@ impl TryFrom<TransactionEffects> for SuiTransactionBlockEffects {
         Ok(SuiTransactionBlockEffects::V1(
             SuiTransactionBlockEffectsV1 {
                 status: effect.status().clone().into(),
+                shared_object_congestion: CongestionCancellation::from_execution_status(
+                    effect.status(),
+                )
+                .map(Into::into),
                 executed_epoch: effect.executed_epoch(),
                 modified_at_versions: effect
                     .modified_at_versions()
@@ -1261,6 +1274,38 @@ impl SuiExecutionStatus {
     }
 }
 
+/// The shared objects that were too congested for a transaction to be scheduled, when consensus
+/// cancelled it instead.
+#[serde_as]
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "SharedObjectCongestion", rename_all = "camelCase")]
+pub struct SuiSharedObjectCongestion {
+    /// The congested shared objects the transaction used.
+    pub congested_objects: Vec<ObjectID>,
+    /// A gas price at which the transaction would likely have been scheduled.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub suggested_gas_price: u64,
+}
+
+impl From<CongestionCancellation> for SuiSharedObjectCongestion {
+    fn from(cancellation: CongestionCancellation) -> Self {
+        Self {
+            congested_objects: cancellation.congested_objects.0,
+            suggested_gas_price: cancellation.suggested_gas_price,
+        }
+    }
+}
+
+impl From<SuiSharedObjectCongestion> for CongestionCancellation {
+    fn from(congestion: SuiSharedObjectCongestion) -> Self {
+        Self {
+            congested_objects: CongestedObjects(congestion.congested_objects),
+            suggested_gas_price: congestion.suggested_gas_price,
+        }
+    }
+}
+
 impl From<ExecutionStatus> for SuiExecutionStatus {
     fn from(status: ExecutionStatus) -> Self {
         match status {
@@ -16,7 +16,9 @@ use sui_types::gas_coin::GasCoin;
 use sui_types::object::{MoveObject, Owner};
 use sui_types::{parse_sui_struct_tag, MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
 
-use crate::{ObjectChange, SuiMoveStruct, SuiMoveValue};
+use crate::{
+    EventFieldCondition, EventFilter, Filter, ObjectChange, SuiEvent, SuiMoveStruct, SuiMoveValue,
+};
 
 #[test]
 fn test_move_value_to_sui_coin() {
@@ -196,3 +198,91 @@ fn test_type_tag_struct_tag_devnet_inc_222() {
         assert_eq!(oc, deser);
     }
 }
+
+#[test]
+fn test_event_field_filters() {
+    let mut event = SuiEvent::random_for_testing();
+    event.parsed_json = json!({
+        "pool": { "reserves": ["1000", "18446744073709551616"] },
+        "side": 1,
+        "trader": "alice",
+    });
+    let matches = |filter: EventFilter| filter.matches(&event);
+    let condition = |path: &str, condition| EventFilter::MoveEventFieldCondition {
+        path: path.to_string(),
+        condition,
+    };
+
+    // Numbers compare by value, whether they are JSON numbers or strings, at any width.
+    assert!(matches(condition(
+        "/pool/reserves/0",
+        EventFieldCondition::Gt(json!(999))
+    )));
+    assert!(!matches(condition(
+        "/pool/reserves/0",
+        EventFieldCondition::Gt(json!("1000"))
+    )));
+    assert!(matches(condition(
+        "pool.reserves.1",
+        EventFieldCondition::Gte(json!("18446744073709551615")),
+    )));
+    assert!(matches(condition(
+        "side",
+        EventFieldCondition::Range {
+            min: json!(1),
+            max: json!(2)
+        },
+    )));
+    assert!(!matches(condition(
+        "side",
+        EventFieldCondition::Range {
+            min: json!(0),
+            max: json!(1)
+        },
+    )));
+    assert!(matches(condition(
+        "side",
+        EventFieldCondition::Lte(json!("0001"))
+    )));
+
+    // Values that are not numbers never satisfy a numeric comparison, but can be listed.
+    assert!(!matches(condition(
+        "trader",
+        EventFieldCondition::Lt(json!(1))
+    )));
+    assert!(matches(condition(
+        "trader",
+        EventFieldCondition::In(vec![json!("bob"), json!("alice")]),
+    )));
+    assert!(matches(condition(
+        "side",
+        EventFieldCondition::In(vec![json!("1")])
+    )));
+
+    // Nested paths work for exact matches too, and filters can be negated.
+    let is_alice = EventFilter::MoveEventField {
+        path: "trader".to_string(),
+        value: json!("alice"),
+    };
+    assert!(matches(is_alice.clone()));
+    assert!(!matches(is_alice.not()));
+}
+
+#[test]
+fn test_event_indexed_filter() {
+    let sender = EventFilter::Sender(SuiAddress::random_for_testing_only());
+    let field = EventFilter::MoveEventField {
+        path: "/side".to_string(),
+        value: json!(1),
+    };
+    let is_indexed = |filter: &EventFilter| matches!(filter, EventFilter::Sender(_));
+
+    let (indexed, exact) = sender.indexed_filter(is_indexed);
+    assert!(matches!(indexed, EventFilter::Sender(_)) && exact);
+
+    let (indexed, exact) = field.clone().and(sender.clone()).indexed_filter(is_indexed);
+    assert!(matches!(indexed, EventFilter::Sender(_)) && !exact);
+
+    let (indexed, exact) = field.or(sender).indexed_filter(is_indexed);
+    assert!(matches!(indexed, EventFilter::All(filters) if filters.is_empty()) && !exact);
+}
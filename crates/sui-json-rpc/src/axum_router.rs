@@ -6,7 +6,7 @@ use std::{net::SocketAddr, sync::Arc};
 use sui_types::traffic_control::RemoteFirewallConfig;
 
 use axum::extract::{ConnectInfo, Json, State};
-use futures::StreamExt;
+use futures::{future, StreamExt};
 use hyper::HeaderMap;
 use jsonrpsee::core::server::helpers::BoundedSubscriptions;
 use jsonrpsee::core::server::helpers::MethodResponse;
@@ -14,17 +14,19 @@ use jsonrpsee::core::server::helpers::MethodSink;
 use jsonrpsee::core::server::rpc_module::MethodKind;
 use jsonrpsee::server::logger::{self, TransportProtocol};
 use jsonrpsee::server::RandomIntegerIdProvider;
-use jsonrpsee::types::error::{ErrorCode, BATCHES_NOT_SUPPORTED_CODE, BATCHES_NOT_SUPPORTED_MSG};
+use jsonrpsee::types::error::ErrorCode;
 use jsonrpsee::types::{ErrorObject, Id, InvalidRequest, Params, Request};
 use jsonrpsee::{core::server::rpc_module::Methods, server::logger::Logger};
 use serde_json::value::{RawValue, Value};
 use sui_core::traffic_controller::{
-    metrics::TrafficControllerMetrics, policies::TrafficTally, TrafficController,
+    access_lists::TrafficAccessLists, metrics::TrafficControllerMetrics, policies::TrafficTally,
+    TrafficController,
 };
 use sui_types::error::{SuiError, SuiResult};
 use sui_types::traffic_control::{PolicyConfig, Weight};
 use tracing::warn;
 
+use crate::metrics::BatchMetrics;
 use crate::routing_layer::RpcRouter;
 use sui_json_rpc_api::CLIENT_TARGET_API_VERSION_HEADER;
 
@@ -41,6 +43,9 @@ pub struct JsonRpcService<L> {
     methods: Methods,
     rpc_router: RpcRouter,
     traffic_controller: Option<Arc<TrafficController>>,
+    /// Maximum number of calls in a batch request.
+    max_batch_size: usize,
+    batch_metrics: BatchMetrics,
 }
 
 impl<L> JsonRpcService<L> {
@@ -50,7 +55,10 @@ impl<L> JsonRpcService<L> {
         logger: L,
         remote_fw_config: Option<RemoteFirewallConfig>,
         policy_config: Option<PolicyConfig>,
+        access_lists: Option<TrafficAccessLists>,
         traffic_controller_metrics: TrafficControllerMetrics,
+        max_batch_size: usize,
+        registry: &prometheus::Registry,
     ) -> Self {
         Self {
             methods,
@@ -58,13 +66,50 @@ impl<L> JsonRpcService<L> {
             logger,
             id_provider: Arc::new(RandomIntegerIdProvider),
             traffic_controller: policy_config.map(|policy| {
-                Arc::new(TrafficController::spawn(
-                    policy,
-                    traffic_controller_metrics,
-                    remote_fw_config,
-                ))
+                Arc::new(match access_lists {
+                    Some(access_lists) => TrafficController::spawn_with_access_lists(
+                        policy,
+                        traffic_controller_metrics,
+                        remote_fw_config,
+                        access_lists,
+                    ),
+                    None => TrafficController::spawn(
+                        policy,
+                        traffic_controller_metrics,
+                        remote_fw_config,
+                    ),
+                })
             }),
+            max_batch_size,
+            batch_metrics: BatchMetrics::new(registry),
+        }
+    }
+
+    /// Returns the error response to a batch request of `batch_size` calls, if it is empty or too
+    /// large to be processed.
+    fn check_batch_size(&self, batch_size: usize) -> Option<MethodResponse> {
+        self.batch_metrics.batch_size.observe(batch_size as f64);
+        if batch_size == 0 {
+            return Some(MethodResponse::error(
+                Id::Null,
+                ErrorObject::from(ErrorCode::InvalidRequest),
+            ));
+        }
+        if batch_size > self.max_batch_size {
+            self.batch_metrics.rejected_batches.inc();
+            return Some(MethodResponse::error(
+                Id::Null,
+                ErrorObject::owned(
+                    ErrorCode::InvalidRequest.code(),
+                    format!(
+                        "Batch of {batch_size} calls exceeds the maximum batch size of {}",
+                        self.max_batch_size
+                    ),
+                    None::<()>,
+                ),
+            ));
         }
+        None
     }
 }
 
@@ -132,7 +177,7 @@ pub async fn json_rpc_handler<L: Logger>(
         .and_then(|h| h.to_str().ok());
     let response = process_raw_request(&service, api_version, raw_request.get(), client_addr).await;
 
-    ok_response(response.result)
+    ok_response(response)
 }
 
 async fn process_raw_request<L: Logger>(
@@ -140,33 +185,98 @@ async fn process_raw_request<L: Logger>(
     api_version: Option<&str>,
     raw_request: &str,
     client_addr: SocketAddr,
-) -> MethodResponse {
+) -> String {
     if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
         // check if either IP is blocked, in which case return early
         if let Some(traffic_controller) = &service.traffic_controller {
             if let Err(blocked_response) =
                 handle_traffic_req(traffic_controller.clone(), client_addr).await
             {
-                return blocked_response;
+                return blocked_response.result;
             }
         }
-        let response =
-            process_request(request, api_version, service.call_data(), client_addr).await;
+        process_call(service, request, api_version, client_addr)
+            .await
+            .result
+    } else if let Ok(batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
+        process_batch(service, api_version, batch, client_addr).await
+    } else {
+        let (id, code) = prepare_error(raw_request);
+        MethodResponse::error(id, ErrorObject::from(code)).result
+    }
+}
 
-        // handle response tallying
+/// Processes the calls of a batch request concurrently. Each call succeeds or fails on its own,
+/// and the response holds the response to every call, in the order of the request. Every call is
+/// checked against the traffic controller and tallied as a separate request, so batching doesn't
+/// let a client make more calls than it could one at a time.
+async fn process_batch<L: Logger>(
+    service: &JsonRpcService<L>,
+    api_version: Option<&str>,
+    batch: Vec<&RawValue>,
+    client_addr: SocketAddr,
+) -> String {
+    if let Some(rejected_response) = service.check_batch_size(batch.len()) {
+        return rejected_response.result;
+    }
+
+    let responses = future::join_all(batch.into_iter().map(|raw_call| async move {
         if let Some(traffic_controller) = &service.traffic_controller {
-            handle_traffic_resp(traffic_controller.clone(), client_addr, &response);
+            if let Err(blocked_response) =
+                handle_traffic_req(traffic_controller.clone(), client_addr).await
+            {
+                return blocked_response;
+            }
         }
-        response
-    } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
-        MethodResponse::error(
-            Id::Null,
-            ErrorObject::borrowed(BATCHES_NOT_SUPPORTED_CODE, &BATCHES_NOT_SUPPORTED_MSG, None),
-        )
-    } else {
-        let (id, code) = prepare_error(raw_request);
-        MethodResponse::error(id, ErrorObject::from(code))
+        match serde_json::from_str::<Request>(raw_call.get()) {
+            Ok(request) => process_call(service, request, api_version, client_addr).await,
+            Err(_) => {
+                let response = invalid_batch_call(raw_call);
+                if let Some(traffic_controller) = &service.traffic_controller {
+                    handle_traffic_resp(traffic_controller.clone(), client_addr, &response);
+                }
+                response
+            }
+        }
+    }))
+    .await;
+    batch_response(responses.iter())
+}
+
+/// Processes a single call, and tallies its response with the traffic controller.
+async fn process_call<L: Logger>(
+    service: &JsonRpcService<L>,
+    request: Request<'_>,
+    api_version: Option<&str>,
+    client_addr: SocketAddr,
+) -> MethodResponse {
+    let response = process_request(request, api_version, service.call_data(), client_addr).await;
+
+    // handle response tallying
+    if let Some(traffic_controller) = &service.traffic_controller {
+        handle_traffic_resp(traffic_controller.clone(), client_addr, &response);
     }
+    response
+}
+
+/// The response to a call in a batch request that is not a valid request. The batch was parsed,
+/// so the call is valid JSON.
+fn invalid_batch_call(raw_call: &RawValue) -> MethodResponse {
+    let (id, _) = prepare_error(raw_call.get());
+    MethodResponse::error(id, ErrorObject::from(ErrorCode::InvalidRequest))
+}
+
+/// Joins the responses to the calls of a batch request into a JSON array.
+fn batch_response<'a>(responses: impl Iterator<Item = &'a MethodResponse>) -> String {
+    let mut result = String::from("[");
+    for (idx, response) in responses.enumerate() {
+        if idx > 0 {
+            result.push(',');
+        }
+        result.push_str(&response.result);
+    }
+    result.push(']');
+    result
 }
 
 async fn handle_traffic_req(
@@ -440,7 +550,7 @@ pub mod ws {
                             let response =
                                 process_raw_request(&service, &msg, bounded_subscriptions.clone(), &sink).await;
                             if let Some(response) = response {
-                                let _ = sink.send_raw(response.result);
+                                let _ = sink.send_raw(response);
                             }
                         }
                     } else {
@@ -461,17 +571,36 @@ pub mod ws {
         raw_request: &str,
         bounded_subscriptions: BoundedSubscriptions,
         sink: &MethodSink,
-    ) -> Option<MethodResponse> {
+    ) -> Option<String> {
         if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
-            process_request(request, service.ws_call_data(bounded_subscriptions, sink)).await
-        } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
-            Some(MethodResponse::error(
-                Id::Null,
-                ErrorObject::borrowed(BATCHES_NOT_SUPPORTED_CODE, &BATCHES_NOT_SUPPORTED_MSG, None),
-            ))
+            process_request(request, service.ws_call_data(bounded_subscriptions, sink))
+                .await
+                .map(|response| response.result)
+        } else if let Ok(batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
+            if let Some(rejected_response) = service.check_batch_size(batch.len()) {
+                return Some(rejected_response.result);
+            }
+            // Calls are processed in order, so that subscriptions are set up in the order they
+            // were requested. Subscription notifications are sent separately, so calls that
+            // start a subscription have no response in the batch.
+            let mut responses = vec![];
+            for raw_call in batch {
+                let response = match serde_json::from_str::<Request>(raw_call.get()) {
+                    Ok(request) => {
+                        process_request(
+                            request,
+                            service.ws_call_data(bounded_subscriptions.clone(), sink),
+                        )
+                        .await
+                    }
+                    Err(_) => Some(invalid_batch_call(raw_call)),
+                };
+                responses.extend(response);
+            }
+            (!responses.is_empty()).then(|| batch_response(responses.iter()))
         } else {
             let (id, code) = prepare_error(raw_request);
-            Some(MethodResponse::error(id, ErrorObject::from(code)))
+            Some(MethodResponse::error(id, ErrorObject::from(code)).result)
         }
     }
 
@@ -3,12 +3,12 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use async_trait::async_trait;
 use futures::{future, Stream};
 use jsonrpsee::{
     core::{error::SubscriptionClosed, RpcResult},
-    types::SubscriptionResult,
+    types::{error::CallError, SubscriptionResult},
     RpcModule, SubscriptionSink,
 };
 use move_bytecode_utils::layout::TypeLayoutBuilder;
@@ -16,15 +16,17 @@ use move_core_types::language_storage::TypeTag;
 use mysten_metrics::spawn_monitored_task;
 use serde::Serialize;
 use sui_core::authority::AuthorityState;
+use sui_core::streamer::{ReplayPage, SubscriptionReplay};
 use sui_json::SuiJsonValue;
 use sui_json_rpc_api::{
     cap_page_limit, validate_limit, IndexerApiOpenRpc, IndexerApiServer, JsonRpcMetrics,
     ReadApiServer, QUERY_MAX_RESULT_LIMIT,
 };
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectDataOptions,
-    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseQuery, TransactionBlocksPage, TransactionFilter,
+    DynamicFieldPage, EventFilter, EventPage, Filter, ObjectsPage, Page, SuiEvent,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseQuery,
+    TransactionBlocksPage, TransactionFilter,
 };
 use sui_open_rpc::Module;
 use sui_storage::key_value_store::TransactionKeyValueStore;
@@ -73,6 +75,114 @@ pub fn spawn_subscription<S, T>(
 }
 const DEFAULT_MAX_SUBSCRIPTIONS: usize = 100;
 
+/// Returns the indexed filter that the events of a resumable subscription to `filter` are read
+/// back by. Replay checks those events against `filter` itself, a page at a time, so that it never
+/// runs into the scan limit of `query_events`. Fails for filters that imply no indexed filter.
+fn event_replay_filter(filter: &EventFilter) -> Result<EventFilter, SuiRpcInputError> {
+    let (indexed_filter, _) = filter.indexed_filter(AuthorityState::is_indexed_event_filter);
+    if matches!(&indexed_filter, EventFilter::All(filters) if filters.is_empty()) {
+        return Err(SuiRpcInputError::GenericInvalid(
+            "Subscribing from a cursor is not supported for this filter unless combined with a supported filter using All or And.".to_string(),
+        ));
+    }
+    Ok(indexed_filter)
+}
+
+/// Replays the events of a resumable subscription from the event indexes.
+struct EventReplay {
+    state: Arc<dyn StateRead>,
+    transaction_kv_store: Arc<TransactionKeyValueStore>,
+    filter: EventFilter,
+    indexed_filter: EventFilter,
+}
+
+#[async_trait]
+impl SubscriptionReplay<SuiEvent> for EventReplay {
+    type Cursor = EventID;
+
+    fn cursor(event: &SuiEvent) -> EventID {
+        event.id
+    }
+
+    async fn replay_after(
+        &self,
+        cursor: EventID,
+        limit: usize,
+    ) -> anyhow::Result<ReplayPage<SuiEvent, EventID>> {
+        let events = self
+            .state
+            .query_events(
+                &self.transaction_kv_store,
+                self.indexed_filter.clone(),
+                Some(cursor),
+                limit,
+                false,
+            )
+            .await?;
+        let next_cursor = if events.len() < limit {
+            None
+        } else {
+            events.last().map(|event| event.id)
+        };
+        Ok(ReplayPage {
+            items: events
+                .into_iter()
+                .filter(|event| self.filter.matches(event))
+                .collect(),
+            next_cursor,
+        })
+    }
+}
+
+/// Replays the transactions of a resumable subscription from the transaction indexes.
+struct TransactionReplay {
+    state: Arc<dyn StateRead>,
+    transaction_kv_store: Arc<TransactionKeyValueStore>,
+    filter: TransactionFilter,
+}
+
+#[async_trait]
+impl SubscriptionReplay<SuiTransactionBlockEffects> for TransactionReplay {
+    type Cursor = TransactionDigest;
+
+    fn cursor(effects: &SuiTransactionBlockEffects) -> TransactionDigest {
+        *effects.transaction_digest()
+    }
+
+    async fn replay_after(
+        &self,
+        cursor: TransactionDigest,
+        limit: usize,
+    ) -> anyhow::Result<ReplayPage<SuiTransactionBlockEffects, TransactionDigest>> {
+        let digests = self
+            .state
+            .get_transactions(
+                &self.transaction_kv_store,
+                Some(self.filter.clone()),
+                Some(cursor),
+                Some(limit),
+                false,
+            )
+            .await?;
+        let (_, effects, _) = self.state.multi_get(&[], &digests, &[]).await?;
+        let next_cursor = if digests.len() < limit {
+            None
+        } else {
+            digests.last().copied()
+        };
+        let items = digests
+            .iter()
+            .zip(effects)
+            .map(|(digest, effects)| {
+                let effects =
+                    effects.ok_or_else(|| anyhow!("Effects of transaction {digest} not found"))?;
+                Ok(SuiTransactionBlockEffects::try_from(effects)?)
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(ReplayPage { items, next_cursor })
+    }
+}
+
 pub struct IndexerApi<R> {
     state: Arc<dyn StateRead>,
     read_api: R,
@@ -295,15 +405,41 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
     }
 
     #[instrument(skip(self))]
-    fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
+    fn subscribe_event(
+        &self,
+        mut sink: SubscriptionSink,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+    ) -> SubscriptionResult {
         let permit = self.acquire_subscribe_permit()?;
-        spawn_subscription(
-            sink,
-            self.state
-                .get_subscription_handler()
-                .subscribe_events(filter),
-            Some(permit),
-        );
+        let subscription_handler = self.state.get_subscription_handler();
+        match cursor {
+            Some(cursor) => {
+                let indexed_filter = match event_replay_filter(&filter) {
+                    Ok(indexed_filter) => indexed_filter,
+                    Err(e) => {
+                        sink.reject(CallError::InvalidParams(e.into()))?;
+                        return Ok(());
+                    }
+                };
+                let replay = EventReplay {
+                    state: self.state.clone(),
+                    transaction_kv_store: self.transaction_kv_store.clone(),
+                    filter: filter.clone(),
+                    indexed_filter,
+                };
+                spawn_subscription(
+                    sink,
+                    subscription_handler.subscribe_events_from(filter, cursor, replay),
+                    Some(permit),
+                );
+            }
+            None => spawn_subscription(
+                sink,
+                subscription_handler.subscribe_events(filter),
+                Some(permit),
+            ),
+        }
         Ok(())
     }
 
@@ -311,15 +447,29 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         &self,
         sink: SubscriptionSink,
         filter: TransactionFilter,
+        cursor: Option<TransactionDigest>,
     ) -> SubscriptionResult {
         let permit = self.acquire_subscribe_permit()?;
-        spawn_subscription(
-            sink,
-            self.state
-                .get_subscription_handler()
-                .subscribe_transactions(filter),
-            Some(permit),
-        );
+        let subscription_handler = self.state.get_subscription_handler();
+        match cursor {
+            Some(cursor) => {
+                let replay = TransactionReplay {
+                    state: self.state.clone(),
+                    transaction_kv_store: self.transaction_kv_store.clone(),
+                    filter: filter.clone(),
+                };
+                spawn_subscription(
+                    sink,
+                    subscription_handler.subscribe_transactions_from(filter, cursor, replay),
+                    Some(permit),
+                );
+            }
+            None => spawn_subscription(
+                sink,
+                subscription_handler.subscribe_transactions(filter),
+                Some(permit),
+            ),
+        }
         Ok(())
     }
 
@@ -509,3 +659,36 @@ impl<R: ReadApiServer> SuiRpcModule for IndexerApi<R> {
         IndexerApiOpenRpc::module_doc()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_replay_filter() {
+        let sender = SuiAddress::random_for_testing_only();
+        let package = ObjectID::random();
+
+        // Indexed filters are replayed as they are.
+        assert!(matches!(
+            event_replay_filter(&EventFilter::Sender(sender)),
+            Ok(EventFilter::Sender(s)) if s == sender
+        ));
+
+        // Filters without an index are replayed from an indexed filter they imply.
+        let filter = EventFilter::Sender(sender).and(EventFilter::Package(package));
+        assert!(matches!(
+            event_replay_filter(&filter),
+            Ok(EventFilter::Sender(s)) if s == sender
+        ));
+
+        // Filters that imply no indexed filter can't be replayed, and subscribing to them from a
+        // cursor is rejected.
+        assert!(event_replay_filter(&EventFilter::Package(package)).is_err());
+        let filter = EventFilter::Any(vec![
+            EventFilter::Sender(sender),
+            EventFilter::Package(package),
+        ]);
+        assert!(event_replay_filter(&filter).is_err());
+    }
+}
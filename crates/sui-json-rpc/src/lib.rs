@@ -12,6 +12,7 @@ use hyper::Method;
 use hyper::Request;
 use jsonrpsee::RpcModule;
 use prometheus::Registry;
+use sui_core::traffic_controller::access_lists::TrafficAccessLists;
 use sui_core::traffic_controller::metrics::TrafficControllerMetrics;
 use sui_types::traffic_control::PolicyConfig;
 use sui_types::traffic_control::RemoteFirewallConfig;
@@ -53,12 +54,16 @@ pub const APP_NAME_HEADER: &str = "app-name";
 
 pub const MAX_REQUEST_SIZE: u32 = 2 << 30;
 
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 50;
+
 pub struct JsonRpcServerBuilder {
     module: RpcModule<()>,
     rpc_doc: Project,
     registry: Registry,
     policy_config: Option<PolicyConfig>,
     firewall_config: Option<RemoteFirewallConfig>,
+    access_lists: Option<TrafficAccessLists>,
+    max_batch_size: usize,
 }
 
 pub fn sui_rpc_doc(version: &str) -> Project {
@@ -92,9 +97,22 @@ impl JsonRpcServerBuilder {
             registry: prometheus_registry.clone(),
             policy_config,
             firewall_config,
+            access_lists: None,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
         }
     }
 
+    /// Makes the traffic controller use `access_lists`, which may be shared with other traffic
+    /// controllers, instead of loading its own from the policy config.
+    pub fn set_traffic_access_lists(&mut self, access_lists: TrafficAccessLists) {
+        self.access_lists = Some(access_lists);
+    }
+
+    /// Sets the maximum number of calls accepted in a single batch request.
+    pub fn set_max_batch_size(&mut self, max_batch_size: usize) {
+        self.max_batch_size = max_batch_size;
+    }
+
     pub fn register_module<T: SuiRpcModule>(&mut self, module: T) -> Result<(), Error> {
         self.rpc_doc.add_module(T::rpc_doc_module());
         Ok(self.module.merge(module.rpc())?)
@@ -189,7 +207,10 @@ impl JsonRpcServerBuilder {
             metrics_logger,
             self.firewall_config.clone(),
             self.policy_config.clone(),
+            self.access_lists.clone(),
             traffic_controller_metrics,
+            self.max_batch_size,
+            &self.registry,
         );
 
         let mut router = axum::Router::new();
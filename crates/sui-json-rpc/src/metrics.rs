@@ -8,8 +8,10 @@ use std::net::SocketAddr;
 use jsonrpsee::server::logger::{HttpRequest, Logger, MethodKind, TransportProtocol};
 use jsonrpsee::types::Params;
 use prometheus::{
-    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_gauge_vec_with_registry, HistogramVec, IntCounterVec, IntGaugeVec,
+    register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGaugeVec,
 };
 use sui_json_rpc_api::TRANSIENT_ERROR_CODE;
 use sui_json_rpc_api::{CLIENT_SDK_TYPE_HEADER, CLIENT_TARGET_API_VERSION_HEADER};
@@ -43,6 +45,36 @@ pub struct Metrics {
     rpc_response_size: HistogramVec,
 }
 
+/// Metrics of batch requests. The calls in a batch are also counted individually, by route, like
+/// any other request.
+#[derive(Debug, Clone)]
+pub struct BatchMetrics {
+    /// Number of calls in each batch request
+    pub batch_size: Histogram,
+    /// Batch requests rejected for exceeding the maximum batch size
+    pub rejected_batches: IntCounter,
+}
+
+impl BatchMetrics {
+    pub fn new(registry: &prometheus::Registry) -> Self {
+        Self {
+            batch_size: register_histogram_with_registry!(
+                "rpc_batch_size",
+                "Number of calls in each batch request",
+                prometheus::exponential_buckets(1.0, 2.0, 10).unwrap(),
+                registry,
+            )
+            .unwrap(),
+            rejected_batches: register_int_counter_with_registry!(
+                "rpc_rejected_batches",
+                "Number of batch requests rejected for exceeding the maximum batch size",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct MetricsLogger {
     metrics: Metrics,
@@ -1,27 +1,94 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use async_trait::async_trait;
 use clap::Parser;
 use move_binary_format::CompiledModule;
 use move_cli::base;
+use move_core_types::account_address::AccountAddress;
 use move_disassembler::disassembler::Disassembler;
+use move_disassembler::structured::DisassemblyFormat;
 use move_ir_types::location::Spanned;
 use move_package::BuildConfig;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{BufReader, Read};
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
+use sui_package_resolver::error::Error as PackageResolverError;
+use sui_package_resolver::{Package, PackageStore, Result as ResolverResult};
+use sui_types::base_types::ObjectID;
 
 #[derive(Parser)]
 #[group(id = "sui-move-disassemmble")]
 pub struct Disassemble {
     /// Path to a .mv file to disassemble
-    #[clap(name = "module_path")]
-    module_path: PathBuf,
+    #[clap(name = "module_path", required_unless_present = "package_id")]
+    module_path: Option<PathBuf>,
+
+    /// Disassemble every module of the on-chain package with this ID instead of a local file
+    #[clap(
+        long = "package-id",
+        conflicts_with = "module_path",
+        requires = "rest_url"
+    )]
+    package_id: Option<ObjectID>,
+
+    /// URL of the REST API of the fullnode to fetch `--package-id` from
+    #[clap(long = "rest-url")]
+    rest_url: Option<String>,
+
+    /// Write each module of `--package-id` to its own file in this directory, instead of printing
+    /// them all
+    #[clap(long = "output-dir", requires = "package_id")]
+    output_dir: Option<PathBuf>,
 
     /// Whether to display the disassembly in raw Debug format
     #[clap(long = "Xdebug")]
     debug: bool,
+
+    /// The format to print the disassembly in. JSON and HTML link each instruction to its source
+    /// line, when the source is available.
+    #[clap(long = "format", value_enum, default_value_t = DisassemblyFormat::Text)]
+    format: DisassemblyFormat,
+}
+
+/// Fetches packages from a fullnode's REST API.
+struct RestPackageStore {
+    client: sui_rest_api::Client,
+}
+
+#[async_trait]
+impl PackageStore for RestPackageStore {
+    async fn fetch(&self, id: AccountAddress) -> ResolverResult<Arc<Package>> {
+        let object =
+            self.client
+                .get_object(id.into())
+                .await
+                .map_err(|e| PackageResolverError::Store {
+                    store: "REST",
+                    source: Arc::from(Box::<dyn std::error::Error + Send + Sync>::from(e)),
+                })?;
+        Ok(Arc::new(Package::read_from_object(&object)?))
+    }
+}
+
+/// Disassembles every module of the package at `address` in `store`, returning the name of each
+/// module along with its disassembly. Modules fetched from a store carry no source information.
+pub async fn disassemble_package<S: PackageStore>(
+    store: &S,
+    address: AccountAddress,
+    format: DisassemblyFormat,
+) -> anyhow::Result<Vec<(String, String)>> {
+    let package = store.fetch(address).await?;
+    package
+        .modules()
+        .iter()
+        .map(|(name, module)| {
+            let d = Disassembler::from_module(module.bytecode(), Spanned::unsafe_no_loc(()).loc)?;
+            Ok((name.clone(), d.disassemble_as(format)?))
+        })
+        .collect()
 }
 
 impl Disassemble {
@@ -30,10 +97,16 @@ impl Disassemble {
         package_path: Option<PathBuf>,
         build_config: BuildConfig,
     ) -> anyhow::Result<()> {
-        if base::reroot_path(Some(self.module_path.clone())).is_ok() {
+        if let Some(package_id) = self.package_id {
+            return self.execute_on_chain(package_id);
+        }
+
+        let module_path = self
+            .module_path
+            .expect("Either a module path or a package ID is required");
+        if base::reroot_path(Some(module_path.clone())).is_ok() {
             // disassembling bytecode inside the source package that produced it--use the source info
-            let module_name = self
-                .module_path
+            let module_name = module_path
                 .file_stem()
                 .expect("Bad module path")
                 .to_str()
@@ -44,19 +117,17 @@ impl Disassemble {
                 package_name: None,
                 module_or_script_name: module_name,
                 debug: self.debug,
+                format: self.format,
             }
             .execute(package_path, build_config)?;
             return Ok(());
         }
 
         // disassembling a bytecode file with no source info
-        assert!(
-            Path::new(&self.module_path).exists(),
-            "Bad path to .mv file"
-        );
+        assert!(Path::new(&module_path).exists(), "Bad path to .mv file");
 
         let mut bytes = Vec::new();
-        let mut file = BufReader::new(File::open(self.module_path)?);
+        let mut file = BufReader::new(File::open(module_path)?);
         file.read_to_end(&mut bytes)?;
         // this deserialized a module to the max version of the bytecode but it's OK here because
         // it's not run as part of the deterministic replicated state machine.
@@ -66,9 +137,46 @@ impl Disassemble {
             println!("{module:#?}");
         } else {
             let d = Disassembler::from_module(&module, Spanned::unsafe_no_loc(()).loc)?;
-            println!("{}", d.disassemble()?);
+            println!("{}", d.disassemble_as(self.format)?);
         }
 
         Ok(())
     }
+
+    fn execute_on_chain(self, package_id: ObjectID) -> anyhow::Result<()> {
+        let rest_url = self
+            .rest_url
+            .expect("clap requires --rest-url with --package-id");
+        let store = RestPackageStore {
+            client: sui_rest_api::Client::new(rest_url),
+        };
+        // The CLI is driven from within a tokio runtime, but this command is synchronous.
+        let modules = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(disassemble_package(
+                &store,
+                package_id.into(),
+                self.format,
+            ))
+        })?;
+
+        let Some(output_dir) = self.output_dir else {
+            for (name, disassembly) in modules {
+                println!("// Module {package_id}::{name}\n{disassembly}");
+            }
+            return Ok(());
+        };
+
+        let extension = match self.format {
+            DisassemblyFormat::Text => "txt",
+            DisassemblyFormat::Json => "json",
+            DisassemblyFormat::Html => "html",
+        };
+        fs::create_dir_all(&output_dir)?;
+        for (name, disassembly) in modules {
+            let path = output_dir.join(format!("{name}.{extension}"));
+            fs::write(&path, disassembly)?;
+            println!("Wrote {}", path.display());
+        }
+        Ok(())
+    }
 }
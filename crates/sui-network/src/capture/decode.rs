@@ -0,0 +1,74 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anemo::rpc::codec::{Codec, Decoder};
+use bytes::Bytes;
+use mysten_network::codec::anemo::BcsSnappyCodec;
+use std::fmt::Debug;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContentsDigest, FullCheckpointContents,
+};
+
+use super::{CapturedEvent, CapturedMessage};
+use crate::{
+    discovery::GetKnownPeersResponse,
+    randomness::SendSignaturesRequest,
+    state_sync::{GetCheckpointAvailabilityResponse, GetCheckpointSummaryRequest},
+};
+
+fn decode<T: serde::de::DeserializeOwned + Debug + Send + 'static>(
+    body: &[u8],
+) -> anyhow::Result<String> {
+    let mut codec = BcsSnappyCodec::<(), T>::default();
+    let item = codec.decoder().decode(Bytes::copy_from_slice(body))?;
+    Ok(format!("{item:#?}"))
+}
+
+/// Decodes the body of a captured message into a human readable form.
+///
+/// Returns None if the body can't be decoded: the route isn't a sui p2p route, the RPC failed, or
+/// the body was truncated when it was captured.
+pub fn decode_body(message: &CapturedMessage) -> Option<anyhow::Result<String>> {
+    if message.is_truncated() {
+        return None;
+    }
+    let body = &message.body;
+    let decoded = match (&message.event, message.route.as_str()) {
+        (CapturedEvent::Request, "/sui.Discovery/GetKnownPeers") => decode::<()>(body),
+        (CapturedEvent::Request, "/sui.StateSync/PushCheckpointSummary") => {
+            decode::<CertifiedCheckpointSummary>(body)
+        }
+        (CapturedEvent::Request, "/sui.StateSync/GetCheckpointSummary") => {
+            decode::<GetCheckpointSummaryRequest>(body)
+        }
+        (CapturedEvent::Request, "/sui.StateSync/GetCheckpointContents") => {
+            decode::<CheckpointContentsDigest>(body)
+        }
+        (CapturedEvent::Request, "/sui.StateSync/GetCheckpointAvailability") => decode::<()>(body),
+        (CapturedEvent::Request, "/sui.Randomness/SendSignatures") => {
+            decode::<SendSignaturesRequest>(body)
+        }
+        // Error responses carry a plain text message rather than an encoded response.
+        (CapturedEvent::Response { status }, _) if *status >= 300 => {
+            Ok(String::from_utf8_lossy(body).into_owned())
+        }
+        (CapturedEvent::Response { .. }, "/sui.Discovery/GetKnownPeers") => {
+            decode::<GetKnownPeersResponse>(body)
+        }
+        (CapturedEvent::Response { .. }, "/sui.StateSync/PushCheckpointSummary") => {
+            decode::<()>(body)
+        }
+        (CapturedEvent::Response { .. }, "/sui.StateSync/GetCheckpointSummary") => {
+            decode::<Option<CertifiedCheckpointSummary>>(body)
+        }
+        (CapturedEvent::Response { .. }, "/sui.StateSync/GetCheckpointContents") => {
+            decode::<Option<FullCheckpointContents>>(body)
+        }
+        (CapturedEvent::Response { .. }, "/sui.StateSync/GetCheckpointAvailability") => {
+            decode::<GetCheckpointAvailabilityResponse>(body)
+        }
+        (CapturedEvent::Response { .. }, "/sui.Randomness/SendSignatures") => decode::<()>(body),
+        _ => return None,
+    };
+    Some(decoded)
+}
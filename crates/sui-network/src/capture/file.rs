@@ -0,0 +1,178 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The capture file format.
+//!
+//! A capture is a directory of files named `capture-<index>.bin`, where higher indices hold more
+//! recent messages. Each file starts with [`MAGIC`], followed by the captured messages, each one
+//! encoded as its BCS length as a little endian `u32` and then its BCS bytes.
+
+use anyhow::Context;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+};
+use tracing::warn;
+
+use super::{CaptureConfig, CapturedMessage};
+
+const MAGIC: &[u8; 8] = b"ANEMOCAP";
+const FILE_PREFIX: &str = "capture-";
+const FILE_EXTENSION: &str = "bin";
+
+/// Writes captured messages to the capture directory, rotating files once they grow too large.
+pub(super) struct RotatingWriter {
+    directory: PathBuf,
+    max_file_bytes: u64,
+    max_files: usize,
+    index: u64,
+    file: BufWriter<File>,
+    file_bytes: u64,
+}
+
+impl RotatingWriter {
+    pub fn new(config: &CaptureConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.directory)?;
+        // Don't overwrite the files of previous captures to the same directory.
+        let index = capture_files(&config.directory)?
+            .last()
+            .and_then(|path| file_index(path))
+            .map_or(0, |index| index + 1);
+        Ok(Self {
+            directory: config.directory.clone(),
+            max_file_bytes: config.max_file_bytes,
+            max_files: config.max_files.max(1),
+            index,
+            file: create_file(&config.directory, index)?,
+            file_bytes: MAGIC.len() as u64,
+        })
+    }
+
+    /// Writes messages until all senders are dropped, taking the body of each written message off
+    /// `queued_bytes`.
+    pub fn run(
+        mut self,
+        receiver: mpsc::Receiver<CapturedMessage>,
+        queued_bytes: Arc<AtomicUsize>,
+    ) {
+        while let Ok(message) = receiver.recv() {
+            queued_bytes.fetch_sub(message.body.len(), Ordering::Relaxed);
+            let result = self.write(&message).and_then(|()| {
+                // Write whatever else is queued up before flushing.
+                while let Ok(message) = receiver.try_recv() {
+                    queued_bytes.fetch_sub(message.body.len(), Ordering::Relaxed);
+                    self.write(&message)?;
+                }
+                self.file.flush()
+            });
+            if let Err(e) = result {
+                warn!(
+                    "failed to write anemo capture to {}, stopping: {e}",
+                    self.directory.display()
+                );
+                return;
+            }
+        }
+    }
+
+    fn write(&mut self, message: &CapturedMessage) -> io::Result<()> {
+        let bytes = bcs::to_bytes(message).expect("serialization should not fail");
+        let record_bytes = (std::mem::size_of::<u32>() + bytes.len()) as u64;
+        if self.file_bytes > MAGIC.len() as u64
+            && self.file_bytes + record_bytes > self.max_file_bytes
+        {
+            self.rotate()?;
+        }
+
+        self.file.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.file.write_all(&bytes)?;
+        self.file_bytes += record_bytes;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.index += 1;
+        self.file = create_file(&self.directory, self.index)?;
+        self.file_bytes = MAGIC.len() as u64;
+
+        let files = capture_files(&self.directory)?;
+        for path in &files[..files.len().saturating_sub(self.max_files)] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+fn create_file(directory: &Path, index: u64) -> io::Result<BufWriter<File>> {
+    let path = directory.join(format!("{FILE_PREFIX}{index:010}.{FILE_EXTENSION}"));
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(MAGIC)?;
+    Ok(file)
+}
+
+fn file_index(path: &Path) -> Option<u64> {
+    if path.extension()? != FILE_EXTENSION {
+        return None;
+    }
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix(FILE_PREFIX)?
+        .parse()
+        .ok()
+}
+
+/// Returns the capture files in `directory`, oldest first.
+fn capture_files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?
+        .into_iter()
+        .filter_map(|path| file_index(&path).map(|index| (index, path)))
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Reads the messages of a single capture file.
+///
+/// A truncated last message, e.g. because the node crashed while writing it, is ignored.
+pub fn read_capture_file(path: &Path) -> anyhow::Result<Vec<CapturedMessage>> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let Some(mut rest) = bytes.strip_prefix(MAGIC.as_slice()) else {
+        anyhow::bail!("{} is not an anemo capture file", path.display());
+    };
+
+    let mut messages = Vec::new();
+    while rest.len() >= std::mem::size_of::<u32>() {
+        let (len, record) = rest.split_at(std::mem::size_of::<u32>());
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        let Some(message) = record.get(..len) else {
+            break;
+        };
+        messages.push(
+            bcs::from_bytes(message)
+                .with_context(|| format!("corrupted message in {}", path.display()))?,
+        );
+        rest = &record[len..];
+    }
+    Ok(messages)
+}
+
+/// Reads all the messages of a capture, given either a capture directory or a single file.
+pub fn read_capture(path: &Path) -> anyhow::Result<Vec<CapturedMessage>> {
+    if !path.is_dir() {
+        return read_capture_file(path);
+    }
+
+    let mut messages = Vec::new();
+    for file in capture_files(path)? {
+        messages.extend(read_capture_file(&file)?);
+    }
+    Ok(messages)
+}
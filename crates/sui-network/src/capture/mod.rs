@@ -0,0 +1,363 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in capture of anemo RPCs, for debugging p2p issues.
+//!
+//! [`RpcCapture`] hands out [`CaptureMakeCallbackHandler`]s that are installed as inbound and
+//! outbound callback layers of an anemo network. While a capture is enabled, requests matching its
+//! [`CaptureFilter`], and their responses, are recorded to a set of rotating files in the capture
+//! directory. Captured messages can be read back with [`read_capture`], their bodies decoded with
+//! [`decode_body`] and the captured requests replayed against a peer with [`replay_request`].
+
+use anemo::{PeerId, Request, Response};
+use anemo_tower::callback::{MakeCallbackHandler, ResponseHandler};
+use arc_swap::ArcSwapOption;
+use bytes::Bytes;
+use fastcrypto::encoding::{Encoding, Hex};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::ServiceExt;
+use tracing::{info, warn};
+
+mod decode;
+mod file;
+#[cfg(test)]
+mod tests;
+
+pub use decode::decode_body;
+pub use file::{read_capture, read_capture_file};
+
+/// Maximum number of captured messages waiting to be written. Messages captured while the writer
+/// is this far behind are dropped rather than slowing down the network. The bodies waiting to be
+/// written are bounded by `CaptureConfig::max_queued_bytes` as well.
+const CHANNEL_CAPACITY: usize = 10_000;
+
+/// Whether an RPC was served by us or sent by us.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CapturedEvent {
+    Request,
+    Response {
+        status: u16,
+    },
+    /// The RPC failed without a response, e.g. because the connection was lost.
+    Error,
+}
+
+/// A request or response captured from the network.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedMessage {
+    /// Identifies the RPC within a capture, a request and its response share the same id.
+    pub rpc_id: u64,
+    pub timestamp_ms: u64,
+    pub direction: Direction,
+    pub peer_id: Option<PeerId>,
+    pub route: String,
+    pub event: CapturedEvent,
+    /// Size of the body on the wire.
+    pub body_len: u64,
+    /// The body, truncated to the capture's `max_body_bytes`.
+    pub body: Vec<u8>,
+}
+
+impl CapturedMessage {
+    pub fn is_truncated(&self) -> bool {
+        (self.body.len() as u64) < self.body_len
+    }
+}
+
+/// Selects which RPCs are captured.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CaptureFilter {
+    /// Routes to capture, e.g. `/sui.StateSync/GetCheckpointContents`. All routes are captured if
+    /// empty.
+    pub routes: Vec<String>,
+    /// Peers to capture RPCs with. RPCs with all peers are captured if empty.
+    pub peers: Vec<PeerId>,
+    /// Fraction of the matching RPCs to capture.
+    pub sample_rate: f64,
+}
+
+impl Default for CaptureFilter {
+    fn default() -> Self {
+        Self {
+            routes: Vec::new(),
+            peers: Vec::new(),
+            sample_rate: 1.0,
+        }
+    }
+}
+
+impl CaptureFilter {
+    fn matches(&self, route: &str, peer_id: Option<&PeerId>) -> bool {
+        (self.routes.is_empty() || self.routes.iter().any(|r| r == route))
+            && (self.peers.is_empty() || peer_id.is_some_and(|p| self.peers.contains(p)))
+            && (self.sample_rate >= 1.0 || rand::random::<f64>() < self.sample_rate)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CaptureConfig {
+    /// Directory the capture files are written to.
+    pub directory: PathBuf,
+    pub filter: CaptureFilter,
+    /// Bodies larger than this are truncated.
+    pub max_body_bytes: usize,
+    /// Maximum total size of the bodies waiting to be written. Messages captured while the writer
+    /// is this far behind are dropped.
+    pub max_queued_bytes: usize,
+    /// Size at which a capture file is rotated.
+    pub max_file_bytes: u64,
+    /// Number of capture files to keep, the oldest ones are deleted on rotation.
+    pub max_files: usize,
+}
+
+impl CaptureConfig {
+    pub fn new(directory: PathBuf) -> Self {
+        Self {
+            directory,
+            filter: CaptureFilter::default(),
+            max_body_bytes: 1 << 20,
+            max_queued_bytes: 64 << 20,
+            max_file_bytes: 64 << 20,
+            max_files: 10,
+        }
+    }
+}
+
+/// The state of an enabled capture, as exposed in the admin API.
+#[derive(Clone, Debug, Serialize)]
+pub struct CaptureStatus {
+    pub config: CaptureConfig,
+    pub captured_messages: u64,
+    /// Messages that were dropped because the writer couldn't keep up.
+    pub dropped_messages: u64,
+}
+
+struct ActiveCapture {
+    config: CaptureConfig,
+    sender: mpsc::SyncSender<CapturedMessage>,
+    /// Total size of the bodies waiting to be written, decremented by the writer.
+    queued_bytes: Arc<AtomicUsize>,
+    next_rpc_id: AtomicU64,
+    captured_messages: AtomicU64,
+    dropped_messages: AtomicU64,
+}
+
+impl ActiveCapture {
+    fn new(config: CaptureConfig, sender: mpsc::SyncSender<CapturedMessage>) -> Self {
+        Self {
+            config,
+            sender,
+            queued_bytes: Arc::new(AtomicUsize::new(0)),
+            next_rpc_id: AtomicU64::new(0),
+            captured_messages: AtomicU64::new(0),
+            dropped_messages: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, message: CapturedMessage) {
+        let bytes = message.body.len();
+        let queued_bytes = self.queued_bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if queued_bytes > self.config.max_queued_bytes || self.sender.try_send(message).is_err() {
+            self.queued_bytes.fetch_sub(bytes, Ordering::Relaxed);
+            self.dropped_messages.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.captured_messages.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn status(&self) -> CaptureStatus {
+        CaptureStatus {
+            config: self.config.clone(),
+            captured_messages: self.captured_messages.load(Ordering::Relaxed),
+            dropped_messages: self.dropped_messages.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Handle used to enable and disable capturing the RPCs of the networks it is installed in.
+#[derive(Clone, Default)]
+pub struct RpcCapture(Arc<ArcSwapOption<ActiveCapture>>);
+
+impl std::fmt::Debug for RpcCapture {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("RpcCapture")
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+impl RpcCapture {
+    /// Starts capturing RPCs, replacing any capture already in progress.
+    pub fn enable(&self, config: CaptureConfig) -> std::io::Result<()> {
+        let writer = file::RotatingWriter::new(&config)?;
+        let (sender, receiver) = mpsc::sync_channel(CHANNEL_CAPACITY);
+        let capture = ActiveCapture::new(config, sender);
+        let queued_bytes = capture.queued_bytes.clone();
+        std::thread::Builder::new()
+            .name("anemo-capture".to_owned())
+            .spawn(move || writer.run(receiver, queued_bytes))?;
+
+        info!(
+            "capturing anemo RPCs to {}",
+            capture.config.directory.display()
+        );
+        self.0.store(Some(Arc::new(capture)));
+        Ok(())
+    }
+
+    /// Stops capturing RPCs, returning the final status of the capture if one was in progress.
+    ///
+    /// The capture files are closed once the RPCs in flight complete.
+    pub fn disable(&self) -> Option<CaptureStatus> {
+        let status = self.0.swap(None).map(|capture| capture.status());
+        if status.is_some() {
+            info!("stopped capturing anemo RPCs");
+        }
+        status
+    }
+
+    pub fn status(&self) -> Option<CaptureStatus> {
+        self.0.load().as_ref().map(|capture| capture.status())
+    }
+
+    pub fn make_callback_handler(&self, direction: Direction) -> CaptureMakeCallbackHandler {
+        CaptureMakeCallbackHandler {
+            capture: self.clone(),
+            direction,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CaptureMakeCallbackHandler {
+    capture: RpcCapture,
+    direction: Direction,
+}
+
+impl MakeCallbackHandler for CaptureMakeCallbackHandler {
+    type Handler = CaptureResponseHandler;
+
+    fn make_handler(&self, request: &Request<Bytes>) -> Self::Handler {
+        let capture = self.capture.0.load_full().filter(|capture| {
+            capture
+                .config
+                .filter
+                .matches(request.route(), request.peer_id())
+        });
+        let Some(capture) = capture else {
+            return CaptureResponseHandler(None);
+        };
+
+        let rpc_id = capture.next_rpc_id.fetch_add(1, Ordering::Relaxed);
+        let rpc = CapturedRpc {
+            rpc_id,
+            direction: self.direction,
+            peer_id: request.peer_id().copied(),
+            route: request.route().to_owned(),
+            capture,
+        };
+        rpc.record(CapturedEvent::Request, request.body());
+        CaptureResponseHandler(Some(rpc))
+    }
+}
+
+/// An RPC being captured, waiting for its response.
+struct CapturedRpc {
+    rpc_id: u64,
+    direction: Direction,
+    peer_id: Option<PeerId>,
+    route: String,
+    capture: Arc<ActiveCapture>,
+}
+
+impl CapturedRpc {
+    fn record(&self, event: CapturedEvent, body: &Bytes) {
+        let max_body_bytes = self.capture.config.max_body_bytes;
+        self.capture.record(CapturedMessage {
+            rpc_id: self.rpc_id,
+            timestamp_ms: now_unix(),
+            direction: self.direction,
+            peer_id: self.peer_id,
+            route: self.route.clone(),
+            event,
+            body_len: body.len() as u64,
+            body: body[..body.len().min(max_body_bytes)].to_vec(),
+        });
+    }
+}
+
+pub struct CaptureResponseHandler(Option<CapturedRpc>);
+
+impl ResponseHandler for CaptureResponseHandler {
+    fn on_response(self, response: &Response<Bytes>) {
+        if let Some(rpc) = self.0 {
+            let status = response.status().to_u16();
+            rpc.record(CapturedEvent::Response { status }, response.body());
+        }
+    }
+
+    fn on_error<E>(self, _error: &E) {
+        if let Some(rpc) = self.0 {
+            rpc.record(CapturedEvent::Error, &Bytes::new());
+        }
+    }
+}
+
+/// Parses a peer id from its hex representation, as displayed in logs.
+pub fn parse_peer_id(s: &str) -> anyhow::Result<PeerId> {
+    let bytes = Hex::decode(s.trim_start_matches("0x"))?;
+    let bytes = bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("peer id must be 32 bytes"))?;
+    Ok(PeerId(bytes))
+}
+
+/// Sends a captured request to `peer_id`, which we must already be connected to, and returns its
+/// response.
+pub async fn replay_request(
+    network: &anemo::Network,
+    peer_id: PeerId,
+    message: &CapturedMessage,
+) -> anyhow::Result<Response<Bytes>> {
+    anyhow::ensure!(
+        message.event == CapturedEvent::Request,
+        "only requests can be replayed"
+    );
+    anyhow::ensure!(
+        !message.is_truncated(),
+        "the body of this request was truncated when it was captured"
+    );
+    let peer = network
+        .peer(peer_id)
+        .ok_or_else(|| anyhow::anyhow!("not connected to peer {peer_id}"))?;
+
+    let mut request = Request::new(Bytes::from(message.body.clone()));
+    *request.route_mut() = message.route.clone();
+    peer.oneshot(request)
+        .await
+        .map_err(|e| anyhow::anyhow!("replaying request failed: {e}"))
+}
+
+fn now_unix() -> u64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_millis() as u64,
+        Err(e) => {
+            warn!("system clock is before the unix epoch: {e}");
+            0
+        }
+    }
+}
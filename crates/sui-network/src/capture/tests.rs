@@ -0,0 +1,152 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::state_sync::GetCheckpointSummaryRequest;
+use anemo::rpc::codec::{Codec, Encoder};
+use mysten_network::codec::anemo::BcsSnappyCodec;
+use std::{path::Path, time::Duration};
+
+fn encode<T: Serialize + Send + 'static>(item: T) -> Bytes {
+    BcsSnappyCodec::<T, ()>::default()
+        .encoder()
+        .encode(item)
+        .unwrap()
+}
+
+fn request(route: &str, body: Bytes) -> Request<Bytes> {
+    let mut request = Request::new(body);
+    *request.route_mut() = route.to_owned();
+    request
+}
+
+/// Waits for the capture writer to have written messages satisfying `done`.
+fn wait_for_capture(
+    path: &Path,
+    done: impl Fn(&[CapturedMessage]) -> bool,
+) -> Vec<CapturedMessage> {
+    for _ in 0..100 {
+        if let Ok(messages) = read_capture(path) {
+            if done(&messages) {
+                return messages;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    panic!("capture was not written in time");
+}
+
+#[test]
+fn capture_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let capture = RpcCapture::default();
+    let mut config = CaptureConfig::new(dir.path().to_owned());
+    config.filter.routes = vec!["/sui.StateSync/GetCheckpointSummary".to_owned()];
+    capture.enable(config).unwrap();
+    let handler = capture.make_callback_handler(Direction::Outbound);
+
+    // Routes that don't match the filter aren't captured
+    handler
+        .make_handler(&request(
+            "/sui.StateSync/GetCheckpointAvailability",
+            encode(()),
+        ))
+        .on_response(&Response::new(encode(())));
+
+    handler
+        .make_handler(&request(
+            "/sui.StateSync/GetCheckpointSummary",
+            encode(GetCheckpointSummaryRequest::BySequenceNumber(5)),
+        ))
+        .on_response(&Response::new(encode(
+            None::<sui_types::messages_checkpoint::CertifiedCheckpointSummary>,
+        )));
+
+    let status = capture.disable().unwrap();
+    assert_eq!(status.captured_messages, 2);
+    assert_eq!(status.dropped_messages, 0);
+    assert!(capture.status().is_none());
+
+    let messages = wait_for_capture(dir.path(), |messages| messages.len() == 2);
+    let (request, response) = (&messages[0], &messages[1]);
+    assert_eq!(request.rpc_id, response.rpc_id);
+    assert_eq!(request.direction, Direction::Outbound);
+    assert_eq!(request.route, "/sui.StateSync/GetCheckpointSummary");
+    assert_eq!(request.event, CapturedEvent::Request);
+    assert_eq!(response.event, CapturedEvent::Response { status: 200 });
+
+    let decoded_request = decode_body(request).unwrap().unwrap();
+    assert!(decoded_request.contains("BySequenceNumber"));
+    assert_eq!(decode_body(response).unwrap().unwrap(), "None");
+}
+
+#[test]
+fn capture_files_are_rotated() {
+    let dir = tempfile::tempdir().unwrap();
+    let capture = RpcCapture::default();
+    let config = CaptureConfig {
+        max_body_bytes: 16,
+        max_file_bytes: 200,
+        max_files: 2,
+        ..CaptureConfig::new(dir.path().to_owned())
+    };
+    capture.enable(config).unwrap();
+    let handler = capture.make_callback_handler(Direction::Inbound);
+    for _ in 0..20 {
+        handler
+            .make_handler(&request("/test/Route", Bytes::from(vec![7; 100])))
+            .on_error(&());
+    }
+    capture.disable();
+
+    let messages = wait_for_capture(dir.path(), |messages| {
+        messages.last().is_some_and(|message| message.rpc_id == 19)
+    });
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    // The oldest messages have been deleted
+    assert!(messages.len() < 40);
+    assert_eq!(messages.last().unwrap().event, CapturedEvent::Error);
+
+    let request = &messages[messages.len() - 2];
+    assert_eq!(request.body, vec![7; 16]);
+    assert_eq!(request.body_len, 100);
+    assert!(request.is_truncated());
+    assert!(decode_body(request).is_none());
+}
+
+#[test]
+fn capture_queue_is_bounded_by_bytes() {
+    let (sender, receiver) = mpsc::sync_channel(CHANNEL_CAPACITY);
+    let config = CaptureConfig {
+        max_queued_bytes: 250,
+        ..CaptureConfig::new(PathBuf::new())
+    };
+    let capture = ActiveCapture::new(config, sender);
+    let message = |rpc_id| CapturedMessage {
+        rpc_id,
+        timestamp_ms: 0,
+        direction: Direction::Inbound,
+        peer_id: None,
+        route: "/test/Route".to_owned(),
+        event: CapturedEvent::Request,
+        body_len: 100,
+        body: vec![7; 100],
+    };
+
+    // Messages are dropped once their bodies would take the queue past `max_queued_bytes`.
+    for rpc_id in 0..3 {
+        capture.record(message(rpc_id));
+    }
+    let status = capture.status();
+    assert_eq!(status.captured_messages, 2);
+    assert_eq!(status.dropped_messages, 1);
+    assert_eq!(receiver.try_iter().count(), 2);
+}
+
+#[test]
+fn parse_peer_ids() {
+    let peer_id = PeerId([0xab; 32]);
+    assert_eq!(parse_peer_id(&peer_id.to_string()).unwrap(), peer_id);
+    assert_eq!(parse_peer_id(&format!("0x{peer_id}")).unwrap(), peer_id);
+    parse_peer_id("abcd").unwrap_err();
+}
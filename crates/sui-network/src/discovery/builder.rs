@@ -8,7 +8,7 @@ use crate::discovery::TrustedPeerChangeEvent;
 use anemo::codegen::InboundRequestLayer;
 use anemo_tower::rate_limit;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, RwLock},
 };
 use sui_config::p2p::P2pConfig;
@@ -84,6 +84,7 @@ impl Builder {
             our_info: None,
             connected_peers: HashMap::default(),
             known_peers: HashMap::default(),
+            connection_stats: HashMap::default(),
         }
         .pipe(RwLock::new)
         .pipe(Arc::new);
@@ -128,11 +129,17 @@ impl UnstartedDiscovery {
         } = self;
 
         let discovery_config = config.discovery.clone().unwrap_or_default();
+        let peer_group_members = discovery_config
+            .peer_groups
+            .iter()
+            .flat_map(|group| group.peers.iter().map(|peer| peer.peer_id))
+            .collect::<HashSet<_>>();
         let allowlisted_peers = Arc::new(
             discovery_config
-                .allowlisted_peers
-                .clone()
-                .into_iter()
+                .peer_groups
+                .iter()
+                .flat_map(|group| group.peers.clone())
+                .chain(discovery_config.allowlisted_peers.clone())
                 .map(|ap| (ap.peer_id, ap.address))
                 .chain(config.seed_peers.iter().filter_map(|peer| {
                     peer.peer_id
@@ -145,10 +152,12 @@ impl UnstartedDiscovery {
                 config,
                 discovery_config: Arc::new(discovery_config),
                 allowlisted_peers,
+                peer_group_members,
                 network,
                 tasks: JoinSet::new(),
                 pending_dials: Default::default(),
                 dial_seed_peers_task: None,
+                peers_persisted_at: None,
                 shutdown_handle,
                 state,
                 trusted_peer_change_rx,
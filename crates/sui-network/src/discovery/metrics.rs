@@ -1,7 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
+use prometheus::{
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, IntGauge, IntGaugeVec,
+    Registry,
+};
 use std::sync::Arc;
 use tap::Pipe;
 
@@ -34,10 +37,20 @@ impl Metrics {
             inner.num_peers_with_external_address.dec();
         }
     }
+
+    pub fn set_peer_group_connections(&self, group: &str, connections: usize) {
+        if let Some(inner) = &self.0 {
+            inner
+                .peer_group_connections
+                .with_label_values(&[group])
+                .set(connections as i64);
+        }
+    }
 }
 
 struct Inner {
     num_peers_with_external_address: IntGauge,
+    peer_group_connections: IntGaugeVec,
 }
 
 impl Inner {
@@ -49,6 +62,13 @@ impl Inner {
                 registry
             )
             .unwrap(),
+            peer_group_connections: register_int_gauge_vec_with_registry!(
+                "peer_group_connections",
+                "Number of peers of each configured peer group we are connected to",
+                &["group"],
+                registry
+            )
+            .unwrap(),
         }
         .pipe(Arc::new)
     }
@@ -6,7 +6,7 @@ use anemo::{types::PeerEvent, Network, Peer, PeerId, Request, Response};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, RwLock},
     time::Duration,
 };
@@ -23,12 +23,14 @@ use tracing::{debug, info, trace};
 
 const TIMEOUT: Duration = Duration::from_secs(1);
 const ONE_DAY_MILLISECONDS: u64 = 24 * 60 * 60 * 1_000;
+const PEER_STORE_PERSIST_INTERVAL: Duration = Duration::from_secs(60);
 
 mod generated {
     include!(concat!(env!("OUT_DIR"), "/sui.Discovery.rs"));
 }
 mod builder;
 mod metrics;
+mod peer_store;
 mod server;
 #[cfg(test)]
 mod tests;
@@ -40,13 +42,17 @@ pub use generated::{
 };
 pub use server::GetKnownPeersResponse;
 
-use self::metrics::Metrics;
+use self::{
+    metrics::Metrics,
+    peer_store::{ConnectionStats, PersistedPeer},
+};
 
 /// The internal discovery state shared between the main event loop and the request handler
 struct State {
     our_info: Option<NodeInfo>,
     connected_peers: HashMap<PeerId, ()>,
     known_peers: HashMap<PeerId, NodeInfo>,
+    connection_stats: HashMap<PeerId, ConnectionStats>,
 }
 
 /// The information necessary to dial another peer.
@@ -76,10 +82,12 @@ struct DiscoveryEventLoop {
     config: P2pConfig,
     discovery_config: Arc<DiscoveryConfig>,
     allowlisted_peers: Arc<HashMap<PeerId, Option<Multiaddr>>>,
+    peer_group_members: HashSet<PeerId>,
     network: Network,
     tasks: JoinSet<()>,
     pending_dials: HashMap<PeerId, AbortHandle>,
     dial_seed_peers_task: Option<AbortHandle>,
+    peers_persisted_at: Option<std::time::Instant>,
     shutdown_handle: oneshot::Receiver<()>,
     state: Arc<RwLock<State>>,
     trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
@@ -92,6 +100,7 @@ impl DiscoveryEventLoop {
 
         self.construct_our_info();
         self.configure_preferred_peers();
+        self.load_persisted_peers();
 
         let mut interval = tokio::time::interval(self.discovery_config.interval_period());
         let mut peer_events = {
@@ -134,6 +143,10 @@ impl DiscoveryEventLoop {
             }
         }
 
+        if let Some(path) = self.discovery_config.peer_store_path.clone() {
+            peer_store::save_peers(path, self.persisted_peers()).await;
+        }
+
         info!("Discovery ended");
     }
 
@@ -160,6 +173,16 @@ impl DiscoveryEventLoop {
     }
 
     fn configure_preferred_peers(&mut self) {
+        // Peer group members are always allowed to connect to us, but we dial them ourselves
+        // rather than having anemo do it, so that each group's connection quota is respected.
+        for peer_id in &self.peer_group_members {
+            self.network.known_peers().insert(anemo::types::PeerInfo {
+                peer_id: *peer_id,
+                affinity: anemo::types::PeerAffinity::High,
+                address: vec![],
+            });
+        }
+
         for (peer_id, address) in self
             .discovery_config
             .allowlisted_peers
@@ -191,6 +214,64 @@ impl DiscoveryEventLoop {
         }
     }
 
+    fn load_persisted_peers(&self) {
+        let Some(path) = &self.discovery_config.peer_store_path else {
+            return;
+        };
+        let (infos, stats): (Vec<_>, Vec<_>) = peer_store::load_peers(path)
+            .into_iter()
+            .map(|PersistedPeer { info, stats }| {
+                let peer_id = info.peer_id;
+                (info, (peer_id, stats))
+            })
+            .unzip();
+        update_known_peers(
+            self.state.clone(),
+            self.metrics.clone(),
+            infos,
+            self.allowlisted_peers.clone(),
+        );
+
+        // Only restore the stats of peers that are still eligible to be known.
+        let mut state = self.state.write().unwrap();
+        for (peer_id, stats) in stats {
+            if state.known_peers.contains_key(&peer_id) {
+                state.connection_stats.insert(peer_id, stats);
+            }
+        }
+    }
+
+    fn persisted_peers(&self) -> Vec<PersistedPeer> {
+        let state = self.state.read().unwrap();
+        state
+            .known_peers
+            .values()
+            .map(|info| PersistedPeer {
+                info: info.clone(),
+                stats: state
+                    .connection_stats
+                    .get(&info.peer_id)
+                    .copied()
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    fn maybe_persist_peers(&mut self, now: std::time::Instant) {
+        let Some(path) = self.discovery_config.peer_store_path.clone() else {
+            return;
+        };
+        if self
+            .peers_persisted_at
+            .is_some_and(|at| now.duration_since(at) < PEER_STORE_PERSIST_INTERVAL)
+        {
+            return;
+        }
+        self.peers_persisted_at = Some(now);
+        self.tasks
+            .spawn(peer_store::save_peers(path, self.persisted_peers()));
+    }
+
     fn update_our_info_timestamp(&mut self, now_unix: u64) {
         if let Some(our_info) = &mut self.state.write().unwrap().our_info {
             our_info.timestamp_ms = now_unix;
@@ -242,7 +323,7 @@ impl DiscoveryEventLoop {
         }
     }
 
-    fn handle_tick(&mut self, _now: std::time::Instant, now_unix: u64) {
+    fn handle_tick(&mut self, now: std::time::Instant, now_unix: u64) {
         self.update_our_info_timestamp(now_unix);
 
         self.tasks
@@ -254,12 +335,27 @@ impl DiscoveryEventLoop {
                 self.allowlisted_peers.clone(),
             ));
 
-        // Cull old peers older than a day
-        self.state
-            .write()
-            .unwrap()
-            .known_peers
-            .retain(|_k, v| now_unix.saturating_sub(v.timestamp_ms) < ONE_DAY_MILLISECONDS);
+        {
+            let mut state = self.state.write().unwrap();
+            let State {
+                connected_peers,
+                known_peers,
+                connection_stats,
+                ..
+            } = &mut *state;
+
+            // Cull old peers older than a day
+            known_peers
+                .retain(|_k, v| now_unix.saturating_sub(v.timestamp_ms) < ONE_DAY_MILLISECONDS);
+
+            for peer_id in connected_peers.keys() {
+                connection_stats.entry(*peer_id).or_default().last_seen_ms = now_unix;
+            }
+            connection_stats.retain(|peer_id, _stats| {
+                known_peers.contains_key(peer_id) || connected_peers.contains_key(peer_id)
+            });
+        }
+        self.maybe_persist_peers(now);
 
         // Clean out the pending_dials
         self.pending_dials.retain(|_k, v| !v.is_finished());
@@ -280,11 +376,16 @@ impl DiscoveryEventLoop {
                 !info.addresses.is_empty() // Peer has addresses we can dial
                 && !state.connected_peers.contains_key(peer_id) // We're not already connected
                 && !self.pending_dials.contains_key(peer_id) // There is no pending dial to this node
+                && !self.peer_group_members.contains(peer_id) // Peer groups are dialed separately
             })
             .collect::<Vec<_>>();
 
         // No need to connect to any more peers if we're already connected to a bunch
-        let number_of_connections = state.connected_peers.len();
+        let number_of_connections = state
+            .connected_peers
+            .keys()
+            .filter(|peer_id| !self.peer_group_members.contains(peer_id))
+            .count();
         let number_to_dial = std::cmp::min(
             eligible.len(),
             self.discovery_config
@@ -292,15 +393,27 @@ impl DiscoveryEventLoop {
                 .saturating_sub(number_of_connections),
         );
 
-        // randomize the order
-        for (peer_id, info) in rand::seq::SliceRandom::choose_multiple(
+        // randomize the order, favoring peers we've successfully connected to before
+        for (peer_id, info) in rand::seq::SliceRandom::choose_multiple_weighted(
             eligible.as_slice(),
             &mut rand::thread_rng(),
             number_to_dial,
-        ) {
+            |(peer_id, _info)| {
+                state
+                    .connection_stats
+                    .get(peer_id)
+                    .copied()
+                    .unwrap_or_default()
+                    .success_rate()
+            },
+        )
+        .expect("success rates are always positive")
+        {
             let abort_handle = self.tasks.spawn(try_to_connect_to_peer(
                 self.network.clone(),
-                info.to_owned(),
+                self.state.clone(),
+                *peer_id,
+                info.addresses.clone(),
             ));
             self.pending_dials.insert(*peer_id, abort_handle);
         }
@@ -320,29 +433,112 @@ impl DiscoveryEventLoop {
 
             self.dial_seed_peers_task = Some(abort_handle);
         }
+        drop(state);
+
+        self.maintain_peer_groups();
+    }
+
+    // Dials members of each peer group we aren't connected to, up to the group's quota.
+    fn maintain_peer_groups(&mut self) {
+        let state = self.state.read().unwrap();
+        for group in &self.discovery_config.peer_groups {
+            let (connected, unconnected): (Vec<_>, Vec<_>) = group
+                .peers
+                .iter()
+                .filter(|peer| peer.peer_id != self.network.peer_id())
+                .partition(|peer| state.connected_peers.contains_key(&peer.peer_id));
+            self.metrics
+                .set_peer_group_connections(&group.name, connected.len());
+
+            let pending = unconnected
+                .iter()
+                .filter(|peer| self.pending_dials.contains_key(&peer.peer_id))
+                .count();
+            let number_to_dial = group
+                .max_connections()
+                .saturating_sub(connected.len() + pending);
+            if number_to_dial == 0 {
+                continue;
+            }
+
+            let mut candidates = unconnected
+                .into_iter()
+                .filter(|peer| !self.pending_dials.contains_key(&peer.peer_id))
+                .filter_map(|peer| {
+                    // Prefer the configured address over the one the peer advertises.
+                    let addresses = match &peer.address {
+                        Some(address) => vec![address.clone()],
+                        None => state
+                            .known_peers
+                            .get(&peer.peer_id)
+                            .map(|info| info.addresses.clone())
+                            .unwrap_or_default(),
+                    };
+                    let success_rate = state
+                        .connection_stats
+                        .get(&peer.peer_id)
+                        .copied()
+                        .unwrap_or_default()
+                        .success_rate();
+                    (!addresses.is_empty()).then_some((peer.peer_id, addresses, success_rate))
+                })
+                .collect::<Vec<_>>();
+            // Dial the members we've had the most success connecting to first.
+            candidates.sort_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+
+            for (peer_id, addresses, _) in candidates.into_iter().take(number_to_dial) {
+                debug!(
+                    "dialing {} from peer group '{}'",
+                    peer_id.short_display(4),
+                    group.name
+                );
+                let abort_handle = self.tasks.spawn(try_to_connect_to_peer(
+                    self.network.clone(),
+                    self.state.clone(),
+                    peer_id,
+                    addresses,
+                ));
+                self.pending_dials.insert(peer_id, abort_handle);
+            }
+        }
     }
 }
 
-async fn try_to_connect_to_peer(network: Network, info: NodeInfo) {
-    for multiaddr in &info.addresses {
+async fn try_to_connect_to_peer(
+    network: Network,
+    state: Arc<RwLock<State>>,
+    peer_id: PeerId,
+    addresses: Vec<Multiaddr>,
+) {
+    let mut connected = false;
+    for multiaddr in &addresses {
         if let Ok(address) = multiaddr.to_anemo_address() {
             // Ignore the result and just log the error if there is one
             if network
-                .connect_with_peer_id(address, info.peer_id)
+                .connect_with_peer_id(address, peer_id)
                 .await
                 .tap_err(|e| {
                     debug!(
                         "error dialing {} at address '{}': {e}",
-                        info.peer_id.short_display(4),
+                        peer_id.short_display(4),
                         multiaddr
                     )
                 })
                 .is_ok()
             {
-                return;
+                connected = true;
+                break;
             }
         }
     }
+
+    let mut state = state.write().unwrap();
+    let stats = state.connection_stats.entry(peer_id).or_default();
+    stats.dial_attempts += 1;
+    if connected {
+        stats.dial_successes += 1;
+        stats.last_seen_ms = now_unix();
+    }
 }
 
 async fn try_to_connect_to_seed_peers(
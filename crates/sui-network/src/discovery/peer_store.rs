@@ -0,0 +1,84 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use super::NodeInfo;
+
+/// How often we've managed to connect to a peer, and when we last saw it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct ConnectionStats {
+    /// Last time we were connected to this peer, in milliseconds since the unix epoch.
+    pub last_seen_ms: u64,
+    pub dial_attempts: u64,
+    pub dial_successes: u64,
+}
+
+impl ConnectionStats {
+    /// Estimated probability that dialing this peer succeeds. Peers we haven't dialed yet get the
+    /// benefit of the doubt.
+    pub fn success_rate(&self) -> f64 {
+        (self.dial_successes as f64 + 1.0) / (self.dial_attempts as f64 + 2.0)
+    }
+}
+
+/// A discovered peer, as persisted to disk across restarts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct PersistedPeer {
+    pub info: NodeInfo,
+    pub stats: ConnectionStats,
+}
+
+/// Loads the peers persisted at `path`. A missing or unreadable peer store is not an error, we
+/// just start from scratch.
+pub(super) fn load_peers(path: &Path) -> Vec<PersistedPeer> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!("no persisted peers found at {}", path.display());
+            return Vec::new();
+        }
+        Err(e) => {
+            warn!(
+                "failed to read persisted peers from {}: {e}",
+                path.display()
+            );
+            return Vec::new();
+        }
+    };
+    match bcs::from_bytes::<Vec<PersistedPeer>>(&bytes) {
+        Ok(peers) => {
+            info!(
+                "loaded {} persisted peers from {}",
+                peers.len(),
+                path.display()
+            );
+            peers
+        }
+        Err(e) => {
+            warn!(
+                "failed to decode persisted peers from {}: {e}",
+                path.display()
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Persists `peers` to `path`, replacing any previously persisted peers.
+pub(super) async fn save_peers(path: PathBuf, peers: Vec<PersistedPeer>) {
+    let bytes = bcs::to_bytes(&peers).expect("serialization should not fail");
+    // Write to a temporary file first so that a crash can't leave a truncated peer store behind.
+    let tmp_path = path.with_extension("tmp");
+    let result = async {
+        tokio::fs::write(&tmp_path, bytes).await?;
+        tokio::fs::rename(&tmp_path, &path).await
+    }
+    .await;
+    match result {
+        Ok(()) => debug!("persisted {} peers to {}", peers.len(), path.display()),
+        Err(e) => warn!("failed to persist peers to {}: {e}", path.display()),
+    }
+}
@@ -8,7 +8,7 @@ use anemo::Result;
 use fastcrypto::ed25519::Ed25519PublicKey;
 use futures::stream::FuturesUnordered;
 use std::collections::HashSet;
-use sui_config::p2p::AllowlistedPeer;
+use sui_config::p2p::{AllowlistedPeer, PeerGroup};
 use tokio::time::timeout;
 
 #[tokio::test]
@@ -617,6 +617,153 @@ async fn test_access_types() {
     );
 }
 
+#[tokio::test]
+async fn peer_store_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("peers");
+
+    // A missing peer store just means there are no persisted peers
+    assert!(peer_store::load_peers(&path).is_empty());
+
+    let peers = vec![PersistedPeer {
+        info: NodeInfo {
+            peer_id: PeerId([1; 32]),
+            addresses: vec!["/dns/localhost/udp/8080".parse().unwrap()],
+            timestamp_ms: now_unix(),
+            access_type: AccessType::Public,
+        },
+        stats: ConnectionStats {
+            last_seen_ms: now_unix(),
+            dial_attempts: 3,
+            dial_successes: 2,
+        },
+    }];
+    peer_store::save_peers(path.clone(), peers.clone()).await;
+    assert_eq!(peer_store::load_peers(&path), peers);
+
+    // A corrupted peer store is ignored
+    std::fs::write(&path, b"not a peer store").unwrap();
+    assert!(peer_store::load_peers(&path).is_empty());
+}
+
+#[tokio::test]
+async fn persisted_peers_are_reloaded() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("peers");
+
+    let public_peer = NodeInfo {
+        peer_id: PeerId([1; 32]),
+        addresses: vec!["/dns/localhost/udp/8080".parse().unwrap()],
+        timestamp_ms: now_unix(),
+        access_type: AccessType::Public,
+    };
+    let private_peer = NodeInfo {
+        peer_id: PeerId([2; 32]),
+        access_type: AccessType::Private,
+        ..public_peer.clone()
+    };
+    let stats = ConnectionStats {
+        last_seen_ms: now_unix(),
+        dial_attempts: 4,
+        dial_successes: 1,
+    };
+    peer_store::save_peers(
+        path.clone(),
+        vec![
+            PersistedPeer {
+                info: public_peer.clone(),
+                stats,
+            },
+            PersistedPeer {
+                info: private_peer.clone(),
+                stats,
+            },
+        ],
+    )
+    .await;
+
+    let mut config = P2pConfig::default();
+    config.discovery = Some(DiscoveryConfig {
+        peer_store_path: Some(path.clone()),
+        ..Default::default()
+    });
+    let (builder, network) = set_up_network(config);
+    let (mut event_loop, _handle, state) = start_network(builder, network);
+    event_loop.construct_our_info();
+    event_loop.load_persisted_peers();
+
+    // Persisted peers go through the same checks as peers found through discovery, so the
+    // private peer, which isn't allowlisted, is dropped.
+    {
+        let state = state.read().unwrap();
+        assert_eq!(
+            state.known_peers.values().cloned().collect::<Vec<_>>(),
+            vec![public_peer.clone()]
+        );
+        assert_eq!(
+            state.connection_stats.get(&public_peer.peer_id),
+            Some(&stats)
+        );
+        assert!(!state.connection_stats.contains_key(&private_peer.peer_id));
+    }
+
+    // And what is persisted is what we know about
+    assert_eq!(
+        event_loop.persisted_peers(),
+        vec![PersistedPeer {
+            info: public_peer,
+            stats,
+        }]
+    );
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn peer_group_connections_are_capped() -> Result<()> {
+    let (builder_2, network_2) = set_up_network(P2pConfig::default());
+    let (builder_3, network_3) = set_up_network(P2pConfig::default());
+
+    let mut config = P2pConfig::default();
+    config.discovery = Some(DiscoveryConfig {
+        peer_groups: vec![PeerGroup {
+            name: "fleet".to_owned(),
+            peers: vec![
+                local_allowlisted_peer(network_2.peer_id(), Some(network_2.local_addr().port())),
+                local_allowlisted_peer(network_3.peer_id(), Some(network_3.local_addr().port())),
+            ],
+            max_connections: Some(1),
+        }],
+        ..Default::default()
+    });
+    let (builder_1, network_1) = set_up_network(config);
+    let (mut subscriber_1, _) = network_1.subscribe()?;
+
+    let (event_loop_1, _handle_1, state_1) = start_network(builder_1, network_1.clone());
+    let (event_loop_2, _handle_2, _state_2) = start_network(builder_2, network_2.clone());
+    let (event_loop_3, _handle_3, _state_3) = start_network(builder_3, network_3.clone());
+    tokio::spawn(event_loop_1.start());
+    tokio::spawn(event_loop_2.start());
+    tokio::spawn(event_loop_3.start());
+
+    // Node 1 connects to one of the group's members...
+    let peer_id = unwrap_new_peer_event(subscriber_1.recv().await?);
+    assert!(peer_id == network_2.peer_id() || peer_id == network_3.peer_id());
+
+    // ...and stays connected to only that one.
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(network_1.peers(), vec![peer_id]);
+    assert_eq!(
+        state_1
+            .read()
+            .unwrap()
+            .connection_stats
+            .get(&peer_id)
+            .map(|stats| stats.dial_successes),
+        Some(1)
+    );
+
+    Ok(())
+}
+
 fn assert_peers(
     self_name: &str,
     network: &Network,
@@ -5,6 +5,7 @@ use mysten_network::config::Config;
 use std::time::Duration;
 
 pub mod api;
+pub mod capture;
 pub mod discovery;
 pub mod randomness;
 pub mod state_sync;
@@ -5,7 +5,7 @@ use anemo::codegen::InboundRequestLayer;
 use anemo_tower::{inflight_limit, rate_limit};
 use std::{
     collections::HashMap,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
 };
 use sui_archival::reader::ArchiveReaderBalancer;
 use sui_config::p2p::StateSyncConfig;
@@ -18,7 +18,9 @@ use tokio::{
 
 use super::{
     metrics::Metrics,
+    peer_scores::PeerScores,
     server::{CheckpointContentsDownloadLimitLayer, Server},
+    sync_source::SyncSourceSelector,
     Handle, PeerHeights, StateSync, StateSyncEventLoop, StateSyncMessage, StateSyncServer,
 };
 use sui_types::storage::WriteStore;
@@ -136,19 +138,21 @@ where
         let (checkpoint_event_sender, _receiver) =
             broadcast::channel(config.synced_checkpoint_broadcast_channel_capacity());
         let weak_sender = sender.downgrade();
-        let handle = Handle {
-            sender,
-            checkpoint_event_sender: checkpoint_event_sender.clone(),
-        };
         let peer_heights = PeerHeights {
             peers: HashMap::new(),
             unprocessed_checkpoints: HashMap::new(),
             sequence_number_to_digest: HashMap::new(),
             wait_interval_when_no_peer_to_sync_content: config
                 .wait_interval_when_no_peer_to_sync_content(),
+            scores: PeerScores::new(&config),
         }
         .pipe(RwLock::new)
         .pipe(Arc::new);
+        let handle = Handle {
+            sender,
+            checkpoint_event_sender: checkpoint_event_sender.clone(),
+            peer_heights: peer_heights.clone(),
+        };
 
         let server = Server {
             store: store.clone(),
@@ -202,6 +206,7 @@ where
             archive_readers,
         } = self;
 
+        let sync_source = Arc::new(Mutex::new(SyncSourceSelector::new(&config)));
         (
             StateSyncEventLoop {
                 config,
@@ -218,6 +223,7 @@ where
                 metrics,
                 archive_readers,
                 sync_checkpoint_from_archive_task: None,
+                sync_source,
             },
             handle,
         )
@@ -2,11 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use mysten_metrics::histogram::Histogram;
-use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
+use prometheus::{
+    register_gauge_vec_with_registry, register_gauge_with_registry,
+    register_int_gauge_with_registry, Gauge, GaugeVec, IntGauge, Registry,
+};
 use std::sync::Arc;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use tap::Pipe;
 
+use super::PeerScoreSnapshot;
+
 #[derive(Clone)]
 pub(super) struct Metrics(Option<Arc<Inner>>);
 
@@ -51,6 +56,51 @@ impl Metrics {
         }
         None
     }
+
+    pub fn set_sync_throughput(&self, archive: Option<f64>, peers: Option<f64>) {
+        if let Some(inner) = &self.0 {
+            if let Some(archive) = archive {
+                inner.archive_sync_throughput.set(archive);
+            }
+            if let Some(peers) = peers {
+                inner.peer_sync_throughput.set(peers);
+            }
+        }
+    }
+
+    pub fn update_peer_scores(&self, scores: &[PeerScoreSnapshot]) {
+        if let Some(inner) = &self.0 {
+            // Reset first so that peers we've disconnected from are no longer reported.
+            inner.peer_latency_ms.reset();
+            inner.peer_throughput_bytes_per_sec.reset();
+            inner.peer_failure_rate.reset();
+            for score in scores {
+                let peer_id = score.peer_id.to_string();
+                if let Some(latency_ms) = score.latency_ms {
+                    inner
+                        .peer_latency_ms
+                        .with_label_values(&[&peer_id])
+                        .set(latency_ms);
+                }
+                if let Some(throughput) = score.throughput_bytes_per_sec {
+                    inner
+                        .peer_throughput_bytes_per_sec
+                        .with_label_values(&[&peer_id])
+                        .set(throughput);
+                }
+                inner
+                    .peer_failure_rate
+                    .with_label_values(&[&peer_id])
+                    .set(score.failure_rate);
+            }
+            inner.banned_peers.set(
+                scores
+                    .iter()
+                    .filter(|score| score.banned_for_ms.is_some())
+                    .count() as i64,
+            );
+        }
+    }
 }
 
 struct Inner {
@@ -58,6 +108,12 @@ struct Inner {
     highest_verified_checkpoint: IntGauge,
     highest_synced_checkpoint: IntGauge,
     checkpoint_summary_age_ms: Histogram,
+    peer_latency_ms: GaugeVec,
+    peer_throughput_bytes_per_sec: GaugeVec,
+    peer_failure_rate: GaugeVec,
+    banned_peers: IntGauge,
+    archive_sync_throughput: Gauge,
+    peer_sync_throughput: Gauge,
 }
 
 impl Inner {
@@ -89,6 +145,51 @@ impl Inner {
                 "Age of checkpoints summaries when they arrive and are verified.",
                 registry,
             ),
+
+            peer_latency_ms: register_gauge_vec_with_registry!(
+                "state_sync_peer_latency_ms",
+                "Moving average of the latency of checkpoint summary requests to each peer",
+                &["peer_id"],
+                registry
+            )
+            .unwrap(),
+
+            peer_throughput_bytes_per_sec: register_gauge_vec_with_registry!(
+                "state_sync_peer_throughput_bytes_per_sec",
+                "Moving average of the checkpoint contents download throughput from each peer",
+                &["peer_id"],
+                registry
+            )
+            .unwrap(),
+
+            peer_failure_rate: register_gauge_vec_with_registry!(
+                "state_sync_peer_failure_rate",
+                "Moving average of the fraction of state sync requests to each peer that failed",
+                &["peer_id"],
+                registry
+            )
+            .unwrap(),
+
+            banned_peers: register_int_gauge_with_registry!(
+                "state_sync_banned_peers",
+                "Number of peers currently banned from state sync downloads",
+                registry
+            )
+            .unwrap(),
+
+            archive_sync_throughput: register_gauge_with_registry!(
+                "state_sync_archive_throughput",
+                "Moving average of the checkpoints per second synced from the archive",
+                registry
+            )
+            .unwrap(),
+
+            peer_sync_throughput: register_gauge_with_registry!(
+                "state_sync_peer_throughput",
+                "Moving average of the checkpoints per second synced from peers",
+                registry
+            )
+            .unwrap(),
         }
         .pipe(Arc::new)
     }
@@ -27,7 +27,10 @@
 //!   our latest checkpoint, and is intended to be used as a guarantee of data availability.
 //!
 //! The `PeerHeights` struct is used to track the highest_synced_checkpoint watermark for all of
-//! our peers.
+//! our peers, along with a score for each of them reflecting the latency, throughput and failure
+//! rate of the requests we've sent them. These scores determine which peers are preferred when
+//! downloading checkpoint summaries and contents, and peers that keep failing requests are
+//! temporarily banned.
 //!
 //! When a new checkpoint is discovered, and we've determined that it is higher than our
 //! highest_verified_checkpoint, then StateSync will kick off a task to synchronize and verify all
@@ -46,6 +49,10 @@
 //! indicating that a new checkpoint has been fully downloaded. Notifications on this broadcast
 //! channel will always be made in order. StateSync will also send out a notification to its peers
 //! of the newly synchronized checkpoint so that it can help other peers synchronize.
+//!
+//! Checkpoint contents can also be read from an archive. Depending on the configured
+//! `StateSyncMode`, the archive is used only for checkpoints our peers have pruned, to bootstrap
+//! until we're close to the tip of the chain, or whenever it is measured to be faster than peers.
 
 use anemo::{types::PeerEvent, PeerId, Request, Response, Result};
 use futures::{stream::FuturesOrdered, FutureExt, StreamExt};
@@ -53,10 +60,10 @@ use rand::Rng;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::{
     collections::{HashMap, VecDeque},
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     time::Duration,
 };
-use sui_config::p2p::StateSyncConfig;
+use sui_config::p2p::{StateSyncConfig, StateSyncMode};
 use sui_types::{
     committee::Committee,
     digests::CheckpointDigest,
@@ -66,7 +73,7 @@ use sui_types::{
     },
     storage::WriteStore,
 };
-use tap::{Pipe, TapFallible, TapOptional};
+use tap::Pipe;
 use tokio::sync::oneshot;
 use tokio::{
     sync::{broadcast, mpsc, watch},
@@ -79,7 +86,9 @@ mod generated {
 }
 mod builder;
 mod metrics;
+mod peer_scores;
 mod server;
+mod sync_source;
 #[cfg(test)]
 mod tests;
 
@@ -88,12 +97,16 @@ pub use generated::{
     state_sync_client::StateSyncClient,
     state_sync_server::{StateSync, StateSyncServer},
 };
+pub use peer_scores::PeerScoreSnapshot;
 pub use server::GetCheckpointAvailabilityResponse;
 pub use server::GetCheckpointSummaryRequest;
 use sui_archival::reader::ArchiveReaderBalancer;
 use sui_storage::verify_checkpoint;
 
-use self::{metrics::Metrics, server::CheckpointContentsDownloadLimitLayer};
+use self::{
+    metrics::Metrics, peer_scores::PeerScores, server::CheckpointContentsDownloadLimitLayer,
+    sync_source::SyncSourceSelector,
+};
 
 /// A handle to the StateSync subsystem.
 ///
@@ -103,6 +116,7 @@ use self::{metrics::Metrics, server::CheckpointContentsDownloadLimitLayer};
 pub struct Handle {
     sender: mpsc::Sender<StateSyncMessage>,
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
+    peer_heights: Arc<RwLock<PeerHeights>>,
 }
 
 impl Handle {
@@ -125,8 +139,14 @@ impl Handle {
     pub fn subscribe_to_synced_checkpoints(&self) -> broadcast::Receiver<VerifiedCheckpoint> {
         self.checkpoint_event_sender.subscribe()
     }
+
+    /// Returns the current download scores of the peers we've sent state sync requests to.
+    pub fn peer_scores(&self) -> Vec<PeerScoreSnapshot> {
+        self.peer_heights.read().unwrap().scores.snapshot()
+    }
 }
 
+#[derive(Debug)]
 struct PeerHeights {
     /// Table used to track the highest checkpoint for each of our peers.
     peers: HashMap<PeerId, PeerStateSyncInfo>,
@@ -135,6 +155,9 @@ struct PeerHeights {
 
     // The amount of time to wait before retry if there are no peers to sync content from.
     wait_interval_when_no_peer_to_sync_content: Duration,
+
+    /// How well each of our peers has served our requests so far.
+    scores: PeerScores,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -266,7 +289,7 @@ impl PeerHeights {
     }
 }
 
-// PeerBalancer is an Iterator that selects peers based on RTT with some added randomness.
+// PeerBalancer is an Iterator that selects peers based on their scores with some added randomness.
 #[derive(Clone)]
 struct PeerBalancer {
     peers: VecDeque<(anemo::Peer, PeerStateSyncInfo)>,
@@ -286,16 +309,16 @@ impl PeerBalancer {
         peer_heights: Arc<RwLock<PeerHeights>>,
         request_type: PeerCheckpointRequestType,
     ) -> Self {
+        let now = tokio::time::Instant::now();
+        let peer_heights = peer_heights.read().unwrap();
         let mut peers: Vec<_> = peer_heights
-            .read()
-            .unwrap()
             .peers_on_same_chain()
+            // Filter out any peers who are currently banned.
+            .filter(|(peer_id, _info)| !peer_heights.scores.is_banned(peer_id, now))
             // Filter out any peers who we aren't connected with.
             .filter_map(|(peer_id, info)| network.peer(*peer_id).map(|peer| (peer, *info)))
             .collect();
-        peers.sort_by(|(peer_a, _), (peer_b, _)| {
-            peer_a.connection_rtt().cmp(&peer_b.connection_rtt())
-        });
+        peer_heights.scores.sort_peers(&mut peers, &request_type);
         Self {
             peers: peers.into(),
             requested_checkpoint: None,
@@ -369,6 +392,7 @@ struct StateSyncEventLoop<S> {
 
     archive_readers: ArchiveReaderBalancer,
     sync_checkpoint_from_archive_task: Option<AbortHandle>,
+    sync_source: Arc<Mutex<SyncSourceSelector>>,
 }
 
 impl<S> StateSyncEventLoop<S>
@@ -420,21 +444,19 @@ where
         let task_handle = self.tasks.spawn(task);
         self.sync_checkpoint_contents_task = Some(task_handle);
 
-        // Start archive based checkpoint content sync loop.
-        // TODO: Consider switching to sync from archive only on startup.
-        // Right now because the peer set is fixed at startup, a node may eventually
-        // end up with peers who have all purged their local state. In such a scenario it will be
-        // stuck until restart when it ends up with a different set of peers. Once the discovery
-        // mechanism can dynamically identify and connect to other peers on the network, we will rely
-        // on sync from archive as a fall back.
-        let task = sync_checkpoint_contents_from_archive(
-            self.network.clone(),
-            self.archive_readers.clone(),
-            self.store.clone(),
-            self.peer_heights.clone(),
-        );
-        let task_handle = self.tasks.spawn(task);
-        self.sync_checkpoint_from_archive_task = Some(task_handle);
+        // Start archive based checkpoint content sync loop, unless we only sync from peers. Which
+        // checkpoints are read from the archive depends on the configured sync mode.
+        if self.config.sync_mode() != StateSyncMode::PeersOnly {
+            let task = sync_checkpoint_contents_from_archive(
+                self.network.clone(),
+                self.archive_readers.clone(),
+                self.store.clone(),
+                self.peer_heights.clone(),
+                self.sync_source.clone(),
+            );
+            let task_handle = self.tasks.spawn(task);
+            self.sync_checkpoint_from_archive_task = Some(task_handle);
+        }
 
         // Start main loop.
         loop {
@@ -606,7 +628,9 @@ where
                 self.spawn_get_latest_from_peer(peer_id);
             }
             Ok(PeerEvent::LostPeer(peer_id, _)) => {
-                self.peer_heights.write().unwrap().peers.remove(&peer_id);
+                let mut peer_heights = self.peer_heights.write().unwrap();
+                peer_heights.peers.remove(&peer_id);
+                peer_heights.scores.remove(&peer_id);
             }
 
             Err(RecvError::Closed) => {
@@ -637,7 +661,7 @@ where
         }
     }
 
-    fn handle_tick(&mut self, _now: std::time::Instant) {
+    fn handle_tick(&mut self, now: std::time::Instant) {
         let task = query_peers_for_their_latest_checkpoint(
             self.network.clone(),
             self.peer_heights.clone(),
@@ -649,6 +673,39 @@ where
         if let Some(layer) = self.download_limit_layer.as_ref() {
             layer.maybe_prune_map();
         }
+
+        self.metrics
+            .update_peer_scores(&self.peer_heights.read().unwrap().scores.snapshot());
+
+        let highest_synced_checkpoint = self
+            .store
+            .get_highest_synced_checkpoint()
+            .expect("store operation should not fail");
+        let tip = self.tip_checkpoint_sequence_number();
+        let mut sync_source = self.sync_source.lock().unwrap();
+        sync_source.sample_peer_throughput(
+            tokio::time::Instant::from_std(now),
+            *highest_synced_checkpoint.sequence_number(),
+            tip,
+        );
+        self.metrics.set_sync_throughput(
+            sync_source.archive_throughput(),
+            sync_source.peer_throughput(),
+        );
+    }
+
+    // The highest checkpoint we know of, either verified locally or advertised by our peers.
+    fn tip_checkpoint_sequence_number(&self) -> CheckpointSequenceNumber {
+        let highest_verified_checkpoint = self
+            .store
+            .get_highest_verified_checkpoint()
+            .expect("store operation should not fail");
+        self.peer_heights
+            .read()
+            .unwrap()
+            .highest_known_checkpoint_sequence_number()
+            .unwrap_or_default()
+            .max(*highest_verified_checkpoint.sequence_number())
     }
 
     fn maybe_start_checkpoint_summary_sync_task(&mut self) {
@@ -709,6 +766,7 @@ where
             .store
             .get_highest_synced_checkpoint()
             .expect("store operation should not fail");
+        let tip = self.tip_checkpoint_sequence_number();
 
         if highest_verified_checkpoint.sequence_number()
             > highest_synced_checkpoint.sequence_number()
@@ -719,6 +777,12 @@ where
                 .unwrap()
                 .highest_known_checkpoint_sequence_number()
                 > Some(*highest_synced_checkpoint.sequence_number())
+            // skip while the archive is the preferred source of checkpoint contents
+            && self
+                .sync_source
+                .lock()
+                .unwrap()
+                .use_peers(*highest_synced_checkpoint.sequence_number(), tip)
         {
             let _ = target_sequence_channel.send_if_modified(|num| {
                 let new_num = *highest_verified_checkpoint.sequence_number();
@@ -982,48 +1046,57 @@ where
                 // Iterate through peers trying each one in turn until we're able to
                 // successfully get the target checkpoint
                 for mut peer in peers {
+                    let peer_id = peer.inner().peer_id();
                     let request = Request::new(GetCheckpointSummaryRequest::BySequenceNumber(next))
                         .with_timeout(timeout);
-                    if let Some(checkpoint) = peer
-                        .get_checkpoint_summary(request)
-                        .await
-                        .tap_err(|e| trace!("{e:?}"))
-                        .ok()
-                        .and_then(Response::into_inner)
-                        .tap_none(|| trace!("peer unable to help sync"))
-                    {
-                        // peer didn't give us a checkpoint with the height that we requested
-                        if *checkpoint.sequence_number() != next {
-                            tracing::debug!(
-                                "peer returned checkpoint with wrong sequence number: expected {next}, got {}",
-                                checkpoint.sequence_number()
-                            );
+                    let start = tokio::time::Instant::now();
+                    let checkpoint = match peer.get_checkpoint_summary(request).await {
+                        Ok(response) => response.into_inner(),
+                        Err(e) => {
+                            trace!("{e:?}");
+                            peer_heights.write().unwrap().scores.record_failure(peer_id);
                             continue;
                         }
+                    };
+                    let Some(checkpoint) = checkpoint else {
+                        // Not having the checkpoint isn't a failure, so the score is unchanged.
+                        trace!("peer unable to help sync");
+                        continue;
+                    };
+                    // peer didn't give us a checkpoint with the height that we requested
+                    if *checkpoint.sequence_number() != next {
+                        tracing::debug!(
+                            "peer returned checkpoint with wrong sequence number: expected {next}, got {}",
+                            checkpoint.sequence_number()
+                        );
+                        peer_heights.write().unwrap().scores.record_failure(peer_id);
+                        continue;
+                    }
 
-                        // peer gave us a checkpoint whose digest does not match pinned digest
-                        let checkpoint_digest = checkpoint.digest();
-                        if let Ok(pinned_digest_index) = pinned_checkpoints.binary_search_by_key(
-                            checkpoint.sequence_number(),
-                            |(seq_num, _digest)| *seq_num
-                        ) {
-                            if pinned_checkpoints[pinned_digest_index].1 != *checkpoint_digest {
-                                tracing::debug!(
-                                    "peer returned checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
-                                    pinned_checkpoints[pinned_digest_index].1,
-                                    checkpoint_digest
-                                );
-                                continue;
-                            }
+                    // peer gave us a checkpoint whose digest does not match pinned digest
+                    let checkpoint_digest = checkpoint.digest();
+                    if let Ok(pinned_digest_index) = pinned_checkpoints.binary_search_by_key(
+                        checkpoint.sequence_number(),
+                        |(seq_num, _digest)| *seq_num
+                    ) {
+                        if pinned_checkpoints[pinned_digest_index].1 != *checkpoint_digest {
+                            tracing::debug!(
+                                "peer returned checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
+                                pinned_checkpoints[pinned_digest_index].1,
+                                checkpoint_digest
+                            );
+                            peer_heights.write().unwrap().scores.record_failure(peer_id);
+                            continue;
                         }
-
-                        // Insert in our store in the event that things fail and we need to retry
-                        peer_heights
-                            .write()
-                            .unwrap()
-                            .insert_checkpoint(checkpoint.clone());
-                        return (Some(checkpoint), next, Some(peer.inner().peer_id()));
                     }
+
+                    // Insert in our store in the event that things fail and we need to retry
+                    let mut peer_heights = peer_heights.write().unwrap();
+                    peer_heights
+                        .scores
+                        .record_summary_success(peer_id, start.elapsed());
+                    peer_heights.insert_checkpoint(checkpoint.clone());
+                    return (Some(checkpoint), next, Some(peer_id));
                 }
                 (None, next, None)
             }
@@ -1098,6 +1171,7 @@ async fn sync_checkpoint_contents_from_archive<S>(
     archive_readers: ArchiveReaderBalancer,
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
+    sync_source: Arc<Mutex<SyncSourceSelector>>,
 ) where
     S: WriteStore + Clone + Send + Sync + 'static,
 {
@@ -1117,24 +1191,27 @@ async fn sync_checkpoint_contents_from_archive<S>(
             .get_highest_synced_checkpoint()
             .expect("store operation should not fail")
             .sequence_number;
-        let sync_from_archive = if let Some(lowest_checkpoint_on_peers) = lowest_checkpoint_on_peers
-        {
-            highest_synced < lowest_checkpoint_on_peers
-        } else {
-            false
+        let tip = peer_heights
+            .read()
+            .unwrap()
+            .highest_known_checkpoint_sequence_number()
+            .unwrap_or(highest_synced);
+        let archive_latest_checkpoint = archive_readers.latest_available_checkpoint().await;
+        let next_batch = {
+            let mut sync_source = sync_source.lock().unwrap();
+            sync_source.set_archive_latest_checkpoint(archive_latest_checkpoint);
+            sync_source.next_archive_batch(highest_synced, tip, lowest_checkpoint_on_peers)
         };
-        if sync_from_archive {
-            let start = highest_synced
-                .checked_add(1)
-                .expect("Checkpoint seq num overflow");
-            let checkpoint_range = start..lowest_checkpoint_on_peers.unwrap();
+        if let Some(checkpoint_range) = next_batch {
             if let Some(archive_reader) = archive_readers
                 .pick_one_random(checkpoint_range.clone())
                 .await
             {
                 let txn_counter = Arc::new(AtomicU64::new(0));
                 let checkpoint_counter = Arc::new(AtomicU64::new(0));
-                if let Err(err) = archive_reader
+                sync_source.lock().unwrap().start_archive_batch();
+                let now = tokio::time::Instant::now();
+                let result = archive_reader
                     .read(
                         store.clone(),
                         checkpoint_range,
@@ -1142,11 +1219,17 @@ async fn sync_checkpoint_contents_from_archive<S>(
                         checkpoint_counter.clone(),
                         true,
                     )
-                    .await
-                {
+                    .await;
+                sync_source.lock().unwrap().finish_archive_batch(
+                    checkpoint_counter.load(Ordering::Relaxed),
+                    now.elapsed(),
+                );
+                if let Err(err) = result {
                     warn!("State sync from archive failed with error: {:?}", err);
                 } else {
                     info!("State sync from archive is complete. Checkpoints downloaded = {:?}, Txns downloaded = {:?}", checkpoint_counter.load(Ordering::Relaxed), txn_counter.load(Ordering::Relaxed));
+                    // Immediately decide whether the next batch should also come from the archive.
+                    continue;
                 }
             } else {
                 warn!("Failed to find an archive reader to complete the state sync request");
@@ -1306,7 +1389,8 @@ where
     )
     .with_checkpoint(*checkpoint.sequence_number());
     let now = tokio::time::Instant::now();
-    let Some(_contents) = get_full_checkpoint_contents(peers, &store, &checkpoint, timeout).await
+    let Some(_contents) =
+        get_full_checkpoint_contents(peers, &peer_heights, &store, &checkpoint, timeout).await
     else {
         // Delay completion in case of error so we don't hammer the network with retries.
         let duration = peer_heights
@@ -1327,6 +1411,7 @@ where
 #[instrument(level = "debug", skip_all)]
 async fn get_full_checkpoint_contents<S>(
     peers: PeerBalancer,
+    peer_heights: &RwLock<PeerHeights>,
     store: S,
     checkpoint: &VerifiedCheckpoint,
     timeout: Duration,
@@ -1351,28 +1436,38 @@ where
     // Iterate through our selected peers trying each one in turn until we're able to
     // successfully get the target checkpoint
     for mut peer in peers {
-        debug!(
-            ?timeout,
-            "requesting checkpoint contents from {}",
-            peer.inner().peer_id(),
-        );
+        let peer_id = peer.inner().peer_id();
+        debug!(?timeout, "requesting checkpoint contents from {peer_id}");
         let request = Request::new(digest).with_timeout(timeout);
-        if let Some(contents) = peer
-            .get_checkpoint_contents(request)
-            .await
-            .tap_err(|e| trace!("{e:?}"))
-            .ok()
-            .and_then(Response::into_inner)
-            .tap_none(|| trace!("peer unable to help sync"))
-        {
-            if contents.verify_digests(digest).is_ok() {
-                let verified_contents = VerifiedCheckpointContents::new_unchecked(contents.clone());
-                store
-                    .insert_checkpoint_contents(checkpoint, verified_contents)
-                    .expect("store operation should not fail");
-                return Some(contents);
+        let start = tokio::time::Instant::now();
+        let contents = match peer.get_checkpoint_contents(request).await {
+            Ok(response) => response.into_inner(),
+            Err(e) => {
+                trace!("{e:?}");
+                peer_heights.write().unwrap().scores.record_failure(peer_id);
+                continue;
             }
+        };
+        let Some(contents) = contents else {
+            // Not having the contents isn't a failure, so the score is unchanged.
+            trace!("peer unable to help sync");
+            continue;
+        };
+        if contents.verify_digests(digest).is_ok() {
+            let elapsed = start.elapsed();
+            let bytes = bcs::serialized_size(&contents).unwrap_or_default();
+            peer_heights
+                .write()
+                .unwrap()
+                .scores
+                .record_contents_success(peer_id, bytes, elapsed);
+            let verified_contents = VerifiedCheckpointContents::new_unchecked(contents.clone());
+            store
+                .insert_checkpoint_contents(checkpoint, verified_contents)
+                .expect("store operation should not fail");
+            return Some(contents);
         }
+        peer_heights.write().unwrap().scores.record_failure(peer_id);
     }
     debug!("no peers had checkpoint contents");
     None
@@ -0,0 +1,225 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anemo::PeerId;
+use serde::Serialize;
+use std::{cmp::Ordering, collections::HashMap, time::Duration};
+use sui_config::p2p::StateSyncConfig;
+use tokio::time::Instant;
+use tracing::debug;
+
+use super::PeerCheckpointRequestType;
+
+/// Weight given to the newest sample when updating the moving averages of a peer's score.
+const PEER_SCORE_EWMA_WEIGHT: f64 = 0.2;
+
+/// Upper bound on the failure rate used to penalize a peer, so that a peer which has failed every
+/// request so far is heavily deprioritized without its expected cost becoming infinite.
+const MAX_FAILURE_RATE: f64 = 0.9;
+
+/// Tracks how well each peer has served our state sync requests.
+///
+/// Checkpoint summary requests are small, so their round trip time is a good measure of how
+/// responsive a peer is. Checkpoint contents can be large, so for those we track the download
+/// throughput instead. Every failed request (error, timeout or invalid response) bumps the peer's
+/// failure rate, and a peer that fails too many requests in a row is banned for a while. A peer
+/// answering that it doesn't have the requested data is not scored either way.
+#[derive(Debug)]
+pub(super) struct PeerScores {
+    scores: HashMap<PeerId, PeerScore>,
+    ban_threshold: u32,
+    ban_duration: Duration,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct PeerScore {
+    /// Moving average of the latency of checkpoint summary requests, in milliseconds.
+    latency_ms: Option<f64>,
+    /// Moving average of the download throughput of checkpoint contents, in bytes per second.
+    throughput: Option<f64>,
+    /// Moving average of the fraction of failed requests.
+    failure_rate: f64,
+    consecutive_failures: u32,
+    successes: u64,
+    failures: u64,
+    banned_until: Option<Instant>,
+}
+
+/// A point-in-time view of the score of a peer, as exposed in metrics and the admin API.
+#[derive(Clone, Debug, Serialize)]
+pub struct PeerScoreSnapshot {
+    pub peer_id: PeerId,
+    pub latency_ms: Option<f64>,
+    pub throughput_bytes_per_sec: Option<f64>,
+    pub failure_rate: f64,
+    pub successes: u64,
+    pub failures: u64,
+    /// Remaining time before the peer is used for downloads again, if it is banned.
+    pub banned_for_ms: Option<u64>,
+}
+
+/// Updates an exponentially weighted moving average with a new sample, given `weight` to the
+/// sample. The first sample becomes the average.
+pub(super) fn ewma(average: Option<f64>, sample: f64, weight: f64) -> f64 {
+    match average {
+        Some(average) => average * (1.0 - weight) + sample * weight,
+        None => sample,
+    }
+}
+
+impl PeerScore {
+    /// The expected cost of sending a request of the given type to this peer, lower is better.
+    /// Returns None if we haven't yet measured this peer for this type of request.
+    fn cost(&self, request_type: &PeerCheckpointRequestType) -> Option<f64> {
+        match request_type {
+            PeerCheckpointRequestType::Summary => self.latency_ms,
+            PeerCheckpointRequestType::Content => self
+                .throughput
+                .map(|throughput| 1.0 / throughput.max(f64::MIN_POSITIVE)),
+        }
+    }
+
+    /// Expected number of attempts needed for a request to this peer to succeed.
+    fn failure_penalty(&self) -> f64 {
+        1.0 / (1.0 - self.failure_rate.min(MAX_FAILURE_RATE))
+    }
+
+    fn is_banned(&self, now: Instant) -> bool {
+        self.banned_until.is_some_and(|until| until > now)
+    }
+}
+
+impl PeerScores {
+    pub fn new(config: &StateSyncConfig) -> Self {
+        Self {
+            scores: HashMap::new(),
+            ban_threshold: config.peer_failure_ban_threshold(),
+            ban_duration: config.peer_ban_duration(),
+        }
+    }
+
+    pub fn record_summary_success(&mut self, peer_id: PeerId, latency: Duration) {
+        let score = self.record_success(peer_id);
+        score.latency_ms = Some(ewma(
+            score.latency_ms,
+            latency.as_secs_f64() * 1000.0,
+            PEER_SCORE_EWMA_WEIGHT,
+        ));
+    }
+
+    pub fn record_contents_success(&mut self, peer_id: PeerId, bytes: usize, elapsed: Duration) {
+        let throughput = bytes as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+        let score = self.record_success(peer_id);
+        score.throughput = Some(ewma(score.throughput, throughput, PEER_SCORE_EWMA_WEIGHT));
+    }
+
+    fn record_success(&mut self, peer_id: PeerId) -> &mut PeerScore {
+        let score = self.scores.entry(peer_id).or_default();
+        score.failure_rate = ewma(Some(score.failure_rate), 0.0, PEER_SCORE_EWMA_WEIGHT);
+        score.consecutive_failures = 0;
+        score.successes += 1;
+        score
+    }
+
+    /// Records a failed request to the given peer, banning it if it has now failed too many
+    /// requests in a row.
+    pub fn record_failure(&mut self, peer_id: PeerId) {
+        let score = self.scores.entry(peer_id).or_default();
+        score.failure_rate = ewma(Some(score.failure_rate), 1.0, PEER_SCORE_EWMA_WEIGHT);
+        score.consecutive_failures += 1;
+        score.failures += 1;
+        if score.consecutive_failures >= self.ban_threshold {
+            score.consecutive_failures = 0;
+            score.banned_until = Some(Instant::now() + self.ban_duration);
+            debug!(
+                "banning peer {peer_id} from state sync for {:?} after {} consecutive failures",
+                self.ban_duration, self.ban_threshold
+            );
+        }
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId, now: Instant) -> bool {
+        self.scores
+            .get(peer_id)
+            .is_some_and(|score| score.is_banned(now))
+    }
+
+    pub fn remove(&mut self, peer_id: &PeerId) {
+        self.scores.remove(peer_id);
+    }
+
+    /// Sorts peers by the expected cost of sending them a request of the given type, cheapest
+    /// first.
+    ///
+    /// Peers we haven't measured yet are assumed to be as good as the median measured peer, so
+    /// that they get a chance to be picked and scored. Ties, including the case where no peer has
+    /// been measured at all, are broken by the RTT of our connection to the peer.
+    pub fn sort_peers<T>(
+        &self,
+        peers: &mut [(anemo::Peer, T)],
+        request_type: &PeerCheckpointRequestType,
+    ) {
+        let score = |peer: &anemo::Peer| {
+            self.scores
+                .get(&peer.peer_id())
+                .copied()
+                .unwrap_or_default()
+        };
+
+        let mut known_costs: Vec<f64> = peers
+            .iter()
+            .filter_map(|(peer, _)| score(peer).cost(request_type))
+            .collect();
+        known_costs.sort_by(f64::total_cmp);
+        let median_cost = known_costs
+            .get(known_costs.len() / 2)
+            .copied()
+            .unwrap_or(1.0);
+
+        peers.sort_by_cached_key(|(peer, _)| {
+            let score = score(peer);
+            let cost = score.cost(request_type).unwrap_or(median_cost) * score.failure_penalty();
+            (TotalOrder(cost), peer.connection_rtt())
+        });
+    }
+
+    pub fn snapshot(&self) -> Vec<PeerScoreSnapshot> {
+        let now = Instant::now();
+        let mut snapshot: Vec<_> = self
+            .scores
+            .iter()
+            .map(|(peer_id, score)| PeerScoreSnapshot {
+                peer_id: *peer_id,
+                latency_ms: score.latency_ms,
+                throughput_bytes_per_sec: score.throughput,
+                failure_rate: score.failure_rate,
+                successes: score.successes,
+                failures: score.failures,
+                banned_for_ms: score
+                    .banned_until
+                    .filter(|until| *until > now)
+                    .map(|until| (until - now).as_millis() as u64),
+            })
+            .collect();
+        snapshot.sort_by_key(|snapshot| snapshot.peer_id);
+        snapshot
+    }
+}
+
+/// Wrapper giving `f64` a total order so that it can be used as a sort key.
+#[derive(PartialEq)]
+struct TotalOrder(f64);
+
+impl Eq for TotalOrder {}
+
+impl PartialOrd for TotalOrder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalOrder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
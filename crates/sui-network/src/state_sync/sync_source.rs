@@ -0,0 +1,168 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{ops::Range, time::Duration};
+use sui_config::p2p::{StateSyncConfig, StateSyncMode};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::time::Instant;
+
+use super::peer_scores::ewma;
+
+/// Weight given to the newest sample when updating the moving averages of sync throughput.
+const SYNC_THROUGHPUT_EWMA_WEIGHT: f64 = 0.3;
+
+/// Decides whether checkpoint contents are downloaded from our peers or from the archive,
+/// according to the configured [`StateSyncMode`].
+///
+/// The archive sync task reads the archive in batches and reports how fast each batch was read.
+/// The throughput of peers is measured by the event loop, by sampling how fast the highest synced
+/// checkpoint advances while peers are the active source and there is a backlog to download.
+#[derive(Debug)]
+pub(super) struct SyncSourceSelector {
+    mode: StateSyncMode,
+    archive_first_lag_threshold: u64,
+    archive_batch_size: u64,
+    /// Highest checkpoint available from our archives, as of the last time we checked.
+    archive_latest_checkpoint: Option<CheckpointSequenceNumber>,
+    archive_batch_in_progress: bool,
+    /// Moving averages of the download throughput of each source, in checkpoints per second.
+    archive_throughput: Option<f64>,
+    peer_throughput: Option<f64>,
+    /// Time and highest synced checkpoint when peer throughput was last sampled.
+    peer_sample: Option<(Instant, CheckpointSequenceNumber)>,
+}
+
+impl SyncSourceSelector {
+    pub fn new(config: &StateSyncConfig) -> Self {
+        Self {
+            mode: config.sync_mode(),
+            archive_first_lag_threshold: config.archive_first_lag_threshold(),
+            archive_batch_size: config.archive_sync_batch_size(),
+            archive_latest_checkpoint: None,
+            archive_batch_in_progress: false,
+            archive_throughput: None,
+            peer_throughput: None,
+            peer_sample: None,
+        }
+    }
+
+    pub fn archive_throughput(&self) -> Option<f64> {
+        self.archive_throughput
+    }
+
+    pub fn peer_throughput(&self) -> Option<f64> {
+        self.peer_throughput
+    }
+
+    pub fn set_archive_latest_checkpoint(&mut self, checkpoint: Option<CheckpointSequenceNumber>) {
+        self.archive_latest_checkpoint = checkpoint;
+    }
+
+    fn archive_has_checkpoints_after(&self, highest_synced: CheckpointSequenceNumber) -> bool {
+        self.archive_latest_checkpoint
+            .is_some_and(|latest| latest > highest_synced)
+    }
+
+    /// Whether checkpoint contents should currently be requested from peers.
+    pub fn use_peers(
+        &self,
+        highest_synced: CheckpointSequenceNumber,
+        tip: CheckpointSequenceNumber,
+    ) -> bool {
+        match self.mode {
+            StateSyncMode::Fallback | StateSyncMode::PeersOnly => true,
+            _ if self.archive_batch_in_progress => false,
+            // Never stall waiting for an archive that can't help us.
+            _ if !self.archive_has_checkpoints_after(highest_synced) => true,
+            StateSyncMode::ArchiveFirst => {
+                tip.saturating_sub(highest_synced) <= self.archive_first_lag_threshold
+            }
+            StateSyncMode::Hybrid => match (self.archive_throughput, self.peer_throughput) {
+                // Try the archive first, and then peers, to get a measurement of both.
+                (None, _) => false,
+                (Some(_), None) => true,
+                (Some(archive), Some(peers)) => peers >= archive,
+            },
+        }
+    }
+
+    /// Returns the range of checkpoints to read from the archive next, if any.
+    ///
+    /// Regardless of the mode (except `PeersOnly`), checkpoints that all of our peers have already
+    /// pruned can only be synced from the archive.
+    pub fn next_archive_batch(
+        &self,
+        highest_synced: CheckpointSequenceNumber,
+        tip: CheckpointSequenceNumber,
+        lowest_checkpoint_on_peers: Option<CheckpointSequenceNumber>,
+    ) -> Option<Range<CheckpointSequenceNumber>> {
+        let start = highest_synced.checked_add(1).unwrap();
+        let mut end = lowest_checkpoint_on_peers.unwrap_or(start);
+        match self.mode {
+            StateSyncMode::PeersOnly => return None,
+            StateSyncMode::Fallback => {}
+            StateSyncMode::ArchiveFirst | StateSyncMode::Hybrid => {
+                if !self.use_peers(highest_synced, tip) {
+                    let mut batch_end = start.saturating_add(self.archive_batch_size);
+                    if self.mode == StateSyncMode::ArchiveFirst {
+                        // Leave the last stretch before the tip to peers.
+                        batch_end =
+                            batch_end.min(tip.saturating_sub(self.archive_first_lag_threshold) + 1);
+                    }
+                    end = end.max(batch_end);
+                }
+                // Don't ask for more than the archive has.
+                if let Some(latest) = self.archive_latest_checkpoint {
+                    end = end.min(latest.saturating_add(1));
+                }
+            }
+        }
+        (start < end).then_some(start..end)
+    }
+
+    pub fn start_archive_batch(&mut self) {
+        self.archive_batch_in_progress = true;
+    }
+
+    /// Records the completion of an archive batch, and how many checkpoints were read in how much
+    /// time. A failed batch is recorded with no checkpoints.
+    pub fn finish_archive_batch(&mut self, checkpoints: u64, elapsed: Duration) {
+        self.archive_batch_in_progress = false;
+        if !elapsed.is_zero() {
+            let throughput = checkpoints as f64 / elapsed.as_secs_f64();
+            self.archive_throughput = Some(ewma(
+                self.archive_throughput,
+                throughput,
+                SYNC_THROUGHPUT_EWMA_WEIGHT,
+            ));
+        }
+    }
+
+    /// Samples the throughput of peers. This should be called periodically with our current
+    /// highest synced checkpoint and the tip of the chain as known to us.
+    pub fn sample_peer_throughput(
+        &mut self,
+        now: Instant,
+        highest_synced: CheckpointSequenceNumber,
+        tip: CheckpointSequenceNumber,
+    ) {
+        // Only measure peers while they are the active source and have something to download,
+        // otherwise we'd be measuring the archive, or how fast the chain is growing.
+        if highest_synced >= tip || !self.use_peers(highest_synced, tip) {
+            self.peer_sample = None;
+            return;
+        }
+        if let Some((sampled_at, sampled_synced)) = self.peer_sample {
+            let elapsed = (now - sampled_at).as_secs_f64();
+            if elapsed > 0.0 {
+                let synced = highest_synced.saturating_sub(sampled_synced);
+                self.peer_throughput = Some(ewma(
+                    self.peer_throughput,
+                    synced as f64 / elapsed,
+                    SYNC_THROUGHPUT_EWMA_WEIGHT,
+                ));
+            }
+        }
+        self.peer_sample = Some((now, highest_synced));
+    }
+}
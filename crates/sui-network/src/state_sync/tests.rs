@@ -3,7 +3,8 @@
 
 use crate::{
     state_sync::{
-        Builder, GetCheckpointSummaryRequest, PeerStateSyncInfo, StateSync, StateSyncMessage,
+        sync_source::SyncSourceSelector, Builder, GetCheckpointSummaryRequest, PeerBalancer,
+        PeerCheckpointRequestType, PeerStateSyncInfo, StateSync, StateSyncMessage,
         UnstartedStateSync,
     },
     utils::build_network,
@@ -17,6 +18,7 @@ use sui_archival::reader::ArchiveReaderBalancer;
 use sui_archival::writer::ArchiveWriter;
 use sui_config::node::ArchiveReaderConfig;
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_config::p2p::{StateSyncConfig, StateSyncMode};
 use sui_storage::{FileCompression, StorageFormat};
 use sui_swarm_config::test_utils::{empty_contents, CommitteeFixture};
 use sui_types::{
@@ -265,6 +267,195 @@ async fn isolated_sync_job() {
     }
 }
 
+#[tokio::test]
+async fn peer_selection_uses_peer_scores() {
+    let config = StateSyncConfig {
+        peer_failure_ban_threshold: Some(2),
+        peer_ban_duration_ms: Some(60_000),
+        ..Default::default()
+    };
+    let (builder, server) = Builder::new()
+        .config(config)
+        .store(SharedInMemoryStore::default())
+        .build();
+    let network_1 = build_network(|router| router.add_rpc_service(server));
+    let (event_loop_1, handle_1) = builder.build(network_1.clone());
+    let network_2 = build_network(|router| router);
+    let network_3 = build_network(|router| router);
+    network_1.connect(network_2.local_addr()).await.unwrap();
+    network_1.connect(network_3.local_addr()).await.unwrap();
+    let (peer_2, peer_3) = (network_2.peer_id(), network_3.peer_id());
+
+    let peer_heights = event_loop_1.peer_heights.clone();
+    for peer_id in [peer_2, peer_3] {
+        peer_heights.write().unwrap().peers.insert(
+            peer_id,
+            PeerStateSyncInfo {
+                genesis_checkpoint_digest: CheckpointDigest::default(),
+                on_same_chain_as_us: true,
+                height: 100,
+                lowest: 0,
+            },
+        );
+    }
+    let selected_peers = |request_type: PeerCheckpointRequestType| {
+        let mut peers = vec![
+            (network_1.peer(peer_2).unwrap(), ()),
+            (network_1.peer(peer_3).unwrap(), ()),
+        ];
+        peer_heights
+            .read()
+            .unwrap()
+            .scores
+            .sort_peers(&mut peers, &request_type);
+        peers
+            .into_iter()
+            .map(|(peer, ())| peer.peer_id())
+            .collect::<Vec<_>>()
+    };
+
+    // Peer 3 answers summary requests faster, while peer 2 downloads contents faster.
+    {
+        let scores = &mut peer_heights.write().unwrap().scores;
+        scores.record_summary_success(peer_2, Duration::from_millis(200));
+        scores.record_summary_success(peer_3, Duration::from_millis(20));
+        scores.record_contents_success(peer_2, 1_000_000, Duration::from_millis(100));
+        scores.record_contents_success(peer_3, 1_000_000, Duration::from_millis(120));
+    }
+    assert_eq!(
+        selected_peers(PeerCheckpointRequestType::Summary),
+        vec![peer_3, peer_2]
+    );
+    assert_eq!(
+        selected_peers(PeerCheckpointRequestType::Content),
+        vec![peer_2, peer_3]
+    );
+
+    // A failure penalizes peer 2 enough for peer 3 to become the preferred contents source.
+    peer_heights.write().unwrap().scores.record_failure(peer_2);
+    assert_eq!(
+        selected_peers(PeerCheckpointRequestType::Content),
+        vec![peer_3, peer_2]
+    );
+
+    // A second consecutive failure bans peer 2, so it is no longer selected at all.
+    peer_heights.write().unwrap().scores.record_failure(peer_2);
+    let balanced_peers = PeerBalancer::new(
+        &network_1,
+        peer_heights.clone(),
+        PeerCheckpointRequestType::Content,
+    )
+    .with_checkpoint(50)
+    .map(|client| client.inner().peer_id())
+    .collect::<Vec<_>>();
+    assert_eq!(balanced_peers, vec![peer_3]);
+
+    let scores = handle_1.peer_scores();
+    let score_2 = scores.iter().find(|s| s.peer_id == peer_2).unwrap();
+    assert_eq!((score_2.successes, score_2.failures), (2, 2));
+    assert!(score_2.banned_for_ms.is_some());
+    let score_3 = scores.iter().find(|s| s.peer_id == peer_3).unwrap();
+    assert_eq!(score_3.failures, 0);
+    assert!(score_3.banned_for_ms.is_none());
+
+    // The ban is lifted once it expires.
+    let after_ban = Instant::now() + Duration::from_secs(61);
+    assert!(!peer_heights
+        .read()
+        .unwrap()
+        .scores
+        .is_banned(&peer_2, after_ban));
+}
+
+#[test]
+fn sync_source_selection() {
+    let config = |sync_mode| StateSyncConfig {
+        sync_mode: Some(sync_mode),
+        archive_first_lag_threshold: Some(1_000),
+        archive_sync_batch_size: Some(500),
+        ..Default::default()
+    };
+    let tip = 10_000;
+
+    // Fallback only reads checkpoints that peers have pruned from the archive.
+    let mut selector = SyncSourceSelector::new(&config(StateSyncMode::Fallback));
+    selector.set_archive_latest_checkpoint(Some(20_000));
+    assert!(selector.use_peers(0, tip));
+    assert_eq!(selector.next_archive_batch(0, tip, Some(0)), None);
+    assert_eq!(selector.next_archive_batch(0, tip, Some(100)), Some(1..100));
+
+    // Peers only never reads from the archive.
+    let mut selector = SyncSourceSelector::new(&config(StateSyncMode::PeersOnly));
+    selector.set_archive_latest_checkpoint(Some(20_000));
+    assert!(selector.use_peers(0, tip));
+    assert_eq!(selector.next_archive_batch(0, tip, Some(100)), None);
+
+    // Archive first reads batches from the archive until within the lag threshold of the tip.
+    let mut selector = SyncSourceSelector::new(&config(StateSyncMode::ArchiveFirst));
+    assert!(selector.use_peers(0, tip), "no archive to sync from");
+    assert_eq!(selector.next_archive_batch(0, tip, Some(0)), None);
+    selector.set_archive_latest_checkpoint(Some(20_000));
+    assert!(!selector.use_peers(0, tip));
+    assert_eq!(selector.next_archive_batch(0, tip, Some(0)), Some(1..501));
+    assert_eq!(
+        selector.next_archive_batch(8_800, tip, Some(0)),
+        Some(8_801..9_001)
+    );
+    assert!(selector.use_peers(9_000, tip));
+    assert_eq!(selector.next_archive_batch(9_000, tip, Some(0)), None);
+    assert_eq!(
+        selector.next_archive_batch(9_000, tip, Some(9_500)),
+        Some(9_001..9_500)
+    );
+    selector.start_archive_batch();
+    assert!(!selector.use_peers(9_000, tip));
+    selector.finish_archive_batch(500, Duration::from_secs(1));
+    assert!(selector.use_peers(9_000, tip));
+    // The archive can't help with checkpoints it doesn't have yet.
+    selector.set_archive_latest_checkpoint(Some(5_000));
+    assert!(selector.use_peers(5_000, tip));
+    assert_eq!(
+        selector.next_archive_batch(4_800, tip, Some(0)),
+        Some(4_801..5_001)
+    );
+
+    // Hybrid measures both sources, then follows whichever is faster.
+    let mut selector = SyncSourceSelector::new(&config(StateSyncMode::Hybrid));
+    selector.set_archive_latest_checkpoint(Some(20_000));
+    assert!(!selector.use_peers(0, tip));
+    assert_eq!(selector.next_archive_batch(0, tip, Some(0)), Some(1..501));
+    selector.start_archive_batch();
+    selector.finish_archive_batch(500, Duration::from_secs(10));
+    assert_eq!(selector.archive_throughput(), Some(50.0));
+    assert!(
+        selector.use_peers(500, tip),
+        "peers haven't been measured yet"
+    );
+    assert_eq!(selector.next_archive_batch(500, tip, Some(0)), None);
+
+    let start = Instant::now();
+    selector.sample_peer_throughput(start, 500, tip);
+    selector.sample_peer_throughput(start + Duration::from_secs(10), 1_500, tip);
+    assert_eq!(selector.peer_throughput(), Some(100.0));
+    assert!(selector.use_peers(1_500, tip));
+
+    // Peers slow down to 10 checkpoints per second, so the archive takes over again.
+    let mut highest_synced = 1_500;
+    for i in 2..10 {
+        highest_synced += 100;
+        selector.sample_peer_throughput(start + Duration::from_secs(i * 10), highest_synced, tip);
+        if !selector.use_peers(highest_synced, tip) {
+            break;
+        }
+    }
+    assert!(selector.peer_throughput().unwrap() < 50.0);
+    assert!(!selector.use_peers(highest_synced, tip));
+    assert_eq!(
+        selector.next_archive_batch(highest_synced, tip, Some(0)),
+        Some(highest_synced + 1..highest_synced + 501)
+    );
+}
+
 #[tokio::test]
 async fn test_state_sync_using_archive() -> anyhow::Result<()> {
     let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
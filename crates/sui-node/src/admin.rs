@@ -8,10 +8,15 @@ use axum::{
     routing::{get, post},
     Router,
 };
+use consensus_core::DagExportFormat;
 use humantime::parse_duration;
 use serde::Deserialize;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
+use sui_core::overload_monitor::ClientLoadSheddingOverride;
+use sui_network::capture::{parse_peer_id, CaptureConfig};
+use sui_types::digests::TransactionDigest;
 use sui_types::error::SuiError;
 use telemetry_subscribers::TracingHandle;
 use tracing::info;
@@ -47,6 +52,65 @@ use tracing::info;
 // Reset tracing to the TRACE_FILTER env var.
 //
 //   $ curl -X POST 'http://127.0.0.1:1337/reset-tracing'
+//
+// View the hit and miss rates of each tier of the execution cache, and the 20 most read objects:
+//
+//   $ curl 'http://127.0.0.1:1337/execution-cache-stats?hottest=20'
+//
+// View the 100 certificates that have waited longest for their inputs in the transaction
+// manager, what each one waits on, and the chain of certificates that must execute first:
+//
+//   $ curl 'http://127.0.0.1:1337/pending-certificates?limit=100'
+//
+// View the same for a single certificate:
+//
+//   $ curl 'http://127.0.0.1:1337/pending-certificates?digest=<transaction digest>'
+//
+// View the 20 clients submitting the most transactions touching congested objects, which are shed
+// first when client-aware load shedding is enabled:
+//
+//   $ curl 'http://127.0.0.1:1337/load-shedding-clients?limit=20'
+//
+// Never shed transactions from a client, or shed all of them while the node is overloaded:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/set-load-shedding-override?client=10.0.0.1&mode=exempt'
+//   $ curl -X POST 'http://127.0.0.1:1337/set-load-shedding-override?client=10.0.0.1&mode=shed'
+//
+// Clear the load shedding override of a client:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/clear-load-shedding-override?client=10.0.0.1'
+//
+// View the traffic control allow and deny lists:
+//
+//   $ curl 'http://127.0.0.1:1337/traffic-control-lists'
+//
+// Reload the traffic control allow and deny lists. The access list file is read again, together
+// with the lists of the policy config the node was started with; changes to the node config itself
+// require a restart:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/reload-traffic-control-lists'
+//
+// Export the consensus DAG between rounds 100 and 120 as Graphviz DOT or JSON, highlighting commit
+// leaders, committed blocks, missing ancestors and equivocations:
+//
+//   $ curl 'http://127.0.0.1:1337/consensus-dag?start_round=100&end_round=120&format=dot' | dot -Tsvg > dag.svg
+//   $ curl 'http://127.0.0.1:1337/consensus-dag?start_round=100&end_round=120&format=json'
+//
+// View the state sync download scores of our peers (latency, throughput, failure rate and
+// remaining ban time):
+//
+//   $ curl 'http://127.0.0.1:1337/state-sync-peers'
+//
+// Capture the anemo RPCs of the p2p network to rotating files in a directory, optionally only for
+// some routes or peers, or a sample of them, and view the progress of the capture. The capture can
+// be decoded and replayed with `sui-tool anemo-capture`.
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/enable-anemo-capture?directory=/tmp/capture&routes=/sui.Randomness/SendSignatures&peers=<peer id>,<peer id>&sample_rate=0.1'
+//   $ curl 'http://127.0.0.1:1337/anemo-capture'
+//
+// Stop capturing anemo RPCs:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/disable-anemo-capture'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -56,6 +120,23 @@ const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const EXECUTION_CACHE_STATS: &str = "/execution-cache-stats";
+const PENDING_CERTIFICATES: &str = "/pending-certificates";
+const LOAD_SHEDDING_CLIENTS: &str = "/load-shedding-clients";
+const SET_LOAD_SHEDDING_OVERRIDE: &str = "/set-load-shedding-override";
+const CLEAR_LOAD_SHEDDING_OVERRIDE: &str = "/clear-load-shedding-override";
+const TRAFFIC_CONTROL_LISTS: &str = "/traffic-control-lists";
+const RELOAD_TRAFFIC_CONTROL_LISTS: &str = "/reload-traffic-control-lists";
+const CONSENSUS_DAG: &str = "/consensus-dag";
+const STATE_SYNC_PEERS: &str = "/state-sync-peers";
+const ANEMO_CAPTURE: &str = "/anemo-capture";
+const ENABLE_ANEMO_CAPTURE: &str = "/enable-anemo-capture";
+const DISABLE_ANEMO_CAPTURE: &str = "/disable-anemo-capture";
+
+const DEFAULT_HOTTEST_OBJECTS: usize = 10;
+const DEFAULT_PENDING_CERTIFICATES_LIMIT: usize = 50;
+const DEFAULT_LOAD_SHEDDING_CLIENTS_LIMIT: usize = 20;
+const MAX_CONSENSUS_DAG_ROUNDS: u32 = 1000;
 
 struct AppState {
     node: Arc<SuiNode>,
@@ -74,6 +155,13 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(LOGGING_ROUTE, get(get_filter))
         .route(CAPABILITIES, get(capabilities))
         .route(NODE_CONFIG, get(node_config))
+        .route(EXECUTION_CACHE_STATS, get(execution_cache_stats))
+        .route(PENDING_CERTIFICATES, get(pending_certificates))
+        .route(LOAD_SHEDDING_CLIENTS, get(load_shedding_clients))
+        .route(TRAFFIC_CONTROL_LISTS, get(traffic_control_lists))
+        .route(CONSENSUS_DAG, get(consensus_dag))
+        .route(STATE_SYNC_PEERS, get(state_sync_peers))
+        .route(ANEMO_CAPTURE, get(anemo_capture))
         .route(LOGGING_ROUTE, post(set_filter))
         .route(
             SET_BUFFER_STAKE_ROUTE,
@@ -84,6 +172,17 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
             post(clear_override_protocol_upgrade_buffer_stake),
         )
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
+        .route(SET_LOAD_SHEDDING_OVERRIDE, post(set_load_shedding_override))
+        .route(
+            CLEAR_LOAD_SHEDDING_OVERRIDE,
+            post(clear_load_shedding_override),
+        )
+        .route(
+            RELOAD_TRAFFIC_CONTROL_LISTS,
+            post(reload_traffic_control_lists),
+        )
+        .route(ENABLE_ANEMO_CAPTURE, post(enable_anemo_capture))
+        .route(DISABLE_ANEMO_CAPTURE, post(disable_anemo_capture))
         .route(TRACING_ROUTE, post(enable_tracing))
         .route(TRACING_RESET_ROUTE, post(reset_tracing))
         .with_state(Arc::new(app_state));
@@ -216,6 +315,298 @@ async fn node_config(State(state): State<Arc<AppState>>) -> (StatusCode, String)
     (StatusCode::OK, format!("{:#?}\n", node_config))
 }
 
+#[derive(Deserialize)]
+struct ExecutionCacheStatsQuery {
+    hottest: Option<usize>,
+}
+
+async fn execution_cache_stats(
+    State(state): State<Arc<AppState>>,
+    query: Query<ExecutionCacheStatsQuery>,
+) -> (StatusCode, String) {
+    let Query(ExecutionCacheStatsQuery { hottest }) = query;
+    let snapshot = state
+        .node
+        .state()
+        .get_execution_cache()
+        .cache_stats()
+        .snapshot(hottest.unwrap_or(DEFAULT_HOTTEST_OBJECTS));
+
+    match serde_json::to_string_pretty(&snapshot) {
+        Ok(stats) => (StatusCode::OK, format!("{stats}\n")),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct PendingCertificatesQuery {
+    digest: Option<TransactionDigest>,
+    limit: Option<usize>,
+}
+
+async fn pending_certificates(
+    State(state): State<Arc<AppState>>,
+    query: Query<PendingCertificatesQuery>,
+) -> (StatusCode, String) {
+    let Query(PendingCertificatesQuery { digest, limit }) = query;
+    let snapshot = state
+        .node
+        .state()
+        .transaction_manager()
+        .snapshot(digest, limit.unwrap_or(DEFAULT_PENDING_CERTIFICATES_LIMIT));
+
+    match serde_json::to_string_pretty(&snapshot) {
+        Ok(snapshot) => (StatusCode::OK, format!("{snapshot}\n")),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct LoadSheddingClientsQuery {
+    limit: Option<usize>,
+}
+
+async fn load_shedding_clients(
+    State(state): State<Arc<AppState>>,
+    query: Query<LoadSheddingClientsQuery>,
+) -> (StatusCode, String) {
+    let Query(LoadSheddingClientsQuery { limit }) = query;
+    let snapshot = state
+        .node
+        .state()
+        .overload_info
+        .client_load
+        .snapshot(limit.unwrap_or(DEFAULT_LOAD_SHEDDING_CLIENTS_LIMIT));
+
+    match serde_json::to_string_pretty(&snapshot) {
+        Ok(snapshot) => (StatusCode::OK, format!("{snapshot}\n")),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct SetLoadSheddingOverride {
+    client: IpAddr,
+    mode: ClientLoadSheddingOverride,
+}
+
+async fn set_load_shedding_override(
+    State(state): State<Arc<AppState>>,
+    query: Query<SetLoadSheddingOverride>,
+) -> (StatusCode, String) {
+    let Query(SetLoadSheddingOverride { client, mode }) = query;
+    state
+        .node
+        .state()
+        .overload_info
+        .client_load
+        .set_override(client, mode);
+    info!(%client, ?mode, "set load shedding override");
+    (
+        StatusCode::OK,
+        format!("load shedding override for '{client}' set to '{mode:?}'\n"),
+    )
+}
+
+#[derive(Deserialize)]
+struct ClearLoadSheddingOverride {
+    client: IpAddr,
+}
+
+async fn clear_load_shedding_override(
+    State(state): State<Arc<AppState>>,
+    query: Query<ClearLoadSheddingOverride>,
+) -> (StatusCode, String) {
+    let Query(ClearLoadSheddingOverride { client }) = query;
+    if state
+        .node
+        .state()
+        .overload_info
+        .client_load
+        .clear_override(&client)
+    {
+        info!(%client, "cleared load shedding override");
+        (
+            StatusCode::OK,
+            format!("load shedding override for '{client}' cleared\n"),
+        )
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            format!("no load shedding override for '{client}'\n"),
+        )
+    }
+}
+
+async fn traffic_control_lists(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let Some(access_lists) = state.node.traffic_access_lists() else {
+        return (
+            StatusCode::NOT_FOUND,
+            "traffic control is not enabled\n".to_string(),
+        );
+    };
+
+    match serde_json::to_string_pretty(&access_lists.load().to_config()) {
+        Ok(lists) => (StatusCode::OK, format!("{lists}\n")),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn reload_traffic_control_lists(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let Some(access_lists) = state.node.traffic_access_lists() else {
+        return (
+            StatusCode::NOT_FOUND,
+            "traffic control is not enabled\n".to_string(),
+        );
+    };
+
+    match access_lists.reload() {
+        Ok(lists) => {
+            let lists = lists.to_config();
+            (
+                StatusCode::OK,
+                format!(
+                    "reloaded {} allowed and {} denied CIDR blocks\n",
+                    lists.allow_list.len(),
+                    lists.deny_list.len()
+                ),
+            )
+        }
+        Err(err) => (StatusCode::BAD_REQUEST, format!("{err:#}\n")),
+    }
+}
+
+#[derive(Deserialize)]
+struct ConsensusDagQuery {
+    start_round: u32,
+    end_round: u32,
+    #[serde(default)]
+    format: DagExportFormat,
+}
+
+async fn consensus_dag(
+    State(state): State<Arc<AppState>>,
+    query: Query<ConsensusDagQuery>,
+) -> (StatusCode, String) {
+    let Query(ConsensusDagQuery {
+        start_round,
+        end_round,
+        format,
+    }) = query;
+    if start_round > end_round || end_round - start_round >= MAX_CONSENSUS_DAG_ROUNDS {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "invalid round range, expected start_round <= end_round and at most {MAX_CONSENSUS_DAG_ROUNDS} rounds\n"
+            ),
+        );
+    }
+
+    match state
+        .node
+        .consensus_dag_snapshot(start_round, end_round)
+        .await
+    {
+        Some(Ok(snapshot)) => (StatusCode::OK, format!("{}\n", snapshot.export(format))),
+        Some(Err(err)) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err}\n")),
+        None => (
+            StatusCode::NOT_FOUND,
+            "mysticeti consensus is not running on this node\n".to_string(),
+        ),
+    }
+}
+
+async fn state_sync_peers(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let scores = state.node.state_sync_handle().peer_scores();
+
+    match serde_json::to_string_pretty(&scores) {
+        Ok(scores) => (StatusCode::OK, format!("{scores}\n")),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn anemo_capture(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let Some(status) = state.node.rpc_capture().status() else {
+        return (StatusCode::OK, "no anemo capture in progress\n".to_string());
+    };
+
+    match serde_json::to_string_pretty(&status) {
+        Ok(status) => (StatusCode::OK, format!("{status}\n")),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct EnableAnemoCapture {
+    directory: PathBuf,
+    // Comma separated lists of the routes and peers to capture, everything is captured if unset.
+    routes: Option<String>,
+    peers: Option<String>,
+    sample_rate: Option<f64>,
+    max_body_bytes: Option<usize>,
+    max_file_bytes: Option<u64>,
+    max_files: Option<usize>,
+}
+
+async fn enable_anemo_capture(
+    State(state): State<Arc<AppState>>,
+    query: Query<EnableAnemoCapture>,
+) -> (StatusCode, String) {
+    let Query(EnableAnemoCapture {
+        directory,
+        routes,
+        peers,
+        sample_rate,
+        max_body_bytes,
+        max_file_bytes,
+        max_files,
+    }) = query;
+
+    let mut config = CaptureConfig::new(directory);
+    if let Some(routes) = routes {
+        config.filter.routes = routes.split(',').map(str::to_owned).collect();
+    }
+    if let Some(peers) = peers {
+        match peers.split(',').map(parse_peer_id).collect() {
+            Ok(peers) => config.filter.peers = peers,
+            Err(err) => return (StatusCode::BAD_REQUEST, format!("invalid peer id: {err}\n")),
+        }
+    }
+    if let Some(sample_rate) = sample_rate {
+        config.filter.sample_rate = sample_rate;
+    }
+    config.max_body_bytes = max_body_bytes.unwrap_or(config.max_body_bytes);
+    config.max_file_bytes = max_file_bytes.unwrap_or(config.max_file_bytes);
+    config.max_files = max_files.unwrap_or(config.max_files);
+
+    let directory = config.directory.clone();
+    match state.node.rpc_capture().enable(config) {
+        Ok(()) => (
+            StatusCode::OK,
+            format!("capturing anemo RPCs to {}\n", directory.display()),
+        ),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to start anemo capture: {err}\n"),
+        ),
+    }
+}
+
+async fn disable_anemo_capture(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match state.node.rpc_capture().disable() {
+        Some(status) => (
+            StatusCode::OK,
+            format!(
+                "stopped anemo capture to {}, {} messages captured and {} dropped\n",
+                status.config.directory.display(),
+                status.captured_messages,
+                status.dropped_messages
+            ),
+        ),
+        None => (StatusCode::OK, "no anemo capture in progress\n".to_string()),
+    }
+}
+
 #[derive(Deserialize)]
 struct Epoch {
     epoch: u64,
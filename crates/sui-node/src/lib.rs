@@ -9,6 +9,7 @@ use anemo_tower::trace::TraceLayer;
 use anyhow::anyhow;
 use anyhow::Result;
 use arc_swap::ArcSwap;
+use consensus_core::{ConsensusResult, DagSnapshot, Round};
 use fastcrypto_zkp::bn254::zk_login::JwkId;
 use fastcrypto_zkp::bn254::zk_login::OIDCProvider;
 use futures::TryFutureExt;
@@ -27,8 +28,10 @@ use sui_core::authority::CHAIN_IDENTIFIER;
 use sui_core::consensus_adapter::SubmitToConsensus;
 use sui_core::consensus_manager::ConsensusClient;
 use sui_core::epoch::randomness::RandomnessManager;
+use sui_core::execution_cache::warm_tier::WarmTier;
 use sui_core::execution_cache::ExecutionCacheMetrics;
 use sui_core::execution_cache::NotifyReadWrapper;
+use sui_core::traffic_controller::access_lists::TrafficAccessLists;
 use sui_core::traffic_controller::metrics::TrafficControllerMetrics;
 use sui_json_rpc::ServerType;
 use sui_json_rpc_api::JsonRpcMetrics;
@@ -105,6 +108,7 @@ use sui_json_rpc::JsonRpcServerBuilder;
 use sui_macros::fail_point;
 use sui_macros::{fail_point_async, replay_log};
 use sui_network::api::ValidatorServer;
+use sui_network::capture::{self, RpcCapture};
 use sui_network::discovery;
 use sui_network::discovery::TrustedPeerChangeEvent;
 use sui_network::state_sync;
@@ -218,10 +222,13 @@ pub struct SuiNode {
     transaction_orchestrator: Option<Arc<TransactiondOrchestrator<NetworkAuthorityClient>>>,
     registry_service: RegistryService,
     metrics: Arc<SuiNodeMetrics>,
+    /// Access lists shared by the traffic controllers of the node, if traffic control is enabled
+    traffic_access_lists: Option<TrafficAccessLists>,
 
     _discovery: discovery::Handle,
     state_sync_handle: state_sync::Handle,
     randomness_handle: randomness::Handle,
+    rpc_capture: RpcCapture,
     checkpoint_store: Arc<CheckpointStore>,
     accumulator: Arc<StateAccumulator>,
     connection_monitor_status: Arc<ConnectionMonitorStatus>,
@@ -456,7 +463,14 @@ impl SuiNode {
         )
         .await?;
         let execution_cache_metrics = Arc::new(ExecutionCacheMetrics::new(&prometheus_registry));
-        let execution_cache = Arc::new(ExecutionCache::new(store.clone(), execution_cache_metrics));
+        let warm_tier = config
+            .enable_execution_cache_warm_tier
+            .then(|| WarmTier::open(config.db_path().join("warm_cache"), store.as_ref()));
+        let execution_cache = Arc::new(ExecutionCache::new_with_warm_tier(
+            store.clone(),
+            execution_cache_metrics,
+            warm_tier,
+        ));
 
         let cur_epoch = store.get_recovery_epoch_at_restart()?;
         let committee = committee_store
@@ -555,6 +569,7 @@ impl SuiNode {
                 .unwrap_or_default()
                 .mailbox_capacity(),
         );
+        let rpc_capture = RpcCapture::default();
         let (p2p_network, discovery_handle, state_sync_handle, randomness_handle) =
             Self::create_p2p_network(
                 &config,
@@ -563,6 +578,7 @@ impl SuiNode {
                 trusted_peer_change_rx,
                 archive_readers.clone(),
                 randomness_tx,
+                &rpc_capture,
                 &prometheus_registry,
             )?;
 
@@ -672,6 +688,12 @@ impl SuiNode {
             None
         };
 
+        let traffic_access_lists = config
+            .policy_config
+            .as_ref()
+            .map(TrafficAccessLists::new)
+            .transpose()?;
+
         let http_server = build_http_server(
             state.clone(),
             state_sync_store,
@@ -680,6 +702,7 @@ impl SuiNode {
             &prometheus_registry,
             custom_rpc_runtime,
             software_version,
+            traffic_access_lists.clone(),
         )
         .await?;
 
@@ -723,6 +746,7 @@ impl SuiNode {
                 connection_monitor_status.clone(),
                 &registry_service,
                 sui_node_metrics.clone(),
+                traffic_access_lists.clone(),
             )
             .await?;
             // This is only needed during cold start.
@@ -744,10 +768,12 @@ impl SuiNode {
             transaction_orchestrator,
             registry_service,
             metrics: sui_node_metrics,
+            traffic_access_lists,
 
             _discovery: discovery_handle,
             state_sync_handle,
             randomness_handle,
+            rpc_capture,
             checkpoint_store,
             accumulator,
             end_of_epoch_channel,
@@ -942,6 +968,7 @@ impl SuiNode {
         trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
         archive_readers: ArchiveReaderBalancer,
         randomness_tx: mpsc::Sender<(EpochId, RandomnessRound, Vec<u8>)>,
+        rpc_capture: &RpcCapture,
         prometheus_registry: &Registry,
     ) -> Result<(
         Network,
@@ -956,8 +983,13 @@ impl SuiNode {
             .with_metrics(prometheus_registry)
             .build();
 
+        let mut p2p_config = config.p2p_config.clone();
+        let discovery_config = p2p_config.discovery.get_or_insert_with(Default::default);
+        if discovery_config.peer_store_path.is_none() {
+            discovery_config.peer_store_path = Some(config.db_path().join("discovery_peers"));
+        }
         let (discovery, discovery_server) = discovery::Builder::new(trusted_peer_change_rx)
-            .config(config.p2p_config.clone())
+            .config(p2p_config)
             .build();
 
         let (randomness, randomness_router) =
@@ -987,6 +1019,9 @@ impl SuiNode {
                     Arc::new(inbound_network_metrics),
                     config.p2p_config.excessive_message_size(),
                 )))
+                .layer(CallbackLayer::new(
+                    rpc_capture.make_callback_handler(capture::Direction::Inbound),
+                ))
                 .service(routes);
 
             let outbound_layer = ServiceBuilder::new()
@@ -999,6 +1034,9 @@ impl SuiNode {
                     Arc::new(outbound_network_metrics),
                     config.p2p_config.excessive_message_size(),
                 )))
+                .layer(CallbackLayer::new(
+                    rpc_capture.make_callback_handler(capture::Direction::Outbound),
+                ))
                 .into_inner();
 
             let mut anemo_config = config.p2p_config.anemo_config.clone().unwrap_or_default();
@@ -1085,6 +1123,7 @@ impl SuiNode {
         connection_monitor_status: Arc<ConnectionMonitorStatus>,
         registry_service: &RegistryService,
         sui_node_metrics: Arc<SuiNodeMetrics>,
+        traffic_access_lists: Option<TrafficAccessLists>,
     ) -> Result<ValidatorComponents> {
         let mut config_clone = config.clone();
         let consensus_config = config_clone
@@ -1120,6 +1159,7 @@ impl SuiNode {
             state.clone(),
             consensus_adapter.clone(),
             &registry_service.default_registry(),
+            traffic_access_lists,
         )
         .await?;
 
@@ -1347,14 +1387,16 @@ impl SuiNode {
         state: Arc<AuthorityState>,
         consensus_adapter: Arc<ConsensusAdapter>,
         prometheus_registry: &Registry,
+        traffic_access_lists: Option<TrafficAccessLists>,
     ) -> Result<tokio::task::JoinHandle<Result<()>>> {
-        let validator_service = ValidatorService::new(
+        let validator_service = ValidatorService::new_with_access_lists(
             state.clone(),
             consensus_adapter,
             Arc::new(ValidatorServiceMetrics::new(prometheus_registry)),
             TrafficControllerMetrics::new(prometheus_registry),
             config.policy_config.clone(),
             config.firewall_config.clone(),
+            traffic_access_lists,
         );
 
         let mut server_conf = mysten_network::config::Config::new();
@@ -1380,6 +1422,42 @@ impl SuiNode {
         self.state.clone()
     }
 
+    pub fn traffic_access_lists(&self) -> Option<&TrafficAccessLists> {
+        self.traffic_access_lists.as_ref()
+    }
+
+    pub fn state_sync_handle(&self) -> &state_sync::Handle {
+        &self.state_sync_handle
+    }
+
+    /// Handle used to capture the RPCs of the p2p network.
+    pub fn rpc_capture(&self) -> &RpcCapture {
+        &self.rpc_capture
+    }
+
+    /// Returns a snapshot of the consensus DAG within [`start_round`, `end_round`], or None when
+    /// the node is not a validator running Mysticeti.
+    pub async fn consensus_dag_snapshot(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> Option<ConsensusResult<DagSnapshot>> {
+        // Only the inspector is taken while holding the locks, since snapshots scan storage.
+        let inspector = self
+            .validator_components
+            .lock()
+            .await
+            .as_ref()?
+            .consensus_manager
+            .dag_inspector()
+            .await?;
+        Some(
+            tokio::task::spawn_blocking(move || inspector.snapshot(start_round, end_round))
+                .await
+                .expect("Consensus DAG snapshot task panicked"),
+        )
+    }
+
     // Only used for testing because of how epoch store is loaded.
     pub fn reference_gas_price_for_testing(&self) -> Result<u64, anyhow::Error> {
         self.state.reference_gas_price_for_testing()
@@ -1619,6 +1697,7 @@ impl SuiNode {
                             self.connection_monitor_status.clone(),
                             &self.registry_service,
                             self.metrics.clone(),
+                            self.traffic_access_lists.clone(),
                         )
                         .await?,
                     )
@@ -1814,6 +1893,7 @@ pub async fn build_http_server(
     prometheus_registry: &Registry,
     _custom_runtime: Option<Handle>,
     software_version: &'static str,
+    traffic_access_lists: Option<TrafficAccessLists>,
 ) -> Result<Option<tokio::task::JoinHandle<()>>> {
     // Validators do not expose these APIs
     if config.consensus_config().is_some() {
@@ -1831,6 +1911,12 @@ pub async fn build_http_server(
             config.policy_config.clone(),
             config.firewall_config.clone(),
         );
+        if let Some(max_batch_size) = config.json_rpc_max_batch_size {
+            server.set_max_batch_size(max_batch_size);
+        }
+        if let Some(traffic_access_lists) = traffic_access_lists {
+            server.set_traffic_access_lists(traffic_access_lists);
+        }
 
         let kv_store = build_kv_store(&state, config, prometheus_registry)?;
 
@@ -723,6 +723,7 @@ impl RpcExampleProvider {
             effects: Some(SuiTransactionBlockEffects::V1(
                 SuiTransactionBlockEffectsV1 {
                     status: SuiExecutionStatus::Success,
+                    shared_object_congestion: None,
                     executed_epoch: 0,
                     modified_at_versions: vec![],
                     gas_used: GasCostSummary {
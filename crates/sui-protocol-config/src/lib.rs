@@ -13,7 +13,7 @@ use tracing::{info, warn};
 
 /// The minimum and maximum protocol versions supported by this build.
 const MIN_PROTOCOL_VERSION: u64 = 1;
-const MAX_PROTOCOL_VERSION: u64 = 45;
+const MAX_PROTOCOL_VERSION: u64 = 46;
 
 // Record history of protocol version allocations here:
 //
@@ -122,6 +122,9 @@ const MAX_PROTOCOL_VERSION: u64 = 45;
 // Version 44: Enable consensus fork detection on mainnet.
 //             Switch between Narwhal and Mysticeti consensus in tests, devnet and testnet.
 // Version 45: Use tonic networking for Mysticeti consensus.
+// Version 46: Enable Mysticeti leader scoring & schedule change, in tests and devnet.
+//             Enforce transaction size limits when verifying Mysticeti blocks, in tests and devnet.
+//             Cancel transactions deferred too long by congestion control, in tests and devnet.
 
 #[derive(Copy, Clone, Debug, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ProtocolVersion(u64);
@@ -417,6 +420,18 @@ struct FeatureFlags {
     // Set the upper bound allowed for max_epoch in zklogin signature.
     #[serde(skip_serializing_if = "Option::is_none")]
     zklogin_max_epoch_upper_bound_delta: Option<u64>,
+
+    // Enables leader scoring & schedule change in Mysticeti consensus.
+    #[serde(skip_serializing_if = "is_false")]
+    mysticeti_leader_scoring_and_schedule: bool,
+
+    // The strategy used to score authorities for the Mysticeti leader schedule.
+    #[serde(skip_serializing_if = "ConsensusLeaderScoringStrategy::is_vote")]
+    consensus_leader_scoring_strategy: ConsensusLeaderScoringStrategy,
+
+    // Rejects Mysticeti blocks with transactions above the consensus transaction size limits.
+    #[serde(skip_serializing_if = "is_false")]
+    consensus_check_transaction_sizes: bool,
 }
 
 fn is_false(b: &bool) -> bool {
@@ -435,6 +450,10 @@ pub enum ConsensusTransactionOrdering {
     None,
     /// Order transactions by gas price, highest first.
     ByGasPrice,
+    /// Order transactions by gas price lane, highest first, where lane `k` holds gas prices from
+    /// 2^k up to 2^(k+1) - 1. Within a lane, transactions are interleaved round-robin by sender,
+    /// so that a single sender cannot crowd out the others by slightly raising its gas price.
+    ByGasPriceFairBySender,
 }
 
 impl ConsensusTransactionOrdering {
@@ -448,7 +467,8 @@ impl ConsensusTransactionOrdering {
 pub enum PerObjectCongestionControlMode {
     #[default]
     None, // No congestion control.
-    TotalGasBudget, // Use txn gas budget as execution cost.
+    TotalGasBudget,        // Use txn gas budget as execution cost.
+    ExecutionCostEstimate, // Estimate execution cost from the txn's Move calls.
 }
 
 impl PerObjectCongestionControlMode {
@@ -486,6 +506,24 @@ impl ConsensusNetwork {
     }
 }
 
+// Strategies to score authorities with, when changing the consensus leader schedule.
+#[derive(Default, Copy, Clone, PartialEq, Eq, Serialize, Debug)]
+pub enum ConsensusLeaderScoringStrategy {
+    // Scores authorities for voting on leaders.
+    #[default]
+    Vote,
+    // Scores authorities for votes on leaders which are certified by a quorum.
+    CertifiedVote,
+    // Scores authorities for blocks which are included by the next round.
+    Timeliness,
+}
+
+impl ConsensusLeaderScoringStrategy {
+    pub fn is_vote(&self) -> bool {
+        matches!(self, ConsensusLeaderScoringStrategy::Vote)
+    }
+}
+
 /// Constants that change the behavior of the protocol.
 ///
 /// The value of each constant here must be fixed for a given protocol version. To change the value
@@ -1042,6 +1080,21 @@ pub struct ProtocolConfig {
     // The max accumulated txn execution cost per object in a checkpoint. Transactions
     // in a checkpoint will be deferred once their touch shared objects hit this limit.
     max_accumulated_txn_cost_per_object_in_checkpoint: Option<u64>,
+
+    // The max number of consensus rounds a transaction can be deferred due to shared object congestion.
+    // Transactions will be cancelled after this many rounds.
+    max_deferral_rounds_for_congestion_control: Option<u64>,
+
+    // Estimated gas units of a transaction, and of each Move call it makes, used as its execution
+    // cost under `PerObjectCongestionControlMode::ExecutionCostEstimate`. They should follow the gas
+    // used by transactions on the network, and are fixed per protocol version so that validators
+    // agree on the estimate of every transaction.
+    congestion_control_base_tx_cost_units: Option<u64>,
+    congestion_control_move_call_cost_units: Option<u64>,
+
+    // The number of commits after which the Mysticeti leader schedule is recalculated from
+    // reputation scores of the authorities.
+    consensus_commits_per_schedule: Option<u64>,
 }
 
 // feature flags
@@ -1279,6 +1332,18 @@ impl ProtocolConfig {
     pub fn consensus_network(&self) -> ConsensusNetwork {
         self.feature_flags.consensus_network
     }
+
+    pub fn mysticeti_leader_scoring_and_schedule(&self) -> bool {
+        self.feature_flags.mysticeti_leader_scoring_and_schedule
+    }
+
+    pub fn consensus_leader_scoring_strategy(&self) -> ConsensusLeaderScoringStrategy {
+        self.feature_flags.consensus_leader_scoring_strategy
+    }
+
+    pub fn consensus_check_transaction_sizes(&self) -> bool {
+        self.feature_flags.consensus_check_transaction_sizes
+    }
 }
 
 #[cfg(not(msim))]
@@ -1720,6 +1785,13 @@ impl ProtocolConfig {
             consensus_max_transactions_in_block_bytes: None,
 
             max_accumulated_txn_cost_per_object_in_checkpoint: None,
+
+            max_deferral_rounds_for_congestion_control: None,
+
+            congestion_control_base_tx_cost_units: None,
+            congestion_control_move_call_cost_units: None,
+
+            consensus_commits_per_schedule: None,
             // When adding a new constant, set it to None in the earliest version, like this:
             // new_constant: None,
         };
@@ -2136,6 +2208,22 @@ impl ProtocolConfig {
                     }
                     // Also bumps framework snapshot to fix binop issue.
                 }
+                46 => {
+                    // Enable leader scoring & schedule change for Mysticeti, in tests and devnet.
+                    if chain != Chain::Testnet && chain != Chain::Mainnet {
+                        cfg.feature_flags.mysticeti_leader_scoring_and_schedule = true;
+                    }
+                    cfg.consensus_commits_per_schedule = Some(300);
+                    // Enforce transaction size limits in Mysticeti blocks, in tests and devnet.
+                    if chain != Chain::Testnet && chain != Chain::Mainnet {
+                        cfg.feature_flags.consensus_check_transaction_sizes = true;
+                    }
+                    // Cancel transactions deferred for too long due to shared object congestion,
+                    // in tests and devnet.
+                    if chain != Chain::Testnet && chain != Chain::Mainnet {
+                        cfg.max_deferral_rounds_for_congestion_control = Some(10);
+                    }
+                }
                 // Use this template when making changes:
                 //
                 //     // modify an existing constant.
@@ -2278,6 +2366,10 @@ impl ProtocolConfig {
         self.feature_flags.per_object_congestion_control_mode = val;
     }
 
+    pub fn set_consensus_transaction_ordering(&mut self, val: ConsensusTransactionOrdering) {
+        self.feature_flags.consensus_transaction_ordering = val;
+    }
+
     pub fn set_consensus_choice(&mut self, val: ConsensusChoice) {
         self.feature_flags.consensus_choice = val;
     }
@@ -2286,10 +2378,30 @@ impl ProtocolConfig {
         self.feature_flags.consensus_network = val;
     }
 
+    pub fn set_mysticeti_leader_scoring_and_schedule(&mut self, val: bool) {
+        self.feature_flags.mysticeti_leader_scoring_and_schedule = val;
+    }
+
+    pub fn set_consensus_leader_scoring_strategy(&mut self, val: ConsensusLeaderScoringStrategy) {
+        self.feature_flags.consensus_leader_scoring_strategy = val;
+    }
+
+    pub fn set_consensus_check_transaction_sizes(&mut self, val: bool) {
+        self.feature_flags.consensus_check_transaction_sizes = val;
+    }
+
+    pub fn set_consensus_commits_per_schedule(&mut self, val: u64) {
+        self.consensus_commits_per_schedule = Some(val);
+    }
+
     pub fn set_max_accumulated_txn_cost_per_object_in_checkpoint(&mut self, val: u64) {
         self.max_accumulated_txn_cost_per_object_in_checkpoint = Some(val);
     }
 
+    pub fn set_max_deferral_rounds_for_congestion_control(&mut self, val: u64) {
+        self.max_deferral_rounds_for_congestion_control = Some(val);
+    }
+
     pub fn set_zklogin_max_epoch_upper_bound_delta(&mut self, val: Option<u64>) {
         self.feature_flags.zklogin_max_epoch_upper_bound_delta = val
     }
@@ -705,6 +705,11 @@ impl LocalExec {
                 transaction_kind.clone(),
                 tx_info.sender,
                 *tx_digest,
+                tx_info
+                    .effects
+                    .shared_object_congestion()
+                    .cloned()
+                    .map(Into::into),
             )
         } else {
             unreachable!("Transaction was valid so gas status must be valid");
@@ -870,6 +875,12 @@ impl LocalExec {
             kind,
             signer,
             *executable.digest(),
+            pre_run_sandbox
+                .transaction_info
+                .effects
+                .shared_object_congestion()
+                .cloned()
+                .map(Into::into),
         );
 
         let effects =
@@ -605,7 +605,27 @@ impl ReadApi {
             ));
         };
         let subscription: Subscription<SuiTransactionBlockEffects> =
-            c.subscribe_transaction(filter).await?;
+            c.subscribe_transaction(filter, None).await?;
+        Ok(subscription.map(|item| Ok(item?)))
+    }
+
+    /// Subscribe to a stream of transactions, starting with those executed after the transaction
+    /// at `cursor`. Unlike `subscribe_transaction`, no transactions are missed if the client
+    /// falls behind, so this can be used to resume a stream after reconnecting.
+    ///
+    /// This is only available through WebSockets.
+    pub async fn subscribe_transaction_from(
+        &self,
+        filter: TransactionFilter,
+        cursor: TransactionDigest,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiTransactionBlockEffects>>> {
+        let Some(c) = &self.api.ws else {
+            return Err(Error::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            ));
+        };
+        let subscription: Subscription<SuiTransactionBlockEffects> =
+            c.subscribe_transaction(filter, Some(cursor)).await?;
         Ok(subscription.map(|item| Ok(item?)))
     }
 
@@ -1043,7 +1063,29 @@ impl EventApi {
     ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiEvent>>> {
         match &self.api.ws {
             Some(c) => {
-                let subscription: Subscription<SuiEvent> = c.subscribe_event(filter).await?;
+                let subscription: Subscription<SuiEvent> = c.subscribe_event(filter, None).await?;
+                Ok(subscription.map(|item| Ok(item?)))
+            }
+            _ => Err(Error::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            )),
+        }
+    }
+
+    /// Subscribe to receive the events matching `filter` emitted after the event at `cursor`.
+    /// Unlike `subscribe_event`, no events are missed if the client falls behind, so this can be
+    /// used to resume a stream after reconnecting, with the ID of the last event received.
+    ///
+    /// This is only available through WebSockets.
+    pub async fn subscribe_event_from(
+        &self,
+        filter: EventFilter,
+        cursor: EventID,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiEvent>>> {
+        match &self.api.ws {
+            Some(c) => {
+                let subscription: Subscription<SuiEvent> =
+                    c.subscribe_event(filter, Some(cursor)).await?;
                 Ok(subscription.map(|item| Ok(item?)))
             }
             _ => Err(Error::Subscription(
@@ -225,6 +225,7 @@ impl SingleValidator {
                 kind,
                 signer,
                 *executable.digest(),
+                None,
             );
         assert!(effects.status().is_ok());
         store.commit_objects(inner_temp_store);
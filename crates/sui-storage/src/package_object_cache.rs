@@ -22,6 +22,10 @@ impl PackageObjectCache {
         })
     }
 
+    pub fn contains(&self, package_id: &ObjectID) -> bool {
+        self.cache.read().contains(package_id)
+    }
+
     pub fn get_package_object(
         &self,
         package_id: &ObjectID,
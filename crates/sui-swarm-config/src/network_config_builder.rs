@@ -560,6 +560,7 @@ mod test {
                 kind,
                 signer,
                 genesis_digest,
+                None,
             );
 
         assert_eq!(&effects, genesis.effects());
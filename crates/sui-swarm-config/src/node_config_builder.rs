@@ -226,6 +226,8 @@ impl ValidatorConfigBuilder {
             websocket_only: false,
             policy_config: self.policy_config,
             firewall_config: self.firewall_config,
+            json_rpc_max_batch_size: None,
+            enable_execution_cache_warm_tier: false,
         }
     }
 
@@ -493,6 +495,8 @@ impl FullnodeConfigBuilder {
             websocket_only: false,
             policy_config: self.policy_config,
             firewall_config: self.fw_config,
+            json_rpc_max_batch_size: None,
+            enable_execution_cache_warm_tier: false,
         }
     }
 }
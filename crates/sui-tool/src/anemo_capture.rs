@@ -0,0 +1,149 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use clap::Parser;
+use std::path::PathBuf;
+use sui_network::capture::{
+    decode_body, parse_peer_id, read_capture, replay_request, CapturedEvent, CapturedMessage,
+};
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub enum AnemoCaptureCommand {
+    /// Print the messages of a capture taken through the admin API
+    Decode(DecodeOptions),
+    /// Send the requests of a capture to a peer, and print the responses
+    Replay(ReplayOptions),
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct FilterOptions {
+    /// Capture directory, or a single capture file
+    #[arg(long)]
+    path: PathBuf,
+    /// Only include messages of this route, e.g. `/sui.StateSync/GetCheckpointSummary`
+    #[arg(long)]
+    route: Option<String>,
+    /// Only include messages exchanged with this peer
+    #[arg(long)]
+    peer: Option<String>,
+    /// Print the decoded bodies of the messages
+    #[arg(long)]
+    bodies: bool,
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct DecodeOptions {
+    #[command(flatten)]
+    filter: FilterOptions,
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct ReplayOptions {
+    #[command(flatten)]
+    filter: FilterOptions,
+    /// Address of the peer to send the requests to, e.g. `127.0.0.1:8084`
+    #[arg(long)]
+    address: String,
+    /// Server name of the peer's network, `sui-<chain identifier>` for sui nodes
+    #[arg(long)]
+    server_name: String,
+}
+
+impl FilterOptions {
+    fn read(&self) -> Result<Vec<CapturedMessage>> {
+        let peer = self.peer.as_deref().map(parse_peer_id).transpose()?;
+        let mut messages = read_capture(&self.path)?;
+        messages.retain(|message| {
+            self.route
+                .as_ref()
+                .map_or(true, |route| &message.route == route)
+                && peer.map_or(true, |peer| message.peer_id == Some(peer))
+        });
+        Ok(messages)
+    }
+}
+
+fn print_message(message: &CapturedMessage, bodies: bool) {
+    let event = match &message.event {
+        CapturedEvent::Request => "request".to_string(),
+        CapturedEvent::Response { status } => format!("response {status}"),
+        CapturedEvent::Error => "error".to_string(),
+    };
+    let peer = message
+        .peer_id
+        .map_or("unknown peer".to_string(), |peer_id| {
+            peer_id.short_display(4).to_string()
+        });
+    println!(
+        "{} #{} {:?} {peer} {} {event} ({} bytes)",
+        message.timestamp_ms, message.rpc_id, message.direction, message.route, message.body_len
+    );
+
+    if !bodies {
+        return;
+    }
+    match decode_body(message) {
+        Some(Ok(body)) => println!("{body}"),
+        Some(Err(e)) => println!("<failed to decode body: {e}>"),
+        None if message.is_truncated() => println!("<body truncated>"),
+        None => {}
+    }
+}
+
+fn random_key() -> [u8; 32] {
+    let mut rng = rand::thread_rng();
+    let mut bytes = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut rng, &mut bytes[..]);
+    bytes
+}
+
+pub async fn execute_anemo_capture_command(cmd: AnemoCaptureCommand) -> Result<()> {
+    match cmd {
+        AnemoCaptureCommand::Decode(DecodeOptions { filter }) => {
+            for message in filter.read()? {
+                print_message(&message, filter.bodies);
+            }
+        }
+        AnemoCaptureCommand::Replay(ReplayOptions {
+            filter,
+            address,
+            server_name,
+        }) => {
+            let network = anemo::Network::bind("0.0.0.0:0")
+                .server_name(&server_name)
+                .private_key(random_key())
+                .start(anemo::Router::new())?;
+            let peer_id = network.connect(address.clone()).await?;
+            println!("connected to {peer_id} at {address}");
+
+            let requests = filter
+                .read()?
+                .into_iter()
+                .filter(|message| message.event == CapturedEvent::Request);
+            for request in requests {
+                print_message(&request, filter.bodies);
+                match replay_request(&network, peer_id, &request).await {
+                    Ok(response) => {
+                        let response = CapturedMessage {
+                            event: CapturedEvent::Response {
+                                status: response.status().to_u16(),
+                            },
+                            peer_id: Some(peer_id),
+                            body_len: response.body().len() as u64,
+                            body: response.body().to_vec(),
+                            ..request
+                        };
+                        print_message(&response, filter.bodies);
+                    }
+                    Err(e) => println!("{e}"),
+                }
+            }
+        }
+    }
+    Ok(())
+}
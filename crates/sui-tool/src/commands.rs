@@ -2,12 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    anemo_capture::{execute_anemo_capture_command, AnemoCaptureCommand},
     check_completed_snapshot,
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
     download_db_snapshot, download_formal_snapshot, dump_checkpoints_from_archive,
     get_latest_available_epoch, get_object, get_transaction_block, make_clients, pkg_dump,
-    restore_from_db_checkpoint, verify_archive, verify_archive_by_checksum, ConciseObjectOutput,
-    GroupedObjectOutput, VerboseObjectOutput,
+    restore_from_db_checkpoint, verify_archive, verify_archive_by_checksum,
+    verify_randomness::verify_randomness,
+    ConciseObjectOutput, GroupedObjectOutput, VerboseObjectOutput,
 };
 use anyhow::Result;
 use futures::{future::join_all, StreamExt};
@@ -26,6 +28,7 @@ use sui_types::{
 };
 
 use clap::*;
+use consensus_core::{DagExportFormat, DagInspector};
 use fastcrypto::encoding::Encoding;
 use sui_archival::{read_manifest_as_json, write_manifest_from_json};
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
@@ -234,6 +237,50 @@ pub enum ToolCommand {
         args: anemo_cli::Args,
     },
 
+    /// Decode and replay anemo RPCs captured through the admin API of a node
+    #[command(name = "anemo-capture")]
+    AnemoCapture {
+        #[command(subcommand)]
+        cmd: AnemoCaptureCommand,
+    },
+
+    /// Export a round range of the DAG in a consensus db, with its commits, as Graphviz DOT or
+    /// JSON. The db must not be in use by a running node.
+    #[command(name = "inspect-consensus-dag")]
+    InspectConsensusDag {
+        /// Path of the consensus db of an epoch
+        #[arg(long = "db-path")]
+        db_path: PathBuf,
+        #[arg(long = "start-round")]
+        start_round: u32,
+        #[arg(long = "end-round")]
+        end_round: u32,
+        /// Either json or dot
+        #[arg(long = "format", default_value = "json")]
+        format: DagExportFormat,
+        /// File to write the DAG to, instead of stdout
+        #[arg(long = "output")]
+        output: Option<PathBuf>,
+    },
+
+    /// Verify the randomness DKG transcript of an epoch, and that the randomness of every round
+    /// in a range of its checkpoints is valid. The db must be that of a validator of the epoch,
+    /// and must not be in use by a running node.
+    #[command(name = "verify-randomness")]
+    VerifyRandomness {
+        /// Path of the node db, the `db-path` of its config
+        #[arg(long = "db-path")]
+        db_path: PathBuf,
+        #[arg(long = "epoch")]
+        epoch: u64,
+        /// First checkpoint to verify, defaults to the first checkpoint of the epoch
+        #[arg(long = "start-checkpoint")]
+        start_checkpoint: Option<CheckpointSequenceNumber>,
+        /// Last checkpoint to verify, defaults to the last checkpoint of the epoch
+        #[arg(long = "end-checkpoint")]
+        end_checkpoint: Option<CheckpointSequenceNumber>,
+    },
+
     #[command(name = "restore-db")]
     RestoreFromDBCheckpoint {
         #[arg(long = "config-path")]
@@ -696,6 +743,31 @@ impl ToolCommand {
                 let config = crate::make_anemo_config();
                 anemo_cli::run(config, args).await
             }
+            ToolCommand::AnemoCapture { cmd } => {
+                execute_anemo_capture_command(cmd).await?;
+            }
+            ToolCommand::InspectConsensusDag {
+                db_path,
+                start_round,
+                end_round,
+                format,
+                output,
+            } => {
+                let snapshot = DagInspector::open(&db_path).snapshot(start_round, end_round)?;
+                let dag = snapshot.export(format);
+                match output {
+                    Some(path) => std::fs::write(path, dag)?,
+                    None => println!("{dag}"),
+                }
+            }
+            ToolCommand::VerifyRandomness {
+                db_path,
+                epoch,
+                start_checkpoint,
+                end_checkpoint,
+            } => {
+                verify_randomness(&db_path, epoch, start_checkpoint, end_checkpoint)?;
+            }
             ToolCommand::RestoreFromDBCheckpoint {
                 config_path,
                 db_checkpoint_path,
@@ -0,0 +1,450 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail};
+use prometheus::Registry;
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use sui_archival::reader::{ArchiveReader, ArchiveReaderMetrics};
+use sui_config::node::ArchiveReaderConfig;
+use sui_config::object_storage_config::ObjectStoreConfig;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
+use sui_types::base_types::{ObjectDigest, ObjectRef, VerifiedExecutionData};
+use sui_types::digests::{CheckpointDigest, TransactionDigest, TransactionEffectsDigest};
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::{
+    CheckpointSequenceNumber, FullCheckpointContents, VerifiedCheckpoint,
+};
+use sui_types::storage::{ObjectStore, ReadStore, SharedInMemoryStore};
+use sui_types::transaction::VerifiedTransaction;
+use tracing::{info, warn};
+
+/// Number of checkpoints downloaded from the archive and held in memory at a time
+const CHECKPOINT_BATCH_SIZE: u64 = 1000;
+
+/// A difference between the local node database and the archive
+#[derive(Debug)]
+pub enum Inconsistency {
+    CheckpointMissing(CheckpointSequenceNumber),
+    CheckpointMismatch {
+        sequence_number: CheckpointSequenceNumber,
+        local: CheckpointDigest,
+        archive: CheckpointDigest,
+    },
+    TransactionMissing(TransactionDigest),
+    TransactionMismatch(TransactionDigest),
+    EffectsMissing(TransactionDigest, TransactionEffectsDigest),
+    EffectsMismatch(TransactionDigest, TransactionEffectsDigest),
+    ExecutedEffectsMissing(TransactionDigest),
+    ExecutedEffectsMismatch {
+        transaction: TransactionDigest,
+        local: TransactionEffectsDigest,
+        archive: TransactionEffectsDigest,
+    },
+    ObjectMissing(TransactionDigest, ObjectRef),
+    ObjectMismatch {
+        transaction: TransactionDigest,
+        expected: ObjectRef,
+        local: ObjectDigest,
+    },
+    Unreadable {
+        transaction: TransactionDigest,
+        table: &'static str,
+        error: String,
+    },
+}
+
+impl Inconsistency {
+    /// Whether the archive holds enough data to rewrite the bad entry. The archive does not
+    /// contain object contents, and checkpoints are never rewritten since they are the root of
+    /// trust for the comparison.
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            Self::TransactionMissing(_)
+                | Self::TransactionMismatch(_)
+                | Self::EffectsMissing(..)
+                | Self::EffectsMismatch(..)
+                | Self::ExecutedEffectsMissing(_)
+                | Self::ExecutedEffectsMismatch { .. }
+        ) || matches!(self, Self::Unreadable { table, .. } if *table != "objects")
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CheckpointMissing(seq) => write!(f, "checkpoint {seq} is missing"),
+            Self::CheckpointMismatch {
+                sequence_number,
+                local,
+                archive,
+            } => write!(
+                f,
+                "checkpoint {sequence_number} has digest {local}, archive has {archive}"
+            ),
+            Self::TransactionMissing(tx) => write!(f, "transaction {tx} is missing"),
+            Self::TransactionMismatch(tx) => {
+                write!(f, "transaction {tx} does not match the archive")
+            }
+            Self::EffectsMissing(tx, effects) => {
+                write!(f, "effects {effects} of transaction {tx} are missing")
+            }
+            Self::EffectsMismatch(tx, effects) => write!(
+                f,
+                "effects {effects} of transaction {tx} do not match the archive"
+            ),
+            Self::ExecutedEffectsMissing(tx) => {
+                write!(f, "executed transaction {tx} has no executed effects")
+            }
+            Self::ExecutedEffectsMismatch {
+                transaction,
+                local,
+                archive,
+            } => write!(
+                f,
+                "transaction {transaction} was executed with effects {local}, archive has {archive}"
+            ),
+            Self::ObjectMissing(tx, obj_ref) => write!(
+                f,
+                "object {:?} written by transaction {tx} is missing",
+                obj_ref
+            ),
+            Self::ObjectMismatch {
+                transaction,
+                expected,
+                local,
+            } => write!(
+                f,
+                "object {:?} written by transaction {transaction} has digest {local}",
+                expected
+            ),
+            Self::Unreadable {
+                transaction,
+                table,
+                error,
+            } => write!(
+                f,
+                "{table} entry of transaction {transaction} cannot be read: {error}"
+            ),
+        }
+    }
+}
+
+/// Compares the perpetual store of the node database at `path` against the archive for every
+/// checkpoint in `[start, end)`, and rewrites the repairable entries if `repair` is set.
+/// The database is only opened for writing when repairing, in which case the node must not be
+/// running.
+pub async fn verify_db_with_archive(
+    path: &Path,
+    remote_store_config: ObjectStoreConfig,
+    download_concurrency: usize,
+    start: Option<CheckpointSequenceNumber>,
+    end: Option<CheckpointSequenceNumber>,
+    repair: bool,
+) -> anyhow::Result<()> {
+    let perpetual_db = if repair {
+        AuthorityPerpetualTables::open(&path.join("store"), None)
+    } else {
+        AuthorityPerpetualTables::open_secondary(&path.join("store"))
+    };
+    let checkpoint_store = CheckpointStore::open_secondary(&path.join("checkpoints"));
+    let Some(highest_executed) = checkpoint_store.get_highest_executed_checkpoint_seq_number()?
+    else {
+        bail!("No checkpoint has been executed by this node");
+    };
+
+    // Transactions and effects below the pruning watermark are expected to be missing.
+    let highest_pruned = perpetual_db.get_highest_pruned_checkpoint()?;
+    let mut start = start.unwrap_or_default();
+    if highest_pruned > 0 && start <= highest_pruned {
+        warn!("Checkpoints up to {highest_pruned} have been pruned, starting the check after them");
+        start = highest_pruned + 1;
+    }
+    let end = end.unwrap_or(highest_executed + 1);
+    if start >= end {
+        bail!("Empty checkpoint range {start}..{end}");
+    }
+
+    let metrics = ArchiveReaderMetrics::new(&Registry::default());
+    let config = ArchiveReaderConfig {
+        remote_store_config,
+        download_concurrency: NonZeroUsize::new(download_concurrency)
+            .ok_or_else(|| anyhow!("Download concurrency must be positive"))?,
+        use_for_pruning_watermark: false,
+    };
+    let archive_reader = ArchiveReader::new(config, &metrics)?;
+    archive_reader.sync_manifest_once().await?;
+
+    let mut num_inconsistencies = 0;
+    let mut num_repaired = 0;
+    let mut batch_start = start;
+    while batch_start < end {
+        let batch_end = std::cmp::min(batch_start + CHECKPOINT_BATCH_SIZE, end);
+        info!("Verifying checkpoints {batch_start}..{batch_end}");
+        let archive_store = SharedInMemoryStore::default();
+        archive_reader
+            .read(
+                archive_store.clone(),
+                batch_start..batch_end,
+                Arc::new(AtomicU64::new(0)),
+                Arc::new(AtomicU64::new(0)),
+                false,
+            )
+            .await?;
+
+        for sequence_number in batch_start..batch_end {
+            let archived = archive_store
+                .get_checkpoint_by_sequence_number(sequence_number)?
+                .ok_or_else(|| anyhow!("Checkpoint {sequence_number} is not in the archive"))?;
+            let contents = archive_store
+                .get_full_checkpoint_contents_by_sequence_number(sequence_number)?
+                .ok_or_else(|| {
+                    anyhow!("Contents of checkpoint {sequence_number} are not in the archive")
+                })?;
+
+            let executed = sequence_number <= highest_executed;
+            let check = check_checkpoint(
+                &checkpoint_store,
+                &perpetual_db,
+                &archived,
+                &contents,
+                executed,
+            )?;
+            for inconsistency in &check.inconsistencies {
+                println!("{}", inconsistency);
+            }
+            num_inconsistencies += check.inconsistencies.len();
+            if repair {
+                for data in &check.needs_repair {
+                    perpetual_db.repair_transaction_and_effects(
+                        &data.transaction,
+                        &data.effects,
+                        executed,
+                    )?;
+                    num_repaired += 1;
+                }
+            }
+        }
+        batch_start = batch_end;
+    }
+
+    println!(
+        "Verified checkpoints {start}..{end}: found {num_inconsistencies} inconsistencies, repaired {num_repaired} transactions"
+    );
+    Ok(())
+}
+
+/// The result of comparing one archived checkpoint against the local database
+#[derive(Default)]
+struct CheckpointCheck {
+    inconsistencies: Vec<Inconsistency>,
+    /// Archived transactions with at least one repairable inconsistency
+    needs_repair: Vec<VerifiedExecutionData>,
+}
+
+fn check_checkpoint(
+    checkpoint_store: &CheckpointStore,
+    perpetual_db: &AuthorityPerpetualTables,
+    archived: &VerifiedCheckpoint,
+    contents: &FullCheckpointContents,
+    executed: bool,
+) -> anyhow::Result<CheckpointCheck> {
+    let sequence_number = archived.sequence_number;
+    let mut check = CheckpointCheck::default();
+
+    // The local certified checkpoint is what makes the archive data trustworthy, so a checkpoint
+    // that cannot be matched is reported but none of its entries are touched.
+    let checkpoint_inconsistency =
+        match checkpoint_store.get_checkpoint_by_sequence_number(sequence_number)? {
+            None => Some(Inconsistency::CheckpointMissing(sequence_number)),
+            Some(local) if local.digest() != archived.digest() => {
+                Some(Inconsistency::CheckpointMismatch {
+                    sequence_number,
+                    local: *local.digest(),
+                    archive: *archived.digest(),
+                })
+            }
+            Some(_) => None,
+        };
+    if let Some(inconsistency) = checkpoint_inconsistency {
+        check.inconsistencies.push(inconsistency);
+        return Ok(check);
+    }
+
+    for execution_data in contents.iter() {
+        let data = VerifiedExecutionData::new(
+            VerifiedTransaction::new_unchecked(execution_data.transaction.clone()),
+            execution_data.effects.clone(),
+        );
+        let inconsistencies = check_transaction(perpetual_db, &data, executed);
+        if inconsistencies.iter().any(Inconsistency::is_repairable) {
+            check.needs_repair.push(data);
+        }
+        check.inconsistencies.extend(inconsistencies);
+    }
+    Ok(check)
+}
+
+fn check_transaction(
+    perpetual_db: &AuthorityPerpetualTables,
+    data: &VerifiedExecutionData,
+    executed: bool,
+) -> Vec<Inconsistency> {
+    let tx_digest = *data.transaction.digest();
+    let effects_digest = data.effects.digest();
+    let mut inconsistencies = vec![];
+
+    match perpetual_db.get_transaction(&tx_digest) {
+        Ok(None) => inconsistencies.push(Inconsistency::TransactionMissing(tx_digest)),
+        Ok(Some(local)) if local.inner().data() != data.transaction.data() => {
+            inconsistencies.push(Inconsistency::TransactionMismatch(tx_digest))
+        }
+        Ok(Some(_)) => {}
+        Err(e) => inconsistencies.push(unreadable(tx_digest, "transactions", e)),
+    }
+
+    match perpetual_db.get_effects_by_digest(&effects_digest) {
+        Ok(None) => inconsistencies.push(Inconsistency::EffectsMissing(tx_digest, effects_digest)),
+        Ok(Some(local)) if local.digest() != effects_digest => {
+            inconsistencies.push(Inconsistency::EffectsMismatch(tx_digest, effects_digest))
+        }
+        Ok(Some(_)) => {}
+        Err(e) => inconsistencies.push(unreadable(tx_digest, "effects", e)),
+    }
+
+    // Only transactions this node has executed are expected to have executed effects and
+    // objects.
+    if !executed {
+        return inconsistencies;
+    }
+    match perpetual_db.get_executed_effects_digest(&tx_digest) {
+        Ok(None) => inconsistencies.push(Inconsistency::ExecutedEffectsMissing(tx_digest)),
+        Ok(Some(local)) if local != effects_digest => {
+            inconsistencies.push(Inconsistency::ExecutedEffectsMismatch {
+                transaction: tx_digest,
+                local,
+                archive: effects_digest,
+            })
+        }
+        Ok(Some(_)) => {}
+        Err(e) => inconsistencies.push(unreadable(tx_digest, "executed_effects", e)),
+    }
+
+    for (obj_ref, _, _) in data.effects.all_changed_objects() {
+        match perpetual_db.get_object_by_key(&obj_ref.0, obj_ref.1) {
+            Ok(Some(object)) => {
+                let local = object.compute_object_reference().2;
+                if local != obj_ref.2 {
+                    inconsistencies.push(Inconsistency::ObjectMismatch {
+                        transaction: tx_digest,
+                        expected: obj_ref,
+                        local,
+                    });
+                }
+            }
+            // Versions that have been superseded may have been pruned.
+            Ok(None) => match perpetual_db.get_newer_object_keys(&(obj_ref.0, obj_ref.1)) {
+                Ok(newer) if newer.is_empty() => {
+                    inconsistencies.push(Inconsistency::ObjectMissing(tx_digest, obj_ref))
+                }
+                Ok(_) => {}
+                Err(e) => inconsistencies.push(unreadable(tx_digest, "objects", e)),
+            },
+            Err(e) => inconsistencies.push(unreadable(tx_digest, "objects", e)),
+        }
+    }
+    inconsistencies
+}
+
+fn unreadable(
+    transaction: TransactionDigest,
+    table: &'static str,
+    error: impl fmt::Display,
+) -> Inconsistency {
+    Inconsistency::Unreadable {
+        transaction,
+        table,
+        error: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_swarm_config::test_utils::CommitteeFixture;
+
+    #[test]
+    fn check_checkpoint_against_archive() {
+        let fixture = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+        let (archived, archived_contents, _, _) = fixture.make_random_checkpoints(4, None);
+        // Same genesis checkpoint, but different contents after it
+        let (forked, _, _, _) = fixture.make_random_checkpoints(4, None);
+        let contents: Vec<_> = archived_contents
+            .into_iter()
+            .map(|contents| contents.into_inner())
+            .collect();
+
+        // The local database is missing checkpoint 1, has a different checkpoint 3, and none of
+        // the transactions of checkpoint 2
+        let path = tempfile::tempdir().unwrap();
+        let checkpoint_store = CheckpointStore::new(&path.path().join("checkpoints"));
+        for checkpoint in [&archived[0], &archived[2], &forked[3]] {
+            checkpoint_store
+                .insert_verified_checkpoint(checkpoint)
+                .unwrap();
+        }
+        let perpetual_db = AuthorityPerpetualTables::open(&path.path().join("store"), None);
+
+        let checkpoint_reader = CheckpointStore::open_secondary(&path.path().join("checkpoints"));
+        let perpetual_reader = AuthorityPerpetualTables::open_secondary(&path.path().join("store"));
+        let check = |perpetual_db: &AuthorityPerpetualTables, seq: usize| {
+            check_checkpoint(
+                &checkpoint_reader,
+                perpetual_db,
+                &archived[seq],
+                &contents[seq],
+                false,
+            )
+            .unwrap()
+        };
+
+        assert!(check(&perpetual_reader, 0).inconsistencies.is_empty());
+        assert!(matches!(
+            check(&perpetual_reader, 1).inconsistencies[..],
+            [Inconsistency::CheckpointMissing(1)]
+        ));
+        let forked_check = check(&perpetual_reader, 3);
+        assert!(matches!(
+            forked_check.inconsistencies[..],
+            [Inconsistency::CheckpointMismatch {
+                sequence_number: 3,
+                ..
+            }]
+        ));
+        assert!(forked_check.needs_repair.is_empty());
+
+        let missing_check = check(&perpetual_reader, 2);
+        assert_eq!(missing_check.needs_repair.len(), contents[2].size());
+        assert_eq!(missing_check.inconsistencies.len(), 2 * contents[2].size());
+        assert!(missing_check
+            .inconsistencies
+            .iter()
+            .all(|inconsistency| matches!(
+                inconsistency,
+                Inconsistency::TransactionMissing(_) | Inconsistency::EffectsMissing(..)
+            )));
+
+        for data in &missing_check.needs_repair {
+            perpetual_db
+                .repair_transaction_and_effects(&data.transaction, &data.effects, false)
+                .unwrap();
+        }
+        assert!(check(&perpetual_db, 2).inconsistencies.is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use typed_store::traits::DebugTableEntry;
+use typed_store::TypedStoreError;
+
+/// A key at which two copies of a table disagree
+#[derive(Debug, PartialEq, Eq)]
+pub enum EntryDiff {
+    /// The key is only present in the first table
+    OnlyInFirst(DebugTableEntry),
+    /// The key is only present in the second table
+    OnlyInSecond(DebugTableEntry),
+    /// The key is present in both tables, with different values
+    Different {
+        first: DebugTableEntry,
+        second: DebugTableEntry,
+    },
+}
+
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct DiffSummary {
+    pub only_in_first: usize,
+    pub only_in_second: usize,
+    pub different: usize,
+}
+
+impl DiffSummary {
+    pub fn record(&mut self, diff: &EntryDiff) {
+        match diff {
+            EntryDiff::OnlyInFirst(_) => self.only_in_first += 1,
+            EntryDiff::OnlyInSecond(_) => self.only_in_second += 1,
+            EntryDiff::Different { .. } => self.different += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.only_in_first + self.only_in_second + self.different
+    }
+}
+
+type EntryResult = Result<DebugTableEntry, TypedStoreError>;
+
+/// Walks two key-ordered iterators over the same table in lockstep, yielding every key that is
+/// missing from one side or whose raw value differs between the two. Errors reading either table
+/// are yielded as soon as they are reached.
+pub struct TableDiff<I: Iterator<Item = EntryResult>, J: Iterator<Item = EntryResult>> {
+    first: Peekable<I>,
+    second: Peekable<J>,
+}
+
+impl<I, J> TableDiff<I, J>
+where
+    I: Iterator<Item = EntryResult>,
+    J: Iterator<Item = EntryResult>,
+{
+    pub fn new(first: I, second: J) -> Self {
+        Self {
+            first: first.peekable(),
+            second: second.peekable(),
+        }
+    }
+}
+
+impl<I, J> Iterator for TableDiff<I, J>
+where
+    I: Iterator<Item = EntryResult>,
+    J: Iterator<Item = EntryResult>,
+{
+    type Item = Result<EntryDiff, TypedStoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let ordering = match (self.first.peek(), self.second.peek()) {
+                (None, None) => return None,
+                // Errors are taken from the side they occurred on, like missing entries.
+                (Some(Err(_)), _) | (Some(_), None) => Ordering::Less,
+                (_, Some(Err(_))) | (None, Some(_)) => Ordering::Greater,
+                (Some(Ok(first)), Some(Ok(second))) => first.raw_key.cmp(&second.raw_key),
+            };
+            match ordering {
+                Ordering::Less => {
+                    return self
+                        .first
+                        .next()
+                        .map(|entry| entry.map(EntryDiff::OnlyInFirst))
+                }
+                Ordering::Greater => {
+                    return self
+                        .second
+                        .next()
+                        .map(|entry| entry.map(EntryDiff::OnlyInSecond))
+                }
+                Ordering::Equal => {
+                    let (Some(Ok(first)), Some(Ok(second))) =
+                        (self.first.next(), self.second.next())
+                    else {
+                        unreachable!("both entries were peeked");
+                    };
+                    if first.raw_value != second.raw_value {
+                        return Some(Ok(EntryDiff::Different { first, second }));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DiffSummary, EntryDiff, TableDiff};
+    use typed_store::traits::DebugTableEntry;
+    use typed_store::TypedStoreError;
+
+    fn entry(key: u8, value: u8) -> DebugTableEntry {
+        DebugTableEntry {
+            raw_key: vec![key],
+            raw_value: vec![value],
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn entries(
+        entries: Vec<DebugTableEntry>,
+    ) -> impl Iterator<Item = Result<DebugTableEntry, TypedStoreError>> {
+        entries.into_iter().map(Ok)
+    }
+
+    #[test]
+    fn table_diff_reports_missing_and_different_keys() {
+        let first = vec![entry(1, 1), entry(2, 2), entry(4, 4), entry(6, 6)];
+        let second = vec![entry(0, 0), entry(2, 2), entry(4, 5), entry(5, 5)];
+
+        let diffs: Vec<_> = TableDiff::new(entries(first), entries(second))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            diffs,
+            vec![
+                EntryDiff::OnlyInSecond(entry(0, 0)),
+                EntryDiff::OnlyInFirst(entry(1, 1)),
+                EntryDiff::Different {
+                    first: entry(4, 4),
+                    second: entry(4, 5),
+                },
+                EntryDiff::OnlyInSecond(entry(5, 5)),
+                EntryDiff::OnlyInFirst(entry(6, 6)),
+            ]
+        );
+
+        let mut summary = DiffSummary::default();
+        diffs.iter().for_each(|diff| summary.record(diff));
+        assert_eq!(
+            summary,
+            DiffSummary {
+                only_in_first: 2,
+                only_in_second: 2,
+                different: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn table_diff_identical_tables() {
+        let table = vec![entry(1, 1), entry(2, 2)];
+        assert_eq!(
+            TableDiff::new(entries(table.clone()), entries(table)).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn table_diff_reports_read_errors() {
+        let first = vec![
+            Ok(entry(1, 1)),
+            Err(TypedStoreError::RocksDBError("io".into())),
+        ];
+        let second = vec![entry(1, 1), entry(2, 2)];
+        let mut diffs = TableDiff::new(first.into_iter(), entries(second));
+        assert!(matches!(
+            diffs.next(),
+            Some(Err(TypedStoreError::RocksDBError(_)))
+        ));
+    }
+}
@@ -26,7 +26,7 @@ use sui_storage::IndexStoreTables;
 use sui_types::base_types::{EpochId, ObjectID};
 use tracing::info;
 use typed_store::rocks::{default_db_options, MetricConf};
-use typed_store::traits::{Map, TableSummary};
+use typed_store::traits::{Map, TableSummary, TypedStoreDebug};
 
 #[derive(EnumString, Clone, Parser, Debug, ValueEnum)]
 pub enum StoreName {
@@ -259,6 +259,40 @@ pub async fn prune_checkpoints(db_path: PathBuf) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Opens the store holding `table_name` in read only mode, for the commands that only need the
+/// `TypedStoreDebug` view of a table.
+pub fn open_debug_store(
+    store_name: StoreName,
+    epoch: Option<EpochId>,
+    db_path: PathBuf,
+    table_name: &str,
+) -> anyhow::Result<Box<dyn TypedStoreDebug>> {
+    let store: Box<dyn TypedStoreDebug> = match store_name {
+        StoreName::Validator => {
+            let epoch_tables = AuthorityEpochTables::describe_tables();
+            if epoch_tables.contains_key(table_name) {
+                let epoch = epoch.ok_or_else(|| anyhow!("--epoch is required"))?;
+                Box::new(AuthorityEpochTables::open_readonly(epoch, &db_path))
+            } else {
+                Box::new(AuthorityPerpetualTables::open_readonly(&db_path))
+            }
+        }
+        StoreName::Index => Box::new(IndexStoreTables::get_read_only_handle(
+            db_path,
+            None,
+            None,
+            MetricConf::default(),
+        )),
+        StoreName::Epoch => Box::new(CommitteeStoreTables::get_read_only_handle(
+            db_path,
+            None,
+            None,
+            MetricConf::default(),
+        )),
+    };
+    Ok(store)
+}
+
 // TODO: condense this using macro or trait dyn skills
 pub fn dump_table(
     store_name: StoreName,
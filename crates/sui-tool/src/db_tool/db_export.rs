@@ -0,0 +1,142 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::anyhow;
+use arrow_array::{ArrayRef, RecordBatch, StringArray};
+use clap::ValueEnum;
+use itertools::Itertools;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use typed_store::traits::{DebugTableEntry, TypedStoreDebug};
+use typed_store::TypedStoreError;
+
+/// Number of entries written to each row group of a parquet export
+const PARQUET_ROW_GROUP_SIZE: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExportFormat {
+    /// One JSON object per line
+    Jsonl,
+    /// A parquet file with one string column per field
+    Parquet,
+}
+
+/// A single exported table entry. Raw keys and values are hex encoded, so that an exported key can
+/// be passed back as a `--start-key` or `--end-key` bound.
+#[derive(Serialize)]
+struct ExportRow<'a> {
+    raw_key: String,
+    key: &'a str,
+    raw_value: String,
+    value: &'a str,
+}
+
+impl<'a> From<&'a DebugTableEntry> for ExportRow<'a> {
+    fn from(entry: &'a DebugTableEntry) -> Self {
+        Self {
+            raw_key: hex::encode(&entry.raw_key),
+            key: &entry.key,
+            raw_value: hex::encode(&entry.raw_value),
+            value: &entry.value,
+        }
+    }
+}
+
+/// Parses a hex encoded serialized key, as printed by `list-db-metadata` or found in the
+/// `raw_key` column of an export.
+pub fn parse_key_bound(key: Option<&str>) -> anyhow::Result<Option<Vec<u8>>> {
+    key.map(|key| {
+        hex::decode(key.trim_start_matches("0x"))
+            .map_err(|e| anyhow!("Invalid hex encoded key {key}: {e}"))
+    })
+    .transpose()
+}
+
+/// Iterates over the entries of `table_name` with serialized keys in `[lower_bound, upper_bound)`.
+pub fn table_entries<'a>(
+    store: &'a dyn TypedStoreDebug,
+    table_name: &str,
+    lower_bound: Option<Vec<u8>>,
+    upper_bound: Option<Vec<u8>>,
+) -> anyhow::Result<Box<dyn Iterator<Item = Result<DebugTableEntry, TypedStoreError>> + 'a>> {
+    store
+        .iter_table_raw_range(table_name.to_owned(), lower_bound, upper_bound)
+        .map_err(|err| anyhow!(err.to_string()))
+}
+
+/// Streams the entries of `table_name` with serialized keys in `[lower_bound, upper_bound)` to
+/// `output`, stopping after `limit` entries if set. Returns the number of entries written.
+pub fn export_table(
+    store: &dyn TypedStoreDebug,
+    table_name: &str,
+    lower_bound: Option<Vec<u8>>,
+    upper_bound: Option<Vec<u8>>,
+    limit: Option<usize>,
+    format: ExportFormat,
+    output: &Path,
+) -> anyhow::Result<usize> {
+    let entries = table_entries(store, table_name, lower_bound, upper_bound)?
+        .take(limit.unwrap_or(usize::MAX));
+    let file = File::create(output)?;
+    match format {
+        ExportFormat::Jsonl => write_jsonl(entries, file),
+        ExportFormat::Parquet => write_parquet(entries, file),
+    }
+}
+
+fn write_jsonl(
+    entries: impl Iterator<Item = Result<DebugTableEntry, TypedStoreError>>,
+    file: File,
+) -> anyhow::Result<usize> {
+    let mut writer = BufWriter::new(file);
+    let mut count = 0;
+    for entry in entries {
+        let entry = entry?;
+        serde_json::to_writer(&mut writer, &ExportRow::from(&entry))?;
+        writer.write_all(b"\n")?;
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+fn write_parquet(
+    entries: impl Iterator<Item = Result<DebugTableEntry, TypedStoreError>>,
+    file: File,
+) -> anyhow::Result<usize> {
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, to_record_batch(&[])?.schema(), Some(properties))?;
+    let mut count = 0;
+    for chunk in &entries.chunks(PARQUET_ROW_GROUP_SIZE) {
+        let chunk = chunk.collect::<Result<Vec<_>, _>>()?;
+        writer.write(&to_record_batch(&chunk)?)?;
+        writer.flush()?;
+        count += chunk.len();
+    }
+    writer.close()?;
+    Ok(count)
+}
+
+fn to_record_batch(entries: &[DebugTableEntry]) -> anyhow::Result<RecordBatch> {
+    let raw_keys: Vec<_> = entries.iter().map(|e| hex::encode(&e.raw_key)).collect();
+    let keys: Vec<_> = entries.iter().map(|e| e.key.as_str()).collect();
+    let raw_values: Vec<_> = entries.iter().map(|e| hex::encode(&e.raw_value)).collect();
+    let values: Vec<_> = entries.iter().map(|e| e.value.as_str()).collect();
+    Ok(RecordBatch::try_from_iter([
+        ("raw_key", Arc::new(StringArray::from(raw_keys)) as ArrayRef),
+        ("key", Arc::new(StringArray::from(keys)) as ArrayRef),
+        (
+            "raw_value",
+            Arc::new(StringArray::from(raw_values)) as ArrayRef,
+        ),
+        ("value", Arc::new(StringArray::from(values)) as ArrayRef),
+    ])?)
+}
@@ -1,13 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use self::db_dump::{dump_table, duplicate_objects_summary, list_tables, table_summary, StoreName};
+use self::archive_check::verify_db_with_archive;
+use self::db_diff::{DiffSummary, EntryDiff, TableDiff};
+use self::db_dump::{
+    dump_table, duplicate_objects_summary, list_tables, open_debug_store, table_summary, StoreName,
+};
+use self::db_export::{export_table, parse_key_bound, table_entries, ExportFormat};
 use self::index_search::{search_index, SearchRange};
 use crate::db_tool::db_dump::{compact, print_table_metadata, prune_checkpoints, prune_objects};
 use anyhow::{anyhow, bail};
 use clap::Parser;
 use narwhal_storage::NodeStorage;
 use std::path::{Path, PathBuf};
+use sui_config::object_storage_config::ObjectStoreConfig;
 use sui_core::authority::authority_per_epoch_store::AuthorityEpochTables;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::checkpoints::CheckpointStore;
@@ -17,7 +23,10 @@ use sui_types::effects::TransactionEffectsAPI;
 use sui_types::messages_checkpoint::{CheckpointDigest, CheckpointSequenceNumber};
 use sui_types::storage::ObjectStore;
 use typed_store::rocks::MetricConf;
+mod archive_check;
+mod db_diff;
 pub mod db_dump;
+mod db_export;
 mod index_search;
 
 #[derive(Parser)]
@@ -42,6 +51,9 @@ pub enum DbToolCommand {
     PruneObjects,
     PruneCheckpoints,
     SetCheckpointWatermark(SetCheckpointWatermarkOptions),
+    Export(ExportOptions),
+    Diff(DiffOptions),
+    VerifyWithArchive(VerifyWithArchiveOptions),
 }
 
 #[derive(Parser)]
@@ -90,6 +102,83 @@ pub struct Options {
     epoch: Option<EpochId>,
 }
 
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct ExportOptions {
+    /// The type of store to export
+    #[arg(long = "store", short = 's', value_enum)]
+    store_name: StoreName,
+    /// The name of the table to export
+    #[arg(long = "table-name", short = 't')]
+    table_name: String,
+    /// The epoch to use when loading AuthorityEpochTables.
+    #[arg(long = "epoch", short = 'e')]
+    epoch: Option<EpochId>,
+    /// Hex encoded serialized key to start the export at (inclusive)
+    #[arg(long = "start-key")]
+    start_key: Option<String>,
+    /// Hex encoded serialized key to end the export at (exclusive)
+    #[arg(long = "end-key")]
+    end_key: Option<String>,
+    /// The maximum number of entries to export
+    #[arg(long = "limit")]
+    limit: Option<usize>,
+    /// The format of the exported file
+    #[arg(long = "format", short = 'f', value_enum, default_value = "jsonl")]
+    format: ExportFormat,
+    /// The file to export the table to
+    #[arg(long = "output", short = 'o')]
+    output: PathBuf,
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct DiffOptions {
+    /// The node database to compare against the one at `--db-path`
+    #[arg(long = "other-db-path")]
+    other_db_path: PathBuf,
+    /// The type of store to compare
+    #[arg(long = "store", short = 's', value_enum)]
+    store_name: StoreName,
+    /// The name of the table to compare
+    #[arg(long = "table-name", short = 't')]
+    table_name: String,
+    /// The epoch to use when loading AuthorityEpochTables.
+    #[arg(long = "epoch", short = 'e')]
+    epoch: Option<EpochId>,
+    /// Hex encoded serialized key to start the comparison at (inclusive)
+    #[arg(long = "start-key")]
+    start_key: Option<String>,
+    /// Hex encoded serialized key to end the comparison at (exclusive)
+    #[arg(long = "end-key")]
+    end_key: Option<String>,
+    /// Stop after reporting this many differences
+    #[arg(long = "max-differences")]
+    max_differences: Option<usize>,
+    /// Only print the number of differences, not the differing entries
+    #[arg(long = "summary-only")]
+    summary_only: bool,
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct VerifyWithArchiveOptions {
+    #[command(flatten)]
+    object_store_config: ObjectStoreConfig,
+    /// First checkpoint to verify. Defaults to the first checkpoint that has not been pruned.
+    #[arg(long = "start-checkpoint")]
+    start_checkpoint: Option<CheckpointSequenceNumber>,
+    /// Checkpoint to stop verifying at (exclusive). Defaults to the checkpoint after the highest
+    /// executed one.
+    #[arg(long = "end-checkpoint")]
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+    #[arg(long = "download-concurrency", default_value_t = 5)]
+    download_concurrency: usize,
+    /// Rewrite missing or corrupted transactions and effects with the archived copies
+    #[arg(long)]
+    repair: bool,
+}
+
 #[derive(Parser)]
 #[command(rename_all = "kebab-case")]
 pub struct PrintConsensusCommitOptions {
@@ -234,6 +323,19 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
             Ok(())
         }
         DbToolCommand::SetCheckpointWatermark(d) => set_checkpoint_watermark(&db_path, d),
+        DbToolCommand::Export(d) => export_db_table(db_path, d),
+        DbToolCommand::Diff(d) => print_db_table_diff(db_path, d),
+        DbToolCommand::VerifyWithArchive(d) => {
+            verify_db_with_archive(
+                &db_path,
+                d.object_store_config,
+                d.download_concurrency,
+                d.start_checkpoint,
+                d.end_checkpoint,
+                d.repair,
+            )
+            .await
+        }
     }
 }
 
@@ -518,3 +620,94 @@ pub fn set_checkpoint_watermark(
     }
     Ok(())
 }
+
+/// Streams a table, or a range of its keys, to a JSONL or parquet file.
+/// Run with (for example):
+/// cargo run --package sui-tool -- db-tool --db-path /opt/sui/db/authorities_db/live/store export --store validator --table-name objects --format parquet --output objects.parquet
+pub fn export_db_table(path: PathBuf, options: ExportOptions) -> anyhow::Result<()> {
+    let store = open_debug_store(options.store_name, options.epoch, path, &options.table_name)?;
+    let count = export_table(
+        store.as_ref(),
+        &options.table_name,
+        parse_key_bound(options.start_key.as_deref())?,
+        parse_key_bound(options.end_key.as_deref())?,
+        options.limit,
+        options.format,
+        &options.output,
+    )?;
+    println!(
+        "Exported {} entries of table {} to {}",
+        count,
+        options.table_name,
+        options.output.display()
+    );
+    Ok(())
+}
+
+/// Compares the same table across two node databases, reporting the keys that are missing from
+/// either side or that hold different values.
+pub fn print_db_table_diff(path: PathBuf, options: DiffOptions) -> anyhow::Result<()> {
+    let lower_bound = parse_key_bound(options.start_key.as_deref())?;
+    let upper_bound = parse_key_bound(options.end_key.as_deref())?;
+    let first_path = path.display().to_string();
+    let second_path = options.other_db_path.display().to_string();
+    let first = open_debug_store(
+        options.store_name.clone(),
+        options.epoch,
+        path,
+        &options.table_name,
+    )?;
+    let second = open_debug_store(
+        options.store_name,
+        options.epoch,
+        options.other_db_path,
+        &options.table_name,
+    )?;
+    let diffs = TableDiff::new(
+        table_entries(
+            first.as_ref(),
+            &options.table_name,
+            lower_bound.clone(),
+            upper_bound.clone(),
+        )?,
+        table_entries(
+            second.as_ref(),
+            &options.table_name,
+            lower_bound,
+            upper_bound,
+        )?,
+    )
+    .take(options.max_differences.unwrap_or(usize::MAX));
+
+    let mut summary = DiffSummary::default();
+    for diff in diffs {
+        let diff = diff?;
+        summary.record(&diff);
+        if options.summary_only {
+            continue;
+        }
+        match diff {
+            EntryDiff::OnlyInFirst(entry) => {
+                println!("Only in {}: {}: {}", first_path, entry.key, entry.value)
+            }
+            EntryDiff::OnlyInSecond(entry) => {
+                println!("Only in {}: {}: {}", second_path, entry.key, entry.value)
+            }
+            EntryDiff::Different { first, second } => println!(
+                "Different value for {}:\n  {}: {}\n  {}: {}",
+                first.key, first_path, first.value, second_path, second.value
+            ),
+        }
+    }
+    println!(
+        "{} differences in table {}: {} only in {}, {} only in {}, {} with different values",
+        summary.total(),
+        options.table_name,
+        summary.only_in_first,
+        first_path,
+        summary.only_in_second,
+        second_path,
+        summary.different
+    );
+    Ok(())
+}
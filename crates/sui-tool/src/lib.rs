@@ -67,9 +67,11 @@ use sui_types::storage::{ReadStore, SharedInMemoryStore};
 use tracing::info;
 use typed_store::rocks::MetricConf;
 
+pub mod anemo_capture;
 pub mod commands;
 pub mod db_tool;
 pub mod pkg_dump;
+pub mod verify_randomness;
 
 // This functions requires at least one of genesis or fullnode_rpc to be `Some`.
 async fn make_clients(
@@ -0,0 +1,201 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use sui_core::authority::authority_per_epoch_store::AuthorityEpochTables;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::epoch::randomness_verifier::RandomnessVerifier;
+use sui_protocol_config::ProtocolConfig;
+use sui_types::committee::{Committee, EpochId};
+use sui_types::digests::ChainIdentifier;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::transaction::{RandomnessStateUpdate, TransactionDataAPI, TransactionKind};
+
+/// Verifies the DKG transcript of `epoch` and the randomness of its rounds in the given checkpoint
+/// range, which defaults to all the checkpoints of the epoch.
+///
+/// `db_path` is the db of a validator of the epoch, since only validators record the DKG
+/// transcript. Its epoch db must not have been pruned. The stores are opened as secondary
+/// instances, so they are never written to.
+pub fn verify_randomness(
+    db_path: &Path,
+    epoch: EpochId,
+    start_checkpoint: Option<CheckpointSequenceNumber>,
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+) -> Result<()> {
+    // The committee and protocol version of an epoch are taken from the last checkpoint of the
+    // previous one, the committee of the first epoch is only recorded in genesis.
+    anyhow::ensure!(epoch > 0, "epoch 0 can't be verified");
+    let checkpoint_store = CheckpointStore::open_secondary(&db_path.join("checkpoints"));
+    let previous_epoch_last_checkpoint = checkpoint_store
+        .get_epoch_last_checkpoint(epoch - 1)?
+        .ok_or_else(|| anyhow!("last checkpoint of epoch {} not found", epoch - 1))?;
+    let end_of_epoch_data = previous_epoch_last_checkpoint
+        .end_of_epoch_data
+        .as_ref()
+        .ok_or_else(|| {
+            anyhow!(
+                "last checkpoint of epoch {} has no end of epoch data",
+                epoch - 1
+            )
+        })?;
+    let committee = Committee::new(
+        epoch,
+        end_of_epoch_data
+            .next_epoch_committee
+            .iter()
+            .cloned()
+            .collect(),
+    );
+    let genesis_checkpoint = checkpoint_store
+        .get_checkpoint_by_sequence_number(0)?
+        .ok_or_else(|| anyhow!("genesis checkpoint not found"))?;
+    let protocol_config = ProtocolConfig::get_for_version(
+        end_of_epoch_data.next_epoch_protocol_version,
+        ChainIdentifier::from(*genesis_checkpoint.digest()).chain(),
+    );
+
+    let epochs_path = db_path.join("epochs");
+    anyhow::ensure!(
+        AuthorityEpochTables::path(epoch, &epochs_path).exists(),
+        "db of epoch {epoch} not found, it may have been pruned"
+    );
+    let transcript =
+        AuthorityEpochTables::open_secondary(epoch, &epochs_path).get_dkg_transcript()?;
+    let verifier = RandomnessVerifier::new(&committee, &protocol_config, transcript)?;
+
+    let mut failed = false;
+    match verifier.verify_dkg() {
+        Ok(summary) => println!(
+            "DKG of epoch {epoch}: {} messages of which {} used, from dealers with {} stake, {} confirmations with {} complaints, t={}, excluded dealers {:?}",
+            summary.num_messages,
+            summary.num_used_messages,
+            summary.used_stake,
+            summary.num_confirmations,
+            summary.num_complaints,
+            summary.t,
+            summary.excluded_dealers,
+        ),
+        Err(errors) => {
+            failed = true;
+            for e in errors {
+                println!("DKG of epoch {epoch}: {e}");
+            }
+        }
+    }
+
+    let first_checkpoint = previous_epoch_last_checkpoint.sequence_number + 1;
+    let last_checkpoint = match checkpoint_store.get_epoch_last_checkpoint(epoch)? {
+        Some(checkpoint) => checkpoint.sequence_number,
+        None => checkpoint_store
+            .get_highest_executed_checkpoint_seq_number()?
+            .ok_or_else(|| anyhow!("no checkpoint has been executed"))?,
+    };
+    let start_checkpoint = start_checkpoint.unwrap_or(first_checkpoint);
+    let end_checkpoint = end_checkpoint.unwrap_or(last_checkpoint);
+    anyhow::ensure!(
+        first_checkpoint <= start_checkpoint && end_checkpoint <= last_checkpoint,
+        "checkpoints {start_checkpoint} to {end_checkpoint} are not all in epoch {epoch}, which spans checkpoints {first_checkpoint} to {last_checkpoint}"
+    );
+
+    let perpetual_tables = AuthorityPerpetualTables::open_secondary(&db_path.join("store"));
+    let mut updates = Vec::new();
+    for seq in start_checkpoint..=end_checkpoint {
+        let checkpoint = checkpoint_store
+            .get_checkpoint_by_sequence_number(seq)?
+            .ok_or_else(|| anyhow!("checkpoint {seq} not found"))?;
+        let contents = checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .ok_or_else(|| anyhow!("contents of checkpoint {seq} not found"))?;
+        let digests: Vec<_> = contents.iter().map(|digests| digests.transaction).collect();
+        let transactions = perpetual_tables
+            .multi_get_transactions(&digests)?
+            .into_iter()
+            .zip(&digests)
+            .map(|(transaction, digest)| {
+                transaction.ok_or_else(|| {
+                    anyhow!(
+                        "transaction {digest} of checkpoint {seq} not found, it may have been pruned"
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        updates.extend(randomness_state_updates(
+            transactions
+                .iter()
+                .map(|transaction| transaction.data().transaction_data().kind()),
+        ));
+    }
+
+    match verifier.verify_rounds(&updates) {
+        Ok(num_rounds) => println!(
+            "randomness of {num_rounds} rounds in checkpoints {start_checkpoint} to {end_checkpoint} is valid"
+        ),
+        Err(errors) => {
+            failed = true;
+            for e in errors {
+                println!("checkpoints {start_checkpoint} to {end_checkpoint}: {e}");
+            }
+        }
+    }
+
+    anyhow::ensure!(!failed, "randomness of epoch {epoch} failed verification");
+    Ok(())
+}
+
+/// Returns the randomness state updates among the transactions of a checkpoint, in order. Updates
+/// can be anywhere in their checkpoint, since its transactions are causally sorted.
+fn randomness_state_updates<'a>(
+    kinds: impl IntoIterator<Item = &'a TransactionKind>,
+) -> Vec<RandomnessStateUpdate> {
+    kinds
+        .into_iter()
+        .filter_map(|kind| match kind {
+            TransactionKind::RandomnessStateUpdate(update) => Some(update.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::SequenceNumber;
+    use sui_types::crypto::RandomnessRound;
+    use sui_types::transaction::VerifiedTransaction;
+
+    #[test]
+    fn test_randomness_state_updates() {
+        let update = |round| {
+            VerifiedTransaction::new_randomness_state_update(
+                1,
+                RandomnessRound::new(round),
+                vec![round as u8; 32],
+                SequenceNumber::from_u64(1),
+            )
+        };
+        let transactions = [
+            VerifiedTransaction::new_consensus_commit_prologue(1, 10, 1000),
+            update(3),
+            VerifiedTransaction::new_consensus_commit_prologue(1, 11, 2000),
+            update(4),
+        ];
+
+        // Updates that don't lead their checkpoint are found too.
+        let updates = randomness_state_updates(
+            transactions
+                .iter()
+                .map(|transaction| transaction.data().transaction_data().kind()),
+        );
+        let rounds: Vec<_> = updates
+            .iter()
+            .map(|update| update.randomness_round)
+            .collect();
+        assert_eq!(
+            rounds,
+            vec![RandomnessRound::new(3), RandomnessRound::new(4)]
+        );
+    }
+}
@@ -188,6 +188,15 @@ pub enum ExecutionFailureStatus {
 
     #[error("Certificate cannot be executed due to a dependency on a deleted shared object")]
     InputObjectDeleted,
+
+    #[error(
+        "Certificate is cancelled due to congestion on shared objects: {congested_objects}. \
+        Resubmit with a gas price of at least {suggested_gas_price} to be scheduled sooner."
+    )]
+    ExecutionCancelledDueToSharedObjectCongestion {
+        congested_objects: CongestedObjects,
+        suggested_gas_price: u64,
+    },
     // NOTE: if you want to add a new enum,
     // please add it at the end for Rust SDK backward compatibility.
 }
@@ -203,6 +212,60 @@ pub struct MoveLocation {
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, Hash)]
 pub struct MoveLocationOpt(pub Option<MoveLocation>);
 
+/// The shared objects whose congestion caused a transaction to be cancelled
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, Hash)]
+pub struct CongestedObjects(pub Vec<ObjectID>);
+
+/// Consensus cancels a transaction that has been deferred for too long because of congestion on
+/// its shared objects. A cancelled transaction is still sequenced and charged for gas, but fails
+/// without being executed.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct CongestionCancellation {
+    pub congested_objects: CongestedObjects,
+    /// A gas price likely to get the transaction scheduled in the next consensus commit
+    pub suggested_gas_price: u64,
+}
+
+impl CongestionCancellation {
+    /// Recovers the cancellation from the status of a cancelled transaction, for nodes that
+    /// execute it from a checkpoint rather than from consensus.
+    pub fn from_execution_status(status: &ExecutionStatus) -> Option<Self> {
+        match status {
+            ExecutionStatus::Failure {
+                error:
+                    ExecutionFailureStatus::ExecutionCancelledDueToSharedObjectCongestion {
+                        congested_objects,
+                        suggested_gas_price,
+                    },
+                ..
+            } => Some(Self {
+                congested_objects: congested_objects.clone(),
+                suggested_gas_price: *suggested_gas_price,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn into_failure_status(self) -> ExecutionFailureStatus {
+        ExecutionFailureStatus::ExecutionCancelledDueToSharedObjectCongestion {
+            congested_objects: self.congested_objects,
+            suggested_gas_price: self.suggested_gas_price,
+        }
+    }
+}
+
+impl Display for CongestedObjects {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, object) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{object}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, Hash, Error)]
 pub enum CommandArgumentError {
     #[error("The type of the value does not match the expected type")]
@@ -134,6 +134,98 @@ fn default_sketch_tolerance() -> f64 {
     DEFAULT_SKETCH_TOLERANCE
 }
 
+/// The source of the IP address that identifies a client in a policy.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClientIdSource {
+    /// The IP address of the socket connection to the node
+    #[default]
+    ConnectionIp,
+    /// The IP address forwarded by a proxy in the `x-forwarded-for` header
+    ForwardedHeader,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TokenBucketConfig {
+    #[serde(default)]
+    pub client_id_source: ClientIdSource,
+    /// Maximum number of tallies a client can burst before being blocked
+    #[serde(default = "default_bucket_capacity")]
+    pub capacity: u64,
+    /// Number of tallies per second a client is allowed to sustain
+    #[serde(default = "default_refill_rate")]
+    pub refill_rate: f64,
+    #[serde(default = "default_max_tracked_clients")]
+    pub max_tracked_clients: usize,
+}
+
+impl Default for TokenBucketConfig {
+    fn default() -> Self {
+        Self {
+            client_id_source: ClientIdSource::default(),
+            capacity: default_bucket_capacity(),
+            refill_rate: default_refill_rate(),
+            max_tracked_clients: default_max_tracked_clients(),
+        }
+    }
+}
+
+fn default_bucket_capacity() -> u64 {
+    100
+}
+
+fn default_refill_rate() -> f64 {
+    10.0
+}
+
+fn default_max_tracked_clients() -> usize {
+    100_000
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SlidingWindowErrorRateConfig {
+    #[serde(default)]
+    pub client_id_source: ClientIdSource,
+    /// Ratio of the error weight of a client's tallies to their number, above which the client
+    /// is blocked
+    #[serde(default = "default_error_rate_threshold")]
+    pub error_rate_threshold: f64,
+    /// Minimum number of tallies in the window before a client can be blocked
+    #[serde(default = "default_min_tallies")]
+    pub min_tallies: u64,
+    #[serde(default = "default_window_size_secs")]
+    pub window_size_secs: u64,
+    #[serde(default = "default_update_interval_secs")]
+    pub update_interval_secs: u64,
+    #[serde(default = "default_max_tracked_clients")]
+    pub max_tracked_clients: usize,
+}
+
+impl Default for SlidingWindowErrorRateConfig {
+    fn default() -> Self {
+        Self {
+            client_id_source: ClientIdSource::default(),
+            error_rate_threshold: default_error_rate_threshold(),
+            min_tallies: default_min_tallies(),
+            window_size_secs: default_window_size_secs(),
+            update_interval_secs: default_update_interval_secs(),
+            max_tracked_clients: default_max_tracked_clients(),
+        }
+    }
+}
+
+fn default_error_rate_threshold() -> f64 {
+    0.5
+}
+
+fn default_min_tallies() -> u64 {
+    20
+}
+
 // Serializable representation of policy types, used in config
 // in order to easily change in tests or to killswitch
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
@@ -147,6 +239,15 @@ pub enum PolicyType {
     /// with granularity of `update_interval_secs`
     FreqThreshold(FreqThresholdConfig),
 
+    /// Blocks a client once it has used the `capacity` tallies of its token bucket, which is
+    /// refilled at `refill_rate` tallies per second
+    TokenBucket(TokenBucketConfig),
+
+    /// Blocks a client when the error weight of its tallies over a sliding window of
+    /// `window_size_secs` reaches `error_rate_threshold` of its tallies. Since error policies
+    /// are only invoked on errors, this policy must be used as the spam policy
+    SlidingWindowErrorRate(SlidingWindowErrorRateConfig),
+
     /* Below this point are test policies, and thus should not be used in production */
     ///
     /// Simple policy that adds connection_ip to blocklist when the same connection_ip
@@ -180,6 +281,26 @@ pub struct PolicyConfig {
     pub spam_sample_rate: Weight,
     #[serde(default = "default_dry_run")]
     pub dry_run: bool,
+    /// CIDR blocks of clients that are never blocked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_list: Vec<String>,
+    /// CIDR blocks of clients that are always blocked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny_list: Vec<String>,
+    /// Path to a YAML file with additional `allow-list` and `deny-list` CIDR blocks, which can
+    /// be reloaded at runtime through the admin interface
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_list_path: Option<PathBuf>,
+}
+
+/// The contents of the file at `PolicyConfig::access_list_path`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AccessListConfig {
+    #[serde(default)]
+    pub allow_list: Vec<String>,
+    #[serde(default)]
+    pub deny_list: Vec<String>,
 }
 
 impl Default for PolicyConfig {
@@ -192,6 +313,9 @@ impl Default for PolicyConfig {
             channel_capacity: 100,
             spam_sample_rate: default_spam_sample_rate(),
             dry_run: default_dry_run(),
+            allow_list: vec![],
+            deny_list: vec![],
+            access_list_path: None,
         }
     }
 }
@@ -524,6 +524,29 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 ) -> #secondary_db_map_struct_name #generics {
                 #secondary_db_map_struct_name::open_tables_read_only(primary_path, with_secondary_path, metric_conf, global_db_options_override)
             }
+
+            /// Opens a set of tables as a secondary instance of the DB at `primary_path`, keeping all the
+            /// accessors of the struct. Writes are rejected by RocksDB
+            /// No limitation on number of processes to do this
+            #[allow(unused_parens)]
+            pub fn open_tables_secondary(
+                primary_path: std::path::PathBuf,
+                with_secondary_path: Option<std::path::PathBuf>,
+                metric_conf: typed_store::rocks::MetricConf,
+                global_db_options_override: Option<rocksdb::Options>,
+            ) -> Self {
+                let secondary_path = with_secondary_path.unwrap_or_else(|| {
+                    tempfile::tempdir()
+                        .expect("Failed to open temporary directory")
+                        .into_path()
+                });
+                let inner = #intermediate_db_map_struct_name::open_tables_impl(primary_path, Some(secondary_path), false, metric_conf, global_db_options_override, None);
+                Self {
+                    #(
+                        #field_names: #post_process_fn(inner.#field_names),
+                    )*
+                }
+            }
         }
 
 
@@ -627,6 +650,24 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 })
             }
 
+            /// Iterate over the entries of the given table whose serialized keys are within `[lower_bound, upper_bound)`
+            /// Tables must be opened in read only mode using `open_tables_read_only`
+            pub fn iter_raw_range(&self, cf_name: &str, lower_bound: Option<Vec<u8>>, upper_bound: Option<Vec<u8>>) -> eyre::Result<Box<dyn Iterator<Item = std::result::Result<typed_store::traits::DebugTableEntry, typed_store::TypedStoreError>> + '_>> {
+                let table_name = Self::cf_name_to_table_name(cf_name)?;
+
+                let iter: Box<dyn Iterator<Item = std::result::Result<typed_store::traits::DebugTableEntry, typed_store::TypedStoreError>> + '_> = match table_name {
+                    #(
+                        stringify!(#field_names) => {
+                            typed_store::traits::Map::try_catch_up_with_primary(&self.#field_names)?;
+                            Box::new(self.#field_names.debug_iter_raw_range(lower_bound, upper_bound))
+                        }
+                    )*
+
+                    _ => eyre::bail!("No such table name: {}", table_name),
+                };
+                Ok(iter)
+            }
+
             pub fn describe_tables() -> std::collections::BTreeMap<String, (String, String)> {
                 vec![#(
                     (stringify!(#field_names).to_owned(), (stringify!(#key_names).to_owned(), stringify!(#value_names).to_owned())),
@@ -673,6 +714,15 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                     self.table_summary(table_name.as_str())
                 }
 
+                fn iter_table_raw_range(
+                    &self,
+                    table_name: String,
+                    lower_bound: Option<Vec<u8>>,
+                    upper_bound: Option<Vec<u8>>,
+                ) -> eyre::Result<Box<dyn Iterator<Item = std::result::Result<typed_store::traits::DebugTableEntry, typed_store::TypedStoreError>> + '_>> {
+                    self.iter_raw_range(table_name.as_str(), lower_bound, upper_bound)
+                }
+
 
         }
 
@@ -1004,6 +1054,27 @@ pub fn derive_sallydb_general(input: TokenStream) -> TokenStream {
                 })
             }
 
+            /// Iterate over the entries of the given table whose serialized keys are within `[lower_bound, upper_bound)`
+            /// Tables must be opened in read only mode using `open_tables_read_only`
+            pub fn iter_raw_range(&self, table_name: &str, lower_bound: Option<Vec<u8>>, upper_bound: Option<Vec<u8>>) -> eyre::Result<Box<dyn Iterator<Item = std::result::Result<typed_store::traits::DebugTableEntry, typed_store::TypedStoreError>> + '_>> {
+                let iter: Box<dyn Iterator<Item = std::result::Result<typed_store::traits::DebugTableEntry, typed_store::TypedStoreError>> + '_> = match table_name {
+                    #(
+                        stringify!(#field_names) => {
+                            match &self.#field_names {
+                                SallyColumn::RocksDB((db_map, typed_store::sally::SallyConfig { mode: typed_store::sally::SallyRunMode::FallbackToDB })) => {
+                                    typed_store::traits::Map::try_catch_up_with_primary(db_map)?;
+                                    Box::new(db_map.debug_iter_raw_range(lower_bound, upper_bound))
+                                }
+                                _ => unimplemented!(),
+                            }
+                        }
+                    )*
+
+                    _ => eyre::bail!("No such table name: {}", table_name),
+                };
+                Ok(iter)
+            }
+
             pub fn describe_tables() -> std::collections::BTreeMap<String, (String, String)> {
                 vec![#(
                     (stringify!(#field_names).to_owned(), (stringify!(#key_names).to_owned(), stringify!(#value_names).to_owned())),
@@ -1041,6 +1112,15 @@ pub fn derive_sallydb_general(input: TokenStream) -> TokenStream {
                     self.table_summary(table_name.as_str())
                 }
 
+                fn iter_table_raw_range(
+                    &self,
+                    table_name: String,
+                    lower_bound: Option<Vec<u8>>,
+                    upper_bound: Option<Vec<u8>>,
+                ) -> eyre::Result<Box<dyn Iterator<Item = std::result::Result<typed_store::traits::DebugTableEntry, typed_store::TypedStoreError>> + '_>> {
+                    self.iter_raw_range(table_name.as_str(), lower_bound, upper_bound)
+                }
+
         }
 
     })
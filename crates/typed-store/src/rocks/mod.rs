@@ -15,7 +15,7 @@ use crate::rocks::safe_iter::SafeIter;
 use crate::TypedStoreError;
 use crate::{
     metrics::{DBMetrics, RocksDBPerfContext, SamplingInterval},
-    traits::{Map, TableSummary},
+    traits::{DebugTableEntry, Map, TableSummary},
 };
 use bincode::Options;
 use collectable::TryExtend;
@@ -36,6 +36,7 @@ use std::{
     borrow::Borrow,
     collections::BTreeMap,
     env,
+    fmt::Debug,
     marker::PhantomData,
     ops::RangeBounds,
     path::{Path, PathBuf},
@@ -1145,6 +1146,58 @@ impl<K, V> DBMap<K, V> {
         })
     }
 
+    /// Returns an iterator over the entries whose serialized keys are within
+    /// `[lower_bound, upper_bound)`, yielding the raw bytes of each entry along with the `Debug`
+    /// representation of its decoded key and value. Entries that fail to decode are still
+    /// returned, with the decoding error in place of the representation. The iterator stops
+    /// after yielding an error from RocksDB.
+    pub fn debug_iter_raw_range(
+        &self,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> impl Iterator<Item = Result<DebugTableEntry, TypedStoreError>> + '_
+    where
+        K: DeserializeOwned + Debug,
+        V: DeserializeOwned + Debug,
+    {
+        let mut readopts = self.opts.readopts();
+        if let Some(lower_bound) = lower_bound {
+            readopts.set_iterate_lower_bound(lower_bound);
+        }
+        if let Some(upper_bound) = upper_bound {
+            readopts.set_iterate_upper_bound(upper_bound);
+        }
+        let mut failed = false;
+        self.rocksdb
+            .iterator_cf(&self.cf(), readopts, IteratorMode::Start)
+            .take_while(move |item| {
+                // Yield the first error, then stop.
+                let take = !failed;
+                failed |= item.is_err();
+                take
+            })
+            .map(|item| {
+                let (raw_key, raw_value) = item.map_err(typed_store_err_from_rocks_err)?;
+                let config = bincode::DefaultOptions::new()
+                    .with_big_endian()
+                    .with_fixint_encoding();
+                let key = match config.deserialize::<K>(&raw_key) {
+                    Ok(key) => format!("{:?}", key),
+                    Err(e) => format!("<undecodable key: {}>", e),
+                };
+                let value = match bcs::from_bytes::<V>(&raw_value) {
+                    Ok(value) => format!("{:?}", value),
+                    Err(e) => format!("<undecodable value: {}>", e),
+                };
+                Ok(DebugTableEntry {
+                    raw_key: raw_key.to_vec(),
+                    raw_value: raw_value.to_vec(),
+                    key,
+                    value,
+                })
+            })
+    }
+
     // Creates metrics and context for tracking an iterator usage and performance.
     fn create_iter_context(
         &self,
@@ -197,6 +197,16 @@ pub struct TableSummary {
     pub value_hist: hdrhistogram::Histogram<u64>,
 }
 
+/// A table entry as seen by the debugging tools: the raw key and value bytes as stored in the
+/// DB, along with the `Debug` representation of the decoded key and value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DebugTableEntry {
+    pub raw_key: Vec<u8>,
+    pub raw_value: Vec<u8>,
+    pub key: String,
+    pub value: String,
+}
+
 pub trait TypedStoreDebug {
     /// Dump a DB table with pagination
     fn dump_table(
@@ -217,4 +227,14 @@ pub trait TypedStoreDebug {
 
     /// Return table summary of the input table
     fn table_summary(&self, table_name: String) -> eyre::Result<TableSummary>;
+
+    /// Iterate in key order over the entries of a DB table whose serialized keys are within
+    /// `[lower_bound, upper_bound)`. Missing bounds leave that end of the range open. The
+    /// iteration stops after yielding an error from the DB.
+    fn iter_table_raw_range(
+        &self,
+        table_name: String,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> eyre::Result<Box<dyn Iterator<Item = Result<DebugTableEntry, TypedStoreError>> + '_>>;
 }
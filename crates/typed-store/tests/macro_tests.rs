@@ -175,6 +175,29 @@ async fn macro_test() {
     assert_eq!(format!("\"8\""), *m.get(&"\"8\"".to_string()).unwrap());
 }
 
+#[tokio::test]
+async fn open_tables_secondary_test() {
+    let primary_path = temp_dir();
+    let tbls_primary =
+        Tables::open_tables_read_write(primary_path.clone(), MetricConf::default(), None, None);
+    tbls_primary
+        .table1
+        .multi_insert((1..10).map(|i| (i.to_string(), i.to_string())))
+        .expect("Failed to multi-insert");
+
+    let tbls_secondary =
+        Tables::open_tables_secondary(primary_path, None, MetricConf::default(), None);
+    assert_eq!(
+        tbls_secondary.table1.get(&"5".to_string()),
+        Ok(Some("5".to_string()))
+    );
+    assert_eq!(tbls_secondary.table2.get(&5), Ok(None));
+
+    // Writes must not reach the primary DB
+    assert!(tbls_secondary.table2.insert(&5, &"5".to_string()).is_err());
+    assert_eq!(tbls_primary.table2.get(&5), Ok(None));
+}
+
 #[tokio::test]
 async fn rename_test() {
     let dbdir = temp_dir();
@@ -4,9 +4,9 @@
 use super::reroot_path;
 use clap::*;
 use move_compiler::compiled_unit::NamedCompiledModule;
-use move_disassembler::disassembler::Disassembler;
+use move_disassembler::{disassembler::Disassembler, structured::DisassemblyFormat};
 use move_package::{compilation::compiled_package::CompiledUnitWithSource, BuildConfig};
-use std::path::PathBuf;
+use std::{fs, path::PathBuf};
 
 /// Disassemble the Move bytecode pointed to
 #[derive(Parser)]
@@ -24,6 +24,10 @@ pub struct Disassemble {
     #[clap(long = "Xdebug")]
     /// Also print the raw disassembly using Rust's Debug output, at the end.
     pub debug: bool,
+    #[clap(long = "format", value_enum, default_value_t = DisassemblyFormat::Text)]
+    /// The format to print the disassembly in. JSON and HTML link each instruction to its source
+    /// line.
+    pub format: DisassemblyFormat,
 }
 
 impl Disassemble {
@@ -34,6 +38,7 @@ impl Disassemble {
             package_name,
             module_or_script_name,
             debug,
+            format,
         } = self;
         // Make sure the package is built
         let package = config.compile_package(&rerooted_path, &mut Vec::new())?;
@@ -66,7 +71,14 @@ impl Disassemble {
                         source_path,
                     )
                 } else {
-                    println!("{}", Disassembler::from_unit(&unit.unit).disassemble()?);
+                    let mut disassembler = Disassembler::from_unit(&unit.unit);
+                    if format != DisassemblyFormat::Text {
+                        if let Ok(source) = fs::read_to_string(&unit.source_path) {
+                            disassembler
+                                .add_source_code((unit.source_path.display().to_string(), source));
+                        }
+                    }
+                    println!("{}", disassembler.disassemble_as(format)?);
                     if debug {
                         println!("\n{:#?}", &unit.unit.module)
                     }
@@ -34,6 +34,9 @@ use std::os::unix::prelude::ExitStatusExt;
 #[cfg(not(any(target_family = "windows", target_family = "unix")))]
 compile_error!("Unsupported OS, currently we only support windows and unix family");
 
+/// The directory gas profiles of tests are written to if none is given, relative to the package root
+const DEFAULT_PROFILE_OUTPUT_DIR: &str = "gas_profiles";
+
 /// Run Move unit tests in this package.
 #[derive(Parser)]
 #[clap(name = "test")]
@@ -70,6 +73,21 @@ pub struct Test {
     /// Collect coverage information for later use with the various `move coverage` subcommands. Currently supported only in debug builds.
     #[clap(long = "coverage")]
     pub compute_coverage: bool,
+    /// Seed to use for generating the arguments of `#[random_test]`s. A random seed is picked if
+    /// none is given, and printed for any failing random test so that it can be reproduced.
+    #[clap(long = "seed")]
+    pub seed: Option<u64>,
+    /// Number of times each `#[random_test]` is run with newly generated arguments.
+    #[clap(long = "rand-num-iters")]
+    pub rand_num_iters: Option<u64>,
+    /// Write a gas profile of each test, with the gas used and instructions executed per function
+    /// call, as a speedscope file and as collapsed stacks for flamegraph tools. Only supported in
+    /// builds with the `gas-profiler` feature.
+    #[clap(long = "profile")]
+    pub profile: bool,
+    /// The directory to write the gas profiles to, `gas_profiles` in the package root by default.
+    #[clap(long = "profile-output", requires = "profile")]
+    pub profile_output: Option<PathBuf>,
 }
 
 impl Test {
@@ -80,6 +98,7 @@ impl Test {
         natives: Vec<NativeFunctionRecord>,
         cost_table: Option<CostTable>,
     ) -> anyhow::Result<()> {
+        self.check_profile_supported()?;
         let rerooted_path = reroot_path(path)?;
         let compute_coverage = self.compute_coverage;
         let result = run_move_unit_tests(
@@ -99,6 +118,17 @@ impl Test {
         Ok(())
     }
 
+    /// Returns an error if gas profiles were requested but this build can't produce them.
+    pub fn check_profile_supported(&self) -> anyhow::Result<()> {
+        if self.profile && !cfg!(feature = "gas-profiler") {
+            anyhow::bail!(
+                "The --profile flag requires the gas-profiler feature, rebuild or reinstall with \
+                 --features gas-profiler"
+            );
+        }
+        Ok(())
+    }
+
     pub fn unit_test_config(self) -> UnitTestingConfig {
         let Self {
             gas_limit,
@@ -109,6 +139,10 @@ impl Test {
             check_stackless_vm,
             verbose_mode,
             compute_coverage: _,
+            seed,
+            rand_num_iters,
+            profile,
+            profile_output,
         } = self;
         UnitTestingConfig {
             gas_limit,
@@ -118,6 +152,11 @@ impl Test {
             report_statistics,
             check_stackless_vm,
             verbose: verbose_mode,
+            seed,
+            rand_num_iters,
+            profile_output: profile.then(|| {
+                profile_output.unwrap_or_else(|| PathBuf::from(DEFAULT_PROFILE_OUTPUT_DIR))
+            }),
             ..UnitTestingConfig::default_with_bound(None)
         }
     }
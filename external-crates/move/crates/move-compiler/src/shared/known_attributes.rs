@@ -35,6 +35,8 @@ pub enum TestingAttribute {
     TestOnly,
     // Is a test that will be run
     Test,
+    // Is a test that will be run multiple times with randomly generated arguments
+    RandTest,
     // This test is expected to fail
     ExpectedFailure,
 }
@@ -89,6 +91,7 @@ impl KnownAttribute {
     pub fn resolve(attribute_str: impl AsRef<str>) -> Option<Self> {
         Some(match attribute_str.as_ref() {
             TestingAttribute::TEST => TestingAttribute::Test.into(),
+            TestingAttribute::RAND_TEST => TestingAttribute::RandTest.into(),
             TestingAttribute::TEST_ONLY => TestingAttribute::TestOnly.into(),
             TestingAttribute::EXPECTED_FAILURE => TestingAttribute::ExpectedFailure.into(),
             VerificationAttribute::VERIFY_ONLY => VerificationAttribute::VerifyOnly.into(),
@@ -132,6 +135,7 @@ impl KnownAttribute {
 
 impl TestingAttribute {
     pub const TEST: &'static str = "test";
+    pub const RAND_TEST: &'static str = "random_test";
     pub const EXPECTED_FAILURE: &'static str = "expected_failure";
     pub const TEST_ONLY: &'static str = "test_only";
     pub const ABORT_CODE_NAME: &'static str = "abort_code";
@@ -145,6 +149,7 @@ impl TestingAttribute {
     pub const fn name(&self) -> &str {
         match self {
             Self::Test => Self::TEST,
+            Self::RandTest => Self::RAND_TEST,
             Self::TestOnly => Self::TEST_ONLY,
             Self::ExpectedFailure => Self::EXPECTED_FAILURE,
         }
@@ -169,7 +174,7 @@ impl TestingAttribute {
             Lazy::new(|| BTreeSet::from([AttributePosition::Function]));
         match self {
             TestingAttribute::TestOnly => &TEST_ONLY_POSITIONS,
-            TestingAttribute::Test => &TEST_POSITIONS,
+            TestingAttribute::Test | TestingAttribute::RandTest => &TEST_POSITIONS,
             TestingAttribute::ExpectedFailure => &EXPECTED_FAILURE_POSITIONS,
         }
     }
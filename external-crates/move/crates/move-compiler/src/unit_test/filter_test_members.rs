@@ -66,14 +66,17 @@ impl FilterContext for Context<'_> {
     fn should_remove_by_attributes(&mut self, attrs: &[P::Attributes]) -> bool {
         use known_attributes::TestingAttribute;
         let flattened_attrs: Vec<_> = attrs.iter().flat_map(test_attributes).collect();
-        let is_test_only = flattened_attrs
-            .iter()
-            .any(|attr| matches!(attr.1, TestingAttribute::Test | TestingAttribute::TestOnly));
+        let is_test_only = flattened_attrs.iter().any(|attr| {
+            matches!(
+                attr.1,
+                TestingAttribute::Test | TestingAttribute::RandTest | TestingAttribute::TestOnly
+            )
+        });
         is_test_only && !self.env.flags().keep_testing_functions()
             || (!self.is_source_def
-                && flattened_attrs
-                    .iter()
-                    .any(|attr| attr.1 == TestingAttribute::Test))
+                && flattened_attrs.iter().any(|attr| {
+                    matches!(attr.1, TestingAttribute::Test | TestingAttribute::RandTest)
+                }))
     }
 }
 
@@ -6,9 +6,13 @@ use crate::{
     compiled_unit::NamedCompiledModule, diagnostics::FilesSourceText, shared::NumericalAddress,
 };
 use move_core_types::{
-    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
-    runtime_value::MoveValue, vm_status::StatusCode,
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+    runtime_value::MoveValue,
+    vm_status::StatusCode,
 };
+use move_symbol_pool::Symbol;
 use std::{collections::BTreeMap, fmt};
 
 pub mod filter_test_members;
@@ -32,10 +36,18 @@ pub struct ModuleTestPlan {
 #[derive(Debug, Clone)]
 pub struct TestCase {
     pub test_name: TestName,
-    pub arguments: Vec<MoveValue>,
+    pub arguments: Vec<TestArgument>,
     pub expected_failure: Option<ExpectedFailure>,
 }
 
+#[derive(Debug, Clone)]
+pub enum TestArgument {
+    // a value assigned to the parameter in the #[test(...)] attribute
+    Value(MoveValue),
+    // a value of this type is generated for each run of a #[random_test]
+    Generate { name: Symbol, ty: TypeTag },
+}
+
 #[derive(Debug, Clone)]
 pub enum ExpectedFailure {
     // expected failure, but codes are not checked
@@ -77,6 +89,15 @@ impl ModuleTestPlan {
     }
 }
 
+impl TestCase {
+    /// Returns true if any argument of this test is generated, i.e., this is a #[random_test]
+    pub fn is_random_test(&self) -> bool {
+        self.arguments
+            .iter()
+            .any(|arg| matches!(arg, TestArgument::Generate { .. }))
+    }
+}
+
 impl TestPlan {
     pub fn new(
         tests: Vec<ModuleTestPlan>,
@@ -9,18 +9,21 @@ use crate::{
         self as E, Address, Attribute, AttributeValue, Attributes, ModuleAccess_, ModuleIdent,
         ModuleIdent_,
     },
-    hlir::translate::display_var,
+    hlir::{ast as H, translate::display_var},
     parser::ast::ConstantName,
     shared::{
         known_attributes::{self, TestingAttribute},
         unique_map::UniqueMap,
         CompilationEnv, Identifier, NumericalAddress,
     },
-    unit_test::{ExpectedFailure, ExpectedMoveError, ModuleTestPlan, TestCase},
+    unit_test::{ExpectedFailure, ExpectedMoveError, ModuleTestPlan, TestArgument, TestCase},
 };
 use move_core_types::{
-    account_address::AccountAddress as MoveAddress, language_storage::ModuleId,
-    runtime_value::MoveValue, u256::U256, vm_status::StatusCode,
+    account_address::AccountAddress as MoveAddress,
+    language_storage::{ModuleId, TypeTag},
+    runtime_value::MoveValue,
+    u256::U256,
+    vm_status::StatusCode,
 };
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
@@ -128,15 +131,16 @@ fn build_test_info<'func>(
     const IN_THIS_TEST_MSG: &str = "Error found in this test";
 
     let test_attribute_opt = get_attrs(TestingAttribute::Test);
+    let rand_test_attribute_opt = get_attrs(TestingAttribute::RandTest);
     let abort_attribute_opt = get_attrs(TestingAttribute::ExpectedFailure);
     let test_only_attribute_opt = get_attrs(TestingAttribute::TestOnly);
 
-    let test_attribute = match test_attribute_opt {
-        None => {
+    let (test_attribute, is_random_test) = match (test_attribute_opt, rand_test_attribute_opt) {
+        (None, None) => {
             // expected failures cannot be annotated on non-#[test] functions
             if let Some(abort_attribute) = abort_attribute_opt {
-                let fn_msg = "Only functions defined as a test with #[test] can also have an \
-                              #[expected_failure] attribute";
+                let fn_msg = "Only functions defined as a test with #[test] or #[random_test] can \
+                              also have an #[expected_failure] attribute";
                 let abort_msg = "Attributed as #[expected_failure] here";
                 context.env.add_diag(diag!(
                     Attributes::InvalidUsage,
@@ -146,7 +150,19 @@ fn build_test_info<'func>(
             }
             return None;
         }
-        Some(test_attribute) => test_attribute,
+        (Some(test_attribute), Some(rand_test_attribute)) => {
+            let msg = "Function annotated as both #[test(...)] and #[random_test]. You need to \
+                       declare it as either one or the other";
+            context.env.add_diag(diag!(
+                Attributes::InvalidUsage,
+                (rand_test_attribute.loc, msg),
+                (test_attribute.loc, PREVIOUSLY_ANNOTATED_MSG),
+                (fn_loc, IN_THIS_TEST_MSG),
+            ));
+            return None;
+        }
+        (Some(test_attribute), None) => (test_attribute, false),
+        (None, Some(rand_test_attribute)) => (rand_test_attribute, true),
     };
 
     // A #[test] function cannot also be annotated #[test_only]
@@ -161,17 +177,39 @@ fn build_test_info<'func>(
         ))
     }
 
-    let test_annotation_params = parse_test_attribute(context, test_attribute, 0);
+    let test_annotation_params = if is_random_test {
+        check_random_test_attribute(context, test_attribute);
+        BTreeMap::new()
+    } else {
+        parse_test_attribute(context, test_attribute, 0)
+    };
     let mut arguments = Vec::new();
-    for (_mut, var, _) in &function.signature.parameters {
+    for (_mut, var, ty) in &function.signature.parameters {
         let sp!(vloc, var_) = var.0;
-        let var_ = match display_var(var_) {
+        let var_: Symbol = match display_var(var_) {
             crate::hlir::translate::DisplayVar::Orig(s) => s.into(),
             crate::hlir::translate::DisplayVar::MatchTmp(_) => panic!("ICE temp as parameter"),
             crate::hlir::translate::DisplayVar::Tmp => panic!("ICE temp as parameter"),
         };
+        if is_random_test {
+            match generated_argument_type(ty) {
+                Some(ty) => arguments.push(TestArgument::Generate { name: var_, ty }),
+                None => {
+                    let unsupported_msg = "Unsupported type for a generated test parameter. Only \
+                                           'bool', 'address', unsigned integers, and vectors of \
+                                           these types can be generated in a #[random_test]";
+                    context.env.add_diag(diag!(
+                        Attributes::InvalidTest,
+                        (ty.loc, unsupported_msg),
+                        (vloc, "Corresponding to this parameter"),
+                        (fn_loc, IN_THIS_TEST_MSG),
+                    ))
+                }
+            }
+            continue;
+        }
         match test_annotation_params.get(&var_) {
-            Some(value) => arguments.push(value.clone()),
+            Some(value) => arguments.push(TestArgument::Value(value.clone())),
             None => {
                 let missing_param_msg = "Missing test parameter assignment in test. Expected a \
                                          parameter to be assigned in this attribute";
@@ -197,6 +235,40 @@ fn build_test_info<'func>(
     })
 }
 
+// Returns the type of a value that can be generated for a #[random_test] parameter of type `ty`,
+// or `None` if values of that type cannot be generated.
+fn generated_argument_type(sp!(_, ty): &H::SingleType) -> Option<TypeTag> {
+    match ty {
+        H::SingleType_::Base(bt) => generated_base_type(bt),
+        H::SingleType_::Ref(_, _) => None,
+    }
+}
+
+fn generated_base_type(sp!(_, bt): &H::BaseType) -> Option<TypeTag> {
+    use crate::naming::ast::BuiltinTypeName_ as B;
+    let H::BaseType_::Apply(_, sp!(_, H::TypeName_::Builtin(sp!(_, builtin))), ty_args) = bt else {
+        return None;
+    };
+    Some(match builtin {
+        B::Bool => TypeTag::Bool,
+        B::U8 => TypeTag::U8,
+        B::U16 => TypeTag::U16,
+        B::U32 => TypeTag::U32,
+        B::U64 => TypeTag::U64,
+        B::U128 => TypeTag::U128,
+        B::U256 => TypeTag::U256,
+        B::Address => TypeTag::Address,
+        B::Vector => {
+            let [elem_ty] = &ty_args[..] else {
+                return None;
+            };
+            TypeTag::Vector(Box::new(generated_base_type(elem_ty)?))
+        }
+        // signers cannot be created out of thin air
+        B::Signer => return None,
+    })
+}
+
 //***************************************************************************
 // Attribute parsers
 //***************************************************************************
@@ -261,6 +333,30 @@ fn parse_test_attribute(
     }
 }
 
+// A #[random_test] takes no arguments, all of its parameters are generated
+fn check_random_test_attribute(
+    context: &mut Context,
+    sp!(aloc, rand_test_attribute): &E::Attribute,
+) {
+    use E::Attribute_ as EA;
+
+    match rand_test_attribute {
+        EA::Name(nm) => {
+            assert!(
+                nm.value.as_str() == TestingAttribute::RandTest.name(),
+                "ICE: We should only be parsing a raw random test attribute"
+            );
+        }
+        EA::Assigned(_, _) | EA::Parameterized(_, _) => {
+            let msg = "Unexpected arguments in #[random_test] declaration. All parameters of a \
+                       random test are generated";
+            context
+                .env
+                .add_diag(diag!(Attributes::InvalidTest, (*aloc, msg)));
+        }
+    }
+}
+
 const BAD_ABORT_VALUE_WARNING: &str = "WARNING: passes for an abort from any module.";
 const INVALID_VALUE: &str = "Invalid value in attribute assignment";
 
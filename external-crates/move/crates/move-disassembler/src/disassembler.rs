@@ -26,6 +26,11 @@ use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
 use move_coverage::coverage_map::{ExecCoverageMap, FunctionCoverage};
 use move_ir_types::location::Loc;
 
+use crate::structured::{
+    line_of_offset, render_html, DisassembledFunction, DisassembledInstruction, DisassembledModule,
+    DisassembledStruct, DisassemblyFormat,
+};
+
 /// Holds the various options that we support while disassembling code.
 #[derive(Debug, Default, Parser)]
 pub struct DisassemblerOptions {
@@ -117,6 +122,12 @@ impl<'a> Disassembler<'a> {
         self.coverage_map = Some(coverage_map);
     }
 
+    /// Attaches the `(path, contents)` of the source file the module was compiled from, used to
+    /// map the bytecode back to source lines in the structured output formats.
+    pub fn add_source_code(&mut self, source_code: (String, String)) {
+        self.source_mapper.with_source_code(source_code);
+    }
+
     //***************************************************************************
     // Helpers
     //***************************************************************************
@@ -1307,4 +1318,156 @@ impl<'a> Disassembler<'a> {
             function_defs = &function_defs.join("\n"),
         ))
     }
+
+    //***************************************************************************
+    // Structured output
+    //***************************************************************************
+
+    fn source_line(&self, loc: Loc) -> Option<usize> {
+        let (_, source_code) = self.source_mapper.source_code.as_ref()?;
+        line_of_offset(source_code, loc.start() as usize)
+    }
+
+    /// Disassembles the module into a `DisassembledModule`, where each struct, function and
+    /// instruction is mapped back to its source line if the source code is available.
+    pub fn disassemble_structured(&self) -> Result<DisassembledModule> {
+        let bytecode = self.source_mapper.bytecode;
+        let (addr, n) = &self.source_mapper.source_map.module_name;
+
+        let imports = bytecode
+            .module_handles()
+            .iter()
+            .filter_map(|h| self.get_import_string(h))
+            .collect();
+
+        let structs = (0..bytecode.struct_defs().len())
+            .map(|i| {
+                let struct_def_idx = StructDefinitionIndex(i as TableIndex);
+                let struct_def = self.get_struct_def(struct_def_idx)?;
+                let struct_handle = bytecode.struct_handle_at(struct_def.struct_handle);
+                let struct_source_map = self
+                    .source_mapper
+                    .source_map
+                    .get_struct_source_map(struct_def_idx)?;
+                Ok(DisassembledStruct {
+                    name: bytecode.identifier_at(struct_handle.name).to_string(),
+                    declaration: self.disassemble_struct_def(struct_def_idx)?,
+                    source_line: self.source_line(struct_source_map.definition_location),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let constants = bytecode
+            .constant_pool()
+            .iter()
+            .enumerate()
+            .map(|(i, constant)| self.disassemble_constant(i, constant))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut functions = vec![];
+        for i in 0..bytecode.function_defs().len() {
+            let fdef_idx = FunctionDefinitionIndex(i as TableIndex);
+            let function_def = self.get_function_def(fdef_idx)?;
+            if self.options.only_externally_visible
+                && function_def.visibility == Visibility::Private
+            {
+                continue;
+            }
+            let function_handle = bytecode.function_handle_at(function_def.function);
+            let name = bytecode.identifier_at(function_handle.name);
+            let function_source_map = self
+                .source_mapper
+                .source_map
+                .get_function_source_map(fdef_idx)?;
+            let declaration = self.disassemble_function_def(
+                function_source_map,
+                Some((function_def, function_handle)),
+                name,
+                &function_handle.type_parameters,
+                function_handle.parameters,
+                None,
+            )?;
+
+            let (locals, instructions) = match &function_def.code {
+                None => (vec![], vec![]),
+                Some(code) => {
+                    let parameters = bytecode.signature_at(function_handle.parameters);
+                    let locals = self.disassemble_locals(
+                        function_source_map,
+                        code.locals,
+                        parameters.len(),
+                    )?;
+                    let locals_sigs = bytecode.signature_at(code.locals);
+                    let block_starts: HashMap<_, _> = VMControlFlowGraph::new(&code.code)
+                        .blocks()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(block_number, block_id)| (block_id, block_number))
+                        .collect();
+                    let instructions = code
+                        .code
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, instruction)| {
+                            let offset = offset as u16;
+                            Ok(DisassembledInstruction {
+                                offset,
+                                basic_block: block_starts.get(&offset).copied(),
+                                instruction: self.disassemble_instruction(
+                                    parameters,
+                                    instruction,
+                                    locals_sigs,
+                                    function_source_map,
+                                    &function_source_map.definition_location,
+                                )?,
+                                source_line: function_source_map
+                                    .get_code_location(offset)
+                                    .and_then(|loc| self.source_line(loc)),
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    (locals, instructions)
+                }
+            };
+
+            functions.push(DisassembledFunction {
+                name: name.to_string(),
+                declaration,
+                source_line: self.source_line(function_source_map.definition_location),
+                locals,
+                instructions,
+            });
+        }
+
+        Ok(DisassembledModule {
+            name: format!("{}::{}", addr.short_str_lossless(), n),
+            bytecode_version: bytecode.version(),
+            source_file: self
+                .source_mapper
+                .source_code
+                .as_ref()
+                .map(|(path, _)| path.clone()),
+            imports,
+            structs,
+            constants,
+            functions,
+        })
+    }
+
+    /// Disassembles the module in the requested format
+    pub fn disassemble_as(&self, format: DisassemblyFormat) -> Result<String> {
+        match format {
+            DisassemblyFormat::Text => self.disassemble(),
+            DisassemblyFormat::Json => Ok(serde_json::to_string_pretty(
+                &self.disassemble_structured()?,
+            )?),
+            DisassemblyFormat::Html => Ok(render_html(
+                &self.disassemble_structured()?,
+                self.source_mapper
+                    .source_code
+                    .as_ref()
+                    .map(|(_, source)| source.as_str()),
+            )),
+        }
+    }
 }
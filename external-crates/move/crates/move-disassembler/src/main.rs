@@ -11,7 +11,10 @@ use move_command_line_common::files::{
     MOVE_COMPILED_EXTENSION, MOVE_EXTENSION, SOURCE_MAP_EXTENSION,
 };
 use move_coverage::coverage_map::CoverageMap;
-use move_disassembler::disassembler::{Disassembler, DisassemblerOptions};
+use move_disassembler::{
+    disassembler::{Disassembler, DisassemblerOptions},
+    structured::DisassemblyFormat,
+};
 use move_ir_types::location::Spanned;
 use std::{fs, path::Path};
 
@@ -44,6 +47,11 @@ struct Args {
     /// disassembled output.
     #[clap(short = 'c', long = "move-coverage-path")]
     pub code_coverage_path: Option<String>,
+
+    /// The format to print the disassembled module in. The JSON and HTML formats map each
+    /// instruction to its source line when the source map and source file are found.
+    #[clap(short = 'f', long = "format", value_enum, default_value_t = DisassemblyFormat::Text)]
+    pub format: DisassemblyFormat,
 }
 
 fn main() {
@@ -107,7 +115,9 @@ fn main() {
         );
     }
 
-    let dissassemble_string = disassembler.disassemble().expect("Unable to dissassemble");
+    let dissassemble_string = disassembler
+        .disassemble_as(args.format)
+        .expect("Unable to dissassemble");
 
     println!("{}", dissassemble_string);
 }
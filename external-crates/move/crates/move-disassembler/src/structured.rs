@@ -0,0 +1,242 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A structured view of a disassembled module, for tools that want to browse the bytecode rather
+//! than read it as text. Every struct, function and instruction carries the source line it was
+//! compiled from, when a source map and the source code are available.
+
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// The output formats supported by the disassembler
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DisassemblyFormat {
+    /// Human readable listing of the module
+    #[default]
+    Text,
+    /// Structured JSON, see `DisassembledModule`
+    Json,
+    /// Self-contained HTML page linking the bytecode to the source code
+    Html,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DisassembledModule {
+    /// `<address>::<name>` of the module
+    pub name: String,
+    pub bytecode_version: u32,
+    /// Path of the source file the line numbers refer to
+    pub source_file: Option<String>,
+    pub imports: Vec<String>,
+    pub structs: Vec<DisassembledStruct>,
+    pub constants: Vec<String>,
+    pub functions: Vec<DisassembledFunction>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DisassembledStruct {
+    pub name: String,
+    pub declaration: String,
+    pub source_line: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DisassembledFunction {
+    pub name: String,
+    /// The signature of the function, including its modifiers
+    pub declaration: String,
+    pub source_line: Option<usize>,
+    pub locals: Vec<String>,
+    pub instructions: Vec<DisassembledInstruction>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DisassembledInstruction {
+    pub offset: u16,
+    /// Set on the first instruction of each basic block, to the index of that block
+    pub basic_block: Option<usize>,
+    pub instruction: String,
+    pub source_line: Option<usize>,
+}
+
+/// Returns the 1-based line of `source` that contains the byte at `offset`
+pub(crate) fn line_of_offset(source: &str, offset: usize) -> Option<usize> {
+    let prefix = source.get(..offset)?;
+    Some(prefix.matches('\n').count() + 1)
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn source_link(line: Option<usize>) -> String {
+    match line {
+        Some(line) => format!("<a class=\"src\" href=\"#L{line}\">L{line}</a>"),
+        None => String::new(),
+    }
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; margin: 0; display: flex; height: 100vh; }
+.pane { overflow: auto; flex: 1; padding: 0 1em; }
+pre { margin: 0; }
+.decl { font-weight: bold; }
+.src { color: #888; margin-left: 1em; text-decoration: none; }
+.block { color: #a60; }
+.line:target { background: #ffd; }
+.lineno { color: #888; display: inline-block; width: 4em; user-select: none; }
+";
+
+/// Renders `module` as a self-contained HTML page. When `source_code` is provided it is shown
+/// next to the bytecode, and every source location in the bytecode links to its line.
+pub fn render_html(module: &DisassembledModule, source_code: Option<&str>) -> String {
+    let mut out = String::new();
+    let title = escape(&module.name);
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>"
+    );
+
+    let _ = writeln!(out, "<div class=\"pane\">");
+    let _ = writeln!(
+        out,
+        "<h1>module {title}</h1>\n<p>Move bytecode v{}</p>",
+        module.bytecode_version
+    );
+
+    if !module.imports.is_empty() {
+        let _ = writeln!(out, "<h2>Imports</h2>\n<pre>");
+        for import in &module.imports {
+            let _ = writeln!(out, "{}", escape(import));
+        }
+        let _ = writeln!(out, "</pre>");
+    }
+
+    if !module.structs.is_empty() {
+        let _ = writeln!(out, "<h2>Structs</h2>");
+        for s in &module.structs {
+            let _ = writeln!(
+                out,
+                "<pre id=\"struct-{name}\" class=\"decl\">{decl}{link}</pre>",
+                name = escape(&s.name),
+                decl = escape(&s.declaration),
+                link = source_link(s.source_line),
+            );
+        }
+    }
+
+    if !module.constants.is_empty() {
+        let _ = writeln!(out, "<h2>Constants</h2>\n<pre>");
+        for constant in &module.constants {
+            let _ = writeln!(out, "{}", escape(constant.trim_start()));
+        }
+        let _ = writeln!(out, "</pre>");
+    }
+
+    if !module.functions.is_empty() {
+        let _ = writeln!(out, "<h2>Functions</h2>");
+        for f in &module.functions {
+            let _ = writeln!(
+                out,
+                "<h3 id=\"fun-{name}\"><pre class=\"decl\">{decl}{link}</pre></h3>\n<pre>",
+                name = escape(&f.name),
+                decl = escape(&f.declaration),
+                link = source_link(f.source_line),
+            );
+            for (idx, local) in f.locals.iter().enumerate() {
+                let _ = writeln!(out, "L{idx}:\t{}", escape(local));
+            }
+            for instr in &f.instructions {
+                if let Some(block) = instr.basic_block {
+                    let _ = writeln!(out, "<span class=\"block\">B{block}:</span>");
+                }
+                let _ = writeln!(
+                    out,
+                    "\t{}: {}{}",
+                    instr.offset,
+                    escape(&instr.instruction),
+                    source_link(instr.source_line),
+                );
+            }
+            let _ = writeln!(out, "</pre>");
+        }
+    }
+    let _ = writeln!(out, "</div>");
+
+    if let Some(source_code) = source_code {
+        let _ = writeln!(out, "<div class=\"pane\">");
+        if let Some(file) = &module.source_file {
+            let _ = writeln!(out, "<h2>{}</h2>", escape(file));
+        }
+        let _ = writeln!(out, "<pre>");
+        for (idx, line) in source_code.lines().enumerate() {
+            let line_number = idx + 1;
+            let _ = writeln!(
+                out,
+                "<span id=\"L{line_number}\" class=\"line\"><span class=\"lineno\">{line_number}</span>{}</span>",
+                escape(line)
+            );
+        }
+        let _ = writeln!(out, "</pre>\n</div>");
+    }
+
+    let _ = writeln!(out, "</body>\n</html>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_of_offset_is_one_based() {
+        let source = "module a::m {\n    fun f() {}\n}\n";
+        assert_eq!(line_of_offset(source, 0), Some(1));
+        assert_eq!(line_of_offset(source, 14), Some(2));
+        assert_eq!(line_of_offset(source, source.len()), Some(4));
+        assert_eq!(line_of_offset(source, source.len() + 1), None);
+    }
+
+    #[test]
+    fn html_escapes_and_links_source() {
+        let module = DisassembledModule {
+            name: "0x1::m".to_string(),
+            bytecode_version: 6,
+            source_file: Some("m.move".to_string()),
+            imports: vec![],
+            structs: vec![],
+            constants: vec![],
+            functions: vec![DisassembledFunction {
+                name: "f".to_string(),
+                declaration: "f(): vector<u8>".to_string(),
+                source_line: Some(2),
+                locals: vec![],
+                instructions: vec![DisassembledInstruction {
+                    offset: 0,
+                    basic_block: Some(0),
+                    instruction: "Ret".to_string(),
+                    source_line: Some(2),
+                }],
+            }],
+        };
+        let html = render_html(
+            &module,
+            Some("module 0x1::m {\n    fun f(): vector<u8> {}\n}"),
+        );
+        assert!(html.contains("f(): vector&lt;u8&gt;"));
+        assert!(html.contains("<a class=\"src\" href=\"#L2\">L2</a>"));
+        assert!(html.contains("<span id=\"L2\" class=\"line\">"));
+    }
+}
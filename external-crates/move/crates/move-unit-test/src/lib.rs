@@ -4,6 +4,7 @@
 
 pub mod cargo_runner;
 pub mod extensions;
+pub mod random_values;
 pub mod test_reporter;
 pub mod test_runner;
 
@@ -24,12 +25,16 @@ use std::{
     collections::BTreeMap,
     io::{Result, Write},
     marker::Send,
+    path::PathBuf,
     sync::Mutex,
 };
 
 /// The default value bounding the amount of gas consumed in a test.
 const DEFAULT_EXECUTION_BOUND: u64 = 1_000_000;
 
+/// The default number of times a `#[random_test]` is run with newly generated arguments.
+const DEFAULT_RAND_NUM_ITERS: u64 = 10;
+
 #[derive(Debug, Parser, Clone)]
 #[clap(author, version, about)]
 pub struct UnitTestingConfig {
@@ -100,6 +105,19 @@ pub struct UnitTestingConfig {
     /// Verbose mode
     #[clap(short = 'v', long = "verbose")]
     pub verbose: bool,
+
+    /// Seed to use for generating the arguments of `#[random_test]`s. A random seed is picked if
+    /// none is given, and printed for any failing random test so that it can be reproduced.
+    #[clap(long = "seed")]
+    pub seed: Option<u64>,
+
+    /// Number of times each `#[random_test]` is run with newly generated arguments.
+    #[clap(long = "rand-num-iters")]
+    pub rand_num_iters: Option<u64>,
+
+    /// Write a gas profile of each test to this directory. Requires the `gas-profiler` feature.
+    #[clap(long = "profile-output")]
+    pub profile_output: Option<PathBuf>,
 }
 
 fn format_module_id(module_id: &ModuleId) -> String {
@@ -125,6 +143,9 @@ impl UnitTestingConfig {
             verbose: false,
             list: false,
             named_address_values: vec![],
+            seed: None,
+            rand_num_iters: None,
+            profile_output: None,
         }
     }
 
@@ -211,6 +232,9 @@ impl UnitTestingConfig {
             self.check_stackless_vm,
             self.verbose,
             self.report_stacktrace_on_abort,
+            self.seed.unwrap_or_else(rand::random),
+            self.rand_num_iters.unwrap_or(DEFAULT_RAND_NUM_ITERS),
+            self.profile_output.clone(),
             test_plan,
             native_function_table,
             cost_table,
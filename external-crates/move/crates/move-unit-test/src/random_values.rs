@@ -0,0 +1,137 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation and shrinking of the arguments passed to `#[random_test]` functions.
+
+use move_core_types::{
+    account_address::AccountAddress, language_storage::TypeTag, runtime_value::MoveValue,
+    u256::U256,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// The maximum length of a generated vector
+const MAX_VECTOR_LENGTH: usize = 32;
+
+/// The percentage of generated integers that are picked from the edge cases of their type (zero,
+/// one and the two largest values) instead of uniformly from the whole range
+const EDGE_CASE_PERCENTAGE: u32 = 25;
+
+macro_rules! gen_int {
+    ($rng:expr, $ty:ty, $zero:expr, $one:expr, $max:expr) => {{
+        if $rng.gen_ratio(EDGE_CASE_PERCENTAGE, 100) {
+            *[$zero, $one, $max - $one, $max].choose(&mut $rng).unwrap()
+        } else {
+            $rng.gen::<$ty>()
+        }
+    }};
+}
+
+// Candidates for an integer `v` are zero, followed by `v - v/2`, `v - v/4`, ..., `v - 1`, so that
+// repeatedly shrinking converges on the smallest failing value in a logarithmic number of steps.
+macro_rules! shrink_int {
+    ($v:expr, $zero:expr, $two:expr, $variant:path) => {{
+        let v = $v;
+        let mut candidates = vec![];
+        if v != $zero {
+            candidates.push($variant($zero));
+            let mut delta = v / $two;
+            while delta != $zero {
+                candidates.push($variant(v - delta));
+                delta /= $two;
+            }
+        }
+        candidates
+    }};
+}
+
+/// A seeded source of values for the parameters of a `#[random_test]`.
+pub struct RandomValueGenerator {
+    rng: StdRng,
+}
+
+impl RandomValueGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Generates a value of type `ty`. Only the types accepted by the test plan builder for a
+    /// `#[random_test]` parameter are supported.
+    pub fn generate(&mut self, ty: &TypeTag) -> MoveValue {
+        match ty {
+            TypeTag::Bool => MoveValue::Bool(self.rng.gen()),
+            TypeTag::U8 => MoveValue::U8(gen_int!(self.rng, u8, 0, 1, u8::MAX)),
+            TypeTag::U16 => MoveValue::U16(gen_int!(self.rng, u16, 0, 1, u16::MAX)),
+            TypeTag::U32 => MoveValue::U32(gen_int!(self.rng, u32, 0, 1, u32::MAX)),
+            TypeTag::U64 => MoveValue::U64(gen_int!(self.rng, u64, 0, 1, u64::MAX)),
+            TypeTag::U128 => MoveValue::U128(gen_int!(self.rng, u128, 0, 1, u128::MAX)),
+            TypeTag::U256 => MoveValue::U256(gen_int!(
+                self.rng,
+                U256,
+                U256::zero(),
+                U256::one(),
+                U256::max_value()
+            )),
+            TypeTag::Address => MoveValue::Address(AccountAddress::new(self.rng.gen())),
+            TypeTag::Vector(elem_ty) => {
+                let len = self.rng.gen_range(0..=MAX_VECTOR_LENGTH);
+                MoveValue::Vector((0..len).map(|_| self.generate(elem_ty)).collect())
+            }
+            TypeTag::Signer | TypeTag::Struct(_) => {
+                unreachable!("Unable to generate a value of type {ty}")
+            }
+        }
+    }
+}
+
+/// Returns values that are simpler than `value`, most aggressive simplification first. Used to
+/// minimize the arguments of a failing `#[random_test]`.
+pub fn shrink_candidates(value: &MoveValue) -> Vec<MoveValue> {
+    match value {
+        MoveValue::Bool(true) => vec![MoveValue::Bool(false)],
+        MoveValue::Bool(false) => vec![],
+        MoveValue::U8(v) => shrink_int!(*v, 0, 2, MoveValue::U8),
+        MoveValue::U16(v) => shrink_int!(*v, 0, 2, MoveValue::U16),
+        MoveValue::U32(v) => shrink_int!(*v, 0, 2, MoveValue::U32),
+        MoveValue::U64(v) => shrink_int!(*v, 0, 2, MoveValue::U64),
+        MoveValue::U128(v) => shrink_int!(*v, 0, 2, MoveValue::U128),
+        MoveValue::U256(v) => shrink_int!(*v, U256::zero(), U256::from(2u8), MoveValue::U256),
+        MoveValue::Address(a) if *a != AccountAddress::ZERO => {
+            vec![MoveValue::Address(AccountAddress::ZERO)]
+        }
+        MoveValue::Address(_) => vec![],
+        MoveValue::Vector(elems) => shrink_vector(elems),
+        MoveValue::Signer(_) | MoveValue::Struct(_) => vec![],
+    }
+}
+
+// Shorter vectors are tried first: the empty vector, each half, and the vector without each of its
+// elements. After that, each element is shrunk in place.
+fn shrink_vector(elems: &[MoveValue]) -> Vec<MoveValue> {
+    let mut candidates = vec![];
+    if elems.is_empty() {
+        return candidates;
+    }
+    candidates.push(MoveValue::Vector(vec![]));
+    let mid = elems.len() / 2;
+    if mid > 0 {
+        candidates.push(MoveValue::Vector(elems[..mid].to_vec()));
+        candidates.push(MoveValue::Vector(elems[mid..].to_vec()));
+    }
+    if elems.len() > 1 {
+        for i in 0..elems.len() {
+            let mut shorter = elems.to_vec();
+            shorter.remove(i);
+            candidates.push(MoveValue::Vector(shorter));
+        }
+    }
+    for (i, elem) in elems.iter().enumerate() {
+        for elem_candidate in shrink_candidates(elem) {
+            let mut simpler = elems.to_vec();
+            simpler[i] = elem_candidate;
+            candidates.push(MoveValue::Vector(simpler));
+        }
+    }
+    candidates
+}
@@ -49,6 +49,14 @@ pub struct TestFailure {
     pub test_run_info: TestRunInfo,
     pub vm_error: Option<VMError>,
     pub failure_reason: FailureReason,
+    // The seed and the (shrunk) generated arguments, if the failing test is a #[random_test]
+    pub random_inputs: Option<RandomTestInputs>,
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
+pub struct RandomTestInputs {
+    pub seed: u64,
+    pub arguments: Vec<(Symbol, String)>,
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
@@ -135,10 +143,36 @@ impl TestFailure {
             test_run_info,
             vm_error,
             failure_reason,
+            random_inputs: None,
         }
     }
 
+    pub fn with_random_inputs(mut self, random_inputs: RandomTestInputs) -> Self {
+        self.random_inputs = Some(random_inputs);
+        self
+    }
+
     pub fn render_error(&self, test_plan: &TestPlan) -> String {
+        let error = self.render_failure_reason(test_plan);
+        match &self.random_inputs {
+            None => error,
+            Some(RandomTestInputs { seed, arguments }) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|(name, value)| format!("{name}: {value}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "{error}\n\
+                    This test uses randomly generated inputs. \
+                    Rerun with `--seed {seed}` to reproduce this failure.\n\
+                    Failing inputs (after shrinking): {arguments}"
+                )
+            }
+        }
+    }
+
+    fn render_failure_reason(&self, test_plan: &TestPlan) -> String {
         match &self.failure_reason {
             FailureReason::NoError(message) => message.to_string(),
             FailureReason::Timeout(message) => message.to_string(),
@@ -4,8 +4,10 @@
 
 use crate::{
     extensions, format_module_id,
+    random_values::{shrink_candidates, RandomValueGenerator},
     test_reporter::{
-        FailureReason, MoveError, TestFailure, TestResults, TestRunInfo, TestStatistics,
+        FailureReason, MoveError, RandomTestInputs, TestFailure, TestResults, TestRunInfo,
+        TestStatistics,
     },
 };
 use anyhow::Result;
@@ -25,11 +27,15 @@ use move_compiler::{
     compiled_unit::NamedCompiledModule,
     diagnostics::WarningFilters,
     shared::{Flags, NumericalAddress, PackagePaths},
-    unit_test::{ExpectedFailure, ModuleTestPlan, MoveErrorType, TestCase, TestPlan},
+    unit_test::{ExpectedFailure, ModuleTestPlan, MoveErrorType, TestArgument, TestCase, TestPlan},
 };
 use move_core_types::{
-    account_address::AccountAddress, effects::ChangeSet, identifier::IdentStr,
-    language_storage::ModuleId, runtime_value::serialize_values, vm_status::StatusCode,
+    account_address::AccountAddress,
+    effects::ChangeSet,
+    identifier::IdentStr,
+    language_storage::ModuleId,
+    runtime_value::{serialize_values, MoveValue},
+    vm_status::StatusCode,
 };
 use move_model::{
     model::GlobalEnv, options::ModelBuilderOptions,
@@ -46,10 +52,20 @@ use move_vm_test_utils::{
     InMemoryStorage,
 };
 use rayon::prelude::*;
-use std::{collections::BTreeMap, io::Write, marker::Send, sync::Mutex, time::Instant};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    marker::Send,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use move_vm_runtime::native_extensions::NativeContextExtensions;
 
+/// The maximum number of runs spent shrinking the arguments of a failing `#[random_test]`
+const MAX_SHRINK_RUNS: u64 = 1_000;
+
 /// Test state common to all tests
 pub struct SharedTestingConfig {
     report_stacktrace_on_abort: bool,
@@ -61,6 +77,9 @@ pub struct SharedTestingConfig {
     named_address_values: BTreeMap<String, NumericalAddress>,
     check_stackless_vm: bool,
     verbose: bool,
+    rand_seed: u64,
+    rand_num_iters: u64,
+    profile_output: Option<PathBuf>,
 }
 
 pub struct TestRunner {
@@ -123,6 +142,9 @@ impl TestRunner {
         check_stackless_vm: bool,
         verbose: bool,
         report_stacktrace_on_abort: bool,
+        rand_seed: u64,
+        rand_num_iters: u64,
+        profile_output: Option<PathBuf>,
         tests: TestPlan,
         // TODO: maybe we should require the clients to always pass in a list of native functions so
         // we don't have to make assumptions about their gas parameters.
@@ -137,6 +159,9 @@ impl TestRunner {
             .collect();
         let modules = tests.module_info.values().map(|info| &info.module);
         let starting_storage_state = setup_test_storage(modules)?;
+        if let Some(profile_output) = &profile_output {
+            std::fs::create_dir_all(profile_output)?;
+        }
         let native_function_table = native_function_table.unwrap_or_else(|| {
             move_stdlib_natives::all_natives(
                 AccountAddress::from_hex_literal("0x1").unwrap(),
@@ -159,6 +184,9 @@ impl TestRunner {
                 check_stackless_vm,
                 verbose,
                 named_address_values,
+                rand_seed,
+                rand_num_iters,
+                profile_output,
             },
             num_threads,
             tests,
@@ -236,6 +264,18 @@ impl<'a, 'b, W: Write> TestOutput<'a, 'b, W> {
         .unwrap()
     }
 
+    fn profile_error(&self, fn_name: &str, error: impl std::fmt::Display) {
+        writeln!(
+            self.writer.lock().unwrap(),
+            "[ {} ] {}::{}: unable to write the gas profile: {}",
+            "WARNING".bold().yellow(),
+            format_module_id(&self.test_plan.module_id),
+            fn_name,
+            error,
+        )
+        .unwrap();
+    }
+
     fn timeout(&self, fn_name: &str) {
         writeln!(
             self.writer.lock().unwrap(),
@@ -249,11 +289,16 @@ impl<'a, 'b, W: Write> TestOutput<'a, 'b, W> {
 }
 
 impl SharedTestingConfig {
+    /// Runs a test in the Move VM. If profiles were requested, the profile of the run is written
+    /// under the file prefix given in `profile`, with failures reported to its output. Runs without
+    /// a `profile` are never profiled.
+    #[cfg_attr(not(feature = "gas-profiler"), allow(unused_variables))]
     fn execute_via_move_vm(
         &self,
         test_plan: &ModuleTestPlan,
         function_name: &str,
-        test_info: &TestCase,
+        arguments: &[MoveValue],
+        profile: Option<(&TestOutput<impl Write>, &str)>,
     ) -> (
         VMResult<ChangeSet>,
         VMResult<NativeContextExtensions>,
@@ -266,12 +311,27 @@ impl SharedTestingConfig {
             move_vm.new_session_with_extensions(&self.starting_storage_state, extensions);
         let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
         move_vm_profiler::gas_profiler_feature_enabled! {
+            use move_vm_config::runtime::VMProfilerConfig;
             use move_vm_profiler::GasProfiler;
             use move_vm_types::gas::GasMeter;
-            gas_meter.set_profiler(GasProfiler::init_default_cfg(
-                function_name.to_owned(),
-                self.execution_bound,
-            ));
+            // Runs whose profile isn't reported, such as shrinking runs, aren't profiled at all,
+            // since a profiler writes its profile to a file when dropped.
+            if profile.is_some() {
+                let profiler_config = match &self.profile_output {
+                    // profiles requested for the tests are exported by the runner, see below
+                    Some(profile_output) => Some(VMProfilerConfig {
+                        full_path: profile_output.clone(),
+                        track_bytecode_instructions: true,
+                        use_long_function_name: true,
+                    }),
+                    None => VMProfilerConfig::get_default_config_if_enabled(),
+                };
+                gas_meter.set_profiler(GasProfiler::init(
+                    &profiler_config,
+                    function_name.to_owned(),
+                    self.execution_bound,
+                ));
+            }
         }
 
         // TODO: collect VM logs if the verbose flag (i.e, `self.verbose`) is set
@@ -281,7 +341,7 @@ impl SharedTestingConfig {
             &test_plan.module_id,
             IdentStr::new(function_name).unwrap(),
             vec![], // no ty args, at least for now
-            serialize_values(test_info.arguments.iter()),
+            serialize_values(arguments.iter()),
             &mut gas_meter,
         );
         let mut return_result = serialized_return_values_result.map(|res| {
@@ -295,6 +355,29 @@ impl SharedTestingConfig {
                 err.remove_exec_state();
             }
         }
+        move_vm_profiler::gas_profiler_feature_enabled! {
+            use move_vm_types::gas::GasMeter;
+            // The profile of a test is written to `<file prefix>.speedscope.json` for viewing
+            // in speedscope, and as collapsed stacks weighted by gas (`.gas.folded`) and by
+            // instructions executed (`.instructions.folded`) for use with flamegraph tools.
+            if let (Some(profile_output), Some((output, file_prefix)), Some(profiler)) =
+                (&self.profile_output, profile, gas_meter.get_profiler_mut())
+            {
+                profiler.finish_without_dump();
+                let profile_path =
+                    |extension: &str| profile_output.join(format!("{file_prefix}.{extension}"));
+                let result = profiler
+                    .to_speedscope_file(&profile_path("speedscope.json"))
+                    .and_then(|_| profiler.to_collapsed_gas_file(&profile_path("gas.folded")))
+                    .and_then(|_| {
+                        profiler
+                            .to_collapsed_instructions_file(&profile_path("instructions.folded"))
+                    });
+                if let Err(e) = result {
+                    output.profile_error(function_name, e);
+                }
+            }
+        }
         let test_run_info = TestRunInfo::new(
             function_name.to_string(),
             now.elapsed(),
@@ -316,7 +399,7 @@ impl SharedTestingConfig {
         env: &GlobalEnv,
         test_plan: &ModuleTestPlan,
         function_name: &str,
-        test_info: &TestCase,
+        arguments: &[MoveValue],
     ) -> (VMResult<Vec<Vec<u8>>>, TestRunInfo, Option<String>) {
         let now = Instant::now();
 
@@ -335,7 +418,7 @@ impl SharedTestingConfig {
             &test_plan.module_id,
             IdentStr::new(function_name).unwrap(),
             &[], // no ty args, at least for now
-            &test_info.arguments,
+            arguments,
             &global_state,
         );
         let prop_check_result = interpreter.report_property_checking_results();
@@ -394,139 +477,285 @@ impl SharedTestingConfig {
         let mut stats = TestStatistics::new();
 
         for (function_name, test_info) in &test_plan.tests {
-            let (_cs_result, _ext_result, exec_result, test_run_info) =
-                self.execute_via_move_vm(test_plan, function_name, test_info);
-
-            if self.check_stackless_vm {
-                let (stackless_vm_result, _, prop_check_result) = self.execute_via_stackless_vm(
-                    stackless_model.as_ref().unwrap(),
+            let result = if test_info.is_random_test() {
+                self.exec_random_test(
                     test_plan,
                     function_name,
                     test_info,
-                );
-                let move_vm_result = adapt_move_vm_result(exec_result.clone());
-                if stackless_vm_result != move_vm_result {
-                    output.fail(function_name);
-                    stats.test_failure(
-                        TestFailure::new(
-                            FailureReason::mismatch(move_vm_result, stackless_vm_result),
-                            test_run_info,
-                            None,
-                        ),
-                        test_plan,
-                    );
-                    continue;
+                    stackless_model.as_ref(),
+                    global_test_context,
+                    output,
+                )
+            } else {
+                let arguments = test_info
+                    .arguments
+                    .iter()
+                    .map(|arg| match arg {
+                        TestArgument::Value(value) => value.clone(),
+                        TestArgument::Generate { .. } => {
+                            unreachable!("ICE: generated argument in a non-random test")
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let profile_file_prefix =
+                    format!("{}__{}", test_plan.module_id.name(), function_name);
+                self.exec_test(
+                    test_plan,
+                    function_name,
+                    test_info,
+                    &arguments,
+                    stackless_model.as_ref(),
+                    global_test_context,
+                    Some((output, &profile_file_prefix)),
+                )
+            };
+
+            match result {
+                Ok(test_run_info) => {
+                    output.pass(function_name);
+                    stats.test_success(test_run_info, test_plan);
                 }
-                if let Some(prop_failure) = prop_check_result {
-                    output.fail(function_name);
-                    stats.test_failure(
-                        TestFailure::new(
-                            FailureReason::property(prop_failure),
-                            test_run_info,
-                            None,
-                        ),
+                Err(test_failure) => {
+                    if matches!(test_failure.failure_reason, FailureReason::Timeout(_)) {
+                        output.timeout(function_name);
+                    } else {
+                        output.fail(function_name);
+                    }
+                    stats.test_failure(test_failure, test_plan);
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Runs `test_info` `rand_num_iters` times with freshly generated arguments, stopping at the
+    /// first failure. The arguments of a failing run are shrunk before the failure is reported.
+    /// On success, the returned run info accumulates the time and gas of all runs. The profile of
+    /// each run is written under the iteration number, the shrinking runs aren't profiled.
+    fn exec_random_test(
+        &self,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        stackless_model: Option<&GlobalEnv>,
+        global_test_context: &BTreeMap<ModuleId, NamedCompiledModule>,
+        output: &TestOutput<impl Write>,
+    ) -> Result<TestRunInfo, TestFailure> {
+        let mut generator = RandomValueGenerator::new(self.rand_seed);
+        let mut total_run_info = TestRunInfo::new(function_name.to_string(), Duration::ZERO, 0);
+        for iteration in 0..self.rand_num_iters {
+            let arguments = test_info
+                .arguments
+                .iter()
+                .map(|arg| match arg {
+                    TestArgument::Value(value) => value.clone(),
+                    TestArgument::Generate { ty, .. } => generator.generate(ty),
+                })
+                .collect::<Vec<_>>();
+            let profile_file_prefix = format!(
+                "{}__{}__{}",
+                test_plan.module_id.name(),
+                function_name,
+                iteration
+            );
+            match self.exec_test(
+                test_plan,
+                function_name,
+                test_info,
+                &arguments,
+                stackless_model,
+                global_test_context,
+                Some((output, &profile_file_prefix)),
+            ) {
+                Ok(test_run_info) => {
+                    total_run_info.elapsed_time += test_run_info.elapsed_time;
+                    total_run_info.instructions_executed += test_run_info.instructions_executed;
+                }
+                Err(test_failure) => {
+                    let (arguments, test_failure) = self.shrink_random_test_failure(
                         test_plan,
+                        function_name,
+                        test_info,
+                        arguments,
+                        test_failure,
+                        stackless_model,
+                        global_test_context,
                     );
-                    continue;
+                    let arguments = test_info
+                        .arguments
+                        .iter()
+                        .zip(arguments)
+                        .filter_map(|(arg, value)| match arg {
+                            TestArgument::Value(_) => None,
+                            TestArgument::Generate { name, .. } => Some((*name, value.to_string())),
+                        })
+                        .collect();
+                    return Err(test_failure.with_random_inputs(RandomTestInputs {
+                        seed: self.rand_seed,
+                        arguments,
+                    }));
                 }
             }
+        }
+        Ok(total_run_info)
+    }
 
-            match exec_result {
-                Err(err) => {
-                    let sub_status = err.sub_status().and_then(|status| {
-                        convert_clever_move_abort_error(status, err.location(), global_test_context)
-                    });
-                    let actual_err =
-                        MoveError(err.major_status(), sub_status, err.location().clone());
-                    assert!(err.major_status() != StatusCode::EXECUTED);
-                    match test_info.expected_failure.as_ref() {
-                        Some(ExpectedFailure::Expected) => {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        Some(ExpectedFailure::ExpectedWithError(expected_err))
-                            if expected_err == &actual_err =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
-                            if actual_err.0 == StatusCode::ABORTED
-                                && actual_err.1.is_some()
-                                && actual_err.1.as_ref().unwrap() == code =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        // incorrect cases
-                        Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_error(expected_err.clone(), actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_abort_deprecated(
-                                        expected_code.clone(),
-                                        actual_err,
-                                    ),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        None if err.major_status() == StatusCode::OUT_OF_GAS => {
-                            // Ran out of ticks, report a test timeout and log a test failure
-                            output.timeout(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::timeout(),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        None => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::unexpected_error(actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
+    /// Greedily simplifies the generated arguments of a failing random test, keeping a simpler
+    /// argument only if the test still fails for the same reason.
+    #[allow(clippy::too_many_arguments)]
+    fn shrink_random_test_failure(
+        &self,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        mut arguments: Vec<MoveValue>,
+        mut test_failure: TestFailure,
+        stackless_model: Option<&GlobalEnv>,
+        global_test_context: &BTreeMap<ModuleId, NamedCompiledModule>,
+    ) -> (Vec<MoveValue>, TestFailure) {
+        let mut num_runs = 0;
+        'shrink: loop {
+            for (idx, arg) in test_info.arguments.iter().enumerate() {
+                if !matches!(arg, TestArgument::Generate { .. }) {
+                    continue;
+                }
+                for candidate in shrink_candidates(&arguments[idx]) {
+                    if num_runs >= MAX_SHRINK_RUNS {
+                        break 'shrink;
+                    }
+                    num_runs += 1;
+                    let mut candidate_arguments = arguments.clone();
+                    candidate_arguments[idx] = candidate;
+                    if let Err(candidate_failure) = self.exec_test(
+                        test_plan,
+                        function_name,
+                        test_info,
+                        &candidate_arguments,
+                        stackless_model,
+                        global_test_context,
+                        // Shrinking runs aren't profiled
+                        None::<(&TestOutput<Vec<u8>>, _)>,
+                    ) {
+                        if candidate_failure.failure_reason == test_failure.failure_reason {
+                            arguments = candidate_arguments;
+                            test_failure = candidate_failure;
+                            continue 'shrink;
                         }
                     }
                 }
-                Ok(_) => {
-                    // Expected the test to fail, but it executed
-                    if test_info.expected_failure.is_some() {
-                        output.fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(FailureReason::no_error(), test_run_info, None),
-                            test_plan,
-                        )
-                    } else {
-                        // Expected the test to execute fully and it did
-                        output.pass(function_name);
-                        stats.test_success(test_run_info, test_plan);
+            }
+            break;
+        }
+        (arguments, test_failure)
+    }
+
+    /// Runs a single test with the given arguments, returning its run info if the test passed, or
+    /// the reason it failed otherwise.
+    #[allow(clippy::too_many_arguments)]
+    fn exec_test(
+        &self,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        arguments: &[MoveValue],
+        stackless_model: Option<&GlobalEnv>,
+        global_test_context: &BTreeMap<ModuleId, NamedCompiledModule>,
+        profile: Option<(&TestOutput<impl Write>, &str)>,
+    ) -> Result<TestRunInfo, TestFailure> {
+        let (_cs_result, _ext_result, exec_result, test_run_info) =
+            self.execute_via_move_vm(test_plan, function_name, arguments, profile);
+
+        if self.check_stackless_vm {
+            let (stackless_vm_result, _, prop_check_result) = self.execute_via_stackless_vm(
+                stackless_model.unwrap(),
+                test_plan,
+                function_name,
+                arguments,
+            );
+            let move_vm_result = adapt_move_vm_result(exec_result.clone());
+            if stackless_vm_result != move_vm_result {
+                return Err(TestFailure::new(
+                    FailureReason::mismatch(move_vm_result, stackless_vm_result),
+                    test_run_info,
+                    None,
+                ));
+            }
+            if let Some(prop_failure) = prop_check_result {
+                return Err(TestFailure::new(
+                    FailureReason::property(prop_failure),
+                    test_run_info,
+                    None,
+                ));
+            }
+        }
+
+        match exec_result {
+            Err(err) => {
+                let sub_status = err.sub_status().and_then(|status| {
+                    convert_clever_move_abort_error(status, err.location(), global_test_context)
+                });
+                let actual_err = MoveError(err.major_status(), sub_status, err.location().clone());
+                assert!(err.major_status() != StatusCode::EXECUTED);
+                match test_info.expected_failure.as_ref() {
+                    Some(ExpectedFailure::Expected) => Ok(test_run_info),
+                    Some(ExpectedFailure::ExpectedWithError(expected_err))
+                        if expected_err == &actual_err =>
+                    {
+                        Ok(test_run_info)
+                    }
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
+                        if actual_err.0 == StatusCode::ABORTED
+                            && actual_err.1.is_some()
+                            && actual_err.1.as_ref().unwrap() == code =>
+                    {
+                        Ok(test_run_info)
+                    }
+                    // incorrect cases
+                    Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
+                        Err(TestFailure::new(
+                            FailureReason::wrong_error(expected_err.clone(), actual_err),
+                            test_run_info,
+                            Some(err),
+                        ))
                     }
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
+                        Err(TestFailure::new(
+                            FailureReason::wrong_abort_deprecated(
+                                expected_code.clone(),
+                                actual_err,
+                            ),
+                            test_run_info,
+                            Some(err),
+                        ))
+                    }
+                    // Ran out of ticks, report a test timeout and log a test failure
+                    None if err.major_status() == StatusCode::OUT_OF_GAS => Err(TestFailure::new(
+                        FailureReason::timeout(),
+                        test_run_info,
+                        Some(err),
+                    )),
+                    None => Err(TestFailure::new(
+                        FailureReason::unexpected_error(actual_err),
+                        test_run_info,
+                        Some(err),
+                    )),
+                }
+            }
+            Ok(_) => {
+                // Expected the test to fail, but it executed
+                if test_info.expected_failure.is_some() {
+                    Err(TestFailure::new(
+                        FailureReason::no_error(),
+                        test_run_info,
+                        None,
+                    ))
+                } else {
+                    // Expected the test to execute fully and it did
+                    Ok(test_run_info)
                 }
             }
         }
-
-        stats
     }
 
     // TODO: comparison of results via different backends
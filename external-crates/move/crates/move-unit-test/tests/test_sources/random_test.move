@@ -0,0 +1,28 @@
+address 0x1 {
+module M {
+    use std::vector;
+
+    #[random_test]
+    fun add_commutes(x: u32, y: u32) {
+        assert!((x as u64) + (y as u64) == (y as u64) + (x as u64), 0);
+    }
+
+    #[random_test]
+    fun push_back_increases_length(v: vector<u8>, x: u8) {
+        let len = vector::length(&v);
+        vector::push_back(&mut v, x);
+        assert!(vector::length(&v) == len + 1, 0);
+    }
+
+    #[random_test]
+    #[expected_failure(abort_code=0, location=0x1::M)]
+    fun aborts_for_any_input(_x: u128) {
+        abort 0
+    }
+
+    #[random_test]
+    fun fails_for_any_input(_x: u64, _v: vector<bool>) {
+        abort 1
+    }
+}
+}
@@ -11,6 +11,9 @@ use tracing::info;
 pub struct FrameName {
     name: String,
     file: String,
+
+    #[serde(skip)]
+    is_instruction: bool,
 }
 
 #[allow(dead_code)]
@@ -131,6 +134,7 @@ impl GasProfiler {
         frame_name: String,
         frame_display_name: String,
         metadata: String,
+        is_instruction: bool,
     ) -> u64 {
         match self.shared.frame_table.get(frame_name.as_str()) {
             Some(idx) => *idx as u64,
@@ -139,6 +143,7 @@ impl GasProfiler {
                 self.shared.frames.push(FrameName {
                     name: frame_display_name,
                     file: metadata,
+                    is_instruction,
                 });
                 self.shared.frame_table.insert(frame_name, val as usize);
                 val
@@ -148,11 +153,37 @@ impl GasProfiler {
 
     #[cfg(feature = "gas-profiler")]
     pub fn open_frame(&mut self, frame_name: String, metadata: String, gas_start: u64) {
+        self.open_frame_impl(frame_name, metadata, gas_start, false)
+    }
+
+    #[cfg(feature = "gas-profiler")]
+    pub fn close_frame(&mut self, frame_name: String, metadata: String, gas_end: u64) {
+        self.close_frame_impl(frame_name, metadata, gas_end, false)
+    }
+
+    #[cfg(feature = "gas-profiler")]
+    pub fn open_instr(&mut self, instr_name: String, gas_start: u64) {
+        self.open_frame_impl(instr_name.clone(), instr_name, gas_start, true)
+    }
+
+    #[cfg(feature = "gas-profiler")]
+    pub fn close_instr(&mut self, instr_name: String, gas_end: u64) {
+        self.close_frame_impl(instr_name.clone(), instr_name, gas_end, true)
+    }
+
+    #[cfg(feature = "gas-profiler")]
+    fn open_frame_impl(
+        &mut self,
+        frame_name: String,
+        metadata: String,
+        gas_start: u64,
+        is_instruction: bool,
+    ) {
         if self.config.is_none() || self.start_gas == 0 {
             return;
         }
 
-        let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata);
+        let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata, is_instruction);
         let start = self.start_gas();
 
         self.profiles[0].events.push(Event {
@@ -163,11 +194,17 @@ impl GasProfiler {
     }
 
     #[cfg(feature = "gas-profiler")]
-    pub fn close_frame(&mut self, frame_name: String, metadata: String, gas_end: u64) {
+    fn close_frame_impl(
+        &mut self,
+        frame_name: String,
+        metadata: String,
+        gas_end: u64,
+        is_instruction: bool,
+    ) {
         if self.config.is_none() || self.start_gas == 0 {
             return;
         }
-        let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata);
+        let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata, is_instruction);
         let start = self.start_gas();
 
         self.profiles[0].events.push(Event {
@@ -218,19 +255,111 @@ impl GasProfiler {
         info!("Gas profile written to file: {}", p.display());
     }
 
+    /// Writes the profile in the speedscope format to `path`.
+    #[cfg(feature = "gas-profiler")]
+    pub fn to_speedscope_file(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        std::fs::write(path, json)
+    }
+
+    /// Writes the profile as collapsed stacks (one `root;caller;callee <weight>` line per call
+    /// stack) to `path`, weighted by the gas used in the top function of each stack. This format
+    /// can be rendered by flamegraph tools such as `inferno` or `flamegraph.pl`.
+    #[cfg(feature = "gas-profiler")]
+    pub fn to_collapsed_gas_file(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let stacks = self.fold_stacks();
+        std::fs::write(
+            path,
+            Self::collapsed(stacks.iter().map(|(s, w)| (s, w.gas))),
+        )
+    }
+
+    /// Like `to_collapsed_gas_file`, but weighted by the number of bytecode instructions executed
+    /// in the top function of each stack. Instructions are only counted if the profiler is
+    /// configured to track them.
+    #[cfg(feature = "gas-profiler")]
+    pub fn to_collapsed_instructions_file(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let stacks = self.fold_stacks();
+        std::fs::write(
+            path,
+            Self::collapsed(stacks.iter().map(|(s, w)| (s, w.instructions))),
+        )
+    }
+
+    #[cfg(feature = "gas-profiler")]
+    fn collapsed<'a>(stacks: impl Iterator<Item = (&'a String, u64)>) -> String {
+        stacks
+            .filter(|(_, weight)| *weight > 0)
+            .map(|(stack, weight)| format!("{stack} {weight}\n"))
+            .collect()
+    }
+
+    // Replays the open/close events of the profile, attributing the gas used and instructions
+    // executed between two events to the function call stack active at the time.
+    #[cfg(feature = "gas-profiler")]
+    fn fold_stacks(&self) -> BTreeMap<String, StackWeight> {
+        let mut folded: BTreeMap<String, StackWeight> = BTreeMap::new();
+        let mut stack: Vec<&str> = vec![];
+        let mut last_at = 0;
+        for event in &self.profiles[0].events {
+            let frame = &self.shared.frames[event.frame as usize];
+            if !stack.is_empty() {
+                let weight = folded.entry(stack.join(";")).or_default();
+                weight.gas += event.at.saturating_sub(last_at);
+                if frame.is_instruction && event.ty == Self::OPEN_FRAME_IDENT {
+                    weight.instructions += 1;
+                }
+            }
+            last_at = event.at;
+            if frame.is_instruction {
+                continue;
+            }
+            if event.ty == Self::OPEN_FRAME_IDENT {
+                stack.push(&frame.name);
+            } else {
+                stack.pop();
+            }
+        }
+        folded
+    }
+
     #[cfg(feature = "gas-profiler")]
     pub fn finish(&mut self) {
         if self.finished {
             return;
         }
+        self.close_top_level_frame();
+        profile_dump_file!(self);
+    }
+
+    /// Completes the profile without writing it to the file in the profiler's config, so that it
+    /// can be exported by the caller instead.
+    #[cfg(feature = "gas-profiler")]
+    pub fn finish_without_dump(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.close_top_level_frame();
+    }
+
+    #[cfg(feature = "gas-profiler")]
+    fn close_top_level_frame(&mut self) {
         self.finished = true;
         let end_gas = self.start_gas() - self.profiles[0].end_value;
         let mut q = Some(self);
         profile_close_frame_impl!(&mut q, Self::TOP_LEVEL_FRAME_NAME.to_string(), end_gas);
-        profile_dump_file!(q.unwrap());
     }
 }
 
+/// The weight of a call stack in a folded profile
+#[cfg(feature = "gas-profiler")]
+#[derive(Default)]
+struct StackWeight {
+    gas: u64,
+    instructions: u64,
+}
+
 #[cfg(feature = "gas-profiler")]
 impl Drop for GasProfiler {
     fn drop(&mut self) {
@@ -315,7 +444,7 @@ macro_rules! profile_open_instr {
             if let Some(profiler) = $gas_meter.get_profiler_mut() {
                 if let Some(config) = &profiler.config {
                     if config.track_bytecode_instructions {
-                        profiler.open_frame($frame_name.clone(), $frame_name, gas_rem)
+                        profiler.open_instr($frame_name, gas_rem)
                     }
                 }
             }
@@ -332,7 +461,7 @@ macro_rules! profile_close_instr {
             if let Some(profiler) = $gas_meter.get_profiler_mut() {
                 if let Some(config) = &profiler.config {
                     if config.track_bytecode_instructions {
-                        profiler.close_frame($frame_name.clone(), $frame_name, gas_rem)
+                        profiler.close_instr($frame_name, gas_rem)
                     }
                 }
             }
@@ -41,7 +41,7 @@ mod checked {
     use sui_types::error::{ExecutionError, ExecutionErrorKind};
     use sui_types::execution::is_certificate_denied;
     use sui_types::execution_config_utils::to_binary_config;
-    use sui_types::execution_status::ExecutionStatus;
+    use sui_types::execution_status::{CongestionCancellation, ExecutionStatus};
     use sui_types::gas::GasCostSummary;
     use sui_types::gas::SuiGasStatus;
     use sui_types::inner_temporary_store::InnerTemporaryStore;
@@ -79,6 +79,7 @@ mod checked {
         metrics: Arc<LimitsMetrics>,
         enable_expensive_checks: bool,
         certificate_deny_set: &HashSet<TransactionDigest>,
+        congestion_cancellation: Option<CongestionCancellation>,
     ) -> (
         InnerTemporaryStore,
         SuiGasStatus,
@@ -128,6 +129,7 @@ mod checked {
             enable_expensive_checks,
             deny_cert,
             contains_deleted_input,
+            congestion_cancellation,
         );
 
         let status = if let Err(error) = &execution_result {
@@ -260,6 +262,7 @@ mod checked {
         enable_expensive_checks: bool,
         deny_cert: bool,
         contains_deleted_input: bool,
+        congestion_cancellation: Option<CongestionCancellation>,
     ) -> (
         GasCostSummary,
         Result<Mode::ExecutionResults, ExecutionError>,
@@ -289,6 +292,11 @@ mod checked {
                     ExecutionErrorKind::InputObjectDeleted,
                     None,
                 ))
+            } else if let Some(cancellation) = congestion_cancellation {
+                Err(ExecutionError::new(
+                    cancellation.into_failure_status(),
+                    None,
+                ))
             } else {
                 execution_loop::<Mode>(
                     temporary_store,
@@ -12,6 +12,7 @@ use sui_types::{
     error::ExecutionError,
     execution::TypeLayoutStore,
     execution_mode::ExecutionResult,
+    execution_status::CongestionCancellation,
     gas::SuiGasStatus,
     inner_temporary_store::InnerTemporaryStore,
     metrics::LimitsMetrics,
@@ -21,6 +22,11 @@ use sui_types::{
 
 /// Abstracts over access to the VM across versions of the execution layer.
 pub trait Executor {
+    /// Whether this version of the execution layer can execute a transaction that consensus
+    /// cancelled due to shared object congestion. Consensus must not cancel transactions when it
+    /// returns false, since they can't be executed.
+    fn supports_congestion_cancellation(&self) -> bool;
+
     fn execute_transaction_to_effects(
         &self,
         store: &dyn BackingStore,
@@ -41,6 +47,8 @@ pub trait Executor {
         transaction_kind: TransactionKind,
         transaction_signer: SuiAddress,
         transaction_digest: TransactionDigest,
+        // Set if consensus cancelled the transaction instead of scheduling it for execution
+        congestion_cancellation: Option<CongestionCancellation>,
     ) -> (
         InnerTemporaryStore,
         SuiGasStatus,
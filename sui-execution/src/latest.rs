@@ -15,6 +15,7 @@ use sui_types::{
     error::{ExecutionError, SuiError, SuiResult},
     execution::TypeLayoutStore,
     execution_mode::{self, ExecutionResult},
+    execution_status::CongestionCancellation,
     gas::SuiGasStatus,
     inner_temporary_store::InnerTemporaryStore,
     metrics::{BytecodeVerifierMetrics, LimitsMetrics},
@@ -64,6 +65,10 @@ impl<'m> Verifier<'m> {
 }
 
 impl executor::Executor for Executor {
+    fn supports_congestion_cancellation(&self) -> bool {
+        true
+    }
+
     fn execute_transaction_to_effects(
         &self,
         store: &dyn BackingStore,
@@ -79,6 +84,7 @@ impl executor::Executor for Executor {
         transaction_kind: TransactionKind,
         transaction_signer: SuiAddress,
         transaction_digest: TransactionDigest,
+        congestion_cancellation: Option<CongestionCancellation>,
     ) -> (
         InnerTemporaryStore,
         SuiGasStatus,
@@ -100,6 +106,7 @@ impl executor::Executor for Executor {
             metrics,
             enable_expensive_checks,
             certificate_deny_set,
+            congestion_cancellation,
         )
     }
 
@@ -141,6 +148,7 @@ impl executor::Executor for Executor {
                 metrics,
                 enable_expensive_checks,
                 certificate_deny_set,
+                None,
             )
         } else {
             execute_transaction_to_effects::<execution_mode::DevInspect<false>>(
@@ -158,6 +166,7 @@ impl executor::Executor for Executor {
                 metrics,
                 enable_expensive_checks,
                 certificate_deny_set,
+                None,
             )
         }
     }